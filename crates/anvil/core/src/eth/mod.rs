@@ -1,13 +1,25 @@
-use crate::{eth::subscription::SubscriptionId, types::ReorgOptions};
-use alloy_primitives::{Address, Bytes, TxHash, B256, B64, U256};
+use crate::{
+    eth::subscription::SubscriptionId,
+    types::{
+        CancelPrivateTransactionRequest, PrivateTransactionRequest, ReorgOptions,
+        SendBundleRequest, StateDiffOptions, UserOperation,
+    },
+};
+use alloy_primitives::{map::HashSet, Address, Bytes, TxHash, B256, B64, U256};
 use alloy_rpc_types::{
     anvil::{Forking, MineOptions},
+    engine::{
+        ExecutionPayloadInputV2, ExecutionPayloadV1, ExecutionPayloadV3, ForkchoiceState,
+        PayloadAttributes, PayloadId,
+    },
     pubsub::{Params as SubscriptionParams, SubscriptionKind},
     request::TransactionRequest,
+    simulate::SimulatePayload,
     state::StateOverride,
     trace::{
         filter::TraceFilter,
         geth::{GethDebugTracingCallOptions, GethDebugTracingOptions},
+        parity::TraceType,
     },
     BlockId, BlockNumberOrTag as BlockNumber, Filter, Index,
 };
@@ -176,6 +188,43 @@ pub enum EthRequest {
     #[cfg_attr(feature = "serde", serde(rename = "eth_sendRawTransaction", with = "sequence"))]
     EthSendRawTransaction(Bytes),
 
+    /// Submits a bundle of transactions that is included atomically and in order
+    #[cfg_attr(feature = "serde", serde(rename = "eth_sendBundle", with = "sequence"))]
+    EthSendBundle(SendBundleRequest),
+
+    /// Submits an ERC-4337 user operation to the bundler of the node
+    #[cfg_attr(feature = "serde", serde(rename = "eth_sendUserOperation"))]
+    EthSendUserOperation(Box<UserOperation>, Address),
+
+    /// Estimates the gas limits of an ERC-4337 user operation
+    #[cfg_attr(feature = "serde", serde(rename = "eth_estimateUserOperationGas"))]
+    EthEstimateUserOperationGas(Box<UserOperation>, Address),
+
+    /// Returns the receipt of an ERC-4337 user operation by its hash
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "eth_getUserOperationReceipt", with = "sequence")
+    )]
+    EthGetUserOperationReceipt(B256),
+
+    /// Returns the entry points supported by the bundler of the node
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "eth_supportedEntryPoints", with = "empty_params")
+    )]
+    EthSupportedEntryPoints(()),
+
+    /// Submits a transaction that bypasses the public pool
+    #[cfg_attr(feature = "serde", serde(rename = "eth_sendPrivateTransaction", with = "sequence"))]
+    EthSendPrivateTransaction(PrivateTransactionRequest),
+
+    /// Cancels a transaction that was submitted via `eth_sendPrivateTransaction`
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "eth_cancelPrivateTransaction", with = "sequence")
+    )]
+    EthCancelPrivateTransaction(CancelPrivateTransactionRequest),
+
     #[cfg_attr(feature = "serde", serde(rename = "eth_call"))]
     EthCall(
         WithOtherFields<TransactionRequest>,
@@ -183,6 +232,9 @@ pub enum EthRequest {
         #[cfg_attr(feature = "serde", serde(default))] Option<StateOverride>,
     ),
 
+    #[cfg_attr(feature = "serde", serde(rename = "eth_simulateV1"))]
+    EthSimulateV1(SimulatePayload, #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>),
+
     #[cfg_attr(feature = "serde", serde(rename = "eth_createAccessList"))]
     EthCreateAccessList(
         WithOtherFields<TransactionRequest>,
@@ -298,6 +350,20 @@ pub enum EthRequest {
         #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingOptions,
     ),
 
+    /// geth's `debug_traceBlockByNumber` endpoint
+    #[cfg_attr(feature = "serde", serde(rename = "debug_traceBlockByNumber"))]
+    DebugTraceBlockByNumber(
+        BlockNumber,
+        #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingOptions,
+    ),
+
+    /// geth's `debug_traceBlockByHash` endpoint
+    #[cfg_attr(feature = "serde", serde(rename = "debug_traceBlockByHash"))]
+    DebugTraceBlockByHash(
+        B256,
+        #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingOptions,
+    ),
+
     /// geth's `debug_traceCall`  endpoint
     #[cfg_attr(feature = "serde", serde(rename = "debug_traceCall"))]
     DebugTraceCall(
@@ -306,6 +372,28 @@ pub enum EthRequest {
         #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingCallOptions,
     ),
 
+    /// geth's `debug_storageRangeAt` endpoint
+    #[cfg_attr(feature = "serde", serde(rename = "debug_storageRangeAt"))]
+    DebugStorageRangeAt(BlockId, u64, Address, B256, u64),
+
+    /// geth's `debug_accountRange` endpoint
+    #[cfg_attr(feature = "serde", serde(rename = "debug_accountRange"))]
+    DebugAccountRange(
+        BlockId,
+        #[cfg_attr(feature = "serde", serde(default))] Bytes,
+        u64,
+        #[cfg_attr(feature = "serde", serde(default))] bool,
+        #[cfg_attr(feature = "serde", serde(default))] bool,
+        #[cfg_attr(feature = "serde", serde(default))] bool,
+    ),
+
+    /// geth's `debug_getModifiedAccountsByNumber` endpoint
+    #[cfg_attr(feature = "serde", serde(rename = "debug_getModifiedAccountsByNumber"))]
+    DebugGetModifiedAccountsByNumber(
+        u64,
+        #[cfg_attr(feature = "serde", serde(default))] Option<u64>,
+    ),
+
     /// Trace transaction endpoint for parity's `trace_transaction`
     #[cfg_attr(feature = "serde", serde(rename = "trace_transaction", with = "sequence"))]
     TraceTransaction(B256),
@@ -324,6 +412,29 @@ pub enum EthRequest {
     #[cfg_attr(feature = "serde", serde(rename = "trace_filter", with = "sequence"))]
     TraceFilter(TraceFilter),
 
+    /// Replays a transaction with parity's `trace_replayTransaction`
+    #[cfg_attr(feature = "serde", serde(rename = "trace_replayTransaction"))]
+    TraceReplayTransaction(B256, HashSet<TraceType>),
+
+    /// Replays all transactions of a block with parity's `trace_replayBlockTransactions`
+    #[cfg_attr(feature = "serde", serde(rename = "trace_replayBlockTransactions"))]
+    TraceReplayBlockTransactions(BlockId, HashSet<TraceType>),
+
+    /// Traces a call with parity's `trace_call`
+    #[cfg_attr(feature = "serde", serde(rename = "trace_call"))]
+    TraceCall(
+        WithOtherFields<TransactionRequest>,
+        HashSet<TraceType>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
+    ),
+
+    /// Traces a sequence of calls with parity's `trace_callMany`
+    #[cfg_attr(feature = "serde", serde(rename = "trace_callMany"))]
+    TraceCallMany(
+        Vec<(WithOtherFields<TransactionRequest>, HashSet<TraceType>)>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
+    ),
+
     // Custom endpoints, they're not extracted to a separate type out of serde convenience
     /// send transactions impersonating specific account and contract addresses.
     #[cfg_attr(
@@ -575,6 +686,15 @@ pub enum EthRequest {
     )]
     EvmRevert(U256),
 
+    /// Returns all balance, nonce, code and storage changes between the state of a block or
+    /// snapshot and the state of a later block.
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_stateDiff", with = "sequence"))]
+    AnvilStateDiff(StateDiffOptions),
+
+    /// Returns the proofs of all withdrawals to L1 that an OP stack transaction initiated.
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_getWithdrawalProofs", with = "sequence"))]
+    AnvilGetWithdrawalProofs(B256),
+
     /// Jump forward in time by the given amount of time, in seconds.
     #[cfg_attr(
         feature = "serde",
@@ -833,6 +953,65 @@ pub enum EthRpcCall {
     PubSub(EthPubSub),
 }
 
+/// Represents the Engine API, only served via the authenticated endpoint
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "method", content = "params"))]
+pub enum EngineRequest {
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "engine_exchangeCapabilities", with = "sequence")
+    )]
+    EngineExchangeCapabilities(Vec<String>),
+
+    #[cfg_attr(feature = "serde", serde(rename = "engine_forkchoiceUpdatedV1"))]
+    EngineForkchoiceUpdatedV1(
+        ForkchoiceState,
+        #[cfg_attr(feature = "serde", serde(default))] Option<PayloadAttributes>,
+    ),
+
+    #[cfg_attr(feature = "serde", serde(rename = "engine_forkchoiceUpdatedV2"))]
+    EngineForkchoiceUpdatedV2(
+        ForkchoiceState,
+        #[cfg_attr(feature = "serde", serde(default))] Option<PayloadAttributes>,
+    ),
+
+    #[cfg_attr(feature = "serde", serde(rename = "engine_forkchoiceUpdatedV3"))]
+    EngineForkchoiceUpdatedV3(
+        ForkchoiceState,
+        #[cfg_attr(feature = "serde", serde(default))] Option<PayloadAttributes>,
+    ),
+
+    #[cfg_attr(feature = "serde", serde(rename = "engine_getPayloadV1", with = "sequence"))]
+    EngineGetPayloadV1(PayloadId),
+
+    #[cfg_attr(feature = "serde", serde(rename = "engine_getPayloadV2", with = "sequence"))]
+    EngineGetPayloadV2(PayloadId),
+
+    #[cfg_attr(feature = "serde", serde(rename = "engine_getPayloadV3", with = "sequence"))]
+    EngineGetPayloadV3(PayloadId),
+
+    #[cfg_attr(feature = "serde", serde(rename = "engine_newPayloadV1", with = "sequence"))]
+    EngineNewPayloadV1(ExecutionPayloadV1),
+
+    #[cfg_attr(feature = "serde", serde(rename = "engine_newPayloadV2", with = "sequence"))]
+    EngineNewPayloadV2(ExecutionPayloadInputV2),
+
+    /// The payload, the expected blob versioned hashes and the parent beacon block root
+    #[cfg_attr(feature = "serde", serde(rename = "engine_newPayloadV3"))]
+    EngineNewPayloadV3(ExecutionPayloadV3, Vec<B256>, B256),
+}
+
+/// Container type for either an Engine API or a regular request, both are served via the
+/// authenticated endpoint
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum EngineRpcCall {
+    Engine(Box<EngineRequest>),
+    Request(Box<EthRequest>),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1283,6 +1462,39 @@ fn test_serde_custom_revert() {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_state_diff() {
+        let s = r#"{"method": "anvil_stateDiff", "params": [{"fromBlock": "0x1", "toBlock": "latest"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::AnvilStateDiff(options) => {
+                assert_eq!(options.from_block, Some(BlockId::number(1)));
+                assert_eq!(options.from_snapshot, None);
+                assert_eq!(options.to_block, Some(BlockId::latest()));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_stateDiff", "params": [{"fromSnapshot": "0x0"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::AnvilStateDiff(options) => {
+                assert_eq!(options.from_snapshot, Some(U256::ZERO));
+                assert_eq!(options.to_block, None);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_custom_get_withdrawal_proofs() {
+        let s = r#"{"method": "anvil_getWithdrawalProofs", "params": ["0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_custom_increase_time() {
         let s = r#"{"method": "anvil_increaseTime", "params": ["0x0"]}"#;
@@ -1516,6 +1728,50 @@ fn test_serde_debug_raw_transaction() {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_eth_simulate() {
+        let s = r#"{"method": "eth_simulateV1", "params": [{"blockStateCalls": [{"calls": [{"from": "0xd84de507f3fada7df80908082d3239466db55a71", "to": "0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d", "value": "0x1"}]}]}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "eth_simulateV1", "params": [{"blockStateCalls": [{"blockOverrides": {"number": "0x10", "baseFeePerGas": "0x0"}, "stateOverrides": {"0xd84de507f3fada7df80908082d3239466db55a71": {"balance": "0xde0b6b3a7640000"}}, "calls": []}], "traceTransfers": true, "validation": true, "returnFullTransactions": true}, "latest"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_engine_forkchoice_updated() {
+        let s = r#"{"method": "engine_forkchoiceUpdatedV3", "params": [{"headBlockHash": "0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a", "safeBlockHash": "0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a", "finalizedBlockHash": "0x0000000000000000000000000000000000000000000000000000000000000000"}, {"timestamp": "0x64", "prevRandao": "0x0000000000000000000000000000000000000000000000000000000000000000", "suggestedFeeRecipient": "0x0000000000000000000000000000000000000000", "withdrawals": [], "parentBeaconBlockRoot": "0x0000000000000000000000000000000000000000000000000000000000000000"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EngineRequest>(value).unwrap();
+        assert!(matches!(req, EngineRequest::EngineForkchoiceUpdatedV3(_, Some(_))));
+
+        let s = r#"{"method": "engine_forkchoiceUpdatedV1", "params": [{"headBlockHash": "0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a", "safeBlockHash": "0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a", "finalizedBlockHash": "0x0000000000000000000000000000000000000000000000000000000000000000"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EngineRequest>(value).unwrap();
+        assert!(matches!(req, EngineRequest::EngineForkchoiceUpdatedV1(_, None)));
+    }
+
+    #[test]
+    fn test_serde_engine_get_payload() {
+        let s = r#"{"method": "engine_getPayloadV3", "params": ["0x0000000021f32cc1"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EngineRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_engine_rpc_call() {
+        let s = r#"{"method": "engine_exchangeCapabilities", "params": [["engine_newPayloadV3"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EngineRpcCall>(value).unwrap();
+        assert!(matches!(req, EngineRpcCall::Engine(_)));
+
+        let s = r#"{"method": "eth_blockNumber", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EngineRpcCall>(value).unwrap();
+        assert!(matches!(req, EngineRpcCall::Request(_)));
+    }
+
     #[test]
     fn test_serde_debug_trace_transaction() {
         let s = r#"{"method": "debug_traceTransaction", "params":
@@ -1535,6 +1791,52 @@ fn test_serde_debug_trace_transaction() {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_debug_trace_block() {
+        let s = r#"{"method": "debug_traceBlockByNumber", "params": ["0x1"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceBlockByNumber", "params": ["latest", {"tracer": "callTracer"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceBlockByHash", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", {"tracer": "prestateTracer", "tracerConfig": {"diffMode": true}}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_debug_storage_range_at() {
+        let s = r#"{"method": "debug_storageRangeAt", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", 0, "0x295a70b2de5e3953354a6a8344e616ed314d7251", "0x0000000000000000000000000000000000000000000000000000000000000000", 10]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_debug_account_range() {
+        let s = r#"{"method": "debug_accountRange", "params": ["latest", "0x", 10]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_accountRange", "params": ["0x1", "0x00", 256, true, false, true]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_debug_get_modified_accounts_by_number() {
+        let s = r#"{"method": "debug_getModifiedAccountsByNumber", "params": [1]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_getModifiedAccountsByNumber", "params": [1, 5]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_debug_trace_call() {
         let s = r#"{"method": "debug_traceCall", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}]}"#;
@@ -1558,6 +1860,37 @@ fn test_serde_debug_trace_call() {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_trace_replay() {
+        let s = r#"{"method": "trace_replayTransaction", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", ["trace", "stateDiff", "vmTrace"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "trace_replayBlockTransactions", "params": ["0x1", ["stateDiff"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "trace_replayBlockTransactions", "params": ["latest", []]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_trace_call() {
+        let s = r#"{"method": "trace_call", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, ["trace"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "trace_call", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, ["trace", "stateDiff"], "latest"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "trace_callMany", "params": [[[{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, ["vmTrace"]]], "0x0"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_eth_storage() {
         let s = r#"{"method": "eth_getStorageAt", "params":
@@ -1717,4 +2050,73 @@ fn test_serde_anvil_reorg() {
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
+
+    #[test]
+    fn test_serde_eth_send_bundle() {
+        let s = r#"{"method": "eth_sendBundle", "params": [{
+            "txs": ["0x02f86b", "0x02f86c"],
+            "blockNumber": "0x10",
+            "minTimestamp": 1700000000,
+            "revertingTxHashes": ["0x19d55c67e1ba8f1bbdfed75f8ad524ebf087e4ecb848a2d19881d7a5e3d2c54e"]
+        }]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::EthSendBundle(bundle) => {
+                assert_eq!(bundle.txs.len(), 2);
+                assert_eq!(bundle.block_number, 16);
+                assert_eq!(bundle.min_timestamp, Some(1700000000));
+                assert_eq!(bundle.max_timestamp, None);
+                assert_eq!(bundle.reverting_tx_hashes.len(), 1);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_eth_send_user_operation() {
+        let s = r#"{"method": "eth_sendUserOperation", "params": [{
+            "sender": "0x00000000000000000000000000000000000000aa",
+            "nonce": "0x1",
+            "callData": "0x",
+            "callGasLimit": "0x10000",
+            "verificationGasLimit": "0x20000",
+            "preVerificationGas": "0xc350",
+            "maxFeePerGas": "0x3b9aca00",
+            "maxPriorityFeePerGas": "0x3b9aca00",
+            "signature": "0x01"
+        }, "0x0000000071727De22E5E9d8BAf0edAc6f37da032"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::EthSendUserOperation(op, _entry_point) => {
+                assert_eq!(op.nonce, U256::from(1));
+                assert_eq!(op.call_gas_limit, 0x10000);
+                assert_eq!(op.factory, None);
+                assert_eq!(op.paymaster_verification_gas_limit, None);
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "eth_supportedEntryPoints", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_eth_send_private_transaction() {
+        let s = r#"{"method": "eth_sendPrivateTransaction", "params": [{"tx": "0x02f86b", "maxBlockNumber": "0x20"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::EthSendPrivateTransaction(request) => {
+                assert_eq!(request.max_block_number, Some(32));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "eth_cancelPrivateTransaction", "params": [{"txHash": "0x19d55c67e1ba8f1bbdfed75f8ad524ebf087e4ecb848a2d19881d7a5e3d2c54e"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
 }
@@ -368,6 +368,18 @@ pub fn with_impersonated(transaction: TypedTransaction, sender: Address) -> Self
         }
     }
 
+    /// Creates a new pending transaction from an already mined transaction, recovering the sender
+    /// unless it was impersonated.
+    #[cfg(feature = "impersonated-tx")]
+    pub fn from_mined(
+        transaction: MaybeImpersonatedTransaction,
+    ) -> Result<Self, alloy_primitives::SignatureError> {
+        match transaction.impersonated_sender {
+            Some(sender) => Ok(Self::with_impersonated(transaction.transaction, sender)),
+            None => Self::new(transaction.transaction),
+        }
+    }
+
     pub fn nonce(&self) -> u64 {
         self.transaction.nonce()
     }
@@ -1,9 +1,14 @@
-use alloy_primitives::{Bytes, B256, U256};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use std::collections::BTreeMap;
 
-use alloy_rpc_types::TransactionRequest;
-use serde::Deserialize;
+use crate::eth::transaction::ReceiptResponse;
+use alloy_rpc_types::{
+    simulate::{SimCallResult, SimulatedBlock},
+    BlockId, Log, TransactionRequest,
+};
 #[cfg(feature = "serde")]
 use serde::Serializer;
+use serde::{Deserialize, Serialize};
 
 /// Represents the result of `eth_getWork`
 /// This may or may not include the block number
@@ -44,3 +49,344 @@ pub enum TransactionData {
     JSON(TransactionRequest),
     Raw(Bytes),
 }
+
+/// Represents the params of `eth_sendBundle`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendBundleRequest {
+    /// The raw transactions of the bundle, in execution order
+    pub txs: Vec<Bytes>,
+    /// The block the bundle is valid for
+    #[serde(with = "alloy_serde::quantity")]
+    pub block_number: u64,
+    /// The minimum timestamp of the block the bundle is valid for
+    #[serde(default)]
+    pub min_timestamp: Option<u64>,
+    /// The maximum timestamp of the block the bundle is valid for
+    #[serde(default)]
+    pub max_timestamp: Option<u64>,
+    /// Transactions of the bundle that are allowed to revert
+    #[serde(default)]
+    pub reverting_tx_hashes: Vec<B256>,
+}
+
+/// Represents the response of `eth_sendBundle`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleHash {
+    pub bundle_hash: B256,
+}
+
+/// Represents the params of `eth_sendPrivateTransaction`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivateTransactionRequest {
+    /// The raw transaction
+    pub tx: Bytes,
+    /// The last block the transaction can be included in
+    #[serde(default, with = "alloy_serde::quantity::opt")]
+    pub max_block_number: Option<u64>,
+}
+
+/// Represents the params of `eth_cancelPrivateTransaction`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelPrivateTransactionRequest {
+    pub tx_hash: B256,
+}
+
+/// Represents the response of `debug_storageRangeAt`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    /// The storage slots of the range, keyed by the hash of the slot
+    pub storage: BTreeMap<B256, StorageRangeEntry>,
+    /// The hash of the first slot after the range, if there are more slots
+    pub next_key: Option<B256>,
+}
+
+/// A storage slot in the response of `debug_storageRangeAt`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageRangeEntry {
+    pub key: Option<B256>,
+    pub value: B256,
+}
+
+/// Represents the response of `debug_accountRange`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountRangeResult {
+    /// The state root
+    pub root: B256,
+    pub accounts: BTreeMap<Address, AccountRangeEntry>,
+    /// The hash of the first account after the range, if there are more accounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<B256>,
+}
+
+/// An account in the response of `debug_accountRange`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRangeEntry {
+    pub balance: U256,
+    pub nonce: u64,
+    /// The storage root
+    pub root: B256,
+    pub code_hash: B256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The non-empty storage slots of the account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<B256, B256>>,
+    pub address: Address,
+    /// The hash of the address
+    pub key: B256,
+}
+
+/// Options for `anvil_stateDiff`
+///
+/// Exactly one of `from_block` and `from_snapshot` must be set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct StateDiffOptions {
+    /// The block whose state the diff starts from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockId>,
+    /// The id of the `evm_snapshot` whose state the diff starts from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_snapshot: Option<U256>,
+    /// The block whose state the diff ends at, defaults to the latest block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockId>,
+}
+
+/// Represents the response of `anvil_stateDiff`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// All accounts that changed, keyed by address
+    pub accounts: BTreeMap<Address, AccountStateDiff>,
+}
+
+/// The changes of a single account in the response of `anvil_stateDiff`
+///
+/// Fields that did not change are omitted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountStateDiff {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<ValueDiff<U256>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<ValueDiff<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<ValueDiff<Bytes>>,
+    /// The changed storage slots
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, ValueDiff<B256>>,
+}
+
+impl AccountStateDiff {
+    /// Returns true if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none() &&
+            self.nonce.is_none() &&
+            self.code.is_none() &&
+            self.storage.is_empty()
+    }
+}
+
+/// A value before and after a change
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueDiff<T> {
+    pub from: T,
+    pub to: T,
+}
+
+impl<T: PartialEq> ValueDiff<T> {
+    /// Returns the diff of the two values, or `None` if they are equal
+    pub fn new(from: T, to: T) -> Option<Self> {
+        (from != to).then_some(Self { from, to })
+    }
+}
+
+/// Represents an entry of the response of `anvil_getWithdrawalProofs`, with the arguments of
+/// `OptimismPortal.proveWithdrawalTransaction`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalProof {
+    /// The withdrawal that was initiated on L2
+    pub withdrawal: WithdrawalTransaction,
+    /// The hash of the withdrawal, as emitted by the `L2ToL1MessagePasser`
+    pub withdrawal_hash: B256,
+    /// The L2 block whose output root the withdrawal is proven against
+    #[serde(with = "alloy_serde::quantity")]
+    pub l2_block_number: u64,
+    /// The output root of the L2 block
+    pub output_root: B256,
+    /// The preimage of the output root
+    pub output_root_proof: OutputRootProof,
+    /// The storage proof of the withdrawal in the `sentMessages` mapping of the
+    /// `L2ToL1MessagePasser`
+    pub withdrawal_proof: Vec<Bytes>,
+}
+
+/// A withdrawal from L2 to L1, as expected by `OptimismPortal`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalTransaction {
+    pub nonce: U256,
+    pub sender: Address,
+    pub target: Address,
+    pub value: U256,
+    pub gas_limit: U256,
+    pub data: Bytes,
+}
+
+/// The preimage of an L2 output root
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputRootProof {
+    pub version: B256,
+    pub state_root: B256,
+    pub message_passer_storage_root: B256,
+    pub latest_blockhash: B256,
+}
+
+impl OutputRootProof {
+    /// Returns the output root, the hash of all fields
+    pub fn output_root(&self) -> B256 {
+        keccak256(
+            [
+                self.version.as_slice(),
+                self.state_root.as_slice(),
+                self.message_passer_storage_root.as_slice(),
+                self.latest_blockhash.as_slice(),
+            ]
+            .concat(),
+        )
+    }
+}
+
+/// An ERC-4337 user operation for the EntryPoint v0.7, as sent to `eth_sendUserOperation`
+///
+/// The gas limits and fees default to zero, since they're unknown when the operation is passed to
+/// `eth_estimateUserOperationGas`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    pub sender: Address,
+    pub nonce: U256,
+    /// The factory that deploys the account, if it's not deployed yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory_data: Option<Bytes>,
+    pub call_data: Bytes,
+    #[serde(default, with = "alloy_serde::quantity")]
+    pub call_gas_limit: u128,
+    #[serde(default, with = "alloy_serde::quantity")]
+    pub verification_gas_limit: u128,
+    #[serde(default)]
+    pub pre_verification_gas: U256,
+    #[serde(default, with = "alloy_serde::quantity")]
+    pub max_fee_per_gas: u128,
+    #[serde(default, with = "alloy_serde::quantity")]
+    pub max_priority_fee_per_gas: u128,
+    /// The paymaster that pays for the operation, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster: Option<Address>,
+    #[serde(default, with = "alloy_serde::quantity::opt", skip_serializing_if = "Option::is_none")]
+    pub paymaster_verification_gas_limit: Option<u128>,
+    #[serde(default, with = "alloy_serde::quantity::opt", skip_serializing_if = "Option::is_none")]
+    pub paymaster_post_op_gas_limit: Option<u128>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster_data: Option<Bytes>,
+    #[serde(default)]
+    pub signature: Bytes,
+}
+
+/// Represents the response of `eth_estimateUserOperationGas`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationGasEstimation {
+    #[serde(with = "alloy_serde::quantity")]
+    pub pre_verification_gas: u64,
+    #[serde(with = "alloy_serde::quantity")]
+    pub verification_gas_limit: u64,
+    #[serde(with = "alloy_serde::quantity")]
+    pub call_gas_limit: u64,
+    /// Only set if the operation has a paymaster
+    #[serde(default, with = "alloy_serde::quantity::opt", skip_serializing_if = "Option::is_none")]
+    pub paymaster_verification_gas_limit: Option<u64>,
+}
+
+/// Represents the response of `eth_getUserOperationReceipt`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationReceipt {
+    pub user_op_hash: B256,
+    pub entry_point: Address,
+    pub sender: Address,
+    pub nonce: U256,
+    /// The paymaster of the operation, or the zero address
+    pub paymaster: Address,
+    pub actual_gas_cost: U256,
+    pub actual_gas_used: U256,
+    pub success: bool,
+    /// The revert data of the execution of the operation, empty if it succeeded
+    pub reason: Bytes,
+    /// The logs emitted by the operation
+    pub logs: Vec<Log>,
+    /// The receipt of the `handleOps` transaction that included the operation
+    pub receipt: ReceiptResponse,
+}
+
+/// A block in the response of `eth_simulateV1`
+///
+/// Like [`SimulatedBlock`], but the errors of failed calls include their revert data.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SimulatedBlockResult<B> {
+    #[serde(flatten)]
+    pub inner: B,
+    pub calls: Vec<SimulatedCallResult>,
+}
+
+impl<B> From<SimulatedBlock<B>> for SimulatedBlockResult<B> {
+    fn from(block: SimulatedBlock<B>) -> Self {
+        Self { inner: block.inner, calls: block.calls.into_iter().map(Into::into).collect() }
+    }
+}
+
+/// The result of a call in the response of `eth_simulateV1`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCallResult {
+    pub return_data: Bytes,
+    #[serde(default)]
+    pub logs: Vec<Log>,
+    #[serde(with = "alloy_serde::quantity")]
+    pub gas_used: u64,
+    #[serde(with = "alloy_serde::quantity")]
+    pub status: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulatedCallError>,
+}
+
+impl From<SimCallResult> for SimulatedCallResult {
+    fn from(result: SimCallResult) -> Self {
+        let SimCallResult { return_data, logs, gas_used, status, error } = result;
+        let error = error.map(|err| SimulatedCallError {
+            code: err.code,
+            message: err.message,
+            data: None,
+        });
+        Self { return_data, logs, gas_used, status, error }
+    }
+}
+
+/// The error of a call that reverted, halted or failed validation in `eth_simulateV1`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulatedCallError {
+    pub code: i32,
+    pub message: String,
+    /// The revert data, if the call reverted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
+}
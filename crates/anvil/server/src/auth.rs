@@ -0,0 +1,32 @@
+//! JWT authentication as required by the Engine API.
+//!
+//! See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/authentication.md>
+
+use alloy_rpc_types_engine::JwtSecret;
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Rejects all requests that don't carry a valid JWT as `Authorization: Bearer <token>` header.
+pub async fn authorize(State(secret): State<JwtSecret>, request: Request, next: Next) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let Some(token) = token else {
+        warn!(target: "rpc::auth", "missing jwt");
+        return (StatusCode::UNAUTHORIZED, "missing jwt").into_response()
+    };
+
+    if let Err(err) = secret.validate(token) {
+        warn!(target: "rpc::auth", %err, "invalid jwt");
+        return (StatusCode::UNAUTHORIZED, err.to_string()).into_response()
+    }
+
+    next.run(request).await
+}
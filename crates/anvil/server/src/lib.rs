@@ -6,6 +6,7 @@
 #[macro_use]
 extern crate tracing;
 
+use alloy_rpc_types_engine::JwtSecret;
 use anvil_rpc::{
     error::RpcError,
     request::RpcMethodCall,
@@ -14,13 +15,19 @@
 use axum::{
     extract::DefaultBodyLimit,
     http::{header, HeaderValue, Method},
+    middleware,
     routing::{post, MethodRouter},
     Router,
 };
 use serde::de::DeserializeOwned;
-use std::fmt;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 
+mod auth;
+
 mod config;
 pub use config::ServerConfig;
 
@@ -52,6 +59,16 @@ pub fn http_router<Http>(config: ServerConfig, http: Http) -> Router
     router_inner(config, post(handler::handle), (http, ()))
 }
 
+/// Configures an [`axum::Router`] that handles JSON-RPC calls via HTTP, authenticated with the
+/// given [`JwtSecret`] like the Engine API.
+pub fn auth_router<Http>(config: ServerConfig, http: Http, secret: JwtSecret) -> Router
+where
+    Http: RpcHandler,
+{
+    router_inner(config, post(handler::handle), (http, ()))
+        .layer(middleware::from_fn_with_state(secret, auth::authorize))
+}
+
 fn router_inner<S: Clone + Send + Sync + 'static>(
     config: ServerConfig,
     root_method_router: MethodRouter<S>,
@@ -88,6 +105,11 @@ pub trait RpcHandler: Clone + Send + Sync + 'static {
     /// Invoked when the request was received
     async fn on_request(&self, request: Self::Request) -> ResponseResult;
 
+    /// Invoked after a request of the `method` was handled in `elapsed`
+    ///
+    /// This is not invoked for calls that couldn't be deserialized into a `Request`.
+    fn on_handled(&self, _method: &str, _elapsed: Duration, _response: &ResponseResult) {}
+
     /// Invoked for every incoming `RpcMethodCall`
     ///
     /// This will attempt to deserialize a `{ "method" : "<name>", "params": "<params>" }` message
@@ -108,7 +130,9 @@ async fn on_call(&self, call: RpcMethodCall) -> RpcResponse {
 
         match serde_json::from_value::<Self::Request>(call) {
             Ok(req) => {
+                let start = Instant::now();
                 let result = self.on_request(req).await;
+                self.on_handled(&method, start.elapsed(), &result);
                 RpcResponse::new(id, result)
             }
             Err(err) => {
@@ -16,6 +16,7 @@
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 /// The general purpose trait for handling RPC requests and subscriptions
@@ -30,6 +31,9 @@ pub trait PubSubRpcHandler: Clone + Send + Sync + Unpin + 'static {
 
     /// Invoked when the request was received
     async fn on_request(&self, request: Self::Request, cx: PubSubContext<Self>) -> ResponseResult;
+
+    /// Invoked after a request of the `method` was handled in `elapsed`
+    fn on_handled(&self, _method: &str, _elapsed: Duration, _response: &ResponseResult) {}
 }
 
 type Subscriptions<SubscriptionId, Subscription> = Arc<Mutex<Vec<(SubscriptionId, Subscription)>>>;
@@ -105,6 +109,10 @@ impl<Handler: PubSubRpcHandler> RpcHandler for ContextAwareHandler<Handler> {
     async fn on_request(&self, request: Self::Request) -> ResponseResult {
         self.handler.on_request(request, self.context.clone()).await
     }
+
+    fn on_handled(&self, method: &str, elapsed: Duration, response: &ResponseResult) {
+        self.handler.on_handled(method, elapsed, response)
+    }
 }
 
 /// Represents a connection to a client via websocket
@@ -0,0 +1,468 @@
+//! Custom precompiles and predeploys that emulate the behavior of a specific chain.
+//!
+//! These are loaded from a JSON file such as:
+//!
+//! ```json
+//! {
+//!   "precompiles": [
+//!     { "address": "0x0000000000000000000000000000000000000100", "bytecode": "0x60016000f3" },
+//!     { "address": "0x0000000000000000000000000000000000000101", "url": "http://localhost:8600" },
+//!     { "address": "0x0000000000000000000000000000000000000102", "command": ["./handler"] }
+//!   ],
+//!   "predeploys": {
+//!     "0x4200000000000000000000000000000000000015": { "balance": "0x0", "code": "0x6000" }
+//!   }
+//! }
+//! ```
+//!
+//! Precompiles backed by a `url` or a `command` are served by a handler that receives a
+//! `precompile_call` JSON-RPC request for every call, either over HTTP or as newline-delimited
+//! JSON over the stdin and stdout of the handler process.
+//!
+//! Precompiles backed by `bytecode` are shims: the bytecode is deployed at the address at genesis,
+//! like a predeploy. Unlike a real precompile, the account is cold until it's accessed (EIP-2929),
+//! and its code can be changed like the code of any other account, e.g. with `anvil_setCode`.
+
+use crate::{eth::util::get_precompiles_for, PrecompileFactory};
+use alloy_genesis::GenesisAccount;
+use alloy_primitives::{map::HashSet, Address, Bytes, U256, U64};
+use alloy_provider::Provider;
+use alloy_transport::TransportError;
+use anvil_rpc::{
+    error::RpcError,
+    request::{Id, RequestParams, RpcMethodCall, Version},
+};
+use eyre::{Context, Result};
+use foundry_common::provider::ProviderBuilder;
+use foundry_evm::revm::primitives::{
+    Env, Precompile, PrecompileError, PrecompileErrors, PrecompileOutput, PrecompileResult, SpecId,
+    StatefulPrecompile,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    sync::{mpsc, Arc},
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+/// The JSON-RPC method that is sent to precompile handlers
+pub const PRECOMPILE_CALL_METHOD: &str = "precompile_call";
+
+/// Custom precompiles and genesis predeploys of a chain
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainOverrides {
+    /// Precompiles to register in addition to the default ones
+    #[serde(default)]
+    pub precompiles: Vec<PrecompileConfig>,
+    /// Accounts to create at genesis, in the `alloc` format of `genesis.json`
+    #[serde(default)]
+    pub predeploys: BTreeMap<Address, GenesisAccount>,
+}
+
+/// A custom precompile at a given address
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrecompileConfig {
+    pub address: Address,
+    #[serde(flatten)]
+    pub kind: PrecompileKind,
+}
+
+/// How a custom precompile is implemented
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PrecompileKind {
+    /// EVM bytecode that is deployed at the address at genesis, see the [module docs](self)
+    #[serde(rename = "bytecode")]
+    Predeploy(Bytes),
+    /// The URL of a JSON-RPC endpoint that handles the calls
+    Url(String),
+    /// The program and arguments of a process that handles the calls over stdin and stdout
+    Command(Vec<String>),
+}
+
+impl ChainOverrides {
+    /// Loads the overrides from the JSON file at the given path
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let overrides: Self = foundry_common::fs::read_json_file(path)?;
+        overrides
+            .validate()
+            .wrap_err_with(|| format!("invalid chain overrides {}", path.display()))?;
+        Ok(overrides)
+    }
+
+    pub(crate) fn parse(path: &str) -> Result<Self, String> {
+        Self::load(path).map_err(|err| format!("{err:#}"))
+    }
+
+    fn validate(&self) -> Result<()> {
+        let builtin = get_precompiles_for(SpecId::LATEST);
+        let mut addresses: HashSet<Address> = HashSet::default();
+        for PrecompileConfig { address, kind } in &self.precompiles {
+            if !addresses.insert(*address) {
+                eyre::bail!("multiple precompiles are registered at {address}");
+            }
+            match kind {
+                PrecompileKind::Predeploy(_) if builtin.contains(address) => {
+                    eyre::bail!(
+                        "bytecode precompile at {address} is shadowed by a builtin precompile"
+                    )
+                }
+                PrecompileKind::Predeploy(_) if self.predeploys.contains_key(address) => {
+                    eyre::bail!("bytecode precompile at {address} conflicts with a predeploy")
+                }
+                PrecompileKind::Command(command) if command.is_empty() => {
+                    eyre::bail!("precompile at {address} has an empty command")
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns all accounts to create at genesis, including the predeploys of bytecode precompiles
+    pub fn genesis_alloc(&self) -> BTreeMap<Address, GenesisAccount> {
+        let mut alloc = self.predeploys.clone();
+        for precompile in &self.precompiles {
+            if let PrecompileKind::Predeploy(code) = &precompile.kind {
+                alloc.insert(
+                    precompile.address,
+                    GenesisAccount { code: Some(code.clone()), ..Default::default() },
+                );
+            }
+        }
+        alloc
+    }
+
+    /// Starts the handlers of all precompiles that are served by a handler.
+    ///
+    /// The returned factory also includes the precompiles of `inner`, it is `None` if there are no
+    /// handler precompiles.
+    pub(crate) fn precompile_factory(
+        &self,
+        inner: Option<Arc<dyn PrecompileFactory>>,
+    ) -> Result<Option<HandlerPrecompiles>> {
+        let handlers = self
+            .precompiles
+            .iter()
+            .filter_map(|PrecompileConfig { address, kind }| {
+                let transport = match kind {
+                    PrecompileKind::Predeploy(_) => return None,
+                    PrecompileKind::Url(url) => HandlerTransport::http(url),
+                    PrecompileKind::Command(command) => HandlerTransport::process(command),
+                };
+                Some(transport.map(|transport| {
+                    (*address, Arc::new(PrecompileHandler::new(*address, transport)))
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if handlers.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(HandlerPrecompiles { handlers, inner }))
+    }
+}
+
+/// Serves the precompiles of [`ChainOverrides`] that are implemented by a handler, in addition to
+/// the precompiles of another factory.
+#[derive(Debug)]
+pub struct HandlerPrecompiles {
+    handlers: Vec<(Address, Arc<PrecompileHandler>)>,
+    inner: Option<Arc<dyn PrecompileFactory>>,
+}
+
+impl PrecompileFactory for HandlerPrecompiles {
+    fn precompiles(&self) -> Vec<(Address, Precompile)> {
+        let mut precompiles =
+            self.inner.as_ref().map(|inner| inner.precompiles()).unwrap_or_default();
+        precompiles.extend(
+            self.handlers
+                .iter()
+                .map(|(address, handler)| (*address, Precompile::Stateful(handler.clone()))),
+        );
+        precompiles
+    }
+}
+
+/// The params of a `precompile_call` request
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrecompileCall {
+    /// The address of the called precompile
+    pub address: Address,
+    pub input: Bytes,
+    pub gas_limit: U64,
+    /// The sender of the transaction
+    pub origin: Address,
+    pub block_number: U256,
+    pub timestamp: U256,
+    pub chain_id: U64,
+}
+
+/// The result of a `precompile_call` request
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrecompileCallOutput {
+    #[serde(default)]
+    pub output: Bytes,
+    #[serde(default)]
+    pub gas_used: U64,
+}
+
+/// A precompile that forwards all calls to a handler
+#[derive(Debug)]
+pub struct PrecompileHandler {
+    address: Address,
+    transport: HandlerTransport,
+}
+
+impl PrecompileHandler {
+    fn new(address: Address, transport: HandlerTransport) -> Self {
+        Self { address, transport }
+    }
+}
+
+impl StatefulPrecompile for PrecompileHandler {
+    fn call(&self, bytes: &Bytes, gas_limit: u64, env: &Env) -> PrecompileResult {
+        let call = PrecompileCall {
+            address: self.address,
+            input: bytes.clone(),
+            gas_limit: U64::from(gas_limit),
+            origin: env.tx.caller,
+            block_number: env.block.number,
+            timestamp: env.block.timestamp,
+            chain_id: U64::from(env.cfg.chain_id),
+        };
+        let PrecompileCallOutput { output, gas_used } = self.transport.call(call)?;
+        let gas_used = gas_used.saturating_to::<u64>();
+        if gas_used > gas_limit {
+            return Err(PrecompileError::OutOfGas.into());
+        }
+        Ok(PrecompileOutput::new(gas_used, output))
+    }
+}
+
+/// Errors of a precompile handler
+#[derive(Debug)]
+enum HandlerError {
+    /// The handler responded with an error, this fails the call to the precompile
+    Rejected(String),
+    /// The handler could not be reached, this aborts the transaction
+    Unavailable(String),
+}
+
+impl From<HandlerError> for PrecompileErrors {
+    fn from(err: HandlerError) -> Self {
+        match err {
+            HandlerError::Rejected(msg) => Self::Error(PrecompileError::Other(msg)),
+            HandlerError::Unavailable(msg) => {
+                Self::Fatal { msg: format!("precompile handler is unavailable: {msg}") }
+            }
+        }
+    }
+}
+
+type HandlerRequest =
+    (PrecompileCall, mpsc::SyncSender<Result<PrecompileCallOutput, HandlerError>>);
+
+#[derive(Debug)]
+enum HandlerTransport {
+    /// Requests are sent to the task of a JSON-RPC endpoint
+    Http(UnboundedSender<HandlerRequest>),
+    /// Requests are written to a child process
+    Process(Mutex<HandlerProcess>),
+}
+
+impl HandlerTransport {
+    /// Spawns a thread that sends the requests to the JSON-RPC endpoint at `url`.
+    ///
+    /// Precompiles are executed synchronously, so the requests are sent from a dedicated runtime
+    /// similar to the backend of a forked database.
+    fn http(url: &str) -> Result<Self> {
+        let provider = ProviderBuilder::new(url).build()?;
+        let (tx, mut rx) = unbounded_channel::<HandlerRequest>();
+        std::thread::Builder::new().name("precompile-handler".to_string()).spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("failed to create precompile handler runtime");
+            rt.block_on(async move {
+                while let Some((call, response)) = rx.recv().await {
+                    let result = provider
+                        .raw_request(PRECOMPILE_CALL_METHOD.into(), (call,))
+                        .await
+                        .map_err(|err| match err {
+                            TransportError::ErrorResp(payload) => {
+                                HandlerError::Rejected(payload.message.to_string())
+                            }
+                            err => HandlerError::Unavailable(err.to_string()),
+                        });
+                    let _ = response.send(result);
+                }
+            });
+        })?;
+        Ok(Self::Http(tx))
+    }
+
+    /// Spawns the handler process
+    fn process(command: &[String]) -> Result<Self> {
+        let mut child = Command::new(&command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .wrap_err_with(|| format!("failed to spawn precompile handler `{}`", command[0]))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        Ok(Self::Process(Mutex::new(HandlerProcess { child, stdin, stdout, next_id: 0 })))
+    }
+
+    fn call(&self, call: PrecompileCall) -> Result<PrecompileCallOutput, HandlerError> {
+        match self {
+            Self::Http(tx) => {
+                let (response_tx, response_rx) = mpsc::sync_channel(1);
+                tx.send((call, response_tx))
+                    .map_err(|_| HandlerError::Unavailable("handler stopped".to_string()))?;
+                response_rx
+                    .recv()
+                    .map_err(|_| HandlerError::Unavailable("handler stopped".to_string()))?
+            }
+            Self::Process(process) => process.lock().call(call),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct HandlerProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: i64,
+}
+
+/// A JSON-RPC response of a handler process
+#[derive(Deserialize)]
+struct HandlerResponse {
+    #[serde(default)]
+    result: Option<PrecompileCallOutput>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+impl HandlerProcess {
+    /// Writes the request as a single line and reads a single line as the response
+    fn call(&mut self, call: PrecompileCall) -> Result<PrecompileCallOutput, HandlerError> {
+        let unavailable = |err: &dyn std::fmt::Display| HandlerError::Unavailable(err.to_string());
+        let request = RpcMethodCall {
+            jsonrpc: Version::V2,
+            method: PRECOMPILE_CALL_METHOD.to_string(),
+            params: RequestParams::Array(vec![
+                serde_json::to_value(call).map_err(|err| unavailable(&err))?
+            ]),
+            id: Id::Number(self.next_id),
+        };
+        self.next_id += 1;
+        let request = serde_json::to_string(&request).map_err(|err| unavailable(&err))?;
+        writeln!(self.stdin, "{request}").map_err(|err| unavailable(&err))?;
+        self.stdin.flush().map_err(|err| unavailable(&err))?;
+
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line).map_err(|err| unavailable(&err))? == 0 {
+            return Err(HandlerError::Unavailable("handler process exited".to_string()));
+        }
+        let response: HandlerResponse =
+            serde_json::from_str(&line).map_err(|err| unavailable(&err))?;
+        match (response.result, response.error) {
+            (_, Some(err)) => Err(HandlerError::Rejected(err.message.to_string())),
+            (Some(output), None) => Ok(output),
+            (None, None) => Err(HandlerError::Unavailable("empty response".to_string())),
+        }
+    }
+}
+
+impl Drop for HandlerProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_chain_overrides() {
+        let s = r#"{
+            "precompiles": [
+                { "address": "0x0000000000000000000000000000000000000100", "bytecode": "0x60016000f3" },
+                { "address": "0x0000000000000000000000000000000000000101", "url": "http://localhost:8600" },
+                { "address": "0x0000000000000000000000000000000000000102", "command": ["./handler", "--fast"] }
+            ],
+            "predeploys": {
+                "0x4200000000000000000000000000000000000015": { "balance": "0x1", "code": "0x6000" }
+            }
+        }"#;
+        let overrides: ChainOverrides = serde_json::from_str(s).unwrap();
+        overrides.validate().unwrap();
+        assert_eq!(
+            overrides.precompiles[2].kind,
+            PrecompileKind::Command(vec!["./handler".to_string(), "--fast".to_string()])
+        );
+
+        let alloc = overrides.genesis_alloc();
+        assert_eq!(alloc.len(), 2);
+        assert_eq!(
+            alloc[&overrides.precompiles[0].address].code,
+            Some(Bytes::from_static(&[0x60, 0x01, 0x60, 0x00, 0xf3]))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_chain_overrides() {
+        let bytecode_at = |address: u8| PrecompileConfig {
+            address: Address::with_last_byte(address),
+            kind: PrecompileKind::Predeploy(Bytes::new()),
+        };
+
+        let overrides =
+            ChainOverrides { precompiles: vec![bytecode_at(1)], predeploys: Default::default() };
+        assert!(overrides.validate().is_err());
+
+        let overrides = ChainOverrides {
+            precompiles: vec![bytecode_at(0xff), bytecode_at(0xff)],
+            predeploys: Default::default(),
+        };
+        assert!(overrides.validate().is_err());
+
+        let overrides = ChainOverrides {
+            precompiles: vec![bytecode_at(0xff)],
+            predeploys: [(Address::with_last_byte(0xff), Default::default())].into(),
+        };
+        assert!(overrides.validate().is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn can_call_process_handler() {
+        let response = r#"{"jsonrpc":"2.0","id":0,"result":{"output":"0x2a","gasUsed":"0x64"}}"#;
+        let command = [
+            "sh".to_string(),
+            "-c".to_string(),
+            format!("while read -r _; do echo '{response}'; done"),
+        ];
+        let handler =
+            PrecompileHandler::new(Address::ZERO, HandlerTransport::process(&command).unwrap());
+
+        let output = handler.call(&Bytes::new(), 1000, &Env::default()).unwrap();
+        assert_eq!(output, PrecompileOutput::new(100, Bytes::from_static(&[0x2a])));
+
+        let err = handler.call(&Bytes::new(), 50, &Env::default()).unwrap_err();
+        assert_eq!(err, PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+}
@@ -1,22 +1,32 @@
 use crate::{
-    config::{ForkChoice, DEFAULT_MNEMONIC},
-    eth::{backend::db::SerializableState, pool::transactions::TransactionOrder, EthApi},
+    config::{write_jwt_secret, ForkChoice, DEFAULT_MNEMONIC},
+    eth::{
+        backend::db::SerializableState,
+        bundler::{BundlerConfig, EntryPointPredeploy},
+        pool::{transactions::TransactionOrder, PoolConfig},
+        EthApi,
+    },
     hardfork::OptimismHardfork,
-    AccountGenerator, EthereumHardfork, NodeConfig, CHAIN_ID,
+    multichain::ChainsConfig,
+    op_bridge::OpBridgeConfig,
+    server::session::replay_session,
+    try_spawn_chains, try_spawn_network, try_spawn_op_bridge, AccountGenerator, ChainOverrides,
+    EthereumHardfork, NodeConfig, CHAIN_ID,
 };
 use alloy_genesis::Genesis;
-use alloy_primitives::{utils::Unit, B256, U256};
+use alloy_primitives::{utils::Unit, Address, B256, U256};
+use alloy_rpc_types::engine::JwtSecret;
 use alloy_signer_local::coins_bip39::{English, Mnemonic};
 use anvil_server::ServerConfig;
 use clap::Parser;
 use core::fmt;
-use foundry_common::shell;
+use foundry_common::{provider::cassette::CassetteConfig, shell};
 use foundry_config::{Chain, Config, FigmentProviders};
 use futures::FutureExt;
 use rand::{rngs::StdRng, SeedableRng};
 use std::{
     future::Future,
-    net::IpAddr,
+    net::{IpAddr, SocketAddr},
     path::{Path, PathBuf},
     pin::Pin,
     str::FromStr,
@@ -190,9 +200,147 @@ pub struct NodeArgs {
     #[command(flatten)]
     pub server_config: ServerConfig,
 
+    #[command(flatten)]
+    pub txpool: TxPoolArgs,
+
+    #[command(flatten)]
+    pub bundler: BundlerArgs,
+
     /// Path to the cache directory where states are stored.    
     #[arg(long, value_name = "PATH")]
     pub cache_path: Option<PathBuf>,
+
+    /// Persist blocks, receipts and state to an on-disk database at the given path.
+    ///
+    /// If the database already contains a chain, the node continues from it on startup.
+    /// Changes are written incrementally after every block.
+    #[arg(long, value_name = "PATH", conflicts_with_all = &["state", "load_state"])]
+    pub chain_db: Option<PathBuf>,
+
+    /// Record every handled JSON-RPC call, with its response, timing, and the block number and
+    /// timestamp of the node, to the given file.
+    ///
+    /// See --replay.
+    #[arg(long, value_name = "PATH", conflicts_with = "replay")]
+    pub record_session: Option<PathBuf>,
+
+    /// Replay a session recorded with --record-session against a fresh node, and report the first
+    /// call whose response diverges.
+    ///
+    /// The node must be launched with the options the session was recorded with, e.g. the same
+    /// --timestamp and --state, for the replay to be deterministic.
+    #[arg(long, value_name = "PATH")]
+    pub replay: Option<PathBuf>,
+
+    /// Serve several chains, forked or fresh, from the given JSON file.
+    ///
+    /// Every chain is served at `/<chain id>` and optionally on its own port. The root serves a
+    /// control endpoint that applies `evm_increaseTime`, `evm_setTime`,
+    /// `evm_setNextBlockTimestamp`, `evm_mine` and `anvil_mine` to all chains together, so
+    /// they share a consistent clock.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_parser = ChainsConfig::parse,
+        conflicts_with_all = &[
+            "fork_url",
+            "chain_id",
+            "state",
+            "dump_state",
+            "load_state",
+            "chain_db",
+            "ipc",
+            "engine",
+            "metrics_port",
+            "record_session",
+            "replay",
+        ]
+    )]
+    pub chains: Option<ChainsConfig>,
+
+    /// Serve an L1 and an OP stack L2 chain from the given JSON file, and bridge them.
+    ///
+    /// Deposits of the `OptimismPortal` on L1 are relayed to L2 as deposit transactions, and
+    /// `anvil_getWithdrawalProofs` on L2 returns the proofs of withdrawals for the portal.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_parser = OpBridgeConfig::parse,
+        conflicts_with_all = &[
+            "fork_url",
+            "chain_id",
+            "state",
+            "dump_state",
+            "load_state",
+            "chain_db",
+            "ipc",
+            "engine",
+            "metrics_port",
+            "record_session",
+            "replay",
+            "chains",
+        ]
+    )]
+    pub op_bridge: Option<OpBridgeConfig>,
+
+    /// Start a network of the given number of nodes that gossip transactions and blocks.
+    ///
+    /// Every node is served at `/<index>` of the port, which also serves a control endpoint that
+    /// partitions the network with `anvil_partitionNetwork` and heals it with `anvil_healNetwork`.
+    #[arg(
+        long,
+        value_name = "NUM",
+        value_parser = clap::value_parser!(u16).range(2..),
+        conflicts_with_all = &[
+            "fork_url",
+            "state",
+            "dump_state",
+            "load_state",
+            "chain_db",
+            "ipc",
+            "engine",
+            "metrics_port",
+            "record_session",
+            "replay",
+            "chains",
+            "op_bridge",
+        ]
+    )]
+    pub nodes: Option<u16>,
+
+    /// Serve the Engine API and let it drive block production.
+    ///
+    /// Blocks are only built and made canonical via `engine_*` calls on the authenticated
+    /// endpoint, like an execution client driven by a consensus client.
+    #[arg(long, conflicts_with_all = &["block_time", "no_mining"])]
+    pub engine: bool,
+
+    /// Port number the authenticated Engine API server listens on.
+    #[arg(
+        long = "authrpc.port",
+        default_value = "8551",
+        value_name = "NUM",
+        requires = "engine",
+        help_heading = "Server options"
+    )]
+    pub auth_port: u16,
+
+    /// Path to the hex encoded secret the Engine API requests are authenticated with.
+    ///
+    /// A random secret is created and written to the path if the file doesn't exist.
+    /// If not set, a random secret is written to a file in anvil's temporary directory, whose
+    /// path is printed on startup.
+    #[arg(
+        long = "authrpc.jwtsecret",
+        value_name = "PATH",
+        requires = "engine",
+        help_heading = "Server options"
+    )]
+    pub jwt_secret: Option<PathBuf>,
+
+    /// Serve Prometheus metrics at `/metrics` on the given port.
+    #[arg(long = "metrics.port", value_name = "NUM", help_heading = "Server options")]
+    pub metrics_port: Option<u16>,
 }
 
 #[cfg(windows)]
@@ -223,6 +371,16 @@ pub fn into_node_config(self) -> eyre::Result<NodeConfig> {
             None => None,
         };
 
+        let jwt_secret = match &self.jwt_secret {
+            Some(path) if path.exists() => Some(JwtSecret::from_file(path)?),
+            Some(path) => {
+                let secret = JwtSecret::random();
+                write_jwt_secret(path, &secret)?;
+                Some(secret)
+            }
+            None => None,
+        };
+
         Ok(NodeConfig::default()
             .with_gas_limit(self.evm.gas_limit)
             .disable_block_gas_limit(self.evm.disable_block_gas_limit)
@@ -246,6 +404,12 @@ pub fn into_node_config(self) -> eyre::Result<NodeConfig> {
             .fork_request_retries(self.evm.fork_request_retries)
             .fork_retry_backoff(self.evm.fork_retry_backoff.map(Duration::from_millis))
             .fork_compute_units_per_second(compute_units_per_second)
+            .with_fork_cassette(
+                self.evm
+                    .fork_record
+                    .map(CassetteConfig::record)
+                    .or_else(|| self.evm.fork_replay.map(CassetteConfig::replay)),
+            )
             .with_eth_rpc_url(self.evm.fork_url.map(|fork| fork.url))
             .with_base_fee(self.evm.block_base_fee_per_gas)
             .disable_min_priority_fee(self.evm.disable_min_priority_fee)
@@ -256,6 +420,7 @@ pub fn into_node_config(self) -> eyre::Result<NodeConfig> {
             .set_config_out(self.config_out)
             .with_chain_id(self.evm.chain_id)
             .with_transaction_order(self.order)
+            .with_pool_config(self.txpool.into_pool_config())
             .with_genesis(self.init)
             .with_steps_tracing(self.evm.steps_tracing)
             .with_print_logs(!self.evm.disable_console_log)
@@ -269,10 +434,18 @@ pub fn into_node_config(self) -> eyre::Result<NodeConfig> {
             .with_max_persisted_states(self.max_persisted_states)
             .with_optimism(self.evm.optimism)
             .with_odyssey(self.evm.odyssey)
+            .with_chain_overrides(self.evm.chain_overrides)
             .with_disable_default_create2_deployer(self.evm.disable_default_create2_deployer)
             .with_slots_in_an_epoch(self.slots_in_an_epoch)
             .with_memory_limit(self.evm.memory_limit)
-            .with_cache_path(self.cache_path))
+            .with_cache_path(self.cache_path)
+            .with_chain_db(self.chain_db)
+            .with_engine_api(self.engine)
+            .with_auth_port(self.auth_port)
+            .with_jwt_secret(jwt_secret)
+            .with_metrics_port(self.metrics_port)
+            .with_record_session(self.record_session)
+            .with_bundler(self.bundler.into_bundler_config()))
     }
 
     fn account_generator(&self) -> AccountGenerator {
@@ -308,6 +481,19 @@ fn dump_state_path(&self) -> Option<PathBuf> {
     ///
     /// See also [crate::spawn()]
     pub async fn run(self) -> eyre::Result<()> {
+        if let Some(session) = self.replay.clone() {
+            return self.replay(&session).await
+        }
+        if let Some(chains) = self.chains.clone() {
+            return self.run_chains(chains).await
+        }
+        if let Some(bridge) = self.op_bridge.clone() {
+            return self.run_op_bridge(bridge).await
+        }
+        if let Some(nodes) = self.nodes {
+            return self.run_network(nodes).await
+        }
+
         let dump_state = self.dump_state_path();
         let dump_interval =
             self.state_interval.map(Duration::from_secs).unwrap_or(DEFAULT_DUMP_INTERVAL);
@@ -386,6 +572,102 @@ pub async fn run(self) -> eyre::Result<()> {
 
         Ok(handle.await??)
     }
+
+    /// Replays the session against a fresh node, that doesn't serve any other requests
+    async fn replay(self, session: &Path) -> eyre::Result<()> {
+        // blocks are mined by the replay, see [replay_session]
+        let config = self
+            .into_node_config()?
+            .with_blocktime(None::<Duration>)
+            .with_no_mining(true)
+            .with_port(0)
+            .set_silent(true);
+        let (api, _handle) = crate::try_spawn(config).await?;
+
+        let report = replay_session(&api, session).await?;
+        if let Some(divergence) = report.divergence {
+            eyre::bail!("session diverged after {} calls\n{divergence}", report.replayed);
+        }
+        sh_println!("Replayed {} calls without divergence", report.replayed)?;
+        Ok(())
+    }
+
+    /// Serves all chains until the process is stopped
+    async fn run_chains(self, chains: ChainsConfig) -> eyre::Result<()> {
+        let config = self.into_node_config()?;
+        let configs = chains.chains.iter().map(|chain| chain.node_config(config.clone()));
+        let chains = try_spawn_chains(configs.map(|config| config.set_silent(true))).await?;
+
+        let addr = SocketAddr::new(config.host[0], config.port);
+        let tcp_listener = tokio::net::TcpListener::bind(addr).await?;
+        let addr = tcp_listener.local_addr()?;
+        if !config.silent {
+            for chain_id in chains.chain_ids() {
+                let handle = chains.handle(chain_id).expect("exists; see above");
+                sh_println!(
+                    "Chain {chain_id} listening on http://{addr}/{chain_id}, {}",
+                    handle.socket_address()
+                )?;
+            }
+            sh_println!("Control endpoint listening on http://{addr}")?;
+        }
+
+        tokio::select! {
+            res = chains.serve_on(tcp_listener, config.server_config) => res?,
+            _ = tokio::signal::ctrl_c() => {}
+        }
+        Ok(())
+    }
+
+    /// Serves all nodes of the network until the process is stopped
+    async fn run_network(self, nodes: u16) -> eyre::Result<()> {
+        let config = self.into_node_config()?;
+        let configs = (0..nodes).map(|_| config.clone().with_port(0).set_silent(true));
+        let network = try_spawn_network(configs).await?;
+
+        let addr = SocketAddr::new(config.host[0], config.port);
+        let tcp_listener = tokio::net::TcpListener::bind(addr).await?;
+        let addr = tcp_listener.local_addr()?;
+        if !config.silent {
+            for node in 0..network.len() {
+                sh_println!(
+                    "Node {node} listening on http://{addr}/{node}, {}",
+                    network.handle(node).socket_address()
+                )?;
+            }
+            sh_println!("Control endpoint listening on http://{addr}")?;
+        }
+
+        tokio::select! {
+            res = network.serve_on(tcp_listener, config.server_config) => res?,
+            _ = tokio::signal::ctrl_c() => {}
+        }
+        Ok(())
+    }
+
+    /// Serves the L1 and the L2 chain of the bridge until the process is stopped
+    async fn run_op_bridge(self, bridge: OpBridgeConfig) -> eyre::Result<()> {
+        let config = self.into_node_config()?;
+        let l1 = bridge.l1.node_config(config.clone()).set_silent(true);
+        let l2 = bridge.l2.node_config(config.clone()).set_silent(true);
+        let bridge = try_spawn_op_bridge(l1, l2, bridge.portal).await?;
+        if !config.silent {
+            for (layer, api, handle) in [
+                ("L1", bridge.l1_api(), bridge.l1_handle()),
+                ("L2", bridge.l2_api(), bridge.l2_handle()),
+            ] {
+                sh_println!(
+                    "{layer} chain {} listening on {}",
+                    api.chain_id(),
+                    handle.socket_address()
+                )?;
+            }
+            sh_println!("Relaying deposits of the portal {}", bridge.portal())?;
+        }
+
+        tokio::signal::ctrl_c().await?;
+        Ok(())
+    }
 }
 
 /// Anvil's EVM related arguments.
@@ -451,6 +733,25 @@ pub struct AnvilEvmArgs {
     #[arg(long, requires = "fork_url", value_name = "BACKOFF", help_heading = "Fork config")]
     pub fork_retry_backoff: Option<u64>,
 
+    /// Records all requests to the fork endpoint and their responses to a cassette file.
+    ///
+    /// See --fork-url and --fork-replay.
+    #[arg(
+        long,
+        requires = "fork_url",
+        value_name = "PATH",
+        help_heading = "Fork config",
+        conflicts_with = "fork_replay"
+    )]
+    pub fork_record: Option<PathBuf>,
+
+    /// Serves all requests to the fork endpoint from a cassette file written with --fork-record,
+    /// without any network access.
+    ///
+    /// Requests that aren't recorded in the cassette fail. See --fork-url.
+    #[arg(long, requires = "fork_url", value_name = "PATH", help_heading = "Fork config")]
+    pub fork_replay: Option<PathBuf>,
+
     /// Specify chain id to skip fetching it from remote endpoint. This enables offline-start mode.
     ///
     /// You still must pass both `--fork-url` and `--fork-block-number`, and already have your
@@ -579,6 +880,13 @@ pub struct AnvilEvmArgs {
     /// Enable Odyssey features
     #[arg(long, alias = "alphanet")]
     pub odyssey: bool,
+
+    /// Register the custom precompiles and predeploys of a chain from the given JSON file.
+    ///
+    /// Precompiles can be implemented by a JSON-RPC endpoint or a process that handles
+    /// `precompile_call` requests, or emulated by EVM bytecode that is deployed at genesis.
+    #[arg(long, value_name = "PATH", value_parser = ChainOverrides::parse)]
+    pub chain_overrides: Option<ChainOverrides>,
 }
 
 /// Resolves an alias passed as fork-url to the matching url defined in the rpc_endpoints section
@@ -596,6 +904,101 @@ pub fn resolve_rpc_alias(&mut self) {
     }
 }
 
+/// Anvil's transaction pool related arguments.
+#[derive(Clone, Debug, Default, Parser)]
+#[command(next_help_heading = "Transaction pool options")]
+pub struct TxPoolArgs {
+    /// Minimum fee bump in percent required to replace a transaction with the same nonce.
+    ///
+    /// By default any increase is enough, geth requires 10 percent.
+    #[arg(long = "txpool.pricebump", value_name = "PERCENT", default_value_t = 0)]
+    pub price_bump: u64,
+
+    /// Maximum number of executable transactions per account.
+    #[arg(long = "txpool.accountslots", value_name = "NUM")]
+    pub account_slots: Option<usize>,
+
+    /// Maximum number of executable transactions across all accounts.
+    #[arg(long = "txpool.globalslots", value_name = "NUM")]
+    pub global_slots: Option<usize>,
+
+    /// Maximum number of non-executable transactions per account.
+    #[arg(long = "txpool.accountqueue", value_name = "NUM")]
+    pub account_queue: Option<usize>,
+
+    /// Maximum number of non-executable transactions across all accounts.
+    #[arg(long = "txpool.globalqueue", value_name = "NUM")]
+    pub global_queue: Option<usize>,
+
+    /// Maximum number of seconds non-executable transactions are queued.
+    #[arg(long = "txpool.lifetime", value_name = "SECONDS")]
+    pub lifetime: Option<u64>,
+}
+
+impl TxPoolArgs {
+    /// Returns the [PoolConfig] for these arguments
+    pub fn into_pool_config(self) -> PoolConfig {
+        PoolConfig {
+            price_bump: self.price_bump,
+            account_slots: self.account_slots,
+            global_slots: self.global_slots,
+            account_queue: self.account_queue,
+            global_queue: self.global_queue,
+            lifetime: self.lifetime.map(Duration::from_secs),
+        }
+    }
+}
+
+/// Anvil's ERC-4337 bundler related arguments.
+#[derive(Clone, Debug, Default, Parser)]
+#[command(next_help_heading = "Bundler options")]
+pub struct BundlerArgs {
+    /// Accept ERC-4337 user operations via `eth_sendUserOperation`.
+    ///
+    /// Every user operation is included in its own `handleOps` transaction.
+    #[arg(long = "bundler", id = "bundler")]
+    pub enabled: bool,
+
+    /// The EntryPoint v0.7 to accept user operations for.
+    #[arg(
+        long = "bundler.entrypoint",
+        value_name = "ADDRESS",
+        requires = "bundler",
+        default_value = "0x0000000071727De22E5E9d8BAf0edAc6f37da032"
+    )]
+    pub entry_point: Address,
+
+    /// The account that receives the fees of the `handleOps` transactions.
+    ///
+    /// The transactions are always sent by the last dev account, which is also the default
+    /// beneficiary.
+    #[arg(long = "bundler.beneficiary", value_name = "ADDRESS", requires = "bundler")]
+    pub beneficiary: Option<Address>,
+
+    /// Predeploy the EntryPoint at genesis from a JSON file with the runtime code of the
+    /// EntryPoint and its `SenderCreator`.
+    ///
+    /// The file has the format `{ "entryPoint": "0x...", "senderCreator": "0x..." }`.
+    #[arg(
+        long = "bundler.predeploy",
+        value_name = "PATH",
+        requires = "bundler",
+        value_parser = EntryPointPredeploy::parse
+    )]
+    pub predeploy: Option<EntryPointPredeploy>,
+}
+
+impl BundlerArgs {
+    /// Returns the [BundlerConfig] for these arguments, if the bundler is enabled
+    pub fn into_bundler_config(self) -> Option<BundlerConfig> {
+        self.enabled.then(|| BundlerConfig {
+            entry_point: Some(self.entry_point),
+            beneficiary: self.beneficiary,
+            predeploy: self.predeploy,
+        })
+    }
+}
+
 /// Helper type to periodically dump the state of the chain to disk
 struct PeriodicStateDumper {
     in_progress_dump: Option<Pin<Box<dyn Future<Output = ()> + Send + Sync + 'static>>>,
@@ -771,7 +1174,7 @@ fn duration_from_secs_f64(s: &str) -> Result<Duration, String> {
 
 #[cfg(test)]
 mod tests {
-    use crate::EthereumHardfork;
+    use crate::{eth::bundler::ENTRY_POINT_V07, EthereumHardfork};
 
     use super::*;
     use std::{env, net::Ipv4Addr};
@@ -842,6 +1245,124 @@ fn can_parse_fork_headers() {
         assert_eq!(args.evm.fork_headers, vec!["User-Agent: test-agent", "Referrer: example.com"]);
     }
 
+    #[test]
+    fn can_parse_fork_cassette() {
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--fork-url",
+            "http://localhost:8545",
+            "--fork-replay",
+            "fork.jsonl",
+        ]);
+        let config = args.into_node_config().unwrap();
+        assert_eq!(config.fork_cassette, Some(CassetteConfig::replay("fork.jsonl")));
+
+        let args = NodeArgs::try_parse_from([
+            "anvil",
+            "--fork-url",
+            "http://localhost:8545",
+            "--fork-record",
+            "fork.jsonl",
+            "--fork-replay",
+            "fork.jsonl",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_session_config() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--record-session", "session.jsonl"]);
+        let config = args.into_node_config().unwrap();
+        assert_eq!(config.record_session, Some(PathBuf::from("session.jsonl")));
+
+        let args = NodeArgs::try_parse_from([
+            "anvil",
+            "--record-session",
+            "session.jsonl",
+            "--replay",
+            "session.jsonl",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_chains_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chains.json");
+        std::fs::write(&path, r#"{ "chains": [{ "chainId": 1 }, { "chainId": 10 }] }"#).unwrap();
+        let path = path.to_str().unwrap();
+
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--chains", path]);
+        assert_eq!(args.chains.unwrap().chains.len(), 2);
+
+        let args = NodeArgs::try_parse_from(["anvil", "--chains", path, "--chain-id", "1"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_op_bridge_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bridge.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "portal": "0xbEb5Fc579115071764c7423A4f12eDde41f106Ed",
+                "l1": { "chainId": 1 },
+                "l2": { "chainId": 10 }
+            }"#,
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--op-bridge", path]);
+        assert_eq!(args.op_bridge.unwrap().l2.chain_id, 10);
+
+        let args = NodeArgs::try_parse_from(["anvil", "--op-bridge", path, "--fork-url", "x"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_nodes() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--nodes", "3"]);
+        assert_eq!(args.nodes, Some(3));
+
+        let args = NodeArgs::try_parse_from(["anvil", "--nodes", "1"]);
+        assert!(args.is_err());
+
+        let args = NodeArgs::try_parse_from(["anvil", "--nodes", "2", "--fork-url", "x"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_bundler_config() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil"]);
+        assert_eq!(args.bundler.into_bundler_config(), None);
+
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--bundler"]);
+        let config = args.into_node_config().unwrap().bundler.unwrap();
+        assert_eq!(config.entry_point(), ENTRY_POINT_V07);
+        assert_eq!(config.beneficiary, None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("entrypoint.json");
+        std::fs::write(&path, r#"{ "entryPoint": "0x6001", "senderCreator": "0x6002" }"#).unwrap();
+        let path = path.to_str().unwrap();
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--bundler",
+            "--bundler.beneficiary",
+            "0x00000000000000000000000000000000000000aa",
+            "--bundler.predeploy",
+            path,
+        ]);
+        let config = args.bundler.into_bundler_config().unwrap();
+        assert!(config.beneficiary.is_some());
+        assert_eq!(config.genesis_alloc().len(), 2);
+
+        let args = NodeArgs::try_parse_from(["anvil", "--bundler.predeploy", path]);
+        assert!(args.is_err());
+    }
+
     #[test]
     fn can_parse_prune_config() {
         let args: NodeArgs = NodeArgs::parse_from(["anvil", "--prune-history"]);
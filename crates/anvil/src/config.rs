@@ -8,19 +8,24 @@
             mem::fork_db::ForkedDatabase,
             time::duration_since_unix_epoch,
         },
+        bundler::BundlerConfig,
         fees::{INITIAL_BASE_FEE, INITIAL_GAS_PRICE},
-        pool::transactions::{PoolTransaction, TransactionOrder},
+        miner::BlockBuilder,
+        pool::{
+            transactions::{PoolTransaction, TransactionOrder},
+            PoolConfig,
+        },
     },
     hardfork::{ChainHardfork, OptimismHardfork},
-    mem::{self, in_memory_db::MemDb},
-    EthereumHardfork, FeeManager, PrecompileFactory,
+    mem::{self, chain_db::ChainDb, in_memory_db::MemDb},
+    ChainOverrides, EthereumHardfork, FeeManager, PrecompileFactory,
 };
 use alloy_consensus::BlockHeader;
 use alloy_genesis::Genesis;
 use alloy_network::{AnyNetwork, TransactionResponse};
 use alloy_primitives::{hex, map::HashMap, utils::Unit, BlockNumber, TxHash, U256};
 use alloy_provider::Provider;
-use alloy_rpc_types::{Block, BlockNumberOrTag};
+use alloy_rpc_types::{engine::JwtSecret, Block, BlockNumberOrTag};
 use alloy_signer::Signer;
 use alloy_signer_local::{
     coins_bip39::{English, Mnemonic},
@@ -30,7 +35,7 @@
 use anvil_server::ServerConfig;
 use eyre::{Context, Result};
 use foundry_common::{
-    provider::{ProviderBuilder, RetryProvider},
+    provider::{cassette::CassetteConfig, ProviderBuilder, RetryProvider},
     ALCHEMY_FREE_TIER_CUPS, NON_ARCHIVE_NODE_WARNING, REQUEST_TIMEOUT,
 };
 use foundry_config::Config;
@@ -61,6 +66,8 @@
 
 /// Default port the rpc will open
 pub const NODE_PORT: u16 = 8545;
+/// Default port the authenticated Engine API server binds to
+pub const AUTH_PORT: u16 = 8551;
 /// Default chain id of the node
 pub const CHAIN_ID: u64 = 31337;
 /// The default gas limit for all transactions
@@ -138,6 +145,8 @@ pub struct NodeConfig {
     pub host: Vec<IpAddr>,
     /// How transactions are sorted in the mempool
     pub transaction_order: TransactionOrder,
+    /// Replacement rules, limits and eviction of the transaction pool
+    pub pool_config: PoolConfig,
     /// Filename to write anvil output as json
     pub config_out: Option<PathBuf>,
     /// The genesis to use to initialize the node
@@ -150,6 +159,8 @@ pub struct NodeConfig {
     pub fork_retry_backoff: Duration,
     /// available CUPS
     pub compute_units_per_second: u64,
+    /// Records all fork requests to or replays them from a cassette
+    pub fork_cassette: Option<CassetteConfig>,
     /// The ipc path
     pub ipc_path: Option<Option<String>>,
     /// Enable transaction/call steps tracing for debug calls returning geth-style traces
@@ -180,12 +191,32 @@ pub struct NodeConfig {
     pub memory_limit: Option<u64>,
     /// Factory used by `anvil` to extend the EVM's precompiles.
     pub precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// Custom precompiles and predeploys of the chain
+    pub chain_overrides: Option<ChainOverrides>,
+    /// Assembles the blocks mined by the node, bundles are put first if not set.
+    pub block_builder: Option<Arc<dyn BlockBuilder>>,
     /// Enable Odyssey features.
     pub odyssey: bool,
     /// Do not print log messages.
     pub silent: bool,
     /// The path where states are cached.
     pub cache_path: Option<PathBuf>,
+    /// The path of the on-disk database the chain is persisted to, if any.
+    ///
+    /// If the database already contains a chain, the node continues from it.
+    pub chain_db_path: Option<PathBuf>,
+    /// Whether to serve the Engine API, in which case blocks are only built via `engine_*` calls
+    pub engine_api: bool,
+    /// port to use for the authenticated Engine API server
+    pub auth_port: u16,
+    /// The secret the Engine API requests are authenticated with, a random one is used if unset
+    pub jwt_secret: Option<JwtSecret>,
+    /// port to serve Prometheus metrics at `/metrics` on, disabled if unset
+    pub metrics_port: Option<u16>,
+    /// The file to record all handled RPC calls to, see [crate::server::session]
+    pub record_session: Option<PathBuf>,
+    /// Accepts ERC-4337 user operations if set, see [crate::eth::bundler]
+    pub bundler: Option<BundlerConfig>,
 }
 
 impl NodeConfig {
@@ -391,7 +422,7 @@ impl NodeConfig {
     /// random, free port by setting it to `0`
     #[doc(hidden)]
     pub fn test() -> Self {
-        Self { enable_tracing: true, port: 0, silent: true, ..Default::default() }
+        Self { enable_tracing: true, port: 0, auth_port: 0, silent: true, ..Default::default() }
     }
 
     /// Returns a new config which does not initialize any accounts on node startup.
@@ -440,10 +471,12 @@ fn default() -> Self {
             server_config: Default::default(),
             host: vec![IpAddr::V4(Ipv4Addr::LOCALHOST)],
             transaction_order: Default::default(),
+            pool_config: Default::default(),
             config_out: None,
             genesis: None,
             fork_request_timeout: REQUEST_TIMEOUT,
             fork_headers: vec![],
+            fork_cassette: None,
             fork_request_retries: 5,
             fork_retry_backoff: Duration::from_millis(1_000),
             fork_chain_id: None,
@@ -460,9 +493,18 @@ fn default() -> Self {
             slots_in_an_epoch: 32,
             memory_limit: None,
             precompile_factory: None,
+            chain_overrides: None,
+            block_builder: None,
             odyssey: false,
             silent: false,
             cache_path: None,
+            chain_db_path: None,
+            engine_api: false,
+            auth_port: AUTH_PORT,
+            jwt_secret: None,
+            metrics_port: None,
+            record_session: None,
+            bundler: None,
         }
     }
 }
@@ -842,6 +884,13 @@ pub fn fork_compute_units_per_second(mut self, compute_units_per_second: Option<
         self
     }
 
+    /// Sets the cassette to record all fork requests to or replay them from
+    #[must_use]
+    pub fn with_fork_cassette(mut self, fork_cassette: Option<CassetteConfig>) -> Self {
+        self.fork_cassette = fork_cassette;
+        self
+    }
+
     /// Sets whether to enable tracing
     #[must_use]
     pub fn with_tracing(mut self, enable_tracing: bool) -> Self {
@@ -889,6 +938,13 @@ pub fn with_transaction_order(mut self, transaction_order: TransactionOrder) ->
         self
     }
 
+    /// Sets the replacement rules, limits and eviction of the transaction pool
+    #[must_use]
+    pub fn with_pool_config(mut self, pool_config: PoolConfig) -> Self {
+        self.pool_config = pool_config;
+        self
+    }
+
     /// Returns the ipc path for the ipc endpoint if any
     pub fn get_ipc_path(&self) -> Option<String> {
         match &self.ipc_path {
@@ -945,6 +1001,20 @@ pub fn with_precompile_factory(mut self, factory: impl PrecompileFactory + 'stat
         self
     }
 
+    /// Registers the custom precompiles and predeploys of the chain
+    #[must_use]
+    pub fn with_chain_overrides(mut self, chain_overrides: Option<ChainOverrides>) -> Self {
+        self.chain_overrides = chain_overrides;
+        self
+    }
+
+    /// Sets how the blocks mined by the node are assembled from bundles and pool transactions
+    #[must_use]
+    pub fn with_block_builder(mut self, builder: impl BlockBuilder + 'static) -> Self {
+        self.block_builder = Some(Arc::new(builder));
+        self
+    }
+
     /// Sets whether to enable Odyssey support
     #[must_use]
     pub fn with_odyssey(mut self, odyssey: bool) -> Self {
@@ -971,6 +1041,55 @@ pub fn with_cache_path(mut self, cache_path: Option<PathBuf>) -> Self {
         self
     }
 
+    /// Sets the path of the on-disk database to persist blocks, receipts and state to
+    #[must_use]
+    pub fn with_chain_db(mut self, chain_db_path: Option<PathBuf>) -> Self {
+        self.chain_db_path = chain_db_path;
+        self
+    }
+
+    /// Sets whether to serve the Engine API and let it drive block production
+    #[must_use]
+    pub fn with_engine_api(mut self, engine_api: bool) -> Self {
+        self.engine_api = engine_api;
+        self
+    }
+
+    /// Sets the port of the authenticated Engine API server
+    #[must_use]
+    pub fn with_auth_port(mut self, auth_port: u16) -> Self {
+        self.auth_port = auth_port;
+        self
+    }
+
+    /// Sets the secret the Engine API requests are authenticated with
+    #[must_use]
+    pub fn with_jwt_secret(mut self, jwt_secret: Option<JwtSecret>) -> Self {
+        self.jwt_secret = jwt_secret;
+        self
+    }
+
+    /// Sets the port to serve Prometheus metrics on, if any
+    #[must_use]
+    pub fn with_metrics_port(mut self, metrics_port: Option<u16>) -> Self {
+        self.metrics_port = metrics_port;
+        self
+    }
+
+    /// Sets the file to record all handled RPC calls to, if any
+    #[must_use]
+    pub fn with_record_session(mut self, record_session: Option<PathBuf>) -> Self {
+        self.record_session = record_session;
+        self
+    }
+
+    /// Enables the ERC-4337 bundler of the node, if set
+    #[must_use]
+    pub fn with_bundler(mut self, bundler: Option<BundlerConfig>) -> Self {
+        self.bundler = bundler;
+        self
+    }
+
     /// Configures everything related to env, backend and database and returns the
     /// [Backend](mem::Backend)
     ///
@@ -1032,15 +1151,30 @@ pub(crate) async fn setup(&mut self) -> Result<mem::Backend> {
             env.block.coinbase = genesis.coinbase;
         }
 
+        let mut predeploys = self
+            .chain_overrides
+            .as_ref()
+            .map(|overrides| overrides.genesis_alloc())
+            .unwrap_or_default();
+        if let Some(bundler) = &self.bundler {
+            predeploys.extend(bundler.genesis_alloc());
+        }
         let genesis = GenesisConfig {
             timestamp: self.get_genesis_timestamp(),
             balance: self.genesis_balance,
             accounts: self.genesis_accounts.iter().map(|acc| acc.address()).collect(),
             genesis_init: self.genesis.clone(),
+            predeploys,
         };
 
+        if let Some(overrides) = &self.chain_overrides {
+            if let Some(factory) = overrides.precompile_factory(self.precompile_factory.clone())? {
+                self.precompile_factory = Some(Arc::new(factory));
+            }
+        }
+
         // only memory based backend for now
-        let backend = mem::Backend::with_genesis(
+        let mut backend = mem::Backend::with_genesis(
             db,
             Arc::new(RwLock::new(env)),
             genesis,
@@ -1071,6 +1205,12 @@ pub(crate) async fn setup(&mut self) -> Result<mem::Backend> {
             backend.load_state(state).await.wrap_err("failed to load init state")?;
         }
 
+        if let Some(path) = &self.chain_db_path {
+            let chain_db = ChainDb::open(path)
+                .wrap_err_with(|| format!("failed to open chain db at {}", path.display()))?;
+            backend.attach_chain_db(chain_db).await?;
+        }
+
         Ok(backend)
     }
 
@@ -1112,6 +1252,7 @@ pub async fn setup_fork_db_config(
                 .max_retry(self.fork_request_retries)
                 .initial_backoff(1000)
                 .headers(self.fork_headers.clone())
+                .maybe_cassette(self.fork_cassette.clone())
                 .build()
                 .wrap_err("failed to establish provider to fork url")?,
         );
@@ -1276,6 +1417,7 @@ pub async fn setup_fork_db_config(
             retries: self.fork_request_retries,
             backoff: self.fork_retry_backoff,
             compute_units_per_second: self.compute_units_per_second,
+            cassette: self.fork_cassette.clone(),
             total_difficulty: block.header.total_difficulty.unwrap_or_default(),
             blob_gas_used: block.header.blob_gas_used.map(|g| g as u128),
             blob_excess_gas_and_price: env.block.blob_excess_gas_and_price.clone(),
@@ -1508,6 +1650,28 @@ pub fn anvil_tmp_dir() -> Option<PathBuf> {
     anvil_dir().map(|p| p.join("tmp"))
 }
 
+/// Writes the hex encoded secret to the given file, which is only accessible by the current user
+pub fn write_jwt_secret(path: &Path, secret: &JwtSecret) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        // the mode only applies to newly created files
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    io::Write::write_all(&mut file, hex::encode(secret.as_bytes()).as_bytes())
+}
+
 /// Finds the latest appropriate block to fork
 ///
 /// This fetches the "latest" block and checks whether the `Block` is fully populated (`hash` field
@@ -1,5 +1,6 @@
 use super::{
-    backend::mem::{state, BlockRequest, State},
+    backend::mem::{state, BlockAttributes, BlockRequest, ImportedBlock, State},
+    engine::{self, BuiltPayload, EngineState, PayloadJob, ENGINE_CAPABILITIES},
     sign::build_typed_transaction,
 };
 use crate::{
@@ -8,16 +9,19 @@
             self,
             db::SerializableState,
             mem::{MIN_CREATE_GAS, MIN_TRANSACTION_GAS},
-            notifications::NewBlockNotifications,
+            notifications::{ChainNotifications, NewBlockNotifications},
             validate::TransactionValidator,
         },
+        bundler::{self, Bundler, SENDER_CREATOR_V07},
         error::{
-            BlockchainError, FeeHistoryError, InvalidTransactionError, Result, ToRpcResponseResult,
+            BlockImportError, BlockchainError, FeeHistoryError, InvalidTransactionError, Result,
+            ToRpcResponseResult,
         },
         fees::{FeeDetails, FeeHistoryCache, MIN_SUGGESTED_PRIORITY_FEE},
         macros::node_info,
         miner::FixedBlockTimeMiner,
         pool::{
+            bundle::Bundle,
             transactions::{
                 to_marker, PoolTransaction, TransactionOrder, TransactionPriority, TxMarker,
             },
@@ -27,7 +31,10 @@
     },
     filter::{EthFilter, Filters, LogsFilter},
     mem::transaction_build,
+    metrics::{NodeMetrics, RpcMetrics},
+    op_bridge::{withdrawal_slot, MessagePassed, L2_TO_L1_MESSAGE_PASSER},
     revm::primitives::{BlobExcessGasAndPrice, Output},
+    server::session::{SessionCall, SessionRecorder},
     ClientFork, LoggingManager, Miner, MiningMode, StorageInfo,
 };
 use alloy_consensus::{transaction::eip4844::TxEip4844Variant, Account};
@@ -49,30 +56,45 @@
     anvil::{
         ForkedNetwork, Forking, Metadata, MineOptions, NodeEnvironment, NodeForkConfig, NodeInfo,
     },
+    engine::{
+        ExecutionPayload, ForkchoiceState, ForkchoiceUpdated, PayloadAttributes, PayloadId,
+        PayloadStatus, PayloadStatusEnum,
+    },
     request::TransactionRequest,
+    simulate::{SimBlock, SimulatePayload},
     state::StateOverride,
     trace::{
+        common::TraceResult,
         filter::TraceFilter,
         geth::{GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace},
-        parity::LocalizedTransactionTrace,
+        parity::{
+            LocalizedTransactionTrace, TraceResults, TraceResultsWithTransactionHash, TraceType,
+        },
     },
     txpool::{TxpoolContent, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
     AccessList, AccessListResult, BlockId, BlockNumberOrTag as BlockNumber, BlockTransactions,
     EIP1186AccountProofResponse, FeeHistory, Filter, FilteredParams, Index, Log,
 };
 use alloy_serde::WithOtherFields;
+use alloy_sol_types::{SolCall, SolEvent};
 use alloy_transport::TransportErrorKind;
 use anvil_core::{
     eth::{
-        block::BlockInfo,
+        block::{Block, BlockInfo},
         transaction::{
-            transaction_request_to_typed, PendingTransaction, ReceiptResponse, TypedTransaction,
-            TypedTransactionRequest,
+            optimism::DepositTransaction, transaction_request_to_typed, PendingTransaction,
+            ReceiptResponse, TypedTransaction, TypedTransactionRequest,
         },
         wallet::{WalletCapabilities, WalletError},
-        EthRequest,
+        EngineRequest, EthRequest,
+    },
+    types::{
+        AccountRangeResult, BundleHash, CancelPrivateTransactionRequest, OutputRootProof,
+        PrivateTransactionRequest, ReorgOptions, SendBundleRequest, SimulatedBlockResult,
+        StateDiff, StateDiffOptions, StorageRangeResult, TransactionData, UserOperation,
+        UserOperationGasEstimation, UserOperationReceipt, WithdrawalProof, WithdrawalTransaction,
+        Work,
     },
-    types::{ReorgOptions, TransactionData, Work},
 };
 use anvil_rpc::{error::RpcError, response::ResponseResult};
 use foundry_common::provider::ProviderBuilder;
@@ -88,11 +110,21 @@
 use futures::channel::{mpsc::Receiver, oneshot};
 use parking_lot::RwLock;
 use revm::primitives::Bytecode;
-use std::{future::Future, sync::Arc, time::Duration};
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// The client version: `anvil/v{major}.{minor}.{patch}`
 pub const CLIENT_VERSION: &str = concat!("anvil/v", env!("CARGO_PKG_VERSION"));
 
+/// The number of blocks a private transaction can be included in if no `maxBlockNumber` is given
+pub const MAX_PRIVATE_TRANSACTION_BLOCKS: u64 = 25;
+
+/// The maximum number of accounts returned by `debug_accountRange`, same as geth
+pub const ACCOUNT_RANGE_MAX_RESULTS: u64 = 256;
+
 /// The entry point for executing eth api RPC call - The Eth RPC interface.
 ///
 /// This type is cheap to clone and can be used concurrently
@@ -126,6 +158,14 @@ pub struct EthApi {
     net_listening: bool,
     /// The instance ID. Changes on every reset.
     instance_id: Arc<RwLock<B256>>,
+    /// Payloads requested via the engine API
+    engine: Arc<EngineState>,
+    /// Metrics of the handled RPC calls
+    rpc_metrics: Arc<RpcMetrics>,
+    /// Records the handled RPC calls to a session file, if enabled
+    session_recorder: Option<Arc<SessionRecorder>>,
+    /// Accepts ERC-4337 user operations, if enabled
+    bundler: Option<Arc<Bundler>>,
 }
 
 impl EthApi {
@@ -155,9 +195,23 @@ pub fn new(
             net_listening: true,
             transaction_order: Arc::new(RwLock::new(transactions_order)),
             instance_id: Arc::new(RwLock::new(B256::random())),
+            engine: Default::default(),
+            rpc_metrics: Default::default(),
+            session_recorder: None,
+            bundler: None,
         }
     }
 
+    /// Records all RPC calls handled by the servers of the node to the session
+    pub fn set_session_recorder(&mut self, recorder: SessionRecorder) {
+        self.session_recorder = Some(Arc::new(recorder));
+    }
+
+    /// Enables the `eth_*UserOperation*` calls, see [bundler]
+    pub fn set_bundler(&mut self, bundler: Bundler) {
+        self.bundler = Some(Arc::new(bundler));
+    }
+
     /// Executes the [EthRequest] and returns an RPC [ResponseResult].
     pub async fn execute(&self, request: EthRequest) -> ResponseResult {
         trace!(target: "rpc::api", "executing eth request");
@@ -243,9 +297,31 @@ pub async fn execute(&self, request: EthRequest) -> ResponseResult {
             EthRequest::EthSendRawTransaction(tx) => {
                 self.send_raw_transaction(tx).await.to_rpc_result()
             }
+            EthRequest::EthSendBundle(bundle) => self.send_bundle(bundle).await.to_rpc_result(),
+            EthRequest::EthSendUserOperation(op, entry_point) => {
+                self.send_user_operation(*op, entry_point).await.to_rpc_result()
+            }
+            EthRequest::EthEstimateUserOperationGas(op, entry_point) => {
+                self.estimate_user_operation_gas(*op, entry_point).await.to_rpc_result()
+            }
+            EthRequest::EthGetUserOperationReceipt(hash) => {
+                self.user_operation_receipt(hash).await.to_rpc_result()
+            }
+            EthRequest::EthSupportedEntryPoints(()) => {
+                self.supported_entry_points().to_rpc_result()
+            }
+            EthRequest::EthSendPrivateTransaction(request) => {
+                self.send_private_transaction(request).await.to_rpc_result()
+            }
+            EthRequest::EthCancelPrivateTransaction(request) => {
+                self.cancel_private_transaction(request).await.to_rpc_result()
+            }
             EthRequest::EthCall(call, block, overrides) => {
                 self.call(call, block, overrides).await.to_rpc_result()
             }
+            EthRequest::EthSimulateV1(simulation, block) => {
+                self.simulate_v1(simulation, block).await.to_rpc_result()
+            }
             EthRequest::EthCreateAccessList(call, block) => {
                 self.create_access_list(call, block).await.to_rpc_result()
             }
@@ -299,13 +375,50 @@ pub async fn execute(&self, request: EthRequest) -> ResponseResult {
             EthRequest::DebugTraceTransaction(tx, opts) => {
                 self.debug_trace_transaction(tx, opts).await.to_rpc_result()
             }
+            EthRequest::DebugTraceBlockByNumber(block, opts) => {
+                self.debug_trace_block_by_number(block, opts).await.to_rpc_result()
+            }
+            EthRequest::DebugTraceBlockByHash(hash, opts) => {
+                self.debug_trace_block_by_hash(hash, opts).await.to_rpc_result()
+            }
             // non eth-standard rpc calls
             EthRequest::DebugTraceCall(tx, block, opts) => {
                 self.debug_trace_call(tx, block, opts).await.to_rpc_result()
             }
+            EthRequest::DebugStorageRangeAt(block, tx_index, address, start_key, max_result) => {
+                self.debug_storage_range_at(block, tx_index, address, start_key, max_result)
+                    .await
+                    .to_rpc_result()
+            }
+            EthRequest::DebugAccountRange(
+                block,
+                start,
+                max_results,
+                no_code,
+                no_storage,
+                incompletes,
+            ) => self
+                .debug_account_range(block, start, max_results, no_code, no_storage, incompletes)
+                .await
+                .to_rpc_result(),
+            EthRequest::DebugGetModifiedAccountsByNumber(start, end) => {
+                self.debug_get_modified_accounts_by_number(start, end).await.to_rpc_result()
+            }
             EthRequest::TraceTransaction(tx) => self.trace_transaction(tx).await.to_rpc_result(),
             EthRequest::TraceBlock(block) => self.trace_block(block).await.to_rpc_result(),
             EthRequest::TraceFilter(filter) => self.trace_filter(filter).await.to_rpc_result(),
+            EthRequest::TraceReplayTransaction(tx, trace_types) => {
+                self.trace_replay_transaction(tx, trace_types).await.to_rpc_result()
+            }
+            EthRequest::TraceReplayBlockTransactions(block, trace_types) => {
+                self.trace_replay_block_transactions(block, trace_types).await.to_rpc_result()
+            }
+            EthRequest::TraceCall(call, trace_types, block) => {
+                self.trace_call(call, trace_types, block).await.to_rpc_result()
+            }
+            EthRequest::TraceCallMany(calls, block) => {
+                self.trace_call_many(calls, block).await.to_rpc_result()
+            }
             EthRequest::ImpersonateAccount(addr) => {
                 self.anvil_impersonate_account(addr).await.to_rpc_result()
             }
@@ -365,6 +478,12 @@ pub async fn execute(&self, request: EthRequest) -> ResponseResult {
             EthRequest::AnvilMetadata(_) => self.anvil_metadata().await.to_rpc_result(),
             EthRequest::EvmSnapshot(_) => self.evm_snapshot().await.to_rpc_result(),
             EthRequest::EvmRevert(id) => self.evm_revert(id).await.to_rpc_result(),
+            EthRequest::AnvilStateDiff(options) => {
+                self.anvil_state_diff(options).await.to_rpc_result()
+            }
+            EthRequest::AnvilGetWithdrawalProofs(hash) => {
+                self.anvil_get_withdrawal_proofs(hash).await.to_rpc_result()
+            }
             EthRequest::EvmIncreaseTime(time) => self.evm_increase_time(time).await.to_rpc_result(),
             EthRequest::EvmSetNextBlockTimeStamp(time) => {
                 if time >= U256::from(u64::MAX) {
@@ -1023,17 +1142,7 @@ pub async fn send_transaction(
     /// Handler for ETH RPC call: `eth_sendRawTransaction`
     pub async fn send_raw_transaction(&self, tx: Bytes) -> Result<TxHash> {
         node_info!("eth_sendRawTransaction");
-        let mut data = tx.as_ref();
-        if data.is_empty() {
-            return Err(BlockchainError::EmptyRawTransactionData);
-        }
-
-        let transaction = TypedTransaction::decode_2718(&mut data)
-            .map_err(|_| BlockchainError::FailedToDecodeSignedTransaction)?;
-
-        self.ensure_typed_transaction_supported(&transaction)?;
-
-        let pending_transaction = PendingTransaction::new(transaction)?;
+        let pending_transaction = self.decode_raw_transaction(tx)?;
 
         // pre-validate
         self.backend.validate_pool_transaction(&pending_transaction).await?;
@@ -1056,6 +1165,300 @@ pub async fn send_raw_transaction(&self, tx: Bytes) -> Result<TxHash> {
         Ok(*tx.hash())
     }
 
+    /// Adds a deposit transaction to the pool, e.g. one that was derived from a deposit on L1.
+    ///
+    /// Like on an OP stack chain, deposits don't depend on the nonces of pending transactions of
+    /// their sender: they're ready right away, and are included before all other transactions of
+    /// the pool, in the order they were added. The nonce of the deposit is set to the current
+    /// nonce of its sender.
+    pub async fn send_deposit_transaction(
+        &self,
+        mut deposit: DepositTransaction,
+    ) -> Result<TxHash> {
+        self.backend.ensure_op_deposits_active()?;
+        deposit.nonce = self.backend.current_nonce(deposit.from).await?;
+        let provides = vec![deposit.source_hash.to_vec()];
+
+        let pending_transaction = PendingTransaction::new(TypedTransaction::Deposit(deposit))?;
+        // pre-validate
+        self.backend.validate_pool_transaction(&pending_transaction).await?;
+
+        let from = *pending_transaction.sender();
+        let pool_transaction = PoolTransaction {
+            requires: vec![],
+            provides,
+            pending_transaction,
+            priority: TransactionPriority(u128::MAX),
+        };
+        let tx = self.pool.add_transaction(pool_transaction)?;
+        trace!(target: "node", "Added deposit transaction: [{:?}] sender={:?}", tx.hash(), from);
+        Ok(*tx.hash())
+    }
+
+    /// Submits a bundle of signed transactions that is only included in the requested block if all
+    /// of its transactions can be included, in order and without reverting.
+    ///
+    /// Bundles bypass the public pool. If auto-mining is enabled, a bundle for the next block is
+    /// mined right away.
+    ///
+    /// Handler for RPC call: `eth_sendBundle`
+    pub async fn send_bundle(&self, request: SendBundleRequest) -> Result<BundleHash> {
+        node_info!("eth_sendBundle");
+        let SendBundleRequest {
+            txs,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+        } = request;
+        if txs.is_empty() {
+            return Err(RpcError::invalid_params("bundle has no transactions").into());
+        }
+        if block_number <= self.backend.best_number() {
+            return Err(RpcError::invalid_params("bundle block number is in the past").into());
+        }
+
+        let transactions = txs
+            .into_iter()
+            .map(|tx| self.decode_raw_transaction(tx).map(|tx| Arc::new(bundled_transaction(tx))))
+            .collect::<Result<Vec<_>>>()?;
+        let bundle = Bundle {
+            min_block_number: Some(block_number),
+            max_block_number: Some(block_number),
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes: reverting_tx_hashes.into_iter().collect(),
+            ..Bundle::new(transactions)
+        };
+        let bundle_hash = bundle.hash;
+        self.backend.add_bundle(bundle);
+
+        if self.miner.is_auto_mine() && block_number == self.backend.best_number() + 1 {
+            self.mine_one().await;
+        }
+        Ok(BundleHash { bundle_hash })
+    }
+
+    /// Submits a signed transaction that bypasses the public pool and is included in one of the
+    /// next blocks, up to `maxBlockNumber`.
+    ///
+    /// Handler for RPC call: `eth_sendPrivateTransaction`
+    pub async fn send_private_transaction(
+        &self,
+        request: PrivateTransactionRequest,
+    ) -> Result<TxHash> {
+        node_info!("eth_sendPrivateTransaction");
+        let PrivateTransactionRequest { tx, max_block_number } = request;
+        let pending_transaction = self.decode_raw_transaction(tx)?;
+        self.backend.validate_pool_transaction(&pending_transaction).await?;
+
+        let max_block_number = max_block_number
+            .unwrap_or_else(|| self.backend.best_number() + MAX_PRIVATE_TRANSACTION_BLOCKS);
+        let transaction = Arc::new(bundled_transaction(pending_transaction));
+        let hash = transaction.hash();
+        self.backend.add_bundle(Bundle::private(transaction, Some(max_block_number)));
+
+        if self.miner.is_auto_mine() {
+            self.mine_one().await;
+        }
+        Ok(hash)
+    }
+
+    /// Stops a transaction submitted via `eth_sendPrivateTransaction` from being included.
+    ///
+    /// Returns false if the transaction is unknown or was already included.
+    ///
+    /// Handler for RPC call: `eth_cancelPrivateTransaction`
+    pub async fn cancel_private_transaction(
+        &self,
+        request: CancelPrivateTransactionRequest,
+    ) -> Result<bool> {
+        node_info!("eth_cancelPrivateTransaction");
+        Ok(self.backend.remove_bundle(request.tx_hash).is_some())
+    }
+
+    /// Submits an ERC-4337 user operation for the EntryPoint of the bundler.
+    ///
+    /// The operation is rejected if `handleOps` fails for it, otherwise it's sent as a `handleOps`
+    /// transaction of the bundler account. Returns the hash of the user operation.
+    ///
+    /// Handler for RPC call: `eth_sendUserOperation`
+    pub async fn send_user_operation(
+        &self,
+        op: UserOperation,
+        entry_point: Address,
+    ) -> Result<B256> {
+        node_info!("eth_sendUserOperation");
+        let bundler = self.bundler_for(entry_point)?;
+        self.ensure_entry_point_deployed(entry_point).await?;
+        let (from, beneficiary) = self.bundler_accounts(bundler)?;
+        let packed = bundler::pack_user_operation(&op);
+        let user_op_hash = bundler::user_operation_hash(&packed, entry_point, self.chain_id());
+        if bundler.transaction(user_op_hash).is_some() {
+            return Err(RpcError::invalid_params("user operation already known").into())
+        }
+
+        let request = WithOtherFields::new(
+            TransactionRequest::default()
+                .with_from(from)
+                .with_to(entry_point)
+                .with_input(bundler::handle_ops_input(vec![packed], beneficiary)),
+        );
+        match self.call(request.clone(), Some(BlockNumber::Pending.into()), None).await {
+            Ok(_) => {}
+            Err(BlockchainError::InvalidTransaction(InvalidTransactionError::Revert(Some(
+                data,
+            )))) => {
+                if let Some(err) = bundler::failed_op_error(&data) {
+                    return Err(err.into())
+                }
+                return Err(InvalidTransactionError::Revert(Some(data)).into())
+            }
+            Err(err) => return Err(err),
+        }
+
+        let tx_hash = self.send_transaction(request).await?;
+        bundler.insert(user_op_hash, tx_hash);
+        Ok(user_op_hash)
+    }
+
+    /// Estimates the gas limits of an ERC-4337 user operation.
+    ///
+    /// The deployment and validation of the account, the validation of the paymaster and the
+    /// execution of the operation are simulated as separate calls, as the EntryPoint would make
+    /// them, but without checking signatures or fees.
+    ///
+    /// Handler for RPC call: `eth_estimateUserOperationGas`
+    pub async fn estimate_user_operation_gas(
+        &self,
+        mut op: UserOperation,
+        entry_point: Address,
+    ) -> Result<UserOperationGasEstimation> {
+        node_info!("eth_estimateUserOperationGas");
+        let bundler = self.bundler_for(entry_point)?;
+        self.ensure_entry_point_deployed(entry_point).await?;
+        let (_, beneficiary) = self.bundler_accounts(bundler)?;
+        let packed = bundler::pack_user_operation(&op);
+        let user_op_hash = bundler::user_operation_hash(&packed, entry_point, self.chain_id());
+
+        let mut calls = Vec::new();
+        if let Some(factory) = op.factory {
+            if self.backend.get_code(op.sender, None).await?.is_empty() {
+                let input = op.factory_data.clone().unwrap_or_default();
+                let call = TransactionRequest::default()
+                    .with_from(SENDER_CREATOR_V07)
+                    .with_to(factory)
+                    .with_input(input);
+                calls.push(("account deployment", call));
+            }
+        }
+        let input = bundler::validateUserOpCall {
+            userOp: packed.clone(),
+            userOpHash: user_op_hash,
+            missingAccountFunds: U256::ZERO,
+        }
+        .abi_encode();
+        let call = TransactionRequest::default()
+            .with_from(entry_point)
+            .with_to(op.sender)
+            .with_input(input);
+        calls.push(("account validation", call));
+        if let Some(paymaster) = op.paymaster {
+            let input = bundler::validatePaymasterUserOpCall {
+                userOp: packed,
+                userOpHash: user_op_hash,
+                maxCost: U256::ZERO,
+            }
+            .abi_encode();
+            let call = TransactionRequest::default()
+                .with_from(entry_point)
+                .with_to(paymaster)
+                .with_input(input);
+            calls.push(("paymaster validation", call));
+        }
+        let call = TransactionRequest::default()
+            .with_from(entry_point)
+            .with_to(op.sender)
+            .with_input(op.call_data.clone());
+        calls.push(("execution", call));
+
+        let payload = SimulatePayload {
+            block_state_calls: vec![SimBlock {
+                calls: calls.iter().map(|(_, call)| call.clone()).collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let results = self
+            .simulate_v1(payload, None)
+            .await?
+            .pop()
+            .map(|block| block.calls)
+            .unwrap_or_default();
+
+        let mut gas_used = Vec::with_capacity(calls.len());
+        for ((phase, call), result) in calls.iter().zip(results) {
+            if !result.status {
+                let reason = RevertDecoder::new()
+                    .maybe_decode(&result.return_data, None)
+                    .unwrap_or_else(|| result.return_data.to_string());
+                let code = if *phase == "execution" {
+                    bundler::USER_OPERATION_REVERTED
+                } else {
+                    bundler::USER_OPERATION_REJECTED
+                };
+                let message = format!("{phase} reverted: {reason}");
+                return Err(bundler::user_operation_error(code, message).into())
+            }
+            // the EntryPoint makes these calls within its transaction
+            let input = call.input.input().map(|input| input.as_ref()).unwrap_or_default();
+            gas_used.push(result.gas_used.saturating_sub(bundler::intrinsic_gas(input)));
+        }
+
+        let call_gas_limit = bundler::with_gas_margin(gas_used.pop().unwrap_or_default());
+        let paymaster_verification_gas_limit =
+            op.paymaster.and_then(|_| gas_used.pop()).map(bundler::with_gas_margin);
+        let verification_gas_limit = bundler::with_gas_margin(gas_used.iter().sum());
+
+        // the operation is bundled on its own, so its pre-verification gas covers the whole
+        // `handleOps` transaction with the estimated limits
+        op.call_gas_limit = call_gas_limit.into();
+        op.verification_gas_limit = verification_gas_limit.into();
+        op.paymaster_verification_gas_limit = paymaster_verification_gas_limit
+            .map(Into::into)
+            .or(op.paymaster_verification_gas_limit);
+        let input = bundler::handle_ops_input(vec![bundler::pack_user_operation(&op)], beneficiary);
+        Ok(UserOperationGasEstimation {
+            pre_verification_gas: bundler::intrinsic_gas(&input),
+            verification_gas_limit,
+            call_gas_limit,
+            paymaster_verification_gas_limit,
+        })
+    }
+
+    /// Returns the receipt of a user operation that was submitted to the bundler, once its
+    /// `handleOps` transaction is mined.
+    ///
+    /// Handler for RPC call: `eth_getUserOperationReceipt`
+    pub async fn user_operation_receipt(&self, hash: B256) -> Result<Option<UserOperationReceipt>> {
+        node_info!("eth_getUserOperationReceipt");
+        let bundler = self.bundler.as_deref().ok_or_else(RpcError::method_not_found)?;
+        let Some(tx_hash) = bundler.transaction(hash) else { return Ok(None) };
+        let Some(receipt) = self.backend.transaction_receipt(tx_hash).await? else {
+            return Ok(None)
+        };
+        Ok(bundler::user_operation_receipt(hash, bundler.entry_point(), receipt))
+    }
+
+    /// Returns the EntryPoints that the bundler accepts user operations for.
+    ///
+    /// Handler for RPC call: `eth_supportedEntryPoints`
+    pub fn supported_entry_points(&self) -> Result<Vec<Address>> {
+        node_info!("eth_supportedEntryPoints");
+        let bundler = self.bundler.as_deref().ok_or_else(RpcError::method_not_found)?;
+        Ok(vec![bundler.entry_point()])
+    }
+
     /// Call contract, returning the output data.
     ///
     /// Handler for ETH RPC call: `eth_call`
@@ -1100,6 +1503,34 @@ pub async fn call(
         .await
     }
 
+    /// Simulates a sequence of blocks with calls on top of the state of the given block.
+    ///
+    /// Handler for ETH RPC call: `eth_simulateV1`
+    pub async fn simulate_v1(
+        &self,
+        request: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> Result<Vec<SimulatedBlockResult<AnyRpcBlock>>> {
+        node_info!("eth_simulateV1");
+        let block_request = self.block_request(block_number).await?;
+        // check if the number predates the fork, if in fork mode
+        if let BlockRequest::Number(number) = block_request {
+            if let Some(fork) = self.get_fork() {
+                if fork.predates_fork(number) {
+                    let blocks = fork.simulate(&request, Some(number.into())).await?;
+                    return Ok(blocks.into_iter().map(Into::into).collect())
+                }
+            }
+        }
+
+        // this can be blocking for a bit, especially in forking mode
+        // <https://github.com/foundry-rs/foundry/issues/6036>
+        self.on_blocking_task(|this| async move {
+            this.backend.simulate(request, Some(block_request)).await
+        })
+        .await
+    }
+
     /// This method creates an EIP2930 type accessList based on a given Transaction. The accessList
     /// contains all storage slots and addresses read and written by the transaction, except for the
     /// sender account and the precompiles.
@@ -1471,7 +1902,7 @@ pub async fn new_filter(&self, filter: Filter) -> Result<String> {
             vec![]
         };
         let filter = EthFilter::Logs(Box::new(LogsFilter {
-            blocks: self.new_block_notifications(),
+            blocks: self.chain_notifications(),
             storage: self.storage_info(),
             filter: FilteredParams::new(Some(filter)),
             historic: Some(historic),
@@ -1573,6 +2004,30 @@ pub async fn debug_trace_transaction(
         self.backend.debug_trace_transaction(tx_hash, opts).await
     }
 
+    /// Returns traces for all transactions of the block for geth's tracing endpoint
+    ///
+    /// Handler for RPC call: `debug_traceBlockByNumber`
+    pub async fn debug_trace_block_by_number(
+        &self,
+        block: BlockNumber,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult<GethTrace, String>>> {
+        node_info!("debug_traceBlockByNumber");
+        self.backend.debug_trace_block(block.into(), opts).await
+    }
+
+    /// Returns traces for all transactions of the block for geth's tracing endpoint
+    ///
+    /// Handler for RPC call: `debug_traceBlockByHash`
+    pub async fn debug_trace_block_by_hash(
+        &self,
+        hash: B256,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult<GethTrace, String>>> {
+        node_info!("debug_traceBlockByHash");
+        self.backend.debug_trace_block(hash.into(), opts).await
+    }
+
     /// Returns traces for the transaction for geth's tracing endpoint
     ///
     /// Handler for RPC call: `debug_traceCall`
@@ -1597,6 +2052,78 @@ pub async fn debug_trace_call(
         result
     }
 
+    /// Returns a range of the storage of the account right before the transaction at `tx_index`
+    /// of the block is executed
+    ///
+    /// Handler for RPC call: `debug_storageRangeAt`
+    pub async fn debug_storage_range_at(
+        &self,
+        block: BlockId,
+        tx_index: u64,
+        address: Address,
+        start_key: B256,
+        max_result: u64,
+    ) -> Result<StorageRangeResult> {
+        node_info!("debug_storageRangeAt");
+        self.backend.storage_range_at(block, tx_index, address, start_key, max_result).await
+    }
+
+    /// Returns a range of the accounts of the state at the block, ordered by the hash of their
+    /// address
+    ///
+    /// Accounts without a known address can't exist in anvil's state, so `incompletes` has no
+    /// effect.
+    ///
+    /// Handler for RPC call: `debug_accountRange`
+    pub async fn debug_account_range(
+        &self,
+        block: BlockId,
+        start: Bytes,
+        max_results: u64,
+        no_code: bool,
+        no_storage: bool,
+        _incompletes: bool,
+    ) -> Result<AccountRangeResult> {
+        node_info!("debug_accountRange");
+        if start.len() > 32 {
+            return Err(RpcError::invalid_params("start key must be at most 32 bytes").into());
+        }
+        let start = B256::left_padding_from(&start);
+        let max_results = if max_results == 0 || max_results > ACCOUNT_RANGE_MAX_RESULTS {
+            ACCOUNT_RANGE_MAX_RESULTS
+        } else {
+            max_results
+        };
+        self.backend.account_range(block, start, max_results, no_code, no_storage).await
+    }
+
+    /// Returns the addresses of all accounts modified between the `start` and `end` blocks, or
+    /// in the `start` block if `end` is not set
+    ///
+    /// Handler for RPC call: `debug_getModifiedAccountsByNumber`
+    pub async fn debug_get_modified_accounts_by_number(
+        &self,
+        start: u64,
+        end: Option<u64>,
+    ) -> Result<Vec<Address>> {
+        node_info!("debug_getModifiedAccountsByNumber");
+        let best_number = self.backend.best_number();
+        let (start, end) = match end {
+            Some(end) => (start, end),
+            None => (start.saturating_sub(1), start),
+        };
+        if end > best_number {
+            return Err(BlockchainError::BlockOutOfRange(best_number, end));
+        }
+        if start >= end {
+            return Err(RpcError::invalid_params(format!(
+                "start block height ({start}) must be less than end block height ({end})"
+            ))
+            .into());
+        }
+        self.backend.modified_accounts(start, end).await
+    }
+
     /// Returns traces for the transaction hash via parity's tracing endpoint
     ///
     /// Handler for RPC call: `trace_transaction`
@@ -1613,6 +2140,84 @@ pub async fn trace_block(&self, block: BlockNumber) -> Result<Vec<LocalizedTrans
         self.backend.trace_block(block).await
     }
 
+    /// Replays the transaction and returns the requested parity trace types
+    ///
+    /// Handler for RPC call: `trace_replayTransaction`
+    pub async fn trace_replay_transaction(
+        &self,
+        tx_hash: B256,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<TraceResults> {
+        node_info!("trace_replayTransaction");
+        self.backend.trace_replay_transaction(tx_hash, trace_types).await
+    }
+
+    /// Replays all transactions of the block and returns the requested parity trace types
+    ///
+    /// Handler for RPC call: `trace_replayBlockTransactions`
+    pub async fn trace_replay_block_transactions(
+        &self,
+        block: BlockId,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<Vec<TraceResultsWithTransactionHash>> {
+        node_info!("trace_replayBlockTransactions");
+        self.backend.trace_replay_block_transactions(block, trace_types).await
+    }
+
+    /// Executes the call and returns the requested parity trace types
+    ///
+    /// Handler for RPC call: `trace_call`
+    pub async fn trace_call(
+        &self,
+        request: WithOtherFields<TransactionRequest>,
+        trace_types: HashSet<TraceType>,
+        block_number: Option<BlockId>,
+    ) -> Result<TraceResults> {
+        node_info!("trace_call");
+        let block_request = self.block_request(block_number).await?;
+        // check if the number predates the fork, if in fork mode
+        if let BlockRequest::Number(number) = block_request {
+            if let Some(fork) = self.get_fork() {
+                if fork.predates_fork(number) {
+                    return Ok(fork.trace_call(&request, trace_types, Some(number.into())).await?)
+                }
+            }
+        }
+
+        let fees = FeeDetails::new(
+            request.gas_price,
+            request.max_fee_per_gas,
+            request.max_priority_fee_per_gas,
+            request.max_fee_per_blob_gas,
+        )?
+        .or_zero_fees();
+
+        self.backend.trace_call(request, trace_types, fees, Some(block_request)).await
+    }
+
+    /// Executes the calls sequentially, each on top of the state changes of the previous ones, and
+    /// returns the requested parity trace types
+    ///
+    /// Handler for RPC call: `trace_callMany`
+    pub async fn trace_call_many(
+        &self,
+        calls: Vec<(WithOtherFields<TransactionRequest>, HashSet<TraceType>)>,
+        block_number: Option<BlockId>,
+    ) -> Result<Vec<TraceResults>> {
+        node_info!("trace_callMany");
+        let block_request = self.block_request(block_number).await?;
+        // check if the number predates the fork, if in fork mode
+        if let BlockRequest::Number(number) = block_request {
+            if let Some(fork) = self.get_fork() {
+                if fork.predates_fork(number) {
+                    return Ok(fork.trace_call_many(calls, Some(number.into())).await?)
+                }
+            }
+        }
+
+        self.backend.trace_call_many(calls, Some(block_request)).await
+    }
+
     /// Returns filtered traces over blocks
     ///
     /// Handler for RPC call: `trace_filter`
@@ -1741,12 +2346,13 @@ pub async fn anvil_drop_transaction(&self, tx_hash: B256) -> Result<Option<B256>
         Ok(self.pool.drop_transaction(tx_hash).map(|tx| tx.hash()))
     }
 
-    /// Removes all transactions from the pool
+    /// Removes all transactions from the pool, including bundles and private transactions
     ///
     /// Handler for RPC call: `anvil_dropAllTransactions`
     pub async fn anvil_drop_all_transactions(&self) -> Result<()> {
         node_info!("anvil_dropAllTransactions");
         self.pool.clear();
+        self.backend.clear_bundles();
         Ok(())
     }
 
@@ -2123,6 +2729,104 @@ pub async fn evm_revert(&self, id: U256) -> Result<bool> {
         self.backend.revert_state_snapshot(id).await
     }
 
+    /// Returns all balance, nonce, code and storage changes between the state of `fromBlock` or
+    /// `fromSnapshot` and the state of `toBlock`, which defaults to the latest block.
+    ///
+    /// In fork mode, accounts and storage slots that weren't fetched yet at either state are
+    /// compared with their values at the forked block.
+    ///
+    /// Handler for RPC call: `anvil_stateDiff`
+    pub async fn anvil_state_diff(&self, options: StateDiffOptions) -> Result<StateDiff> {
+        node_info!("anvil_stateDiff");
+        let StateDiffOptions { from_block, from_snapshot, to_block } = options;
+        let before = match (from_block, from_snapshot) {
+            (Some(from_block), None) => {
+                let block_request = self.block_request(Some(from_block)).await?;
+                self.backend.accounts_at(block_request).await?
+            }
+            (None, Some(id)) => {
+                self.backend.state_snapshot_accounts(id).await.ok_or_else(|| {
+                    RpcError::invalid_params(format!("state snapshot {id} does not exist"))
+                })?
+            }
+            _ => {
+                return Err(RpcError::invalid_params(
+                    "exactly one of `fromBlock` and `fromSnapshot` must be set",
+                )
+                .into())
+            }
+        };
+        let block_request = self.block_request(to_block).await?;
+        self.backend.state_diff(before, block_request).await
+    }
+
+    /// Returns the proofs of all withdrawals that the transaction initiated via the
+    /// `L2ToL1MessagePasser` predeploy, with the arguments of
+    /// `OptimismPortal.proveWithdrawalTransaction`.
+    ///
+    /// The withdrawals are proven against the output root of the block that included the
+    /// transaction.
+    ///
+    /// Handler for RPC call: `anvil_getWithdrawalProofs`
+    pub async fn anvil_get_withdrawal_proofs(&self, hash: B256) -> Result<Vec<WithdrawalProof>> {
+        node_info!("anvil_getWithdrawalProofs");
+        self.backend.ensure_op_deposits_active()?;
+        let receipt =
+            self.backend.transaction_receipt(hash).await?.ok_or_else(|| {
+                RpcError::invalid_params(format!("transaction {hash} is not mined"))
+            })?;
+        let (Some(block_hash), Some(block_number)) = (receipt.block_hash, receipt.block_number)
+        else {
+            return Err(RpcError::invalid_params(format!("transaction {hash} is not mined")).into())
+        };
+
+        let withdrawals = receipt
+            .inner
+            .as_receipt_with_bloom()
+            .receipt
+            .logs
+            .iter()
+            .filter(|log| log.address() == L2_TO_L1_MESSAGE_PASSER)
+            .filter_map(|log| MessagePassed::decode_log_data(log.data(), true).ok())
+            .collect::<Vec<_>>();
+        if withdrawals.is_empty() {
+            return Ok(Vec::new())
+        }
+
+        let slots = withdrawals.iter().map(|event| withdrawal_slot(event.withdrawalHash)).collect();
+        let proof = self
+            .get_proof(L2_TO_L1_MESSAGE_PASSER, slots, Some(BlockId::number(block_number)))
+            .await?;
+        let block = self.backend.get_block(block_number).ok_or(BlockchainError::BlockNotFound)?;
+        let output_root_proof = OutputRootProof {
+            version: B256::ZERO,
+            state_root: block.header.state_root,
+            message_passer_storage_root: proof.storage_hash,
+            latest_blockhash: block_hash,
+        };
+        let output_root = output_root_proof.output_root();
+
+        Ok(withdrawals
+            .into_iter()
+            .zip(proof.storage_proof)
+            .map(|(event, storage_proof)| WithdrawalProof {
+                withdrawal: WithdrawalTransaction {
+                    nonce: event.nonce,
+                    sender: event.sender,
+                    target: event.target,
+                    value: event.value,
+                    gas_limit: event.gasLimit,
+                    data: event.data,
+                },
+                withdrawal_hash: event.withdrawalHash,
+                l2_block_number: block_number,
+                output_root,
+                output_root_proof: output_root_proof.clone(),
+                withdrawal_proof: storage_proof.proof,
+            })
+            .collect())
+    }
+
     /// Jump forward in time by the given amount of time, in seconds.
     ///
     /// Handler for RPC call: `evm_increaseTime`
@@ -2273,14 +2977,17 @@ pub fn anvil_set_rpc_url(&self, url: String) -> Result<()> {
             let mut config = fork.config.write();
             // let interval = config.provider.get_interval();
             let new_provider = Arc::new(
-                ProviderBuilder::new(&url).max_retry(10).initial_backoff(1000).build().map_err(
-                    |_| {
+                ProviderBuilder::new(&url)
+                    .max_retry(10)
+                    .initial_backoff(1000)
+                    .maybe_cassette(config.cassette.clone())
+                    .build()
+                    .map_err(|_| {
                         TransportErrorKind::custom_str(
                             format!("Failed to parse invalid url {url}").as_str(),
                         )
-                    },
-                    // TODO: Add interval
-                )?, // .interval(interval),
+                        // TODO: Add interval
+                    })?, // .interval(interval),
             );
             config.provider = new_provider;
             trace!(target: "backend", "Updated fork rpc from \"{}\" to \"{}\"", config.eth_rpc_url, url);
@@ -2418,6 +3125,312 @@ fn convert(tx: Arc<PoolTransaction>) -> Result<AnyRpcTransaction> {
     }
 }
 
+// == impl EthApi engine endpoints ==
+
+impl EthApi {
+    /// Executes the [EngineRequest] and returns an RPC [ResponseResult].
+    pub async fn execute_engine(&self, request: EngineRequest) -> ResponseResult {
+        trace!(target: "rpc::api", "executing engine request");
+        match request {
+            EngineRequest::EngineExchangeCapabilities(capabilities) => {
+                self.engine_exchange_capabilities(capabilities).to_rpc_result()
+            }
+            EngineRequest::EngineForkchoiceUpdatedV1(state, attributes) => {
+                self.engine_forkchoice_updated(1, state, attributes).await.to_rpc_result()
+            }
+            EngineRequest::EngineForkchoiceUpdatedV2(state, attributes) => {
+                self.engine_forkchoice_updated(2, state, attributes).await.to_rpc_result()
+            }
+            EngineRequest::EngineForkchoiceUpdatedV3(state, attributes) => {
+                self.engine_forkchoice_updated(3, state, attributes).await.to_rpc_result()
+            }
+            EngineRequest::EngineGetPayloadV1(id) => self
+                .engine_get_payload(id)
+                .await
+                .map(|payload| payload.envelope_v1())
+                .to_rpc_result(),
+            EngineRequest::EngineGetPayloadV2(id) => self
+                .engine_get_payload(id)
+                .await
+                .map(|payload| payload.envelope_v2())
+                .to_rpc_result(),
+            EngineRequest::EngineGetPayloadV3(id) => self
+                .engine_get_payload(id)
+                .await
+                .map(|payload| payload.envelope_v3())
+                .to_rpc_result(),
+            EngineRequest::EngineNewPayloadV1(payload) => self
+                .engine_new_payload(ExecutionPayload::V1(payload), None, None)
+                .await
+                .to_rpc_result(),
+            EngineRequest::EngineNewPayloadV2(payload) => {
+                self.engine_new_payload(payload.into_payload(), None, None).await.to_rpc_result()
+            }
+            EngineRequest::EngineNewPayloadV3(
+                payload,
+                versioned_hashes,
+                parent_beacon_block_root,
+            ) => self
+                .engine_new_payload(
+                    ExecutionPayload::V3(payload),
+                    Some(versioned_hashes),
+                    Some(parent_beacon_block_root),
+                )
+                .await
+                .to_rpc_result(),
+        }
+    }
+
+    /// Returns the engine API methods supported by this node.
+    ///
+    /// Handler for RPC call: `engine_exchangeCapabilities`
+    pub fn engine_exchange_capabilities(&self, _capabilities: Vec<String>) -> Result<Vec<String>> {
+        node_info!("engine_exchangeCapabilities");
+        Ok(ENGINE_CAPABILITIES.iter().map(|method| method.to_string()).collect())
+    }
+
+    /// Makes the given head the best block and starts building a payload on top of it, if
+    /// attributes are provided.
+    ///
+    /// If the head is an ancestor of the current best block, all blocks after it are discarded. If
+    /// it's a valid payload that was received via `engine_newPayload` but doesn't extend the
+    /// canonical chain, the chain is reorged onto it.
+    ///
+    /// Handler for RPC call: `engine_forkchoiceUpdatedV{1,2,3}`
+    pub async fn engine_forkchoice_updated(
+        &self,
+        version: u8,
+        state: ForkchoiceState,
+        payload_attributes: Option<PayloadAttributes>,
+    ) -> Result<ForkchoiceUpdated> {
+        node_info!("engine_forkchoiceUpdatedV{}", version);
+        let head_hash = state.head_block_hash;
+        let head = match self.canonical_block(head_hash) {
+            Some(head) => head,
+            None => {
+                let side_chain = self.engine.side_chain(head_hash);
+                let Some(ancestor) =
+                    side_chain.first().and_then(|block| self.canonical_block(block.parent_hash))
+                else {
+                    return Ok(ForkchoiceUpdated::from_status(PayloadStatusEnum::Syncing));
+                };
+                // keep the discarded blocks, so the chain can be reorged back onto them
+                for block in self.discarded_blocks(&ancestor) {
+                    self.engine.insert_side_block(block);
+                }
+                self.backend.rewind_to(ancestor).await?;
+                for block in side_chain {
+                    let hash = block.hash;
+                    match self.backend.import_block(block).await {
+                        Ok(outcome) => {
+                            self.engine.remove_side_blocks(&[hash]);
+                            self.pool.on_mined_block(outcome);
+                        }
+                        Err(err) => {
+                            let status = PayloadStatus::new(
+                                PayloadStatusEnum::Invalid { validation_error: err.to_string() },
+                                Some(self.backend.best_hash()),
+                            );
+                            return Ok(ForkchoiceUpdated::new(status));
+                        }
+                    }
+                }
+                self.canonical_block(head_hash).ok_or(BlockchainError::BlockNotFound)?
+            }
+        };
+        for hash in [state.safe_block_hash, state.finalized_block_hash] {
+            if hash.is_zero() {
+                continue
+            }
+            match self.canonical_block(hash) {
+                Some(block) if block.header.number <= head.header.number => {}
+                _ => return Err(BlockchainError::InvalidForkchoiceState),
+            }
+        }
+
+        if head_hash != self.backend.best_hash() {
+            for block in self.discarded_blocks(&head) {
+                self.engine.insert_side_block(block);
+            }
+            self.backend.rewind_to(head.clone()).await?;
+        }
+
+        let status = PayloadStatus::new(PayloadStatusEnum::Valid, Some(head_hash));
+        let Some(payload_attributes) = payload_attributes else {
+            return Ok(ForkchoiceUpdated::new(status));
+        };
+
+        if payload_attributes.timestamp <= head.header.timestamp {
+            return Err(BlockchainError::InvalidPayloadAttributes(
+                "timestamp must be greater than the timestamp of the head block".to_string(),
+            ));
+        }
+        let id = engine::payload_id(head_hash, &payload_attributes);
+        let attributes = engine::block_attributes(version, payload_attributes)?;
+        let payload = self.build_payload(&attributes).await;
+        self.engine.insert(id, PayloadJob { parent_hash: head_hash, attributes, payload });
+
+        Ok(ForkchoiceUpdated::new(status).with_payload_id(id))
+    }
+
+    /// Returns the payload requested via `engine_forkchoiceUpdated`.
+    ///
+    /// If the best block didn't change since, the payload is rebuilt to include all transactions
+    /// that became ready in the meantime.
+    ///
+    /// Handler for RPC call: `engine_getPayloadV{1,2,3}`
+    pub async fn engine_get_payload(&self, id: PayloadId) -> Result<BuiltPayload> {
+        node_info!("engine_getPayload");
+        let job = self.engine.get(&id).ok_or(BlockchainError::UnknownPayload)?;
+        if job.parent_hash != self.backend.best_hash() {
+            return Ok(job.payload);
+        }
+        let payload = self.build_payload(&job.attributes).await;
+        self.engine.set_payload(&id, payload.clone());
+        Ok(payload)
+    }
+
+    /// Executes the payload and makes it the new best block if it's valid.
+    ///
+    /// Payloads that don't extend the current best block are validated on top of their parent and
+    /// kept, so a forkchoice update can reorg onto them. `SYNCING` is returned if the parent is
+    /// unknown or its state is no longer available.
+    ///
+    /// Handler for RPC call: `engine_newPayloadV{1,2,3}`
+    pub async fn engine_new_payload(
+        &self,
+        payload: ExecutionPayload,
+        versioned_hashes: Option<Vec<B256>>,
+        parent_beacon_block_root: Option<B256>,
+    ) -> Result<PayloadStatus> {
+        node_info!("engine_newPayload");
+        let block_hash = payload.block_hash();
+        if self.canonical_block(block_hash).is_some() ||
+            !self.engine.side_chain(block_hash).is_empty()
+        {
+            return Ok(PayloadStatus::new(PayloadStatusEnum::Valid, Some(block_hash)));
+        }
+
+        let parent_hash = payload.parent_hash();
+        let side_chain = self.engine.side_chain(parent_hash);
+        let parent_timestamp = match side_chain.last() {
+            Some(parent) => parent.attributes.timestamp,
+            None => match self.canonical_block(parent_hash) {
+                Some(parent) => parent.header.timestamp,
+                None => return Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing)),
+            },
+        };
+
+        let invalid = |validation_error: String| {
+            Ok(PayloadStatus::new(
+                PayloadStatusEnum::Invalid { validation_error },
+                Some(parent_hash),
+            ))
+        };
+
+        if payload.withdrawals().is_some_and(|withdrawals| !withdrawals.is_empty()) {
+            return invalid("withdrawals are not supported".to_string());
+        }
+        let payload = payload.into_v1();
+        if payload.timestamp <= parent_timestamp {
+            return invalid(
+                "timestamp must be greater than the timestamp of the parent".to_string(),
+            );
+        }
+
+        let Ok(base_fee_per_gas) = u64::try_from(payload.base_fee_per_gas) else {
+            return invalid("base fee exceeds 64 bits".to_string());
+        };
+
+        let mut transactions = Vec::with_capacity(payload.transactions.len());
+        let mut blob_versioned_hashes = Vec::new();
+        for raw in &payload.transactions {
+            let Ok(transaction) = TypedTransaction::decode_2718(&mut raw.as_ref()) else {
+                return invalid("failed to decode transaction".to_string());
+            };
+            let Ok(pending_transaction) = PendingTransaction::new(transaction) else {
+                return invalid("failed to recover transaction signer".to_string());
+            };
+            blob_versioned_hashes.extend(
+                pending_transaction
+                    .transaction
+                    .essentials()
+                    .blob_versioned_hashes
+                    .unwrap_or_default(),
+            );
+            let nonce = pending_transaction.nonce();
+            let provides = vec![to_marker(nonce, *pending_transaction.sender())];
+            transactions.push(Arc::new(PoolTransaction {
+                pending_transaction,
+                requires: vec![],
+                provides,
+                priority: TransactionPriority(0),
+            }));
+        }
+        if versioned_hashes.is_some_and(|expected| expected != blob_versioned_hashes) {
+            return invalid("mismatched blob versioned hashes".to_string());
+        }
+
+        let attributes = BlockAttributes {
+            timestamp: payload.timestamp,
+            prev_randao: payload.prev_randao,
+            fee_recipient: payload.fee_recipient,
+            parent_beacon_block_root,
+            extra_data: payload.extra_data,
+            gas_limit: Some(payload.gas_limit),
+            base_fee_per_gas: Some(base_fee_per_gas),
+        };
+
+        let block = ImportedBlock { hash: block_hash, parent_hash, transactions, attributes };
+        if parent_hash == self.backend.best_hash() {
+            return match self.backend.import_block(block).await {
+                Ok(outcome) => {
+                    self.pool.on_mined_block(outcome);
+                    Ok(PayloadStatus::new(PayloadStatusEnum::Valid, Some(block_hash)))
+                }
+                // the best block changed in the meantime
+                Err(BlockImportError::UnknownParent(_)) => {
+                    Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing))
+                }
+                Err(err) => invalid(err.to_string()),
+            };
+        }
+
+        // the payload extends a side chain or an ancestor of the best block
+        let ancestor_hash = side_chain.first().map_or(parent_hash, |block| block.parent_hash);
+        let Some(ancestor) = self.canonical_block(ancestor_hash) else {
+            return Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing));
+        };
+        let mut chain = side_chain;
+        chain.push(block.clone());
+        match self.backend.validate_side_chain(&ancestor.header, &chain).await {
+            Ok(()) => {
+                self.engine.insert_side_block(block);
+                Ok(PayloadStatus::new(PayloadStatusEnum::Valid, Some(block_hash)))
+            }
+            Err(BlockImportError::MissingState(_)) => {
+                Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing))
+            }
+            Err(err) => invalid(err.to_string()),
+        }
+    }
+
+    /// Builds a payload with all ready transactions on top of the current best block
+    async fn build_payload(&self, attributes: &BlockAttributes) -> BuiltPayload {
+        let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
+        let (block, hash) =
+            self.backend.build_block_with_attributes(transactions, attributes).await;
+        BuiltPayload::new(&block, hash)
+    }
+
+    /// Returns the block with the given hash, if it's part of the canonical chain
+    pub(crate) fn canonical_block(&self, hash: B256) -> Option<Block> {
+        let block = self.backend.get_block_by_hash(hash)?;
+        let canonical = self.backend.get_block(block.header.number)?;
+        (canonical.header.hash_slow() == hash).then_some(block)
+    }
+}
+
 // ===== impl Wallet endppoints =====
 impl EthApi {
     /// Get the capabilities of the wallet.
@@ -2803,11 +3816,46 @@ pub fn new_block_notifications(&self) -> NewBlockNotifications {
         self.backend.new_block_notifications()
     }
 
+    /// Returns a new stream of canonical chain changes, including blocks removed by reorgs
+    pub fn chain_notifications(&self) -> ChainNotifications {
+        self.backend.chain_notifications()
+    }
+
     /// Returns a new listeners for ready transactions
     pub fn new_ready_transactions(&self) -> Receiver<TxHash> {
         self.pool.add_ready_listener()
     }
 
+    /// Records an RPC call of the `method` that was handled in `elapsed`
+    pub fn on_rpc_call_handled(&self, method: &str, elapsed: Duration, response: &ResponseResult) {
+        self.rpc_metrics.record(method, elapsed, matches!(response, ResponseResult::Error(_)));
+    }
+
+    /// Executes the [EthRequest] of an RPC call, and records the call if a session is recorded
+    pub async fn execute_session_call(&self, call: SessionCall<EthRequest>) -> ResponseResult {
+        let SessionCall { method, params, request } = call;
+        let Some(recorder) = &self.session_recorder else { return self.execute(request).await };
+
+        let received = recorder.receive(self);
+        let response = self.execute(request).await;
+        received.record(method, params, response)
+    }
+
+    /// Returns the metrics of the node in the Prometheus text format
+    pub fn metrics(&self) -> String {
+        let status = self.pool.txpool_status();
+        NodeMetrics {
+            rpc: &self.rpc_metrics,
+            pool: self.pool.metrics(),
+            pending_transactions: status.pending,
+            queued_transactions: status.queued,
+            miner: self.miner.metrics(),
+            block_number: self.backend.best_number(),
+            fork: self.get_fork().map(|fork| fork.metrics),
+        }
+        .encode()
+    }
+
     /// Returns a new accessor for certain storage elements
     pub fn storage_info(&self) -> StorageInfo {
         StorageInfo::new(Arc::clone(&self.backend))
@@ -2821,12 +3869,54 @@ pub fn is_fork(&self) -> bool {
     /// Mines exactly one block
     pub async fn mine_one(&self) {
         let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
+        let start = Instant::now();
         let outcome = self.backend.mine_block(transactions).await;
+        self.miner.metrics().on_mined_block(&outcome, start.elapsed());
 
         trace!(target: "node", blocknumber = ?outcome.block_number, "mined block");
         self.pool.on_mined_block(outcome);
     }
 
+    /// Returns the transaction with the given hash if it's in the pool
+    pub fn pool_transaction(&self, hash: TxHash) -> Option<PendingTransaction> {
+        self.pool.get_transaction(hash)
+    }
+
+    /// Adds a transaction that was received from another node to the pool
+    pub async fn add_peer_transaction(
+        &self,
+        pending_transaction: PendingTransaction,
+    ) -> Result<TxHash> {
+        if self.pool.contains(pending_transaction.hash()) {
+            return Ok(*pending_transaction.hash());
+        }
+        self.backend.validate_pool_transaction(&pending_transaction).await?;
+
+        let from = *pending_transaction.sender();
+        let nonce = pending_transaction.nonce();
+        let on_chain_nonce = self.backend.current_nonce(from).await?;
+        let requires = required_marker(nonce, on_chain_nonce, from);
+        self.add_pending_transaction(pending_transaction, requires, vec![to_marker(nonce, from)])
+    }
+
+    /// Imports a block that was mined by another node on top of the current best block.
+    ///
+    /// The block is re-executed and only added to the chain if this results in the same block.
+    pub async fn import_block(&self, block: &Block) -> std::result::Result<(), BlockImportError> {
+        let outcome = self.backend.import_block(imported_block(block)?).await?;
+        self.pool.on_mined_block(outcome);
+        Ok(())
+    }
+
+    /// Returns the blocks of the canonical chain after `ancestor`, which are discarded when the
+    /// chain is rewound to it
+    fn discarded_blocks(&self, ancestor: &Block) -> Vec<ImportedBlock> {
+        ((ancestor.header.number + 1)..=self.backend.best_number())
+            .filter_map(|number| self.backend.get_block(number))
+            .filter_map(|block| imported_block(&block).ok())
+            .collect()
+    }
+
     /// Returns the pending block with tx hashes
     async fn pending_block(&self) -> AnyRpcBlock {
         let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
@@ -3001,6 +4091,44 @@ async fn request_nonce(
         Ok((nonce, highest_nonce))
     }
 
+    /// Returns the bundler if it accepts user operations for the EntryPoint
+    fn bundler_for(&self, entry_point: Address) -> Result<&Bundler> {
+        let bundler = self.bundler.as_deref().ok_or_else(RpcError::method_not_found)?;
+        if bundler.entry_point() != entry_point {
+            return Err(RpcError::invalid_params(format!(
+                "unsupported EntryPoint {entry_point}, supported is {}",
+                bundler.entry_point()
+            ))
+            .into())
+        }
+        Ok(bundler)
+    }
+
+    /// Returns an error if there's no code at the EntryPoint, as `handleOps` calls to it would
+    /// succeed without executing the user operation
+    async fn ensure_entry_point_deployed(&self, entry_point: Address) -> Result<()> {
+        if self.backend.get_code(entry_point, None).await?.is_empty() {
+            return Err(bundler::user_operation_error(
+                bundler::USER_OPERATION_REJECTED,
+                format!("EntryPoint {entry_point} is not deployed"),
+            )
+            .into())
+        }
+        Ok(())
+    }
+
+    /// Returns the account that sends the `handleOps` transactions of the bundler, the last
+    /// account the node can sign for, and the beneficiary of their fees
+    fn bundler_accounts(&self, bundler: &Bundler) -> Result<(Address, Address)> {
+        let from = self
+            .signers
+            .iter()
+            .flat_map(|signer| signer.accounts())
+            .last()
+            .ok_or(BlockchainError::NoSignerAvailable)?;
+        Ok((from, bundler.beneficiary().unwrap_or(from)))
+    }
+
     /// Adds the given transaction to the pool
     fn add_pending_transaction(
         &self,
@@ -3033,6 +4161,49 @@ fn ensure_typed_transaction_supported(&self, tx: &TypedTransaction) -> Result<()
             TypedTransaction::Legacy(_) => Ok(()),
         }
     }
+
+    /// Decodes a signed, EIP-2718 encoded transaction and recovers its sender
+    fn decode_raw_transaction(&self, tx: Bytes) -> Result<PendingTransaction> {
+        let mut data = tx.as_ref();
+        if data.is_empty() {
+            return Err(BlockchainError::EmptyRawTransactionData);
+        }
+
+        let transaction = TypedTransaction::decode_2718(&mut data)
+            .map_err(|_| BlockchainError::FailedToDecodeSignedTransaction)?;
+
+        self.ensure_typed_transaction_supported(&transaction)?;
+
+        Ok(PendingTransaction::new(transaction)?)
+    }
+}
+
+/// Returns the transaction of a bundle, which only provides its own nonce since bundles are never
+/// part of the pool
+fn bundled_transaction(transaction: PendingTransaction) -> PoolTransaction {
+    let provides = vec![to_marker(transaction.nonce(), *transaction.sender())];
+    PoolTransaction { provides, ..PoolTransaction::new(transaction) }
+}
+
+/// Converts a mined block so it can be imported, its transactions only provide their own nonce
+/// since they're never part of the pool
+fn imported_block(block: &Block) -> std::result::Result<ImportedBlock, BlockImportError> {
+    let transactions = block
+        .transactions
+        .iter()
+        .map(|tx| {
+            let transaction = PendingTransaction::from_mined(tx.clone())
+                .map_err(|_| BlockImportError::InvalidTransactions)?;
+            let provides = vec![to_marker(transaction.nonce(), *transaction.sender())];
+            Ok(Arc::new(PoolTransaction { provides, ..PoolTransaction::new(transaction) }))
+        })
+        .collect::<std::result::Result<_, _>>()?;
+    Ok(ImportedBlock {
+        hash: block.header.hash_slow(),
+        parent_hash: block.header.parent_hash,
+        transactions,
+        attributes: BlockAttributes::from_header(&block.header),
+    })
 }
 
 fn required_marker(provided_nonce: u64, on_chain_nonce: u64, from: Address) -> Vec<TxMarker> {
@@ -185,6 +185,11 @@ fn load_state(&mut self, state: SerializableState) -> DatabaseResult<bool> {
     /// Returns `true` if the state snapshot was reverted.
     fn revert_state(&mut self, state_snapshot: U256, action: RevertStateSnapshotAction) -> bool;
 
+    /// Returns the accounts of the state snapshot with the given `id`, if it exists.
+    fn state_snapshot_accounts(&self, _id: U256) -> Option<HashMap<Address, DbAccount>> {
+        None
+    }
+
     /// Returns the state root if possible to compute
     fn maybe_state_root(&self) -> Option<B256> {
         None
@@ -335,6 +340,12 @@ pub fn serialize_state(&mut self) -> StateSnapshot {
     }
 }
 
+impl fmt::Debug for StateDb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateDb").finish_non_exhaustive()
+    }
+}
+
 impl DatabaseRef for StateDb {
     type Error = DatabaseError;
     fn basic_ref(&self, address: Address) -> DatabaseResult<Option<AccountInfo>> {
@@ -1,8 +1,9 @@
 use crate::{
     eth::{
-        backend::{db::Db, validate::TransactionValidator},
+        backend::{db::Db, mem::chain_db::StateChangeset, validate::TransactionValidator},
         error::InvalidTransactionError,
-        pool::transactions::PoolTransaction,
+        miner::BlockItem,
+        pool::{bundle::Bundle, transactions::PoolTransaction},
     },
     inject_precompiles,
     mem::inspector::Inspector,
@@ -23,14 +24,17 @@
     revm::{
         interpreter::InstructionResult,
         primitives::{
-            BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, ExecutionResult, Output,
-            SpecId,
+            BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, EvmState, ExecutionResult,
+            Output, ResultAndState, SpecId,
         },
     },
     traces::CallTraceNode,
     utils::odyssey_handler_register,
 };
-use revm::db::WrapDatabaseRef;
+use revm::{
+    db::{CacheDB, WrapDatabaseRef},
+    DatabaseCommit,
+};
 use std::sync::Arc;
 
 /// Represents an executed transaction (transacted on the DB)
@@ -43,18 +47,25 @@ pub struct ExecutedTransaction {
     logs: Vec<Log>,
     traces: Vec<CallTraceNode>,
     nonce: u64,
+    /// The state modified by the transaction
+    state_changes: StateChangeset,
 }
 
 // == impl ExecutedTransaction ==
 
 impl ExecutedTransaction {
+    /// Returns whether the transaction succeeded
+    fn is_success(&self) -> bool {
+        self.exit_reason as u8 <= InstructionResult::SelfDestruct as u8
+    }
+
     /// Creates the receipt for the transaction
     fn create_receipt(&self, cumulative_gas_used: &mut u64) -> TypedReceipt {
         let logs = self.logs.clone();
         *cumulative_gas_used = cumulative_gas_used.saturating_add(self.gas_used);
 
         // successful return see [Return]
-        let status_code = u8::from(self.exit_reason as u8 <= InstructionResult::SelfDestruct as u8);
+        let status_code = u8::from(self.is_success());
         let receipt_with_bloom: ReceiptWithBloom = Receipt {
             status: (status_code == 1).into(),
             cumulative_gas_used: *cumulative_gas_used,
@@ -87,6 +98,8 @@ pub struct ExecutedTransactions {
     /// All transactions that were invalid at the point of their execution and were not included in
     /// the block
     pub invalid: Vec<Arc<PoolTransaction>>,
+    /// The state modified by all `included` transactions
+    pub state_changes: StateChangeset,
 }
 
 /// An executor for a series of transactions
@@ -95,8 +108,8 @@ pub struct TransactionExecutor<'a, Db: ?Sized, V: TransactionValidator> {
     pub db: &'a mut Db,
     /// type used to validate before inclusion
     pub validator: &'a V,
-    /// all pending transactions
-    pub pending: std::vec::IntoIter<Arc<PoolTransaction>>,
+    /// all pending transactions and bundles
+    pub pending: std::vec::IntoIter<BlockItem>,
     pub block_env: BlockEnv,
     /// The configuration environment and spec id
     pub cfg_env: CfgEnvWithHandlerCfg,
@@ -122,6 +135,7 @@ pub fn execute(mut self) -> ExecutedTransactions {
         let mut cumulative_gas_used = 0u64;
         let mut invalid = Vec::new();
         let mut included = Vec::new();
+        let mut state_changes = StateChangeset::default();
         let gas_limit = self.block_env.gas_limit.to::<u64>();
         let parent_hash = self.parent_hash;
         let block_number = self.block_env.number.to::<u64>();
@@ -140,11 +154,13 @@ pub fn execute(mut self) -> ExecutedTransactions {
         let excess_blob_gas = if is_cancun { self.block_env.get_blob_excess_gas() } else { None };
         let mut cumulative_blob_gas_used = if is_cancun { Some(0u64) } else { None };
 
-        for tx in self.into_iter() {
-            let tx = match tx {
-                TransactionExecutionOutcome::Executed(tx) => {
-                    included.push(tx.transaction.clone());
-                    tx
+        for outcome in self.into_iter() {
+            let executed = match outcome {
+                TransactionExecutionOutcome::Executed(tx) => vec![tx],
+                TransactionExecutionOutcome::BundleExecuted(txs) => txs,
+                TransactionExecutionOutcome::BundleRejected(bundle, reason) => {
+                    trace!(target: "backend", bundle = ?bundle.hash, %reason, "skipping bundle");
+                    continue
                 }
                 TransactionExecutionOutcome::Exhausted(tx) => {
                     trace!(target: "backend",  tx_gas_limit = %tx.pending_transaction.transaction.gas_limit(), ?tx,  "block gas limit exhausting, skipping transaction");
@@ -166,48 +182,60 @@ pub fn execute(mut self) -> ExecutedTransactions {
                     continue
                 }
             };
-            if is_cancun {
-                let tx_blob_gas = tx
-                    .transaction
-                    .pending_transaction
-                    .transaction
-                    .transaction
-                    .blob_gas()
-                    .unwrap_or(0);
-                cumulative_blob_gas_used =
-                    Some(cumulative_blob_gas_used.unwrap_or(0u64).saturating_add(tx_blob_gas));
-            }
-            let receipt = tx.create_receipt(&mut cumulative_gas_used);
-
-            let ExecutedTransaction { transaction, logs, out, traces, exit_reason: exit, .. } = tx;
-            build_logs_bloom(logs.clone(), &mut bloom);
-
-            let contract_address = out.as_ref().and_then(|out| {
-                if let Output::Create(_, contract_address) = out {
-                    trace!(target: "backend", "New contract deployed: at {:?}", contract_address);
-                    *contract_address
-                } else {
-                    None
+            for tx in executed {
+                included.push(tx.transaction.clone());
+                if is_cancun {
+                    let tx_blob_gas = tx
+                        .transaction
+                        .pending_transaction
+                        .transaction
+                        .transaction
+                        .blob_gas()
+                        .unwrap_or(0);
+                    cumulative_blob_gas_used =
+                        Some(cumulative_blob_gas_used.unwrap_or(0u64).saturating_add(tx_blob_gas));
                 }
-            });
-
-            let transaction_index = transaction_infos.len() as u64;
-            let info = TransactionInfo {
-                transaction_hash: transaction.hash(),
-                transaction_index,
-                from: *transaction.pending_transaction.sender(),
-                to: transaction.pending_transaction.transaction.to(),
-                contract_address,
-                traces,
-                exit,
-                out: out.map(Output::into_data),
-                nonce: tx.nonce,
-                gas_used: tx.gas_used,
-            };
-
-            transaction_infos.push(info);
-            receipts.push(receipt);
-            transactions.push(transaction.pending_transaction.transaction.clone());
+                let receipt = tx.create_receipt(&mut cumulative_gas_used);
+
+                let ExecutedTransaction {
+                    transaction,
+                    logs,
+                    out,
+                    traces,
+                    exit_reason: exit,
+                    state_changes: changes,
+                    ..
+                } = tx;
+                state_changes.extend(changes);
+                build_logs_bloom(logs.clone(), &mut bloom);
+
+                let contract_address = out.as_ref().and_then(|out| {
+                    if let Output::Create(_, contract_address) = out {
+                        trace!(target: "backend", "New contract deployed: at {:?}", contract_address);
+                        *contract_address
+                    } else {
+                        None
+                    }
+                });
+
+                let transaction_index = transaction_infos.len() as u64;
+                let info = TransactionInfo {
+                    transaction_hash: transaction.hash(),
+                    transaction_index,
+                    from: *transaction.pending_transaction.sender(),
+                    to: transaction.pending_transaction.transaction.to(),
+                    contract_address,
+                    traces,
+                    exit,
+                    out: out.map(Output::into_data),
+                    nonce: tx.nonce,
+                    gas_used: tx.gas_used,
+                };
+
+                transaction_infos.push(info);
+                receipts.push(receipt);
+                transactions.push(transaction.pending_transaction.transaction.clone());
+            }
         }
 
         let receipts_root =
@@ -237,7 +265,7 @@ pub fn execute(mut self) -> ExecutedTransactions {
 
         let block = Block::new(partial_header, transactions.clone());
         let block = BlockInfo { block, transactions: transaction_infos, receipts };
-        ExecutedTransactions { block, included, invalid }
+        ExecutedTransactions { block, included, invalid, state_changes }
     }
 
     fn env_for(&self, tx: &PendingTransaction) -> EnvWithHandlerCfg {
@@ -264,24 +292,129 @@ pub enum TransactionExecutionOutcome {
     BlobGasExhausted(Arc<PoolTransaction>),
     /// When an error occurred during execution
     DatabaseError(Arc<PoolTransaction>, DatabaseError),
+    /// All transactions of the bundle were successfully executed
+    BundleExecuted(Vec<ExecutedTransaction>),
+    /// The bundle was not executed because not all of its transactions can be included
+    BundleRejected(Bundle, String),
 }
 
 impl<DB: Db + ?Sized, V: TransactionValidator> Iterator for &mut TransactionExecutor<'_, DB, V> {
     type Item = TransactionExecutionOutcome;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let transaction = self.pending.next()?;
+        match self.pending.next()? {
+            BlockItem::Transaction(transaction) => Some(self.execute_transaction(transaction)),
+            BlockItem::Bundle(bundle) => Some(self.execute_bundle(bundle)),
+        }
+    }
+}
+
+impl<DB: Db + ?Sized, V: TransactionValidator> TransactionExecutor<'_, DB, V> {
+    /// Executes the bundle if all of its transactions can be included.
+    ///
+    /// The transactions are executed on top of the current state without committing them, and
+    /// only committed if none of them is invalid, exceeds the block's limits, or reverts without
+    /// being allowed to.
+    fn execute_bundle(&mut self, bundle: Bundle) -> TransactionExecutionOutcome {
+        if !bundle.is_valid_for_timestamp(self.block_env.timestamp.to()) {
+            let reason = "block timestamp out of range".to_string();
+            return TransactionExecutionOutcome::BundleRejected(bundle, reason)
+        }
+
+        let mut overlay = CacheDB::new(&*self.db);
+        let mut executor = TransactionExecutor {
+            db: &mut overlay,
+            validator: self.validator,
+            pending: Vec::new().into_iter(),
+            block_env: self.block_env.clone(),
+            cfg_env: self.cfg_env.clone(),
+            parent_hash: self.parent_hash,
+            gas_used: self.gas_used,
+            blob_gas_used: self.blob_gas_used,
+            enable_steps_tracing: self.enable_steps_tracing,
+            odyssey: self.odyssey,
+            print_logs: self.print_logs,
+            precompile_factory: self.precompile_factory.clone(),
+        };
+
+        let mut executed = Vec::with_capacity(bundle.transactions.len());
+        let mut states = Vec::with_capacity(bundle.transactions.len());
+        for transaction in &bundle.transactions {
+            let hash = transaction.hash();
+            let (outcome, state) = executor.transact(transaction.clone());
+            let reason = match outcome {
+                TransactionExecutionOutcome::Executed(tx)
+                    if !tx.is_success() && !bundle.can_revert(&hash) =>
+                {
+                    format!("transaction {hash} reverted")
+                }
+                TransactionExecutionOutcome::Executed(tx) => {
+                    executor.db.commit(state.clone());
+                    executed.push(tx);
+                    states.push(state);
+                    continue
+                }
+                TransactionExecutionOutcome::Invalid(_, err) => {
+                    format!("transaction {hash} is invalid: {err}")
+                }
+                TransactionExecutionOutcome::Exhausted(_) => {
+                    format!("transaction {hash} exceeds the block gas limit")
+                }
+                TransactionExecutionOutcome::BlobGasExhausted(_) => {
+                    format!("transaction {hash} exceeds the block blob gas limit")
+                }
+                TransactionExecutionOutcome::DatabaseError(_, err) => {
+                    format!("transaction {hash} failed: {err}")
+                }
+                outcome => format!("transaction {hash} failed: {outcome:?}"),
+            };
+            return TransactionExecutionOutcome::BundleRejected(bundle, reason)
+        }
+
+        let (gas_used, blob_gas_used) = (executor.gas_used, executor.blob_gas_used);
+        drop(overlay);
+        for state in states {
+            self.db.commit(state);
+        }
+        self.gas_used = gas_used;
+        self.blob_gas_used = blob_gas_used;
+        TransactionExecutionOutcome::BundleExecuted(executed)
+    }
+
+    /// Executes a single transaction and commits it
+    fn execute_transaction(
+        &mut self,
+        transaction: Arc<PoolTransaction>,
+    ) -> TransactionExecutionOutcome {
+        let (outcome, state) = self.transact(transaction);
+        self.db.commit(state);
+        outcome
+    }
+
+    /// Executes a single transaction without committing it.
+    ///
+    /// Returns the outcome and the state modified by the transaction, which is empty unless it
+    /// was executed.
+    fn transact(
+        &mut self,
+        transaction: Arc<PoolTransaction>,
+    ) -> (TransactionExecutionOutcome, EvmState) {
         let sender = *transaction.pending_transaction.sender();
         let account = match self.db.basic(sender).map(|acc| acc.unwrap_or_default()) {
             Ok(account) => account,
-            Err(err) => return Some(TransactionExecutionOutcome::DatabaseError(transaction, err)),
+            Err(err) => {
+                return (
+                    TransactionExecutionOutcome::DatabaseError(transaction, err),
+                    EvmState::default(),
+                )
+            }
         };
         let env = self.env_for(&transaction.pending_transaction);
 
         // check that we comply with the block's gas limit, if not disabled
         let max_gas = self.gas_used.saturating_add(env.tx.gas_limit);
         if !env.cfg.disable_block_gas_limit && max_gas > env.block.gas_limit.to::<u64>() {
-            return Some(TransactionExecutionOutcome::Exhausted(transaction))
+            return (TransactionExecutionOutcome::Exhausted(transaction), EvmState::default())
         }
 
         // check that we comply with the block's blob gas limit
@@ -289,7 +422,7 @@ fn next(&mut self) -> Option<Self::Item> {
             transaction.pending_transaction.transaction.transaction.blob_gas().unwrap_or(0),
         );
         if max_blob_gas > alloy_eips::eip4844::MAX_DATA_GAS_PER_BLOCK {
-            return Some(TransactionExecutionOutcome::BlobGasExhausted(transaction))
+            return (TransactionExecutionOutcome::BlobGasExhausted(transaction), EvmState::default())
         }
 
         // validate before executing
@@ -299,7 +432,7 @@ fn next(&mut self) -> Option<Self::Item> {
             &env,
         ) {
             warn!(target: "backend", "Skipping invalid tx execution [{:?}] {}", transaction.hash(), err);
-            return Some(TransactionExecutionOutcome::Invalid(transaction, err))
+            return (TransactionExecutionOutcome::Invalid(transaction, err), EvmState::default())
         }
 
         let nonce = account.nonce;
@@ -313,30 +446,29 @@ fn next(&mut self) -> Option<Self::Item> {
             inspector = inspector.with_log_collector();
         }
 
-        let exec_result = {
+        let ResultAndState { result: exec_result, state } = {
             let mut evm = new_evm_with_inspector(&mut *self.db, env, &mut inspector, self.odyssey);
             if let Some(factory) = &self.precompile_factory {
                 inject_precompiles(&mut evm, factory.precompiles());
             }
 
             trace!(target: "backend", "[{:?}] executing", transaction.hash());
-            // transact and commit the transaction
-            match evm.transact_commit() {
+            match evm.transact() {
                 Ok(exec_result) => exec_result,
                 Err(err) => {
                     warn!(target: "backend", "[{:?}] failed to execute: {:?}", transaction.hash(), err);
                     match err {
                         EVMError::Database(err) => {
-                            return Some(TransactionExecutionOutcome::DatabaseError(
-                                transaction,
-                                err,
-                            ))
+                            return (
+                                TransactionExecutionOutcome::DatabaseError(transaction, err),
+                                EvmState::default(),
+                            )
                         }
                         EVMError::Transaction(err) => {
-                            return Some(TransactionExecutionOutcome::Invalid(
-                                transaction,
-                                err.into(),
-                            ))
+                            return (
+                                TransactionExecutionOutcome::Invalid(transaction, err.into()),
+                                EvmState::default(),
+                            )
                         }
                         // This will correspond to prevrandao not set, and it should never happen.
                         // If it does, it's a bug.
@@ -347,6 +479,10 @@ fn next(&mut self) -> Option<Self::Item> {
         };
         inspector.print_logs();
 
+        // record the modified state
+        let mut state_changes = StateChangeset::default();
+        state_changes.record(&state);
+
         let (exit_reason, gas_used, out, logs) = match exec_result {
             ExecutionResult::Success { reason, gas_used, logs, output, .. } => {
                 (reason.into(), gas_used, Some(output), Some(logs))
@@ -382,14 +518,15 @@ fn next(&mut self) -> Option<Self::Item> {
             logs: logs.unwrap_or_default(),
             traces: inspector.tracer.map(|t| t.into_traces().into_nodes()).unwrap_or_default(),
             nonce,
+            state_changes,
         };
 
-        Some(TransactionExecutionOutcome::Executed(tx))
+        (TransactionExecutionOutcome::Executed(tx), state)
     }
 }
 
 /// Inserts all logs into the bloom
-fn build_logs_bloom(logs: Vec<Log>, bloom: &mut Bloom) {
+pub(crate) fn build_logs_bloom(logs: Vec<Log>, bloom: &mut Bloom) {
     for log in logs {
         bloom.accrue(BloomInput::Raw(&log.address[..]));
         for topic in log.topics() {
@@ -1,11 +1,14 @@
 //! Support for forking off another client
 
-use crate::eth::{backend::db::Db, error::BlockchainError, pool::transactions::PoolTransaction};
+use crate::{
+    eth::{backend::db::Db, error::BlockchainError, pool::transactions::PoolTransaction},
+    metrics::ForkMetrics,
+};
 use alloy_consensus::Account;
 use alloy_eips::eip2930::AccessListResult;
 use alloy_network::{AnyRpcBlock, AnyRpcTransaction, BlockResponse, TransactionResponse};
 use alloy_primitives::{
-    map::{FbHashMap, HashMap},
+    map::{FbHashMap, HashMap, HashSet},
     Address, Bytes, StorageValue, B256, U256,
 };
 use alloy_provider::{
@@ -14,17 +17,25 @@
 };
 use alloy_rpc_types::{
     request::TransactionRequest,
+    simulate::{SimulatePayload, SimulatedBlock},
     trace::{
+        common::TraceResult,
         geth::{GethDebugTracingOptions, GethTrace},
-        parity::LocalizedTransactionTrace as Trace,
+        parity::{
+            LocalizedTransactionTrace as Trace, TraceResults, TraceResultsWithTransactionHash,
+            TraceType,
+        },
     },
     BlockId, BlockNumberOrTag as BlockNumber, BlockTransactions, EIP1186AccountProofResponse,
     FeeHistory, Filter, Log,
 };
 use alloy_serde::WithOtherFields;
 use alloy_transport::TransportError;
-use anvil_core::eth::transaction::{convert_to_anvil_receipt, ReceiptResponse};
-use foundry_common::provider::{ProviderBuilder, RetryProvider};
+use anvil_core::{
+    eth::transaction::{convert_to_anvil_receipt, ReceiptResponse},
+    types::StorageRangeResult,
+};
+use foundry_common::provider::{cassette::CassetteConfig, ProviderBuilder, RetryProvider};
 use parking_lot::{
     lock_api::{RwLockReadGuard, RwLockWriteGuard},
     RawRwLock, RwLock,
@@ -47,12 +58,19 @@ pub struct ClientFork {
     pub config: Arc<RwLock<ClientForkConfig>>,
     /// This also holds a handle to the underlying database
     pub database: Arc<AsyncRwLock<Box<dyn Db>>>,
+    /// Counts cache lookups and requests to the remote client
+    pub metrics: Arc<ForkMetrics>,
 }
 
 impl ClientFork {
     /// Creates a new instance of the fork
     pub fn new(config: ClientForkConfig, database: Arc<AsyncRwLock<Box<dyn Db>>>) -> Self {
-        Self { storage: Default::default(), config: Arc::new(RwLock::new(config)), database }
+        Self {
+            storage: Default::default(),
+            config: Arc::new(RwLock::new(config)),
+            database,
+            metrics: Default::default(),
+        }
     }
 
     /// Reset the fork to a fresh forked state, and optionally update the fork config
@@ -156,9 +174,20 @@ pub fn chain_id(&self) -> u64 {
     }
 
     fn provider(&self) -> Arc<RetryProvider> {
+        self.metrics.remote_calls.increment();
         self.config.read().provider.clone()
     }
 
+    /// Records whether a lookup could be served from the cache
+    fn cached<T>(&self, value: Option<T>) -> Option<T> {
+        if value.is_some() {
+            self.metrics.cache_hits.increment();
+        } else {
+            self.metrics.cache_misses.increment();
+        }
+        value
+    }
+
     fn storage_read(&self) -> RwLockReadGuard<'_, RawRwLock, ForkedStorage> {
         self.storage.read()
     }
@@ -199,6 +228,54 @@ pub async fn call(
         Ok(res)
     }
 
+    /// Sends `eth_simulateV1`
+    pub async fn simulate(
+        &self,
+        payload: &SimulatePayload,
+        block: Option<BlockNumber>,
+    ) -> Result<Vec<SimulatedBlock<AnyRpcBlock>>, TransportError> {
+        let block = block.unwrap_or(BlockNumber::Latest);
+        let res = self.provider().simulate(payload).block_id(block.into()).await?;
+
+        Ok(res)
+    }
+
+    /// Sends `trace_call`
+    pub async fn trace_call(
+        &self,
+        request: &WithOtherFields<TransactionRequest>,
+        trace_types: HashSet<TraceType>,
+        block: Option<BlockNumber>,
+    ) -> Result<TraceResults, TransportError> {
+        let block = block.unwrap_or(BlockNumber::Latest);
+        let trace_types = trace_types.into_iter().collect::<Vec<_>>();
+        let res = self.provider().trace_call(request, &trace_types).block_id(block.into()).await?;
+
+        Ok(res)
+    }
+
+    /// Sends `trace_callMany`
+    pub async fn trace_call_many(
+        &self,
+        calls: Vec<(WithOtherFields<TransactionRequest>, HashSet<TraceType>)>,
+        block: Option<BlockNumber>,
+    ) -> Result<Vec<TraceResults>, TransportError> {
+        let block = block.unwrap_or(BlockNumber::Latest);
+        let calls = calls
+            .into_iter()
+            .map(|(request, trace_types)| (request, trace_types.into_iter().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let calls =
+            calls.iter().map(|(request, trace_types)| (request.clone(), trace_types.as_slice()));
+        let res = self
+            .provider()
+            .trace_call_many(&calls.collect::<Vec<_>>())
+            .block_id(block.into())
+            .await?;
+
+        Ok(res)
+    }
+
     /// Sends `eth_call`
     pub async fn estimate_gas(
         &self,
@@ -233,7 +310,7 @@ pub async fn storage_at(
     }
 
     pub async fn logs(&self, filter: &Filter) -> Result<Vec<Log>, TransportError> {
-        if let Some(logs) = self.storage_read().logs.get(filter).cloned() {
+        if let Some(logs) = self.cached(self.storage_read().logs.get(filter).cloned()) {
             return Ok(logs);
         }
 
@@ -250,7 +327,9 @@ pub async fn get_code(
         blocknumber: u64,
     ) -> Result<Bytes, TransportError> {
         trace!(target: "backend::fork", "get_code={:?}", address);
-        if let Some(code) = self.storage_read().code_at.get(&(address, blocknumber)).cloned() {
+        if let Some(code) =
+            self.cached(self.storage_read().code_at.get(&(address, blocknumber)).cloned())
+        {
             return Ok(code);
         }
 
@@ -340,7 +419,7 @@ pub async fn transaction_by_hash(
         hash: B256,
     ) -> Result<Option<AnyRpcTransaction>, TransportError> {
         trace!(target: "backend::fork", "transaction_by_hash={:?}", hash);
-        if let tx @ Some(_) = self.storage_read().transactions.get(&hash).cloned() {
+        if let tx @ Some(_) = self.cached(self.storage_read().transactions.get(&hash).cloned()) {
             return Ok(tx);
         }
 
@@ -353,7 +432,9 @@ pub async fn transaction_by_hash(
     }
 
     pub async fn trace_transaction(&self, hash: B256) -> Result<Vec<Trace>, TransportError> {
-        if let Some(traces) = self.storage_read().transaction_traces.get(&hash).cloned() {
+        if let Some(traces) =
+            self.cached(self.storage_read().transaction_traces.get(&hash).cloned())
+        {
             return Ok(traces);
         }
 
@@ -370,7 +451,9 @@ pub async fn debug_trace_transaction(
         hash: B256,
         opts: GethDebugTracingOptions,
     ) -> Result<GethTrace, TransportError> {
-        if let Some(traces) = self.storage_read().geth_transaction_traces.get(&hash).cloned() {
+        if let Some(traces) =
+            self.cached(self.storage_read().geth_transaction_traces.get(&hash).cloned())
+        {
             return Ok(traces);
         }
 
@@ -382,8 +465,50 @@ pub async fn debug_trace_transaction(
         Ok(trace)
     }
 
+    pub async fn debug_trace_block(
+        &self,
+        number: u64,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult<GethTrace, String>>, TransportError> {
+        self.provider().debug_trace_block_by_number(number.into(), opts).await
+    }
+
+    pub async fn storage_range_at(
+        &self,
+        number: u64,
+        tx_index: u64,
+        address: Address,
+        start_key: B256,
+        max_result: u64,
+    ) -> Result<StorageRangeResult, TransportError> {
+        self.provider()
+            .raw_request(
+                "debug_storageRangeAt".into(),
+                (BlockId::number(number), tx_index, address, start_key, max_result),
+            )
+            .await
+    }
+
+    pub async fn trace_replay_transaction(
+        &self,
+        hash: B256,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<TraceResults, TransportError> {
+        let trace_types = trace_types.into_iter().collect::<Vec<_>>();
+        self.provider().trace_replay_transaction(hash, &trace_types).await
+    }
+
+    pub async fn trace_replay_block_transactions(
+        &self,
+        number: u64,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<Vec<TraceResultsWithTransactionHash>, TransportError> {
+        let trace_types = trace_types.into_iter().collect::<Vec<_>>();
+        self.provider().trace_replay_block_transactions(number.into(), &trace_types).await
+    }
+
     pub async fn trace_block(&self, number: u64) -> Result<Vec<Trace>, TransportError> {
-        if let Some(traces) = self.storage_read().block_traces.get(&number).cloned() {
+        if let Some(traces) = self.cached(self.storage_read().block_traces.get(&number).cloned()) {
             return Ok(traces);
         }
 
@@ -400,7 +525,9 @@ pub async fn transaction_receipt(
         &self,
         hash: B256,
     ) -> Result<Option<ReceiptResponse>, BlockchainError> {
-        if let Some(receipt) = self.storage_read().transaction_receipts.get(&hash).cloned() {
+        if let Some(receipt) =
+            self.cached(self.storage_read().transaction_receipts.get(&hash).cloned())
+        {
             return Ok(Some(receipt));
         }
 
@@ -419,7 +546,9 @@ pub async fn block_receipts(
         &self,
         number: u64,
     ) -> Result<Option<Vec<ReceiptResponse>>, BlockchainError> {
-        if let receipts @ Some(_) = self.storage_read().block_receipts.get(&number).cloned() {
+        if let receipts @ Some(_) =
+            self.cached(self.storage_read().block_receipts.get(&number).cloned())
+        {
             return Ok(receipts);
         }
 
@@ -451,7 +580,7 @@ pub async fn block_receipts(
     }
 
     pub async fn block_by_hash(&self, hash: B256) -> Result<Option<AnyRpcBlock>, TransportError> {
-        if let Some(mut block) = self.storage_read().blocks.get(&hash).cloned() {
+        if let Some(mut block) = self.cached(self.storage_read().blocks.get(&hash).cloned()) {
             block.transactions.convert_to_hashes();
             return Ok(Some(block));
         }
@@ -466,7 +595,7 @@ pub async fn block_by_hash_full(
         &self,
         hash: B256,
     ) -> Result<Option<AnyRpcBlock>, TransportError> {
-        if let Some(block) = self.storage_read().blocks.get(&hash).cloned() {
+        if let Some(block) = self.cached(self.storage_read().blocks.get(&hash).cloned()) {
             return Ok(Some(self.convert_to_full_block(block)));
         }
         self.fetch_full_block(hash).await
@@ -476,12 +605,12 @@ pub async fn block_by_number(
         &self,
         block_number: u64,
     ) -> Result<Option<AnyRpcBlock>, TransportError> {
-        if let Some(mut block) = self
-            .storage_read()
-            .hashes
-            .get(&block_number)
-            .and_then(|hash| self.storage_read().blocks.get(hash).cloned())
-        {
+        if let Some(mut block) = self.cached(
+            self.storage_read()
+                .hashes
+                .get(&block_number)
+                .and_then(|hash| self.storage_read().blocks.get(hash).cloned()),
+        ) {
             block.transactions.convert_to_hashes();
             return Ok(Some(block));
         }
@@ -497,13 +626,13 @@ pub async fn block_by_number_full(
         &self,
         block_number: u64,
     ) -> Result<Option<AnyRpcBlock>, TransportError> {
-        if let Some(block) = self
-            .storage_read()
-            .hashes
-            .get(&block_number)
-            .copied()
-            .and_then(|hash| self.storage_read().blocks.get(&hash).cloned())
-        {
+        if let Some(block) = self.cached(
+            self.storage_read()
+                .hashes
+                .get(&block_number)
+                .copied()
+                .and_then(|hash| self.storage_read().blocks.get(&hash).cloned()),
+        ) {
             return Ok(Some(self.convert_to_full_block(block)));
         }
 
@@ -561,7 +690,7 @@ async fn uncles_by_block_and_index(
     ) -> Result<Option<AnyRpcBlock>, TransportError> {
         let block_hash = block.header.hash;
         let block_number = block.header.number;
-        if let Some(uncles) = self.storage_read().uncles.get(&block_hash) {
+        if let Some(uncles) = self.cached(self.storage_read().uncles.get(&block_hash).cloned()) {
             return Ok(uncles.get(index).cloned());
         }
 
@@ -630,6 +759,8 @@ pub struct ClientForkConfig {
     pub backoff: Duration,
     /// available CUPS
     pub compute_units_per_second: u64,
+    /// Records all requests to or replays them from a cassette
+    pub cassette: Option<CassetteConfig>,
     /// total difficulty of the chain until this block
     pub total_difficulty: U256,
     /// Transactions to force include in the forked chain
@@ -651,6 +782,7 @@ fn update_url(&mut self, url: String) -> Result<(), BlockchainError> {
                 .max_retry(self.retries)
                 .initial_backoff(self.backoff.as_millis() as u64)
                 .compute_units_per_second(self.compute_units_per_second)
+                .maybe_cassette(self.cassette.clone())
                 .build()
                 .map_err(|_| BlockchainError::InvalidUrl(url.clone()))?, // .interval(interval),
         );
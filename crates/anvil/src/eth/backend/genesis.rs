@@ -7,6 +7,7 @@
     backend::DatabaseResult,
     revm::primitives::{AccountInfo, Bytecode, KECCAK_EMPTY},
 };
+use std::collections::BTreeMap;
 use tokio::sync::RwLockWriteGuard;
 
 /// Genesis settings
@@ -20,6 +21,8 @@ pub struct GenesisConfig {
     pub accounts: Vec<Address>,
     /// The `genesis.json` if provided
     pub genesis_init: Option<Genesis>,
+    /// Additional accounts to create at genesis, such as predeploys
+    pub predeploys: BTreeMap<Address, GenesisAccount>,
 }
 
 impl GenesisConfig {
@@ -37,20 +40,24 @@ pub fn account_infos(&self) -> impl Iterator<Item = (Address, AccountInfo)> + '_
         })
     }
 
-    /// If an initial `genesis.json` was provided, this applies the account alloc to the db
+    /// If an initial `genesis.json` was provided, this applies the account alloc to the db,
+    /// followed by the predeploys
     pub fn apply_genesis_json_alloc(
         &self,
         mut db: RwLockWriteGuard<'_, Box<dyn Db>>,
     ) -> DatabaseResult<()> {
-        if let Some(ref genesis) = self.genesis_init {
-            for (addr, mut acc) in genesis.alloc.clone() {
-                let storage = std::mem::take(&mut acc.storage);
-                // insert all accounts
-                db.insert_account(addr, self.genesis_to_account_info(&acc));
-                // insert all storage values
-                for (k, v) in storage.unwrap_or_default().iter() {
-                    db.set_storage_at(addr, *k, *v)?;
-                }
+        let alloc = self
+            .genesis_init
+            .iter()
+            .flat_map(|genesis| genesis.alloc.clone())
+            .chain(self.predeploys.clone());
+        for (addr, mut acc) in alloc {
+            let storage = std::mem::take(&mut acc.storage);
+            // insert all accounts
+            db.insert_account(addr, self.genesis_to_account_info(&acc));
+            // insert all storage values
+            for (k, v) in storage.unwrap_or_default().iter() {
+                db.set_storage_at(addr, *k, *v)?;
             }
         }
         Ok(())
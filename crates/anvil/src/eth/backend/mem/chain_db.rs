@@ -0,0 +1,513 @@
+//! Persistent on-disk storage of the chain
+
+use crate::eth::backend::db::{
+    Db, SerializableAccountRecord, SerializableBlock, SerializableState, SerializableTransaction,
+};
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    Address, Bytes, B256, U256, U64,
+};
+use anvil_core::eth::transaction::MaybeImpersonatedTransaction;
+use foundry_evm::revm::primitives::{BlockEnv, EvmState, KECCAK_EMPTY};
+use redb::{Database, ReadableTable, TableDefinition, WriteTransaction};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+/// Block number -> json encoded [SerializableBlock]
+const BLOCKS: TableDefinition<'_, u64, &[u8]> = TableDefinition::new("blocks");
+/// Transaction hash -> json encoded [SerializableTransaction], including the receipt
+const TRANSACTIONS: TableDefinition<'_, &[u8; 32], &[u8]> = TableDefinition::new("transactions");
+/// Address -> json encoded [SerializableAccountRecord], without storage
+const ACCOUNTS: TableDefinition<'_, &[u8; 20], &[u8]> = TableDefinition::new("accounts");
+/// Address ++ slot -> value
+const STORAGE: TableDefinition<'_, &[u8; 52], &[u8; 32]> = TableDefinition::new("storage");
+/// Block number -> json encoded [StateChangeset] of the block, including the changes made
+/// outside of blocks on top of its parent
+const CHANGES: TableDefinition<'_, u64, &[u8]> = TableDefinition::new("changes");
+/// Chain metadata, see the `META_*` keys
+const META: TableDefinition<'_, &str, &[u8]> = TableDefinition::new("meta");
+
+const META_BLOCK_ENV: &str = "block_env";
+const META_BEST_NUMBER: &str = "best_block_number";
+
+/// Errors that can occur when reading from or writing to the [ChainDb]
+#[derive(Debug, thiserror::Error)]
+pub enum ChainDbError {
+    #[error(transparent)]
+    Database(#[from] redb::DatabaseError),
+    #[error(transparent)]
+    Transaction(Box<redb::TransactionError>),
+    #[error(transparent)]
+    Table(#[from] redb::TableError),
+    #[error(transparent)]
+    Storage(#[from] redb::StorageError),
+    #[error(transparent)]
+    Commit(#[from] redb::CommitError),
+    #[error(transparent)]
+    Backend(#[from] foundry_evm::backend::DatabaseError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+}
+
+impl From<redb::TransactionError> for ChainDbError {
+    fn from(err: redb::TransactionError) -> Self {
+        Self::Transaction(Box::new(err))
+    }
+}
+
+pub type ChainDbResult<T> = Result<T, ChainDbError>;
+
+/// Tracks which parts of the state were modified, so only those need to be persisted.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StateChangeset {
+    /// Accounts whose info (nonce, balance or code) changed
+    pub accounts: HashSet<Address>,
+    /// Storage slots that changed
+    pub storage: HashSet<(Address, U256)>,
+    /// Accounts whose entire storage was wiped, because they were (re)created or destroyed
+    pub cleared: HashSet<Address>,
+}
+
+impl StateChangeset {
+    /// Records all changes of the state resulting from a transaction
+    pub fn record(&mut self, state: &EvmState) {
+        for (address, account) in state {
+            if !account.is_touched() {
+                continue
+            }
+            self.accounts.insert(*address);
+            if account.is_created() || account.is_selfdestructed() {
+                self.cleared.insert(*address);
+            }
+            self.storage.extend(
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| slot.is_changed())
+                    .map(|(key, _)| (*address, *key)),
+            );
+        }
+    }
+
+    /// Merges the changes of `other` into this set
+    pub fn extend(&mut self, other: Self) {
+        self.accounts.extend(other.accounts);
+        self.storage.extend(other.storage);
+        self.cleared.extend(other.cleared);
+    }
+
+    /// Reads the current values of all changed accounts and slots from the state.
+    ///
+    /// The entire storage of cleared accounts is read, since all of it is rewritten.
+    pub fn read_values(&self, db: &dyn Db) -> ChainDbResult<StateValues> {
+        let mut code_cache: HashMap<B256, Bytes> = HashMap::default();
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|address| Ok((*address, account_record(db, *address, &mut code_cache)?)))
+            .collect::<ChainDbResult<_>>()?;
+
+        let mut storage = self
+            .storage
+            .iter()
+            .map(|(address, slot)| Ok(((*address, *slot), db.storage_ref(*address, *slot)?)))
+            .collect::<ChainDbResult<HashMap<_, _>>>()?;
+        let live = db.maybe_as_full_db();
+        for address in &self.cleared {
+            let slots = live.and_then(|accounts| accounts.get(address)).map(|a| &a.storage);
+            for (slot, value) in slots.into_iter().flatten() {
+                storage.insert((*address, *slot), *value);
+            }
+        }
+
+        Ok(StateValues { accounts, storage, cleared: self.cleared.clone() })
+    }
+}
+
+/// The values of the changed accounts and slots of a [StateChangeset], which are written to the
+/// [ChainDb]
+#[derive(Debug, Default)]
+pub struct StateValues {
+    /// The info of the changed accounts, `None` if the account doesn't exist anymore
+    accounts: HashMap<Address, Option<SerializableAccountRecord>>,
+    /// The values of the changed slots
+    storage: HashMap<(Address, U256), U256>,
+    /// Accounts whose persisted storage is deleted before the slots are written
+    cleared: HashSet<Address>,
+}
+
+/// An embedded key-value store that persists blocks, receipts and state, so the chain can be
+/// resumed after a restart.
+///
+/// Unlike state dumps, every mined block only writes the new block, its transactions and the
+/// state that was modified by it. The keys modified by every block are recorded as well, so
+/// rewinding the chain only rewrites those.
+///
+/// Writes are committed on the blocking thread pool.
+pub struct ChainDb {
+    db: Arc<Database>,
+}
+
+impl ChainDb {
+    /// Opens the store at the given path, creating it if it doesn't exist yet.
+    pub fn open(path: impl AsRef<Path>) -> ChainDbResult<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(redb::StorageError::from)?;
+        }
+        let db = Database::create(path)?;
+
+        // make sure all tables exist, so reads don't have to deal with missing tables
+        let tx = db.begin_write()?;
+        tx.open_table(BLOCKS)?;
+        tx.open_table(TRANSACTIONS)?;
+        tx.open_table(ACCOUNTS)?;
+        tx.open_table(STORAGE)?;
+        tx.open_table(CHANGES)?;
+        tx.open_table(META)?;
+        tx.commit()?;
+
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    /// Runs the given database operation on the blocking thread pool
+    async fn blocking<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Database) -> ChainDbResult<T> + Send + 'static,
+    ) -> ChainDbResult<T> {
+        let db = Arc::clone(&self.db);
+        tokio::task::spawn_blocking(move || f(&db)).await?
+    }
+
+    /// Reads the entire persisted chain.
+    ///
+    /// Only the state of the best block is persisted, so the returned state has no historical
+    /// states and queries against older blocks fail after a restart.
+    ///
+    /// Returns `None` if nothing was persisted yet.
+    pub fn read_state(&self) -> ChainDbResult<Option<SerializableState>> {
+        let tx = self.db.begin_read()?;
+
+        let meta = tx.open_table(META)?;
+        let Some(block) = meta.get(META_BLOCK_ENV)? else { return Ok(None) };
+        let block: BlockEnv = serde_json::from_slice(block.value())?;
+        let best_block_number = meta
+            .get(META_BEST_NUMBER)?
+            .map(|num| serde_json::from_slice::<U64>(num.value()))
+            .transpose()?;
+
+        let mut accounts = BTreeMap::new();
+        for entry in tx.open_table(ACCOUNTS)?.iter()? {
+            let (address, account) = entry?;
+            let account: SerializableAccountRecord = serde_json::from_slice(account.value())?;
+            accounts.insert(Address::from(address.value()), account);
+        }
+        for entry in tx.open_table(STORAGE)?.iter()? {
+            let (key, value) = entry?;
+            let key = key.value();
+            let address = Address::from_slice(&key[..20]);
+            accounts
+                .entry(address)
+                .or_insert_with(empty_account)
+                .storage
+                .insert(B256::from_slice(&key[20..]), B256::from(value.value()));
+        }
+
+        let mut blocks = Vec::new();
+        for entry in tx.open_table(BLOCKS)?.iter()? {
+            blocks.push(serde_json::from_slice::<SerializableBlock>(entry?.1.value())?);
+        }
+
+        let mut transactions = Vec::new();
+        for entry in tx.open_table(TRANSACTIONS)?.iter()? {
+            transactions.push(serde_json::from_slice::<SerializableTransaction>(entry?.1.value())?);
+        }
+
+        Ok(Some(SerializableState {
+            block: Some(block),
+            accounts,
+            best_block_number,
+            blocks,
+            transactions,
+            historical_states: None,
+        }))
+    }
+
+    /// Replaces everything that was persisted with the given state.
+    ///
+    /// The recorded changes of the previous blocks are discarded.
+    pub async fn write_state(&self, state: SerializableState) -> ChainDbResult<()> {
+        self.blocking(move |db| write_state(db, &state)).await
+    }
+
+    /// Persists a newly mined block with its transactions and all state it modified.
+    ///
+    /// The transactions of the `pruned` block are deleted, like they are from memory when they
+    /// exceed the transaction block keeper.
+    pub async fn write_block(
+        &self,
+        block: SerializableBlock,
+        transactions: Vec<SerializableTransaction>,
+        changes: StateChangeset,
+        values: StateValues,
+        block_env: BlockEnv,
+        pruned: Option<u64>,
+    ) -> ChainDbResult<()> {
+        self.blocking(move |db| {
+            let tx = db.begin_write()?;
+            {
+                let mut blocks = tx.open_table(BLOCKS)?;
+                let mut table = tx.open_table(TRANSACTIONS)?;
+                for transaction in transactions {
+                    table.insert(
+                        &transaction.info.transaction_hash.0,
+                        serde_json::to_vec(&transaction)?.as_slice(),
+                    )?;
+                }
+                if let Some(pruned) = pruned {
+                    if let Some(pruned) = blocks.get(pruned)? {
+                        let pruned: SerializableBlock = serde_json::from_slice(pruned.value())?;
+                        for transaction in pruned.transactions {
+                            table.remove(
+                                &MaybeImpersonatedTransaction::from(transaction).hash().0,
+                            )?;
+                        }
+                    }
+                }
+                blocks.insert(block.header.number, serde_json::to_vec(&block)?.as_slice())?;
+            }
+            record_changes(&tx, block.header.number, changes)?;
+            write_values(&tx, &values)?;
+            write_meta(&tx, Some(&block_env), Some(U64::from(block.header.number)))?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Returns all accounts and slots that were modified after the block `best_number`, by the
+    /// blocks above it or outside of blocks.
+    ///
+    /// These are the keys that need to be rewritten to rewind the chain to `best_number`.
+    pub async fn changes_after(&self, best_number: u64) -> ChainDbResult<StateChangeset> {
+        self.blocking(move |db| {
+            let tx = db.begin_read()?;
+            let mut changes = StateChangeset::default();
+            for entry in tx.open_table(CHANGES)?.range(best_number + 1..)? {
+                changes.extend(serde_json::from_slice(entry?.1.value())?);
+            }
+            Ok(changes)
+        })
+        .await
+    }
+
+    /// Rewinds the persisted chain to `best_number`, e.g. after a snapshot was reverted.
+    ///
+    /// Only the blocks above the new head and their transactions are deleted, and only the
+    /// accounts and slots returned by [Self::changes_after] are rewritten with the given values.
+    pub async fn rewind(
+        &self,
+        values: StateValues,
+        block_env: BlockEnv,
+        best_number: U64,
+    ) -> ChainDbResult<()> {
+        self.blocking(move |db| {
+            let tx = db.begin_write()?;
+            {
+                let mut blocks = tx.open_table(BLOCKS)?;
+                let mut transactions = tx.open_table(TRANSACTIONS)?;
+                let removed = best_number.to::<u64>() + 1..;
+                for entry in blocks.range(removed.clone())? {
+                    let block: SerializableBlock = serde_json::from_slice(entry?.1.value())?;
+                    for transaction in block.transactions {
+                        transactions
+                            .remove(&MaybeImpersonatedTransaction::from(transaction).hash().0)?;
+                    }
+                }
+                blocks.retain_in(removed.clone(), |_, _| false)?;
+                tx.open_table(CHANGES)?.retain_in(removed, |_, _| false)?;
+            }
+            write_values(&tx, &values)?;
+            write_meta(&tx, Some(&block_env), Some(best_number))?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Persists state modified outside of a block, e.g. via `anvil_setBalance`.
+    ///
+    /// The changes are recorded as part of the next block `pending_number`, so they're discarded
+    /// when the chain is rewound to an earlier block.
+    pub async fn write_changes(
+        &self,
+        changes: StateChangeset,
+        values: StateValues,
+        pending_number: u64,
+    ) -> ChainDbResult<()> {
+        self.blocking(move |db| {
+            let tx = db.begin_write()?;
+            record_changes(&tx, pending_number, changes)?;
+            write_values(&tx, &values)?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+impl std::fmt::Debug for ChainDb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChainDb").finish_non_exhaustive()
+    }
+}
+
+fn write_state(db: &Database, state: &SerializableState) -> ChainDbResult<()> {
+    let tx = db.begin_write()?;
+    tx.delete_table(BLOCKS)?;
+    tx.delete_table(TRANSACTIONS)?;
+    tx.delete_table(ACCOUNTS)?;
+    tx.delete_table(STORAGE)?;
+    tx.delete_table(CHANGES)?;
+    {
+        let mut blocks = tx.open_table(BLOCKS)?;
+        for block in &state.blocks {
+            blocks.insert(block.header.number, serde_json::to_vec(block)?.as_slice())?;
+        }
+        let mut transactions = tx.open_table(TRANSACTIONS)?;
+        for transaction in &state.transactions {
+            transactions.insert(
+                &transaction.info.transaction_hash.0,
+                serde_json::to_vec(transaction)?.as_slice(),
+            )?;
+        }
+        let mut accounts = tx.open_table(ACCOUNTS)?;
+        let mut storage = tx.open_table(STORAGE)?;
+        for (address, account) in &state.accounts {
+            let info = SerializableAccountRecord { storage: Default::default(), ..account.clone() };
+            accounts.insert(&address.0 .0, serde_json::to_vec(&info)?.as_slice())?;
+            for (slot, value) in &account.storage {
+                if !value.is_zero() {
+                    storage.insert(&storage_key(*address, (*slot).into()), &value.0)?;
+                }
+            }
+        }
+        tx.open_table(CHANGES)?;
+    }
+    write_meta(&tx, state.block.as_ref(), state.best_block_number)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Adds the changes to the recorded changes of the given block
+fn record_changes(
+    tx: &WriteTransaction,
+    number: u64,
+    mut changes: StateChangeset,
+) -> ChainDbResult<()> {
+    let mut table = tx.open_table(CHANGES)?;
+    let recorded = table
+        .get(number)?
+        .map(|recorded| serde_json::from_slice::<StateChangeset>(recorded.value()))
+        .transpose()?;
+    if let Some(recorded) = recorded {
+        changes.extend(recorded);
+    }
+    table.insert(number, serde_json::to_vec(&changes)?.as_slice())?;
+    Ok(())
+}
+
+/// Writes the values of all changed accounts and slots
+fn write_values(tx: &WriteTransaction, values: &StateValues) -> ChainDbResult<()> {
+    let mut accounts = tx.open_table(ACCOUNTS)?;
+    let mut storage = tx.open_table(STORAGE)?;
+
+    for address in &values.cleared {
+        let start = storage_key(*address, U256::ZERO);
+        let end = storage_key(*address, U256::MAX);
+        storage.retain_in::<&[u8; 52], _>(&start..=&end, |_, _| false)?;
+    }
+
+    for (address, record) in &values.accounts {
+        match record {
+            Some(record) => {
+                accounts.insert(&address.0 .0, serde_json::to_vec(record)?.as_slice())?;
+            }
+            None => {
+                accounts.remove(&address.0 .0)?;
+            }
+        }
+    }
+
+    for ((address, slot), value) in &values.storage {
+        let key = storage_key(*address, *slot);
+        if value.is_zero() {
+            storage.remove(&key)?;
+        } else {
+            storage.insert(&key, &value.to_be_bytes::<32>())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the current info of the account, without storage
+fn account_record(
+    db: &dyn Db,
+    address: Address,
+    code_cache: &mut HashMap<B256, Bytes>,
+) -> ChainDbResult<Option<SerializableAccountRecord>> {
+    let Some(info) = db.basic_ref(address)? else { return Ok(None) };
+    let code = match info.code {
+        Some(code) => code.original_bytes(),
+        None if info.code_hash == KECCAK_EMPTY => Default::default(),
+        None => match code_cache.get(&info.code_hash) {
+            Some(code) => code.clone(),
+            None => {
+                let code = db.code_by_hash_ref(info.code_hash)?.original_bytes();
+                code_cache.insert(info.code_hash, code.clone());
+                code
+            }
+        },
+    };
+    Ok(Some(SerializableAccountRecord {
+        nonce: info.nonce,
+        balance: info.balance,
+        code,
+        storage: Default::default(),
+    }))
+}
+
+fn write_meta(
+    tx: &WriteTransaction,
+    block_env: Option<&BlockEnv>,
+    best_number: Option<U64>,
+) -> ChainDbResult<()> {
+    let mut meta = tx.open_table(META)?;
+    if let Some(block_env) = block_env {
+        meta.insert(META_BLOCK_ENV, serde_json::to_vec(block_env)?.as_slice())?;
+    }
+    if let Some(best_number) = best_number {
+        meta.insert(META_BEST_NUMBER, serde_json::to_vec(&best_number)?.as_slice())?;
+    }
+    Ok(())
+}
+
+/// Returns the key of the given storage slot: `address ++ slot`
+fn storage_key(address: Address, slot: U256) -> [u8; 52] {
+    let mut key = [0u8; 52];
+    key[..20].copy_from_slice(address.as_slice());
+    key[20..].copy_from_slice(&slot.to_be_bytes::<32>());
+    key
+}
+
+fn empty_account() -> SerializableAccountRecord {
+    SerializableAccountRecord {
+        nonce: 0,
+        balance: U256::ZERO,
+        code: Default::default(),
+        storage: Default::default(),
+    }
+}
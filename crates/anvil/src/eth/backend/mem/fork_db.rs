@@ -82,6 +82,13 @@ fn revert_state(&mut self, id: U256, action: RevertStateSnapshotAction) -> bool
         self.revert_state_snapshot(id, action)
     }
 
+    fn state_snapshot_accounts(&self, id: U256) -> Option<HashMap<Address, DbAccount>> {
+        self.state_snapshots()
+            .lock()
+            .get(id)
+            .map(|state_snapshot| state_snapshot.local.accounts.clone())
+    }
+
     fn current_state(&self) -> StateDb {
         StateDb::new(self.create_state_snapshot())
     }
@@ -91,6 +91,10 @@ fn revert_state(&mut self, id: U256, action: RevertStateSnapshotAction) -> bool
         }
     }
 
+    fn state_snapshot_accounts(&self, id: U256) -> Option<HashMap<Address, DbAccount>> {
+        self.state_snapshots.get(id).map(|state_snapshot| state_snapshot.accounts.clone())
+    }
+
     fn maybe_state_root(&self) -> Option<B256> {
         Some(state_root(&self.inner.accounts))
     }
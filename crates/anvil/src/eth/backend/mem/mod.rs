@@ -7,22 +7,31 @@
     eth::{
         backend::{
             cheats::CheatsManager,
-            db::{Db, MaybeFullDatabase, SerializableState},
-            executor::{ExecutedTransactions, TransactionExecutor},
+            db::{Db, MaybeFullDatabase, SerializableBlock, SerializableState},
+            executor::{build_logs_bloom, ExecutedTransactions, TransactionExecutor},
             fork::ClientFork,
             genesis::GenesisConfig,
             mem::{
-                state::{storage_root, trie_accounts},
+                chain_db::{ChainDb, StateChangeset},
+                state::{state_root, storage_root, trie_accounts},
                 storage::MinedTransactionReceipt,
             },
-            notifications::{NewBlockNotification, NewBlockNotifications},
+            notifications::{
+                ChainNotification, ChainNotifications, NewBlockNotification, NewBlockNotifications,
+                RemovedBlock,
+            },
             time::{utc_from_secs, TimeManager},
             validate::TransactionValidator,
         },
-        error::{BlockchainError, ErrDetail, InvalidTransactionError},
+        error::{BlockImportError, BlockchainError, ErrDetail, InvalidTransactionError},
         fees::{FeeDetails, FeeManager, MIN_SUGGESTED_PRIORITY_FEE},
         macros::node_info,
-        pool::transactions::PoolTransaction,
+        miner::{BlockBuilder, BlockItem, BundlesFirst},
+        pool::{
+            bundle::{Bundle, BundlePool},
+            transactions::PoolTransaction,
+        },
+        sign::build_typed_transaction,
         util::get_precompiles_for,
     },
     inject_precompiles,
@@ -35,43 +44,61 @@
 };
 use alloy_chains::NamedChain;
 use alloy_consensus::{
-    Account, Header, Receipt, ReceiptWithBloom, Signed, Transaction as TransactionTrait, TxEnvelope,
+    constants::EMPTY_WITHDRAWALS, Account, Header, Receipt, ReceiptWithBloom, Signed,
+    Transaction as TransactionTrait, TxEnvelope,
+};
+use alloy_eips::{
+    eip2718::Encodable2718, eip4844::MAX_BLOBS_PER_BLOCK, eip7685::EMPTY_REQUESTS_HASH,
 };
-use alloy_eips::eip4844::MAX_BLOBS_PER_BLOCK;
 use alloy_network::{
     AnyHeader, AnyRpcBlock, AnyRpcTransaction, AnyTxEnvelope, AnyTxType, EthereumWallet,
     UnknownTxEnvelope, UnknownTypedTransaction,
 };
 use alloy_primitives::{
-    address, hex, keccak256, utils::Unit, Address, Bytes, TxHash, TxKind, B256, U256, U64,
+    address, hex, keccak256, map::HashSet, utils::Unit, Address, Bloom, Bytes,
+    PrimitiveSignature as Signature, TxHash, TxKind, B256, U256, U64,
 };
 use alloy_rpc_types::{
     anvil::Forking,
     request::TransactionRequest,
     serde_helpers::JsonStorageKey,
+    simulate::{SimBlock, SimulatePayload, MAX_SIMULATE_BLOCKS},
     state::StateOverride,
     trace::{
+        common::TraceResult,
         filter::TraceFilter,
         geth::{
-            GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingCallOptions,
-            GethDebugTracingOptions, GethTrace, NoopFrame,
+            FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerConfig, GethDebugTracerType,
+            GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame,
+        },
+        parity::{
+            LocalizedTransactionTrace, TraceResults, TraceResultsWithTransactionHash, TraceType,
         },
-        parity::LocalizedTransactionTrace,
     },
     AccessList, Block as AlloyBlock, BlockId, BlockNumberOrTag as BlockNumber, BlockTransactions,
     EIP1186AccountProofResponse as AccountProof, EIP1186StorageProof as StorageProof, Filter,
-    FilteredParams, Header as AlloyHeader, Index, Log, Transaction, TransactionReceipt,
+    FilteredParams, Header as AlloyHeader, Index, Log, Transaction,
+    TransactionInfo as RethTransactionInfo, TransactionReceipt,
 };
 use alloy_serde::{OtherFields, WithOtherFields};
 use alloy_signer_local::PrivateKeySigner;
 use alloy_trie::{proof::ProofRetainer, HashBuilder, Nibbles};
-use anvil_core::eth::{
-    block::{Block, BlockInfo},
-    transaction::{
-        optimism::DepositTransaction, DepositReceipt, MaybeImpersonatedTransaction,
-        PendingTransaction, ReceiptResponse, TransactionInfo, TypedReceipt, TypedTransaction,
+use anvil_core::{
+    eth::{
+        block::{Block, BlockInfo, PartialHeader},
+        transaction::{
+            optimism::DepositTransaction, transaction_request_to_typed, DepositReceipt,
+            MaybeImpersonatedTransaction, PendingTransaction, ReceiptResponse, TransactionInfo,
+            TypedReceipt, TypedTransaction,
+        },
+        trie,
+        wallet::{Capabilities, DelegationCapability, WalletCapabilities},
+    },
+    types::{
+        AccountRangeEntry, AccountRangeResult, AccountStateDiff, SimulatedBlockResult,
+        SimulatedCallError, SimulatedCallResult, StateDiff, StorageRangeEntry, StorageRangeResult,
+        ValueDiff,
     },
-    wallet::{Capabilities, DelegationCapability, WalletCapabilities},
 };
 use anvil_rpc::error::RpcError;
 use chrono::Datelike;
@@ -83,24 +110,29 @@
     decode::RevertDecoder,
     inspectors::AccessListInspector,
     revm::{
-        db::CacheDB,
+        db::{AccountState, CacheDB, DbAccount},
         interpreter::InstructionResult,
         primitives::{
-            BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, Output, SpecId,
-            TxEnv, KECCAK_EMPTY,
+            BlockEnv, Bytecode, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, Output,
+            SpecId, TxEnv, KECCAK_EMPTY,
         },
     },
-    traces::TracingInspectorConfig,
+    traces::{FourByteInspector, MuxInspector, TracingInspectorConfig, TransferInspector},
 };
 use futures::channel::mpsc::{unbounded, UnboundedSender};
 use op_alloy_consensus::{TxDeposit, DEPOSIT_TX_TYPE_ID};
 use parking_lot::{Mutex, RwLock};
 use revm::{
     db::WrapDatabaseRef,
-    primitives::{BlobExcessGasAndPrice, HashMap, OptimismFields, ResultAndState},
+    primitives::{
+        AccountStatus, BlobExcessGasAndPrice, EVMError, EvmStorageSlot, HashMap, OptimismFields,
+        ResultAndState,
+    },
+    DatabaseCommit,
 };
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
+    fmt,
     io::{Read, Write},
     path::PathBuf,
     sync::Arc,
@@ -109,6 +141,7 @@
 use storage::{Blockchain, MinedTransaction, DEFAULT_HISTORY_LIMIT};
 use tokio::sync::RwLock as AsyncRwLock;
 pub mod cache;
+pub mod chain_db;
 pub mod fork_db;
 pub mod in_memory_db;
 pub mod inspector;
@@ -133,8 +166,11 @@
 
 pub type State = foundry_evm::utils::StateChangeset;
 
+/// The hash of a replayed transaction and its trace, or the error that prevented tracing it
+type ReplayedTrace<T> = (B256, Result<T, BlockchainError>);
+
 /// A block request, which includes the Pool Transactions if it's Pending
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum BlockRequest {
     Pending(Vec<Arc<PoolTransaction>>),
     Number(u64),
@@ -149,6 +185,80 @@ pub fn block_number(&self) -> BlockNumber {
     }
 }
 
+/// Header values of a block that are provided by the consensus layer, e.g. via the engine API,
+/// instead of being chosen by the node itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockAttributes {
+    /// Timestamp of the block
+    pub timestamp: u64,
+    /// The `prevrandao` value, stored as `mixHash` in the header
+    pub prev_randao: B256,
+    /// Beneficiary of the block
+    pub fee_recipient: Address,
+    /// Root of the parent beacon block, only used after cancun
+    pub parent_beacon_block_root: Option<B256>,
+    /// Extra data of the header
+    pub extra_data: Bytes,
+    /// Gas limit of the block, the configured gas limit is used if unset
+    pub gas_limit: Option<u64>,
+    /// Base fee of the block, the node's next base fee is used if unset
+    pub base_fee_per_gas: Option<u64>,
+}
+
+impl BlockAttributes {
+    /// Returns the attributes that were used to build the block with the given header
+    pub fn from_header(header: &Header) -> Self {
+        Self {
+            timestamp: header.timestamp,
+            prev_randao: header.mix_hash,
+            fee_recipient: header.beneficiary,
+            parent_beacon_block_root: header.parent_beacon_block_root,
+            extra_data: header.extra_data.clone(),
+            gas_limit: Some(header.gas_limit),
+            base_fee_per_gas: header.base_fee_per_gas,
+        }
+    }
+}
+
+/// A block that was built by someone else, e.g. another node or a consensus client
+#[derive(Clone, Debug)]
+pub struct ImportedBlock {
+    /// The hash the block must have after executing it
+    pub hash: B256,
+    /// Hash of the block it's built on
+    pub parent_hash: B256,
+    /// All transactions of the block, in order
+    pub transactions: Vec<Arc<PoolTransaction>>,
+    /// The header values that don't result from executing the transactions
+    pub attributes: BlockAttributes,
+}
+
+impl ImportedBlock {
+    /// Checks that all transactions were included and that the executed block has the expected
+    /// hash
+    fn check_executed(
+        &self,
+        executed: &ExecutedTransactions,
+        hash: B256,
+    ) -> Result<(), BlockImportError> {
+        if executed.included.len() != self.transactions.len() {
+            return Err(BlockImportError::InvalidTransactions);
+        }
+        if hash != self.hash {
+            return Err(BlockImportError::HashMismatch { expected: self.hash, got: hash });
+        }
+        Ok(())
+    }
+}
+
+/// The transactions of a block that is mined
+enum BlockContents {
+    /// Transactions and bundles that are included if valid
+    Items(Vec<BlockItem>),
+    /// A block that is only mined if all transactions are valid and it has the expected hash
+    Imported(Box<ImportedBlock>),
+}
+
 /// Gives access to the [revm::Database]
 #[derive(Clone)]
 pub struct Backend {
@@ -189,6 +299,8 @@ pub struct Backend {
     genesis: GenesisConfig,
     /// Listeners for new blocks that get notified when a new block was imported.
     new_block_listeners: Arc<Mutex<Vec<UnboundedSender<NewBlockNotification>>>>,
+    /// Listeners for changes of the canonical chain, including blocks removed by reorgs.
+    chain_listeners: Arc<Mutex<Vec<UnboundedSender<ChainNotification>>>>,
     /// Keeps track of active state snapshots at a specific block.
     active_state_snapshots: Arc<Mutex<HashMap<U256, (u64, B256)>>>,
     enable_steps_tracing: bool,
@@ -201,10 +313,18 @@ pub struct Backend {
     node_config: Arc<AsyncRwLock<NodeConfig>>,
     /// Slots in an epoch
     slots_in_an_epoch: u64,
+    /// Duration of a slot in seconds, used to map block timestamps to beacon chain slots
+    seconds_per_slot: u64,
     /// Precompiles to inject to the EVM.
     precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// Assembles the blocks mined by this node
+    block_builder: Arc<dyn BlockBuilder>,
+    /// Bundles and private transactions that are waiting to be included
+    bundles: Arc<RwLock<BundlePool>>,
     /// Prevent race conditions during mining
     mining: Arc<tokio::sync::Mutex<()>>,
+    /// Persistent storage of the chain, if enabled
+    chain_db: Option<Arc<ChainDb>>,
     // === wallet === //
     capabilities: Arc<RwLock<WalletCapabilities>>,
     executor_wallet: Arc<RwLock<Option<EthereumWallet>>>,
@@ -268,9 +388,10 @@ pub async fn with_genesis(
             states = states.disk_path(cache_path);
         }
 
-        let (slots_in_an_epoch, precompile_factory) = {
+        let (slots_in_an_epoch, precompile_factory, block_builder) = {
             let cfg = node_config.read().await;
-            (cfg.slots_in_an_epoch, cfg.precompile_factory.clone())
+            let block_builder = cfg.block_builder.clone().unwrap_or_else(|| Arc::new(BundlesFirst));
+            (cfg.slots_in_an_epoch, cfg.precompile_factory.clone(), block_builder)
         };
 
         let (capabilities, executor_wallet) = if odyssey {
@@ -319,6 +440,7 @@ pub async fn with_genesis(
             time: TimeManager::new(start_timestamp),
             cheats: Default::default(),
             new_block_listeners: Default::default(),
+            chain_listeners: Default::default(),
             fees,
             genesis,
             active_state_snapshots: Arc::new(Mutex::new(Default::default())),
@@ -329,8 +451,12 @@ pub async fn with_genesis(
             transaction_block_keeper,
             node_config,
             slots_in_an_epoch,
+            seconds_per_slot: automine_block_time.map_or(1, |time| time.as_secs().max(1)),
             precompile_factory,
+            block_builder,
+            bundles: Default::default(),
             mining: Arc::new(tokio::sync::Mutex::new(())),
+            chain_db: None,
             capabilities: Arc::new(RwLock::new(capabilities)),
             executor_wallet: Arc::new(RwLock::new(executor_wallet)),
         };
@@ -344,6 +470,70 @@ pub async fn with_genesis(
         Ok(backend)
     }
 
+    /// Attaches the persistent [ChainDb].
+    ///
+    /// If the store already contains a chain, it is loaded into the backend, otherwise the store
+    /// is initialised with the current chain. Afterwards all changes to the chain are persisted
+    /// incrementally.
+    ///
+    /// The states of historical blocks are not persisted, so after a restart state queries are
+    /// only supported for the best block.
+    pub async fn attach_chain_db(&mut self, chain_db: ChainDb) -> Result<()> {
+        if let Some(state) = chain_db.read_state().wrap_err("failed to read chain db")? {
+            trace!(target: "backend", best_number=?state.best_block_number, "restoring chain from chain db");
+            self.load_state(state).await.wrap_err("failed to restore chain from chain db")?;
+            self.chain_db = Some(Arc::new(chain_db));
+        } else {
+            self.chain_db = Some(Arc::new(chain_db));
+            self.persist_chain().await;
+        }
+        Ok(())
+    }
+
+    /// Rewrites the entire chain to the [ChainDb], if enabled.
+    ///
+    /// This is used after operations that replace the state entirely, see also
+    /// [Self::persist_rewind].
+    async fn persist_chain(&self) {
+        let Some(chain_db) = self.chain_db.clone() else { return };
+        let res = match self.serialized_state(false).await {
+            Ok(state) => chain_db.write_state(state).await.map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        if let Err(err) = res {
+            error!(target: "backend", %err, "failed to persist chain");
+        }
+    }
+
+    /// Rewinds the [ChainDb] to the current best block, if enabled.
+    ///
+    /// This is used after operations that discard blocks, like reverting a snapshot.
+    async fn persist_rewind(&self) {
+        let Some(chain_db) = self.chain_db.clone() else { return };
+        let best_number = self.best_number();
+        let res = async {
+            let changes = chain_db.changes_after(best_number).await?;
+            let values = changes.read_values(&**self.db.read().await)?;
+            let block_env = self.env.read().block.clone();
+            chain_db.rewind(values, block_env, U64::from(best_number)).await
+        };
+        if let Err(err) = res.await {
+            error!(target: "backend", %err, "failed to rewind chain db");
+        }
+    }
+
+    /// Writes modified state to the [ChainDb], if enabled.
+    async fn persist_state_changes(&self, changes: StateChangeset) {
+        let Some(chain_db) = self.chain_db.clone() else { return };
+        let res = match changes.read_values(&**self.db.read().await) {
+            Ok(values) => chain_db.write_changes(changes, values, self.best_number() + 1).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = res {
+            error!(target: "backend", %err, "failed to persist state changes");
+        }
+    }
+
     /// Writes the CREATE2 deployer code directly to the database at the address provided.
     pub async fn set_create2_deployer(&self, address: Address) -> DatabaseResult<()> {
         self.set_code(address, Bytes::from_static(DEFAULT_CREATE2_DEPLOYER_RUNTIME_CODE)).await?;
@@ -585,6 +775,7 @@ pub async fn reset_fork(&self, forking: Forking) -> Result<(), BlockchainError>
             self.db.write().await.clear();
 
             self.apply_genesis().await?;
+            self.persist_chain().await;
 
             Ok(())
         } else {
@@ -622,6 +813,18 @@ pub fn best_number(&self) -> u64 {
         self.blockchain.storage.read().best_number.try_into().unwrap_or(u64::MAX)
     }
 
+    /// Returns the number of slots in an epoch
+    pub fn slots_in_an_epoch(&self) -> u64 {
+        self.slots_in_an_epoch
+    }
+
+    /// Returns the duration of a beacon chain slot in seconds.
+    ///
+    /// This is the block time if interval mining is enabled, and one second otherwise.
+    pub fn seconds_per_slot(&self) -> u64 {
+        self.seconds_per_slot
+    }
+
     /// Sets the block number
     pub fn set_block_number(&self, number: U256) {
         let mut env = self.env.write();
@@ -659,17 +862,23 @@ pub fn set_coinbase(&self, address: Address) {
 
     /// Sets the nonce of the given address
     pub async fn set_nonce(&self, address: Address, nonce: U256) -> DatabaseResult<()> {
-        self.db.write().await.set_nonce(address, nonce.try_into().unwrap_or(u64::MAX))
+        self.db.write().await.set_nonce(address, nonce.try_into().unwrap_or(u64::MAX))?;
+        self.persist_account_changes(address).await;
+        Ok(())
     }
 
     /// Sets the balance of the given address
     pub async fn set_balance(&self, address: Address, balance: U256) -> DatabaseResult<()> {
-        self.db.write().await.set_balance(address, balance)
+        self.db.write().await.set_balance(address, balance)?;
+        self.persist_account_changes(address).await;
+        Ok(())
     }
 
     /// Sets the code of the given address
     pub async fn set_code(&self, address: Address, code: Bytes) -> DatabaseResult<()> {
-        self.db.write().await.set_code(address, code.0.into())
+        self.db.write().await.set_code(address, code.0.into())?;
+        self.persist_account_changes(address).await;
+        Ok(())
     }
 
     /// Sets the value for the given slot of the given address
@@ -679,7 +888,23 @@ pub async fn set_storage_at(
         slot: U256,
         val: B256,
     ) -> DatabaseResult<()> {
-        self.db.write().await.set_storage_at(address, slot.into(), val)
+        self.db.write().await.set_storage_at(address, slot.into(), val)?;
+        if self.chain_db.is_some() {
+            let mut changes = StateChangeset::default();
+            changes.accounts.insert(address);
+            changes.storage.insert((address, slot));
+            self.persist_state_changes(changes).await;
+        }
+        Ok(())
+    }
+
+    /// Persists the info of the given account after it was modified directly
+    async fn persist_account_changes(&self, address: Address) {
+        if self.chain_db.is_some() {
+            let mut changes = StateChangeset::default();
+            changes.accounts.insert(address);
+            self.persist_state_changes(changes).await;
+        }
     }
 
     /// Returns the configured specid
@@ -831,6 +1056,7 @@ pub async fn revert_state_snapshot(&self, id: U256) -> Result<bool, BlockchainEr
                                 let _ = storage.transactions.remove(&tx.hash());
                             }
                         }
+                        storage.changed_accounts.remove(&hash);
                     }
                 }
 
@@ -858,7 +1084,12 @@ pub async fn revert_state_snapshot(&self, id: U256) -> Result<bool, BlockchainEr
                 ..Default::default()
             };
         }
-        Ok(self.db.write().await.revert_state(id, RevertStateSnapshotAction::RevertRemove))
+        let reverted =
+            self.db.write().await.revert_state(id, RevertStateSnapshotAction::RevertRemove);
+        if reverted {
+            self.persist_rewind().await;
+        }
+        Ok(reverted)
     }
 
     pub fn list_state_snapshots(&self) -> BTreeMap<U256, (u64, B256)> {
@@ -969,6 +1200,8 @@ pub async fn load_state(&self, state: SerializableState) -> Result<bool, Blockch
             self.states.write().load_states(historical_states);
         }
 
+        self.persist_chain().await;
+
         Ok(true)
     }
 
@@ -1001,6 +1234,54 @@ fn next_env(&self) -> EnvWithHandlerCfg {
         env
     }
 
+    /// Returns the environment for mining the block with the given number on top of the current
+    /// best block.
+    ///
+    /// The timestamp is not set yet, because it should be as close as possible to the execution.
+    fn next_block_env(
+        &self,
+        block_number: U64,
+        attributes: Option<&BlockAttributes>,
+    ) -> EnvWithHandlerCfg {
+        let current_base_fee = self.base_fee();
+        let current_excess_blob_gas_and_price = self.excess_blob_gas_and_price();
+
+        let mut env = self.env.read().clone();
+
+        if env.block.basefee.is_zero() {
+            // this is an edge case because the evm fails if `tx.effective_gas_price < base_fee`
+            // 0 is only possible if it's manually set
+            env.cfg.disable_base_fee = true;
+        }
+
+        // increase block number for this block
+        if is_arbitrum(env.cfg.chain_id) {
+            // Temporary set `env.block.number` to `block_number` for Arbitrum chains.
+            env.block.number = block_number.to();
+        } else {
+            env.block.number = env.block.number.saturating_add(U256::from(1));
+        }
+
+        env.block.basefee = U256::from(current_base_fee);
+        env.block.blob_excess_gas_and_price = current_excess_blob_gas_and_price;
+
+        if let Some(attributes) = attributes {
+            env.block.coinbase = attributes.fee_recipient;
+            env.block.prevrandao = Some(attributes.prev_randao);
+            if let Some(gas_limit) = attributes.gas_limit {
+                env.block.gas_limit = U256::from(gas_limit);
+            }
+            if let Some(base_fee) = attributes.base_fee_per_gas {
+                env.block.basefee = U256::from(base_fee);
+            }
+        } else {
+            // pick a random value for prevrandao
+            env.block.prevrandao = Some(B256::random());
+        }
+
+        env
+    }
+
     /// Creates an EVM instance with optionally injected precompiles.
     #[allow(clippy::type_complexity)]
     fn new_evm_with_inspector_ref<'i, 'db>(
@@ -1087,7 +1368,11 @@ pub async fn with_pending_block<F, T>(
         let executor = TransactionExecutor {
             db: &mut cache_db,
             validator: self,
-            pending: pool_transactions.into_iter(),
+            pending: pool_transactions
+                .into_iter()
+                .map(BlockItem::from)
+                .collect::<Vec<_>>()
+                .into_iter(),
             block_env: env.block.clone(),
             cfg_env,
             parent_hash: storage.best_hash,
@@ -1104,52 +1389,243 @@ pub async fn with_pending_block<F, T>(
         f(Box::new(cache_db), executed.block)
     }
 
+    /// Builds a block with the given attributes on top of the current best block, without mining
+    /// it.
+    ///
+    /// The block includes pending bundles, see [Self::add_bundle]. Mining the transactions of the
+    /// returned block via [Self::mine_block_with_attributes] results in the same block.
+    pub async fn build_block_with_attributes(
+        &self,
+        pool_transactions: Vec<Arc<PoolTransaction>>,
+        attributes: &BlockAttributes,
+    ) -> (BlockInfo, B256) {
+        // prevent the best block from changing while building
+        let _mining_guard = self.mining.lock().await;
+        let items = self.block_items(pool_transactions);
+        self.execute_block_with_attributes(items, attributes).await
+    }
+
+    /// Adds a bundle that is included in the next blocks this node mines, if possible.
+    pub fn add_bundle(&self, bundle: Bundle) {
+        trace!(target: "backend", bundle = ?bundle.hash, "adding bundle");
+        self.bundles.write().add(bundle);
+    }
+
+    /// Removes a bundle that hasn't been included yet
+    pub fn remove_bundle(&self, hash: B256) -> Option<Bundle> {
+        self.bundles.write().remove(&hash)
+    }
+
+    /// Returns the number of bundles that haven't been included yet
+    pub fn pending_bundles(&self) -> usize {
+        self.bundles.read().len()
+    }
+
+    /// Removes all bundles that haven't been included yet
+    pub fn clear_bundles(&self) {
+        self.bundles.write().clear();
+    }
+
+    /// Assembles the items of the next block from the pending bundles and the pool transactions,
+    /// expects the mining lock to be held.
+    fn block_items(&self, pool_transactions: Vec<Arc<PoolTransaction>>) -> Vec<BlockItem> {
+        let block_number = self.best_number().saturating_add(1);
+        let bundles = self.bundles.read().bundles_for_block(block_number);
+        self.block_builder.build(bundles, pool_transactions)
+    }
+
+    /// Imports a block that was built by someone else, e.g. another node.
+    ///
+    /// The transactions are executed with the given attributes on top of the parent, which must be
+    /// the current best block, and the block is only mined if this results in the expected hash.
+    pub async fn import_block(
+        &self,
+        block: ImportedBlock,
+    ) -> Result<MinedBlockOutcome, BlockImportError> {
+        let _mining_guard = self.mining.lock().await;
+        if self.blockchain.storage.read().best_hash != block.parent_hash {
+            return Err(BlockImportError::UnknownParent(block.parent_hash));
+        }
+        let attributes = block.attributes.clone();
+        self.mine_block_locked(BlockContents::Imported(Box::new(block)), Some(attributes)).await
+    }
+
+    /// Checks that the blocks, each built on the one before, are valid on top of `parent`, a block
+    /// of the canonical chain, without changing the chain.
+    ///
+    /// This requires the state of `parent`, which is only kept for recent blocks.
+    pub async fn validate_side_chain(
+        &self,
+        parent: &Header,
+        blocks: &[ImportedBlock],
+    ) -> Result<(), BlockImportError> {
+        let parent_hash = parent.hash_slow();
+        let db = self.db.read().await;
+        if parent_hash == self.blockchain.storage.read().best_hash {
+            return self.execute_side_chain(&**db, parent, blocks);
+        }
+        drop(db);
+
+        let mut states = self.states.write();
+        let state = states.get(&parent_hash).ok_or(BlockImportError::MissingState(parent_hash))?;
+        self.execute_side_chain(state, parent, blocks)
+    }
+
+    /// Executes the blocks one after another on top of `db`, the state of `parent`
+    fn execute_side_chain<DB>(
+        &self,
+        db: DB,
+        parent: &Header,
+        blocks: &[ImportedBlock],
+    ) -> Result<(), BlockImportError>
+    where
+        DB: MaybeFullDatabase + Send + Sync + Clone + fmt::Debug,
+    {
+        // the state root can't be computed in fork mode
+        let base = if self.is_fork() { None } else { db.maybe_as_full_db() };
+        let mut overlay = CacheDB::new(db.clone());
+        let mut parent = parent.clone();
+        for block in blocks {
+            let mut env =
+                self.next_block_env(U64::from(parent.number + 1), Some(&block.attributes));
+            env.block.number = U256::from(parent.number + 1);
+            env.block.timestamp = U256::from(block.attributes.timestamp);
+            if let (Some(excess_blob_gas), Some(blob_gas_used)) =
+                (parent.excess_blob_gas, parent.blob_gas_used)
+            {
+                env.block.blob_excess_gas_and_price = Some(BlobExcessGasAndPrice::new(
+                    self.fees.get_next_block_blob_excess_gas(
+                        excess_blob_gas as u128,
+                        blob_gas_used as u128,
+                    ),
+                    false,
+                ));
+            }
+
+            let items = block.transactions.iter().cloned().map(BlockItem::from).collect();
+            let (executed, hash) = self.execute_on_overlay(
+                &mut overlay,
+                base,
+                env,
+                block.parent_hash,
+                items,
+                &block.attributes,
+            );
+            block.check_executed(&executed, hash)?;
+            overlay.block_hashes.insert(U256::from(executed.block.block.header.number), hash);
+            parent = executed.block.block.header;
+        }
+        Ok(())
+    }
+
+    /// Executes the transactions on top of the current best block without committing any changes,
+    /// expects the mining lock to be held.
+    async fn execute_block_with_attributes(
+        &self,
+        items: Vec<BlockItem>,
+        attributes: &BlockAttributes,
+    ) -> (BlockInfo, B256) {
+        let (block_number, best_hash) = {
+            let storage = self.blockchain.storage.read();
+            (storage.best_number.saturating_add(U64::from(1)), storage.best_hash)
+        };
+        let mut env = self.next_block_env(block_number, Some(attributes));
+        env.block.timestamp = U256::from(attributes.timestamp);
+
+        let db = self.db.read().await;
+        let base = if self.is_fork() { None } else { db.maybe_as_full_db() };
+        let mut overlay = CacheDB::new(&**db);
+        let (executed, block_hash) =
+            self.execute_on_overlay(&mut overlay, base, env, best_hash, items, attributes);
+        (executed.block, block_hash)
+    }
+
+    /// Executes the items on top of the overlay, the state root is computed as if the changes were
+    /// committed to `base`, the accounts of the database below the overlay.
+    fn execute_on_overlay<DB>(
+        &self,
+        overlay: &mut CacheDB<DB>,
+        base: Option<&HashMap<Address, DbAccount>>,
+        env: EnvWithHandlerCfg,
+        parent_hash: B256,
+        items: Vec<BlockItem>,
+        attributes: &BlockAttributes,
+    ) -> (ExecutedTransactions, B256)
+    where
+        DB: DatabaseRef<Error = DatabaseError> + Send + Sync + Clone + fmt::Debug,
+    {
+        let executor = TransactionExecutor {
+            db: &mut *overlay,
+            validator: self,
+            pending: items.into_iter(),
+            block_env: env.block.clone(),
+            cfg_env: CfgEnvWithHandlerCfg::new(env.cfg.clone(), env.handler_cfg),
+            parent_hash,
+            gas_used: 0,
+            blob_gas_used: 0,
+            enable_steps_tracing: self.enable_steps_tracing,
+            print_logs: self.print_logs,
+            precompile_factory: self.precompile_factory.clone(),
+            odyssey: self.odyssey,
+        };
+        let mut executed = executor.execute();
+        executed.block.block.header.state_root = base
+            .map(|accounts| state::state_root_with_overlay(accounts, &overlay.accounts))
+            .unwrap_or_default();
+        apply_block_attributes(&mut executed.block.block.header, attributes);
+        let block_hash = executed.block.block.header.hash_slow();
+        (executed, block_hash)
+    }
+
     /// Mines a new block and stores it.
     ///
-    /// this will execute all transaction in the order they come in and return all the markers they
-    /// provide.
+    /// The block is assembled from the transactions and the pending bundles by the configured
+    /// [BlockBuilder], which by default executes the bundles first and then all transactions in the
+    /// order they come in.
     pub async fn mine_block(
         &self,
         pool_transactions: Vec<Arc<PoolTransaction>>,
     ) -> MinedBlockOutcome {
-        self.do_mine_block(pool_transactions).await
+        self.do_mine_block(pool_transactions, None).await
+    }
+
+    /// Mines a new block on top of the current best block, using the given header values instead
+    /// of the ones the node would pick.
+    ///
+    /// The timestamp of the attributes is used as is, and the node's clock continues from it.
+    pub async fn mine_block_with_attributes(
+        &self,
+        pool_transactions: Vec<Arc<PoolTransaction>>,
+        attributes: BlockAttributes,
+    ) -> MinedBlockOutcome {
+        self.do_mine_block(pool_transactions, Some(attributes)).await
     }
 
     async fn do_mine_block(
         &self,
         pool_transactions: Vec<Arc<PoolTransaction>>,
+        attributes: Option<BlockAttributes>,
     ) -> MinedBlockOutcome {
         let _mining_guard = self.mining.lock().await;
-        trace!(target: "backend", "creating new block with {} transactions", pool_transactions.len());
-
-        let (outcome, header, block_hash) = {
-            let current_base_fee = self.base_fee();
-            let current_excess_blob_gas_and_price = self.excess_blob_gas_and_price();
-
-            let mut env = self.env.read().clone();
-
-            if env.block.basefee.is_zero() {
-                // this is an edge case because the evm fails if `tx.effective_gas_price < base_fee`
-                // 0 is only possible if it's manually set
-                env.cfg.disable_base_fee = true;
-            }
+        let items = self.block_items(pool_transactions);
+        self.mine_block_locked(BlockContents::Items(items), attributes)
+            .await
+            .expect("only imported blocks are rejected")
+    }
 
+    /// Mines a new block on top of the current best block, expects the mining lock to be held.
+    ///
+    /// An imported block is executed on top of an overlay first, and its changes are only committed
+    /// if it's valid.
+    async fn mine_block_locked(
+        &self,
+        contents: BlockContents,
+        attributes: Option<BlockAttributes>,
+    ) -> Result<MinedBlockOutcome, BlockImportError> {
+        let (outcome, header, block_hash, persisted) = {
             let block_number =
                 self.blockchain.storage.read().best_number.saturating_add(U64::from(1));
-
-            // increase block number for this block
-            if is_arbitrum(env.cfg.chain_id) {
-                // Temporary set `env.block.number` to `block_number` for Arbitrum chains.
-                env.block.number = block_number.to();
-            } else {
-                env.block.number = env.block.number.saturating_add(U256::from(1));
-            }
-
-            env.block.basefee = U256::from(current_base_fee);
-            env.block.blob_excess_gas_and_price = current_excess_blob_gas_and_price;
-
-            // pick a random value for prevrandao
-            env.block.prevrandao = Some(B256::random());
+            let mut env = self.next_block_env(block_number, attributes.as_ref());
 
             let best_hash = self.blockchain.storage.read().best_hash;
 
@@ -1165,23 +1641,57 @@ async fn do_mine_block(
                 // finally set the next block timestamp, this is done just before execution, because
                 // there can be concurrent requests that can delay acquiring the db lock and we want
                 // to ensure the timestamp is as close as possible to the actual execution.
-                env.block.timestamp = U256::from(self.time.next_timestamp());
-
-                let executor = TransactionExecutor {
-                    db: &mut **db,
-                    validator: self,
-                    pending: pool_transactions.into_iter(),
-                    block_env: env.block.clone(),
-                    cfg_env: CfgEnvWithHandlerCfg::new(env.cfg.clone(), env.handler_cfg),
-                    parent_hash: best_hash,
-                    gas_used: 0,
-                    blob_gas_used: 0,
-                    enable_steps_tracing: self.enable_steps_tracing,
-                    print_logs: self.print_logs,
-                    odyssey: self.odyssey,
-                    precompile_factory: self.precompile_factory.clone(),
+                env.block.timestamp = match &attributes {
+                    Some(attributes) => U256::from(attributes.timestamp),
+                    None => U256::from(self.time.next_timestamp()),
+                };
+
+                let executed_tx = match contents {
+                    BlockContents::Items(items) => {
+                        trace!(target: "backend", "creating new block with {} items", items.len());
+                        let executor = TransactionExecutor {
+                            db: &mut **db,
+                            validator: self,
+                            pending: items.into_iter(),
+                            block_env: env.block.clone(),
+                            cfg_env: CfgEnvWithHandlerCfg::new(env.cfg.clone(), env.handler_cfg),
+                            parent_hash: best_hash,
+                            gas_used: 0,
+                            blob_gas_used: 0,
+                            enable_steps_tracing: self.enable_steps_tracing,
+                            print_logs: self.print_logs,
+                            odyssey: self.odyssey,
+                            precompile_factory: self.precompile_factory.clone(),
+                        };
+                        let mut executed_tx = executor.execute();
+                        if let Some(attributes) = &attributes {
+                            apply_block_attributes(&mut executed_tx.block.block.header, attributes);
+                        }
+                        executed_tx
+                    }
+                    BlockContents::Imported(block) => {
+                        trace!(target: "backend", "importing block {}", block.hash);
+                        let base = if self.is_fork() { None } else { db.maybe_as_full_db() };
+                        let mut overlay = CacheDB::new(&**db);
+                        let items =
+                            block.transactions.iter().cloned().map(BlockItem::from).collect();
+                        let (executed_tx, hash) = self.execute_on_overlay(
+                            &mut overlay,
+                            base,
+                            env.clone(),
+                            best_hash,
+                            items,
+                            &block.attributes,
+                        );
+                        block.check_executed(&executed_tx, hash)?;
+                        let accounts = overlay.accounts;
+                        commit_overlay(&mut **db, accounts);
+                        executed_tx
+                    }
                 };
-                let executed_tx = executor.execute();
+                if let Some(attributes) = &attributes {
+                    self.time.reset(attributes.timestamp);
+                }
 
                 // we also need to update the new blockhash in the db itself
                 let block_hash = executed_tx.block.block.header.hash_slow();
@@ -1191,8 +1701,15 @@ async fn do_mine_block(
             };
 
             // create the new block with the current timestamp
-            let ExecutedTransactions { block, included, invalid } = executed_tx;
+            let ExecutedTransactions { block, included, invalid, state_changes } = executed_tx;
             let BlockInfo { block, transactions, receipts } = block;
+            let changed_accounts = state_changes.accounts.iter().copied().collect();
+
+            // the block and its transactions to write to the chain db
+            let mut persisted = self
+                .chain_db
+                .is_some()
+                .then(|| (SerializableBlock::from(block.clone()), Vec::new(), state_changes, None));
 
             let header = block.header.clone();
 
@@ -1216,6 +1733,7 @@ async fn do_mine_block(
 
             storage.blocks.insert(block_hash, block);
             storage.hashes.insert(block_number, block_hash);
+            storage.changed_accounts.insert(block_hash, changed_accounts);
 
             node_info!("");
             // insert all transactions
@@ -1241,6 +1759,9 @@ async fn do_mine_block(
                     block_hash,
                     block_number: block_number.to::<u64>(),
                 };
+                if let Some((_, transactions, ..)) = &mut persisted {
+                    transactions.push(mined_tx.clone().into());
+                }
                 storage.transactions.insert(mined_tx.info.transaction_hash, mined_tx);
             }
 
@@ -1250,13 +1771,21 @@ async fn do_mine_block(
                     let to_clear = block_number
                         .to::<u64>()
                         .saturating_sub(transaction_block_keeper.try_into().unwrap_or(u64::MAX));
-                    storage.remove_block_transactions_by_number(to_clear)
+                    storage.remove_block_transactions_by_number(to_clear);
+                    if let Some((.., pruned)) = &mut persisted {
+                        *pruned = Some(to_clear);
+                    }
                 }
             }
 
             // we intentionally set the difficulty to `0` for newer blocks
             env.block.difficulty = U256::from(0);
 
+            if attributes.is_some() {
+                // the fee recipient only applies to this block
+                env.block.coinbase = self.env.read().block.coinbase;
+            }
+
             // update env with new values
             *self.env.write() = env;
 
@@ -1271,10 +1800,32 @@ async fn do_mine_block(
                 node_info!("    Block Time: {:?}\n", timestamp.to_rfc2822());
             }
 
+            // drop the bundles that were included or expired
+            let included_hashes = included.iter().map(|tx| tx.hash()).collect();
+            self.bundles.write().on_mined_block(block_number.to(), &included_hashes);
+
             let outcome = MinedBlockOutcome { block_number, included, invalid };
 
-            (outcome, header, block_hash)
+            (outcome, header, block_hash, persisted)
         };
+
+        if let (Some(chain_db), Some((block, transactions, changes, pruned))) =
+            (self.chain_db.clone(), persisted)
+        {
+            let block_env = self.env.read().block.clone();
+            let res = match changes.read_values(&**self.db.read().await) {
+                Ok(values) => {
+                    chain_db
+                        .write_block(block, transactions, changes, values, block_env, pruned)
+                        .await
+                }
+                Err(err) => Err(err),
+            };
+            if let Err(err) = res {
+                error!(target: "backend", %err, "failed to persist block {}", outcome.block_number);
+            }
+        }
+
         let next_block_base_fee = self.fees.get_next_block_base_fee_per_gas(
             header.gas_used as u128,
             header.gas_limit as u128,
@@ -1295,7 +1846,7 @@ async fn do_mine_block(
         // notify all listeners
         self.notify_on_new_block(header, block_hash);
 
-        outcome
+        Ok(outcome)
     }
 
     /// Executes the [TransactionRequest] without writing to the DB
@@ -1461,6 +2012,303 @@ pub fn call_with_state(
         Ok((exit_reason, out, gas_used as u128, state))
     }
 
+    /// Simulates the blocks of the [SimulatePayload] on top of the state of the requested block
+    /// without writing to the DB, see `eth_simulateV1`
+    ///
+    /// Each simulated block builds on top of the state and header of the previous one: its number
+    /// is incremented by one and its timestamp by 12 seconds, unless set by the block overrides.
+    pub async fn simulate(
+        &self,
+        request: SimulatePayload,
+        block_request: Option<BlockRequest>,
+    ) -> Result<Vec<SimulatedBlockResult<AnyRpcBlock>>, BlockchainError> {
+        self.with_database_at(block_request, |state, mut block_env| {
+            let SimulatePayload {
+                block_state_calls,
+                trace_transfers,
+                validation,
+                return_full_transactions,
+            } = request;
+            if block_state_calls.len() as u64 > MAX_SIMULATE_BLOCKS {
+                return Err(RpcError::invalid_params(format!(
+                    "too many blocks, at most {MAX_SIMULATE_BLOCKS} blocks can be simulated"
+                ))
+                .into());
+            }
+
+            let mut cache_db = CacheDB::new(state);
+            let mut parent_hash = self
+                .blockchain
+                .storage
+                .read()
+                .hashes
+                .get(&U64::from(block_env.number.to::<u64>()))
+                .copied()
+                .unwrap_or_default();
+            let mut simulated_blocks = Vec::with_capacity(block_state_calls.len());
+
+            for block in block_state_calls {
+                let SimBlock { block_overrides, state_overrides, calls } = block;
+
+                let parent_number = block_env.number;
+                block_env.number = block_env.number.saturating_add(U256::from(1));
+                block_env.timestamp = block_env.timestamp.saturating_add(U256::from(12));
+                if let Some(overrides) = state_overrides {
+                    state::apply_cached_db_state_override(overrides, &mut cache_db)?;
+                }
+                let base_fee_override =
+                    block_overrides.as_ref().is_some_and(|overrides| overrides.base_fee.is_some());
+                if let Some(overrides) = block_overrides {
+                    state::apply_block_overrides(overrides, &mut cache_db, &mut block_env);
+                }
+                if block_env.number <= parent_number {
+                    return Err(RpcError::invalid_params(format!(
+                        "block numbers must be strictly increasing: {} follows {parent_number}",
+                        block_env.number
+                    ))
+                    .into());
+                }
+                if !validation && !base_fee_override {
+                    // fees are not enforced if validation is disabled, unless explicitly set
+                    block_env.basefee = U256::ZERO;
+                }
+
+                let block_number = block_env.number.to::<u64>();
+                let timestamp = block_env.timestamp.to::<u64>();
+                let base_fee = block_env.basefee.to::<u64>();
+                let block_gas_limit = block_env.gas_limit.to::<u64>();
+
+                let mut transactions = Vec::with_capacity(calls.len());
+                let mut receipts = Vec::with_capacity(calls.len());
+                let mut results = Vec::with_capacity(calls.len());
+                let mut bloom = Bloom::default();
+                let mut cumulative_gas_used = 0u64;
+                let mut log_index = 0u64;
+
+                for mut request in calls {
+                    let from = request.from.unwrap_or_default();
+                    let nonce = match request.nonce {
+                        Some(nonce) => nonce,
+                        None => cache_db.basic_ref(from)?.unwrap_or_default().nonce,
+                    };
+                    let fee_details = FeeDetails::new(
+                        request.gas_price,
+                        request.max_fee_per_gas,
+                        request.max_priority_fee_per_gas,
+                        request.max_fee_per_blob_gas,
+                    )?;
+                    let fee_details =
+                        if validation { fee_details } else { fee_details.or_zero_fees() };
+
+                    let mut env = self.build_call_env(
+                        WithOtherFields::new(request.clone()),
+                        fee_details,
+                        block_env.clone(),
+                    );
+                    if validation {
+                        // enforce the nonce and fee checks a mined transaction would be subject to
+                        env.tx.nonce = Some(nonce);
+                        env.cfg.disable_base_fee = false;
+                    }
+
+                    // the calls of a block share its gas limit, calls without a gas limit get
+                    // the gas that is left
+                    let gas_left = block_gas_limit.saturating_sub(cumulative_gas_used);
+                    if request.gas.is_none() {
+                        env.tx.gas_limit = env.tx.gas_limit.min(gas_left);
+                    }
+                    if env.tx.gas_limit > gas_left {
+                        results.push(invalid_simulated_call(
+                            -38015,
+                            format!(
+                                "block gas limit reached: {} exceeds the {gas_left} gas left",
+                                env.tx.gas_limit
+                            ),
+                        ));
+                        continue;
+                    }
+
+                    let transacted = if trace_transfers {
+                        let mut inspector = TransferInspector::new(false).with_logs(true);
+                        let mut evm =
+                            self.new_evm_with_inspector_ref(&cache_db, env.clone(), &mut inspector);
+                        evm.transact()
+                    } else {
+                        let mut inspector = self.build_inspector();
+                        let mut evm =
+                            self.new_evm_with_inspector_ref(&cache_db, env.clone(), &mut inspector);
+                        let result = evm.transact();
+                        drop(evm);
+                        inspector.print_logs();
+                        result
+                    };
+                    // a call that fails validation is reported on its own and isn't included in
+                    // the block
+                    let ResultAndState { result, state } = match transacted {
+                        Ok(result) => result,
+                        Err(EVMError::Transaction(err)) => {
+                            let err = InvalidTransactionError::from(err);
+                            results.push(invalid_simulated_call(
+                                simulate_error_code(&err),
+                                err.to_string(),
+                            ));
+                            continue;
+                        }
+                        Err(err) => return Err(err.into()),
+                    };
+                    cache_db.commit(state);
+
+                    // build the transaction that corresponds to the simulated call
+                    request.from = Some(from);
+                    request.nonce = Some(nonce);
+                    request.gas = Some(env.tx.gas_limit);
+                    request.chain_id = Some(self.chain_id().to());
+                    let typed = transaction_request_to_typed(WithOtherFields::new(request))
+                        .ok_or(BlockchainError::FailedToDecodeTransaction)?;
+                    let bypass_signature = Signature::from_scalars_and_parity(
+                        B256::with_last_byte(1),
+                        B256::with_last_byte(1),
+                        false,
+                    );
+                    let transaction = MaybeImpersonatedTransaction::impersonated(
+                        build_typed_transaction(typed, bypass_signature)?,
+                        from,
+                    );
+                    let transaction_hash = transaction.hash();
+
+                    let gas_used = result.gas_used();
+                    cumulative_gas_used = cumulative_gas_used.saturating_add(gas_used);
+                    let status = result.is_success();
+                    let error = match &result {
+                        ExecutionResult::Success { .. } => None,
+                        ExecutionResult::Revert { output, .. } => Some(SimulatedCallError {
+                            code: -32000,
+                            message: "execution reverted".to_string(),
+                            data: Some(output.clone()),
+                        }),
+                        ExecutionResult::Halt { reason, .. } => Some(SimulatedCallError {
+                            code: -32015,
+                            message: format!("vm execution error: {reason:?}"),
+                            data: None,
+                        }),
+                    };
+                    let return_data = result.output().cloned().unwrap_or_default();
+                    let logs = result.into_logs();
+                    build_logs_bloom(logs.clone(), &mut bloom);
+
+                    let receipt_with_bloom: ReceiptWithBloom =
+                        Receipt { status: status.into(), cumulative_gas_used, logs: logs.clone() }
+                            .into();
+                    receipts.push(match &transaction.transaction {
+                        TypedTransaction::Legacy(_) => TypedReceipt::Legacy(receipt_with_bloom),
+                        TypedTransaction::EIP2930(_) => TypedReceipt::EIP2930(receipt_with_bloom),
+                        TypedTransaction::EIP1559(_) => TypedReceipt::EIP1559(receipt_with_bloom),
+                        TypedTransaction::EIP4844(_) => TypedReceipt::EIP4844(receipt_with_bloom),
+                        TypedTransaction::EIP7702(_) => TypedReceipt::EIP7702(receipt_with_bloom),
+                        TypedTransaction::Deposit(tx) => TypedReceipt::Deposit(DepositReceipt {
+                            inner: receipt_with_bloom,
+                            deposit_nonce: Some(tx.nonce),
+                            deposit_receipt_version: Some(1),
+                        }),
+                    });
+
+                    let logs = logs
+                        .into_iter()
+                        .map(|inner| {
+                            let log = Log {
+                                inner,
+                                block_hash: None,
+                                block_number: Some(block_number),
+                                block_timestamp: Some(timestamp),
+                                transaction_hash: Some(transaction_hash),
+                                transaction_index: Some(transactions.len() as u64),
+                                log_index: Some(log_index),
+                                removed: false,
+                            };
+                            log_index += 1;
+                            log
+                        })
+                        .collect();
+                    results.push(SimulatedCallResult {
+                        return_data,
+                        logs,
+                        gas_used,
+                        status,
+                        error,
+                    });
+                    transactions.push(transaction);
+                }
+
+                let spec_id = self.spec_id();
+                let partial_header = PartialHeader {
+                    parent_hash,
+                    beneficiary: block_env.coinbase,
+                    state_root: Default::default(),
+                    receipts_root: trie::ordered_trie_root(
+                        receipts.iter().map(Encodable2718::encoded_2718),
+                    ),
+                    logs_bloom: bloom,
+                    difficulty: block_env.difficulty,
+                    number: block_number,
+                    gas_limit: block_env.gas_limit.to(),
+                    gas_used: cumulative_gas_used,
+                    timestamp,
+                    extra_data: Default::default(),
+                    mix_hash: block_env.prevrandao.unwrap_or_default(),
+                    nonce: Default::default(),
+                    base_fee: spec_id.is_enabled_in(SpecId::LONDON).then_some(base_fee),
+                    parent_beacon_block_root: (spec_id >= SpecId::CANCUN)
+                        .then_some(Default::default()),
+                    blob_gas_used: (spec_id >= SpecId::CANCUN).then_some(0),
+                    excess_blob_gas: block_env.get_blob_excess_gas(),
+                    withdrawals_root: (spec_id >= SpecId::SHANGHAI).then_some(EMPTY_WITHDRAWALS),
+                    requests_hash: (spec_id >= SpecId::PRAGUE).then_some(EMPTY_REQUESTS_HASH),
+                };
+                let block = Block::new(partial_header, transactions);
+                let block_hash = block.header.hash_slow();
+                for log in results.iter_mut().flat_map(|result| result.logs.iter_mut()) {
+                    log.block_hash = Some(block_hash);
+                }
+
+                let full_transactions = return_full_transactions.then(|| {
+                    block
+                        .transactions
+                        .iter()
+                        .enumerate()
+                        .map(|(index, tx)| {
+                            let mut tx = transaction_build(
+                                Some(tx.hash()),
+                                tx.clone(),
+                                Some(&block),
+                                None,
+                                block.header.base_fee_per_gas,
+                            );
+                            tx.inner.transaction_index = Some(index as u64);
+                            tx
+                        })
+                        .collect::<Vec<_>>()
+                });
+                let next_base_fee = self.fees().get_next_block_base_fee_per_gas(
+                    block.header.gas_used as u128,
+                    block.header.gas_limit as u128,
+                    base_fee,
+                );
+
+                let mut inner = self.convert_block(block);
+                if let Some(transactions) = full_transactions {
+                    inner.inner.transactions = BlockTransactions::Full(transactions);
+                }
+                simulated_blocks.push(SimulatedBlockResult { inner, calls: results });
+
+                parent_hash = block_hash;
+                block_env.basefee = U256::from(next_base_fee);
+            }
+
+            Ok(simulated_blocks)
+        })
+        .await?
+    }
+
     pub async fn call_with_tracing(
         &self,
         request: WithOtherFields<TransactionRequest>,
@@ -1484,41 +2332,17 @@ pub async fn call_with_tracing(
 
             if let Some(tracer) = tracer {
                 return match tracer {
-                    GethDebugTracerType::BuiltInTracer(tracer) => match tracer {
-                        GethDebugBuiltInTracerType::CallTracer => {
-                            let call_config = tracer_config
-                                .into_call_config()
-                                .map_err(|e| (RpcError::invalid_params(e.to_string())))?;
-
-                            let mut inspector = self.build_inspector().with_tracing_config(
-                                TracingInspectorConfig::from_geth_call_config(&call_config),
-                            );
-
-                            let env = self.build_call_env(request, fee_details, block);
-                            let mut evm = self.new_evm_with_inspector_ref(
-                                state.as_dyn(),
-                                env,
-                                &mut inspector,
-                            );
-                            let ResultAndState { result, state: _ } = evm.transact()?;
-
-                            drop(evm);
-                            let tracing_inspector = inspector.tracer.expect("tracer disappeared");
-
-                            Ok(tracing_inspector
-                                .into_geth_builder()
-                                .geth_call_traces(call_config, result.gas_used())
-                                .into())
-                        }
-                        GethDebugBuiltInTracerType::NoopTracer => Ok(NoopFrame::default().into()),
-                        GethDebugBuiltInTracerType::FourByteTracer |
-                        GethDebugBuiltInTracerType::PreStateTracer |
-                        GethDebugBuiltInTracerType::MuxTracer |
-                        GethDebugBuiltInTracerType::FlatCallTracer => {
-                            Err(RpcError::invalid_params("unsupported tracer type").into())
-                        }
-                    },
-
+                    GethDebugTracerType::BuiltInTracer(tracer) => {
+                        let env = self.build_call_env(request, fee_details, block);
+                        self.trace_env_with_builtin_tracer(
+                            state.as_dyn(),
+                            env,
+                            tracer,
+                            tracer_config,
+                            Default::default(),
+                        )
+                        .map(|(trace, _)| trace)
+                    }
                     GethDebugTracerType::JsTracer(_code) => {
                         Err(RpcError::invalid_params("unsupported tracer type").into())
                     }
@@ -1560,17 +2384,200 @@ pub async fn call_with_tracing(
         .await?
     }
 
-    pub fn build_access_list_with_state(
+    /// Executes the transaction described by `env` on top of `state` with the inspector required
+    /// by the given built-in geth `tracer` and builds the corresponding [GethTrace].
+    ///
+    /// The transaction is not committed, so `state` still represents the pre-transaction state,
+    /// which is required by the `prestateTracer`. The resulting state changes are returned
+    /// alongside the trace so callers can commit them when replaying several transactions.
+    fn trace_env_with_builtin_tracer(
         &self,
         state: &dyn DatabaseRef<Error = DatabaseError>,
-        request: WithOtherFields<TransactionRequest>,
-        fee_details: FeeDetails,
-        block_env: BlockEnv,
-    ) -> Result<(InstructionResult, Option<Output>, u64, AccessList), BlockchainError> {
-        let mut inspector =
-            AccessListInspector::new(request.access_list.clone().unwrap_or_default());
+        env: EnvWithHandlerCfg,
+        tracer: GethDebugBuiltInTracerType,
+        tracer_config: GethDebugTracerConfig,
+        tx_info: RethTransactionInfo,
+    ) -> Result<(GethTrace, State), BlockchainError> {
+        match tracer {
+            GethDebugBuiltInTracerType::CallTracer => {
+                let call_config = tracer_config
+                    .into_call_config()
+                    .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+
+                let mut inspector = self.build_inspector().with_tracing_config(
+                    TracingInspectorConfig::from_geth_call_config(&call_config),
+                );
+                let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+                let ResultAndState { result, state } = evm.transact()?;
 
-        let env = self.build_call_env(request, fee_details, block_env);
+                drop(evm);
+                let tracing_inspector = inspector.tracer.expect("tracer disappeared");
+
+                let trace = tracing_inspector
+                    .into_geth_builder()
+                    .geth_call_traces(call_config, result.gas_used())
+                    .into();
+                Ok((trace, state))
+            }
+            GethDebugBuiltInTracerType::FlatCallTracer => {
+                let flat_call_config = tracer_config
+                    .into_flat_call_config()
+                    .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+
+                let mut inspector = self.build_inspector().with_tracing_config(
+                    TracingInspectorConfig::from_flat_call_config(&flat_call_config),
+                );
+                let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+                let ResultAndState { result, state } = evm.transact()?;
+
+                drop(evm);
+                let tracing_inspector = inspector.tracer.expect("tracer disappeared");
+
+                let trace = tracing_inspector
+                    .with_transaction_gas_used(result.gas_used())
+                    .into_parity_builder()
+                    .into_localized_transaction_traces(tx_info)
+                    .into();
+                Ok((trace, state))
+            }
+            GethDebugBuiltInTracerType::PreStateTracer => {
+                let prestate_config = tracer_config
+                    .into_pre_state_config()
+                    .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+
+                let mut inspector = self.build_inspector().with_tracing_config(
+                    TracingInspectorConfig::from_geth_prestate_config(&prestate_config),
+                );
+                let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+                let result = evm.transact()?;
+
+                drop(evm);
+                let tracing_inspector = inspector.tracer.expect("tracer disappeared");
+
+                let trace = tracing_inspector
+                    .into_geth_builder()
+                    .geth_prestate_traces(&result, &prestate_config, state)?
+                    .into();
+                Ok((trace, result.state))
+            }
+            GethDebugBuiltInTracerType::FourByteTracer => {
+                let mut inspector = FourByteInspector::default();
+                let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+                let ResultAndState { state, .. } = evm.transact()?;
+
+                drop(evm);
+                Ok((FourByteFrame::from(inspector).into(), state))
+            }
+            GethDebugBuiltInTracerType::MuxTracer => {
+                let mux_config = tracer_config
+                    .into_mux_config()
+                    .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+
+                let mut inspector = MuxInspector::try_from_config(mux_config)
+                    .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+                let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+                let result = evm.transact()?;
+
+                drop(evm);
+                let trace = inspector.try_into_mux_frame(&result, &state, tx_info)?.into();
+                Ok((trace, result.state))
+            }
+            GethDebugBuiltInTracerType::NoopTracer => {
+                let mut inspector = Inspector::default();
+                let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+                let ResultAndState { state, .. } = evm.transact()?;
+
+                drop(evm);
+                Ok((NoopFrame::default().into(), state))
+            }
+        }
+    }
+
+    /// Executes the transaction described by `env` on top of `state` with a tracer configured for
+    /// the given parity `trace_types` and builds the corresponding [TraceResults].
+    ///
+    /// Like [Self::trace_env_with_builtin_tracer] the state changes are returned but not committed.
+    fn trace_env_with_parity_tracer(
+        &self,
+        state: &dyn DatabaseRef<Error = DatabaseError>,
+        env: EnvWithHandlerCfg,
+        trace_types: &HashSet<TraceType>,
+    ) -> Result<(TraceResults, State), BlockchainError> {
+        let mut inspector = self
+            .build_inspector()
+            .with_tracing_config(TracingInspectorConfig::from_parity_config(trace_types));
+        let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+        let result = evm.transact()?;
+
+        drop(evm);
+        inspector.print_logs();
+        let tracing_inspector = inspector.tracer.expect("tracer disappeared");
+
+        let trace = tracing_inspector
+            .with_transaction_gas_used(result.result.gas_used())
+            .into_parity_builder()
+            .into_trace_results_with_state(&result, trace_types, state)?;
+        Ok((trace, result.state))
+    }
+
+    /// Executes the [TransactionRequest] without writing to the DB and returns the parity traces
+    /// of the requested `trace_types`, see `trace_call`
+    pub async fn trace_call(
+        &self,
+        request: WithOtherFields<TransactionRequest>,
+        trace_types: HashSet<TraceType>,
+        fee_details: FeeDetails,
+        block_request: Option<BlockRequest>,
+    ) -> Result<TraceResults, BlockchainError> {
+        self.with_database_at(block_request, |state, block| {
+            let env = self.build_call_env(request, fee_details, block);
+            self.trace_env_with_parity_tracer(state.as_dyn(), env, &trace_types)
+                .map(|(trace, _)| trace)
+        })
+        .await?
+    }
+
+    /// Executes the [TransactionRequest]s sequentially without writing to the DB, each one on top
+    /// of the state changes of the previous ones, and returns the parity traces of the requested
+    /// `trace_types`, see `trace_callMany`
+    pub async fn trace_call_many(
+        &self,
+        calls: Vec<(WithOtherFields<TransactionRequest>, HashSet<TraceType>)>,
+        block_request: Option<BlockRequest>,
+    ) -> Result<Vec<TraceResults>, BlockchainError> {
+        self.with_database_at(block_request, |state, block| {
+            let mut cache_db = CacheDB::new(state);
+            let mut traces = Vec::with_capacity(calls.len());
+            for (request, trace_types) in calls {
+                let fee_details = FeeDetails::new(
+                    request.gas_price,
+                    request.max_fee_per_gas,
+                    request.max_priority_fee_per_gas,
+                    request.max_fee_per_blob_gas,
+                )?
+                .or_zero_fees();
+                let env = self.build_call_env(request, fee_details, block.clone());
+                let (trace, state) =
+                    self.trace_env_with_parity_tracer(&cache_db, env, &trace_types)?;
+                cache_db.commit(state);
+                traces.push(trace);
+            }
+            Ok(traces)
+        })
+        .await?
+    }
+
+    pub fn build_access_list_with_state(
+        &self,
+        state: &dyn DatabaseRef<Error = DatabaseError>,
+        request: WithOtherFields<TransactionRequest>,
+        fee_details: FeeDetails,
+        block_env: BlockEnv,
+    ) -> Result<(InstructionResult, Option<Output>, u64, AccessList), BlockchainError> {
+        let mut inspector =
+            AccessListInspector::new(request.access_list.clone().unwrap_or_default());
+
+        let env = self.build_call_env(request, fee_details, block_env);
         let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
         let ResultAndState { result, state: _ } = evm.transact()?;
         let (exit_reason, gas_used, out) = match result {
@@ -2201,7 +3208,260 @@ fn mined_geth_trace_transaction(
         hash: B256,
         opts: GethDebugTracingOptions,
     ) -> Option<Result<GethTrace, BlockchainError>> {
-        self.blockchain.storage.read().transactions.get(&hash).map(|tx| tx.geth_trace(opts))
+        let tx = self.mined_transaction(hash)?;
+        if let Some(tracer) = opts.tracer.as_ref().and_then(replay_builtin_tracer) {
+            let trace = self
+                .get_block_by_hash(tx.block_hash)
+                .ok_or(BlockchainError::BlockNotFound)
+                .and_then(|block| {
+                    self.replay_block_transactions(&block, Some(hash), |state, env, tx_info| {
+                        self.trace_env_with_builtin_tracer(
+                            state,
+                            env,
+                            tracer,
+                            opts.tracer_config.clone(),
+                            tx_info,
+                        )
+                    })?
+                    .pop()
+                    .map(|(_, trace)| trace)
+                    .unwrap_or(Err(BlockchainError::DataUnavailable))
+                });
+            return Some(trace);
+        }
+        Some(tx.geth_trace(opts))
+    }
+
+    /// Returns the geth traces of all transactions of the given block
+    pub async fn debug_trace_block(
+        &self,
+        block_id: BlockId,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult<GethTrace, String>>, BlockchainError> {
+        let number = self.ensure_block_number(Some(block_id)).await?;
+
+        if let Some(block) = self.get_block(number) {
+            return self.mined_geth_trace_block(&block, opts);
+        }
+
+        if let Some(fork) = self.get_fork() {
+            if fork.predates_fork_inclusive(number) {
+                return Ok(fork.debug_trace_block(number, opts).await?)
+            }
+        }
+
+        Err(BlockchainError::BlockNotFound)
+    }
+
+    /// Returns the geth traces of all transactions of the given mined block
+    fn mined_geth_trace_block(
+        &self,
+        block: &Block,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult<GethTrace, String>>, BlockchainError> {
+        let traces = if let Some(tracer) = opts.tracer.as_ref().and_then(replay_builtin_tracer) {
+            self.replay_block_transactions(block, None, |state, env, tx_info| {
+                self.trace_env_with_builtin_tracer(
+                    state,
+                    env,
+                    tracer,
+                    opts.tracer_config.clone(),
+                    tx_info,
+                )
+            })?
+        } else {
+            let storage = self.blockchain.storage.read();
+            block
+                .transactions
+                .iter()
+                .map(|tx| {
+                    let hash = tx.hash();
+                    let trace = storage
+                        .transactions
+                        .get(&hash)
+                        .ok_or(BlockchainError::DataUnavailable)
+                        .and_then(|tx| tx.geth_trace(opts.clone()));
+                    (hash, trace)
+                })
+                .collect()
+        };
+
+        Ok(traces
+            .into_iter()
+            .map(|(hash, trace)| match trace {
+                Ok(result) => TraceResult::Success { result, tx_hash: Some(hash) },
+                Err(err) => TraceResult::Error { error: err.to_string(), tx_hash: Some(hash) },
+            })
+            .collect())
+    }
+
+    /// Returns the environment to replay the transactions of the given block with
+    fn replay_block_env(&self, block: &Block) -> EnvWithHandlerCfg {
+        let mut env = self.env.read().clone();
+        env.block = BlockEnv {
+            number: U256::from(block.header.number),
+            coinbase: block.header.beneficiary,
+            timestamp: U256::from(block.header.timestamp),
+            difficulty: block.header.difficulty,
+            prevrandao: Some(block.header.mix_hash),
+            basefee: U256::from(block.header.base_fee_per_gas.unwrap_or_default()),
+            gas_limit: U256::from(block.header.gas_limit),
+            blob_excess_gas_and_price: block
+                .header
+                .excess_blob_gas
+                .map(|excess_blob_gas| BlobExcessGasAndPrice::new(excess_blob_gas, false)),
+        };
+        if env.block.basefee.is_zero() {
+            // this is an edge case because the evm fails if `tx.effective_gas_price < base_fee`
+            // 0 is only possible if it's manually set
+            env.cfg.disable_base_fee = true;
+        }
+        env
+    }
+
+    /// Re-executes the transactions of the given mined block on top of the state of its parent
+    /// block and traces them with `trace_tx`.
+    ///
+    /// If `target` is set, only the transaction with that hash is traced and all transactions that
+    /// precede it are replayed without tracing, so the traced transaction observes the exact state
+    /// it was originally executed against.
+    ///
+    /// If a transaction can't be traced, the error is returned in place of its trace and the
+    /// transaction is replayed without tracing. If that fails as well, the whole replay fails.
+    fn replay_block_transactions<T>(
+        &self,
+        block: &Block,
+        target: Option<B256>,
+        mut trace_tx: impl FnMut(
+            &dyn DatabaseRef<Error = DatabaseError>,
+            EnvWithHandlerCfg,
+            RethTransactionInfo,
+        ) -> Result<(T, State), BlockchainError>,
+    ) -> Result<Vec<ReplayedTrace<T>>, BlockchainError> {
+        let mut states = self.states.write();
+        let parent_state =
+            states.get(&block.header.parent_hash).ok_or(BlockchainError::DataUnavailable)?;
+        let mut cache_db = CacheDB::new(parent_state);
+
+        let env = self.replay_block_env(block);
+        let block_hash = block.header.hash_slow();
+
+        let mut traces = Vec::with_capacity(block.transactions.len());
+        for (index, transaction) in block.transactions.iter().enumerate() {
+            let (hash, env) = replay_transaction_env(&env, transaction)?;
+
+            if target.is_none_or(|target| target == hash) {
+                let tx_info = RethTransactionInfo {
+                    hash: Some(hash),
+                    index: Some(index as u64),
+                    block_hash: Some(block_hash),
+                    block_number: Some(block.header.number),
+                    base_fee: block.header.base_fee_per_gas,
+                };
+                match trace_tx(&cache_db, env.clone(), tx_info) {
+                    Ok((trace, state)) => {
+                        cache_db.commit(state);
+                        traces.push((hash, Ok(trace)));
+                    }
+                    Err(err) => {
+                        // the following transactions still have to observe the state changes of
+                        // this one, the whole replay fails if it can't be executed at all
+                        if target.is_none() {
+                            cache_db.commit(self.replay_transaction(&cache_db, env)?);
+                        }
+                        traces.push((hash, Err(err)));
+                    }
+                }
+
+                if target.is_some() {
+                    break
+                }
+                continue
+            }
+
+            cache_db.commit(self.replay_transaction(&cache_db, env)?);
+        }
+
+        Ok(traces)
+    }
+
+    /// Executes the transaction without tracing and returns its state changes
+    fn replay_transaction(
+        &self,
+        state: &dyn DatabaseRef<Error = DatabaseError>,
+        env: EnvWithHandlerCfg,
+    ) -> Result<State, BlockchainError> {
+        let mut inspector = Inspector::default();
+        let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+        let ResultAndState { state, .. } = evm.transact()?;
+        Ok(state)
+    }
+
+    /// Replays the given transaction on top of the state it was originally executed against and
+    /// returns the parity traces of the requested `trace_types`
+    pub async fn trace_replay_transaction(
+        &self,
+        hash: B256,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<TraceResults, BlockchainError> {
+        if let Some(tx) = self.mined_transaction(hash) {
+            let block =
+                self.get_block_by_hash(tx.block_hash).ok_or(BlockchainError::BlockNotFound)?;
+            return self
+                .replay_block_transactions(&block, Some(hash), |state, env, _| {
+                    self.trace_env_with_parity_tracer(state, env, &trace_types)
+                })?
+                .pop()
+                .map(|(_, trace)| trace)
+                .unwrap_or(Err(BlockchainError::DataUnavailable))
+        }
+
+        if let Some(fork) = self.get_fork() {
+            return Ok(fork.trace_replay_transaction(hash, trace_types).await?)
+        }
+
+        Err(BlockchainError::DataUnavailable)
+    }
+
+    /// Replays all transactions of the given block and returns the parity traces of the requested
+    /// `trace_types`
+    pub async fn trace_replay_block_transactions(
+        &self,
+        block_id: BlockId,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<Vec<TraceResultsWithTransactionHash>, BlockchainError> {
+        let number = self.ensure_block_number(Some(block_id)).await?;
+
+        if let Some(block) = self.get_block(number) {
+            return Ok(self
+                .replay_block_transactions(&block, None, |state, env, _| {
+                    self.trace_env_with_parity_tracer(state, env, &trace_types)
+                })?
+                .into_iter()
+                .map(|(transaction_hash, trace)| {
+                    // a transaction that can't be traced keeps its entry without traces, so the
+                    // other transactions of the block are still reported
+                    let full_trace = trace.unwrap_or_else(|err| {
+                        warn!(target: "backend", ?transaction_hash, %err, "failed to trace tx");
+                        TraceResults {
+                            output: Bytes::new(),
+                            state_diff: None,
+                            trace: vec![],
+                            vm_trace: None,
+                        }
+                    });
+                    TraceResultsWithTransactionHash { full_trace, transaction_hash }
+                })
+                .collect())
+        }
+
+        if let Some(fork) = self.get_fork() {
+            if fork.predates_fork_inclusive(number) {
+                return Ok(fork.trace_replay_block_transactions(number, trace_types).await?)
+            }
+        }
+
+        Err(BlockchainError::BlockNotFound)
     }
 
     /// Returns the traces for the given block
@@ -2585,6 +3845,255 @@ pub async fn prove_account_at(
         .await?
     }
 
+    /// Returns the non-empty storage slots of the account, ordered by the hash of the slot and
+    /// starting at `start_key`, in the state right before the transaction at `tx_index` of the
+    /// block is executed.
+    ///
+    /// If the block predates the fork then this will fetch it from the endpoint. For later blocks
+    /// in fork mode, this only includes the slots that were fetched from the endpoint or modified
+    /// locally.
+    pub async fn storage_range_at(
+        &self,
+        block_id: BlockId,
+        tx_index: u64,
+        address: Address,
+        start_key: B256,
+        max_result: u64,
+    ) -> Result<StorageRangeResult, BlockchainError> {
+        let number = self.ensure_block_number(Some(block_id)).await?;
+
+        if let Some(fork) = self.get_fork() {
+            if fork.predates_fork_inclusive(number) {
+                return Ok(fork
+                    .storage_range_at(number, tx_index, address, start_key, max_result)
+                    .await?)
+            }
+        }
+
+        let block = self.get_block(number).ok_or(BlockchainError::BlockNotFound)?;
+        let storage = if (tx_index as usize) < block.transactions.len() {
+            self.storage_before_transaction(&block, tx_index as usize, address)?
+        } else {
+            self.with_database_at(Some(BlockRequest::Number(number)), |db, _| {
+                let db = db.maybe_as_full_db().ok_or(BlockchainError::DataUnavailable)?;
+                Ok::<_, BlockchainError>(
+                    db.get(&address).map(|account| account.storage.clone()).unwrap_or_default(),
+                )
+            })
+            .await??
+        };
+
+        Ok(storage_range(&storage, start_key, max_result))
+    }
+
+    /// Returns the storage of the account in the state right before the transaction at
+    /// `tx_index` of the given mined block is executed
+    fn storage_before_transaction(
+        &self,
+        block: &Block,
+        tx_index: usize,
+        address: Address,
+    ) -> Result<HashMap<U256, U256>, BlockchainError> {
+        let mut states = self.states.write();
+        let parent_state =
+            states.get(&block.header.parent_hash).ok_or(BlockchainError::DataUnavailable)?;
+        let mut storage = parent_state
+            .maybe_as_full_db()
+            .ok_or(BlockchainError::DataUnavailable)?
+            .get(&address)
+            .map(|account| account.storage.clone())
+            .unwrap_or_default();
+
+        let mut cache_db = CacheDB::new(parent_state);
+        let env = self.replay_block_env(block);
+        for transaction in &block.transactions[..tx_index] {
+            let (_, env) = replay_transaction_env(&env, transaction)?;
+            cache_db.commit(self.replay_transaction(&cache_db, env)?);
+        }
+
+        if let Some(account) = cache_db.accounts.get(&address) {
+            match account.account_state {
+                // the storage was wiped, so only the slots written afterwards remain
+                AccountState::NotExisting | AccountState::StorageCleared => {
+                    storage.clone_from(&account.storage)
+                }
+                _ => storage.extend(account.storage.iter().map(|(key, value)| (*key, *value))),
+            }
+        }
+
+        Ok(storage)
+    }
+
+    /// Returns the accounts of the state at the given block, ordered by the hash of their address
+    /// and starting at `start`.
+    ///
+    /// In fork mode, this only includes the accounts that were fetched from the endpoint or
+    /// modified locally.
+    pub async fn account_range(
+        &self,
+        block_id: BlockId,
+        start: B256,
+        max_results: u64,
+        no_code: bool,
+        no_storage: bool,
+    ) -> Result<AccountRangeResult, BlockchainError> {
+        let number = self.ensure_block_number(Some(block_id)).await?;
+        self.with_database_at(Some(BlockRequest::Number(number)), |state, _| {
+            let db = state.maybe_as_full_db().ok_or(BlockchainError::DataUnavailable)?;
+
+            let mut accounts = db
+                .iter()
+                .filter(|(_, account)| account.account_state != AccountState::NotExisting)
+                .map(|(address, account)| (keccak256(address), (*address, account)))
+                .filter(|(key, _)| *key >= start)
+                .collect::<BTreeMap<_, _>>();
+            let next = accounts.keys().nth(max_results as usize).copied();
+            if let Some(next) = next {
+                accounts.split_off(&next);
+            }
+
+            let accounts = accounts
+                .into_iter()
+                .map(|(key, (address, account))| {
+                    let code = if no_code {
+                        None
+                    } else {
+                        Some(self.get_code_with_state(state.as_dyn(), address)?)
+                            .filter(|code| !code.is_empty())
+                    };
+                    let storage = if no_storage {
+                        None
+                    } else {
+                        Some(
+                            account
+                                .storage
+                                .iter()
+                                .filter(|(_, value)| !value.is_zero())
+                                .map(|(slot, value)| (B256::from(*slot), B256::from(*value)))
+                                .collect::<BTreeMap<_, _>>(),
+                        )
+                        .filter(|storage| !storage.is_empty())
+                    };
+                    let entry = AccountRangeEntry {
+                        balance: account.info.balance,
+                        nonce: account.info.nonce,
+                        root: storage_root(&account.storage),
+                        code_hash: account.info.code_hash,
+                        code,
+                        storage,
+                        address,
+                        key,
+                    };
+                    Ok((address, entry))
+                })
+                .collect::<Result<_, BlockchainError>>()?;
+
+            Ok(AccountRangeResult { root: state_root(db), accounts, next })
+        })
+        .await?
+    }
+
+    /// Returns the addresses of all accounts whose nonce, balance, code or storage differ between
+    /// the states at the `start` and `end` blocks.
+    ///
+    /// Like geth, this only considers the accounts that were touched by the transactions of the
+    /// blocks in between, so accounts that were modified directly, e.g. via `anvil_setBalance`,
+    /// are not included. Returns [BlockchainError::DataUnavailable] for blocks that weren't mined
+    /// by this node, like blocks before the fork or of a loaded state.
+    pub async fn modified_accounts(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<Address>, BlockchainError> {
+        let touched = {
+            let storage = self.blockchain.storage.read();
+            let mut touched = BTreeSet::new();
+            for number in start + 1..=end {
+                let hash =
+                    storage.hashes.get(&U64::from(number)).ok_or(BlockchainError::BlockNotFound)?;
+                let accounts =
+                    storage.changed_accounts.get(hash).ok_or(BlockchainError::DataUnavailable)?;
+                touched.extend(accounts.iter().copied());
+            }
+            touched
+        };
+
+        let start_accounts = self
+            .with_database_at(Some(BlockRequest::Number(start)), |db, _| {
+                let db = db.maybe_as_full_db().ok_or(BlockchainError::DataUnavailable)?;
+                Ok::<_, BlockchainError>(
+                    touched.iter().map(|address| db.get(address).cloned()).collect::<Vec<_>>(),
+                )
+            })
+            .await??;
+
+        self.with_database_at(Some(BlockRequest::Number(end)), |db, _| {
+            let end_accounts = db.maybe_as_full_db().ok_or(BlockchainError::DataUnavailable)?;
+            Ok(touched
+                .iter()
+                .zip(&start_accounts)
+                .filter(|(address, before)| {
+                    is_modified_account(before.as_ref(), end_accounts.get(*address))
+                })
+                .map(|(address, _)| *address)
+                .collect())
+        })
+        .await?
+    }
+
+    /// Returns all accounts of the state at the given block.
+    ///
+    /// In fork mode, this only includes the accounts that were fetched from the endpoint or
+    /// modified locally.
+    pub async fn accounts_at(
+        &self,
+        block_request: BlockRequest,
+    ) -> Result<HashMap<Address, DbAccount>, BlockchainError> {
+        self.with_database_at(Some(block_request), |db, _| {
+            db.maybe_as_full_db().cloned().ok_or(BlockchainError::DataUnavailable)
+        })
+        .await?
+    }
+
+    /// Returns all accounts of the state snapshot with the given `id`, if it exists.
+    pub async fn state_snapshot_accounts(&self, id: U256) -> Option<HashMap<Address, DbAccount>> {
+        self.db.read().await.state_snapshot_accounts(id)
+    }
+
+    /// Returns all balance, nonce, code and storage changes between the given accounts and the
+    /// state at the given block.
+    ///
+    /// In fork mode, the local states only hold the accounts and storage slots that were fetched
+    /// from the endpoint or modified until then. The ones that are missing from either side were
+    /// never touched locally, so they're resolved with their values at the forked block.
+    pub async fn state_diff(
+        &self,
+        mut before: HashMap<Address, DbAccount>,
+        block_request: BlockRequest,
+    ) -> Result<StateDiff, BlockchainError> {
+        let mut after = self.accounts_at(block_request.clone()).await?;
+        if let Some(fork) = self.get_fork() {
+            resolve_from_fork(&fork, &mut before, &after).await?;
+            resolve_from_fork(&fork, &mut after, &before).await?;
+        }
+
+        self.with_database_at(Some(block_request), |db, _| {
+            let accounts = before
+                .keys()
+                .chain(after.keys())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .filter_map(|address| {
+                    let diff =
+                        account_state_diff(before.get(address), after.get(address), db.as_dyn());
+                    (!diff.is_empty()).then_some((*address, diff))
+                })
+                .collect();
+            Ok(StateDiff { accounts })
+        })
+        .await?
+    }
+
     /// Returns a new block event stream
     pub fn new_block_notifications(&self) -> NewBlockNotifications {
         let (tx, rx) = unbounded();
@@ -2604,6 +4113,21 @@ fn notify_on_new_block(&self, header: Header, hash: B256) {
         self.new_block_listeners
             .lock()
             .retain(|tx| tx.unbounded_send(notification.clone()).is_ok());
+
+        self.notify_chain_listeners(ChainNotification::NewBlock(notification));
+    }
+
+    /// Returns a new stream of canonical chain changes
+    pub fn chain_notifications(&self) -> ChainNotifications {
+        let (tx, rx) = unbounded();
+        self.chain_listeners.lock().push(tx);
+        trace!(target: "backed", "added new chain listener");
+        rx
+    }
+
+    /// Notifies all `chain_listeners` about the change of the chain
+    fn notify_chain_listeners(&self, notification: ChainNotification) {
+        self.chain_listeners.lock().retain(|tx| tx.unbounded_send(notification.clone()).is_ok());
     }
 
     /// Reorg the chain to a common height and execute blocks to build new chain.
@@ -2622,7 +4146,7 @@ pub async fn reorg(
         // Create the new reorged chain, filling the blocks with transactions if supplied
         for i in 0..depth {
             let to_be_mined = tx_pairs.get(&i).cloned().unwrap_or_else(Vec::new);
-            let outcome = self.do_mine_block(to_be_mined).await;
+            let outcome = self.do_mine_block(to_be_mined, None).await;
             node_info!(
                 "    Mined reorg block number {}. With {} valid txs and with invalid {} txs",
                 outcome.block_number,
@@ -2660,6 +4184,15 @@ pub async fn rollback(&self, common_block: Block) -> Result<(), BlockchainError>
             }
         }
 
+        // Collect the blocks that are removed from the chain, so listeners can retract their logs
+        let removed = ((common_block.header.number + 1)..=self.best_number())
+            .filter_map(|number| {
+                let block = self.get_block(number)?;
+                let receipts = self.mined_receipts(block.header.hash_slow())?;
+                Some(RemovedBlock { block, receipts })
+            })
+            .collect::<Vec<_>>();
+
         {
             // Unwind the storage back to the common ancestor
             self.blockchain
@@ -2677,8 +4210,260 @@ pub async fn rollback(&self, common_block: Block) -> Result<(), BlockchainError>
 
             self.time.reset(env.block.timestamp.to::<u64>());
         }
+        if !removed.is_empty() {
+            self.notify_chain_listeners(ChainNotification::Removed(Arc::new(removed)));
+        }
+        self.persist_rewind().await;
         Ok(())
     }
+
+    /// Makes the given block of the canonical chain the new best block, discarding all blocks
+    /// after it.
+    ///
+    /// Unlike [Self::rollback], this also restores the fees the next block is built with.
+    pub async fn rewind_to(&self, block: Block) -> Result<(), BlockchainError> {
+        let header = block.header.clone();
+        self.rollback(block).await?;
+
+        let next_block_base_fee = if header.number == 0 && !self.is_fork() {
+            // the first block of a new chain uses the base fee of the genesis block
+            header.base_fee_per_gas.unwrap_or_default()
+        } else {
+            self.fees.get_next_block_base_fee_per_gas(
+                header.gas_used as u128,
+                header.gas_limit as u128,
+                header.base_fee_per_gas.unwrap_or_default(),
+            )
+        };
+        let next_block_excess_blob_gas = self.fees.get_next_block_blob_excess_gas(
+            header.excess_blob_gas.map(|g| g as u128).unwrap_or_default(),
+            header.blob_gas_used.map(|g| g as u128).unwrap_or_default(),
+        );
+        self.fees.set_base_fee(next_block_base_fee);
+        self.fees.set_blob_excess_gas_and_price(BlobExcessGasAndPrice::new(
+            next_block_excess_blob_gas,
+            false,
+        ));
+        Ok(())
+    }
+}
+
+/// Sets the header values that were provided by the [BlockAttributes]
+fn apply_block_attributes(header: &mut Header, attributes: &BlockAttributes) {
+    header.mix_hash = attributes.prev_randao;
+    header.extra_data = attributes.extra_data.clone();
+    if header.parent_beacon_block_root.is_some() {
+        header.parent_beacon_block_root =
+            Some(attributes.parent_beacon_block_root.unwrap_or_default());
+    }
+}
+
+/// Commits the accounts of a [CacheDB] that was used as overlay of the database
+fn commit_overlay(db: &mut dyn Db, accounts: HashMap<Address, DbAccount>) {
+    let changes = accounts
+        .into_iter()
+        .map(|(address, account)| {
+            let mut status = AccountStatus::Touched;
+            match account.account_state {
+                AccountState::NotExisting => status |= AccountStatus::SelfDestructed,
+                // the previous storage of the account is discarded
+                AccountState::StorageCleared => status |= AccountStatus::Created,
+                AccountState::Touched | AccountState::None => {}
+            }
+            let storage = account
+                .storage
+                .into_iter()
+                .map(|(slot, value)| (slot, EvmStorageSlot::new(value)))
+                .collect();
+            (address, revm::primitives::Account { info: account.info, storage, status })
+        })
+        .collect();
+    db.commit(changes);
+}
+
+/// Returns the built-in tracer if it can't be derived from the call traces recorded while mining
+/// and instead requires the transaction to be re-executed.
+/// Returns the result of a simulated call that failed validation
+fn invalid_simulated_call(code: i32, message: String) -> SimulatedCallResult {
+    SimulatedCallResult {
+        return_data: Bytes::new(),
+        logs: vec![],
+        gas_used: 0,
+        status: false,
+        error: Some(SimulatedCallError { code, message, data: None }),
+    }
+}
+
+/// Returns the `eth_simulateV1` error code of a call that failed validation, like geth
+fn simulate_error_code(err: &InvalidTransactionError) -> i32 {
+    match err {
+        InvalidTransactionError::NonceTooLow => -38010,
+        InvalidTransactionError::NonceTooHigh => -38011,
+        InvalidTransactionError::FeeCapTooLow => -38012,
+        InvalidTransactionError::GasTooLow => -38013,
+        InvalidTransactionError::InsufficientFunds |
+        InvalidTransactionError::InsufficientFundsForTransfer => -38014,
+        InvalidTransactionError::GasTooHigh(_) => -38015,
+        InvalidTransactionError::SenderNoEOA => -38024,
+        InvalidTransactionError::MaxInitCodeSizeExceeded => -38025,
+        _ => -32603,
+    }
+}
+
+fn replay_builtin_tracer(tracer: &GethDebugTracerType) -> Option<GethDebugBuiltInTracerType> {
+    match tracer {
+        GethDebugTracerType::BuiltInTracer(
+            tracer @ (GethDebugBuiltInTracerType::PreStateTracer |
+            GethDebugBuiltInTracerType::FourByteTracer |
+            GethDebugBuiltInTracerType::MuxTracer |
+            GethDebugBuiltInTracerType::FlatCallTracer),
+        ) => Some(*tracer),
+        _ => None,
+    }
+}
+
+/// Returns the environment to replay the mined transaction with on top of the environment of its
+/// block, and the hash of the transaction
+fn replay_transaction_env(
+    env: &EnvWithHandlerCfg,
+    transaction: &MaybeImpersonatedTransaction,
+) -> Result<(B256, EnvWithHandlerCfg), BlockchainError> {
+    let pending = match transaction.impersonated_sender {
+        Some(sender) => {
+            PendingTransaction::with_impersonated(transaction.transaction.clone(), sender)
+        }
+        None => PendingTransaction::new(transaction.transaction.clone())?,
+    };
+
+    let mut env = env.clone();
+    env.tx = pending.to_revm_tx_env();
+    if env.handler_cfg.is_optimism {
+        env.tx.optimism.enveloped_tx =
+            Some(alloy_rlp::encode(&pending.transaction.transaction).into());
+    }
+    Ok((*pending.hash(), env))
+}
+
+/// Returns up to `max_result` non-empty slots of the storage, ordered by the hash of the slot and
+/// starting at `start_key`
+fn storage_range(
+    storage: &HashMap<U256, U256>,
+    start_key: B256,
+    max_result: u64,
+) -> StorageRangeResult {
+    let mut slots = storage
+        .iter()
+        .filter(|(_, value)| !value.is_zero())
+        .map(|(slot, value)| {
+            let key = B256::from(*slot);
+            (keccak256(key), StorageRangeEntry { key: Some(key), value: B256::from(*value) })
+        })
+        .filter(|(hash, _)| *hash >= start_key)
+        .collect::<BTreeMap<_, _>>();
+    let next_key = slots.keys().nth(max_result as usize).copied();
+    if let Some(next_key) = next_key {
+        slots.split_off(&next_key);
+    }
+    StorageRangeResult { storage: slots, next_key }
+}
+
+/// Returns whether the nonce, balance, code or non-empty storage of the account differ
+fn is_modified_account(before: Option<&DbAccount>, after: Option<&DbAccount>) -> bool {
+    let non_empty_storage = |account: Option<&DbAccount>| {
+        account
+            .map(|account| {
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(slot, value)| (*slot, *value))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .unwrap_or_default()
+    };
+    // `AccountInfo` equality only compares the nonce, balance and code hash
+    before.map(|account| account.info.clone()).unwrap_or_default() !=
+        after.map(|account| account.info.clone()).unwrap_or_default() ||
+        non_empty_storage(before) != non_empty_storage(after)
+}
+
+/// Adds the accounts and storage slots of `other` that are missing from `state`, with their values
+/// at the forked block
+async fn resolve_from_fork(
+    fork: &ClientFork,
+    state: &mut HashMap<Address, DbAccount>,
+    other: &HashMap<Address, DbAccount>,
+) -> Result<(), BlockchainError> {
+    let number = fork.block_number();
+    for (address, account) in other {
+        if !state.contains_key(address) {
+            let (balance, nonce, code) = futures::try_join!(
+                fork.get_balance(*address, number),
+                fork.get_nonce(*address, number),
+                fork.get_code(*address, number),
+            )?;
+            let code_hash = if code.is_empty() { KECCAK_EMPTY } else { keccak256(&code) };
+            let info =
+                AccountInfo { balance, nonce, code_hash, code: Some(Bytecode::new_raw(code)) };
+            state.insert(*address, DbAccount { info, ..Default::default() });
+        }
+
+        for slot in account.storage.keys() {
+            if !state[address].storage.contains_key(slot) {
+                let value = fork.storage_at(*address, *slot, Some(number.into())).await?;
+                state.get_mut(address).expect("inserted above").storage.insert(*slot, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the changes of an account between two states, a missing account is treated as empty.
+///
+/// Code that isn't stored in the account itself is resolved by its hash from the given database.
+fn account_state_diff(
+    before: Option<&DbAccount>,
+    after: Option<&DbAccount>,
+    db: &dyn DatabaseRef<Error = DatabaseError>,
+) -> AccountStateDiff {
+    let info = |account: Option<&DbAccount>| {
+        account.map(|account| account.info.clone()).unwrap_or_default()
+    };
+    let (before_info, after_info) = (info(before), info(after));
+    let code = |info: &AccountInfo| match &info.code {
+        Some(code) if !code.is_empty() => code.original_bytes(),
+        _ if info.code_hash == KECCAK_EMPTY => Bytes::new(),
+        _ => db
+            .code_by_hash_ref(info.code_hash)
+            .map(|code| code.original_bytes())
+            .unwrap_or_default(),
+    };
+    let storage_at = |account: Option<&DbAccount>, slot: &U256| {
+        account.and_then(|account| account.storage.get(slot)).copied().unwrap_or_default()
+    };
+
+    let storage = before
+        .into_iter()
+        .chain(after)
+        .flat_map(|account| account.storage.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|slot| {
+            let diff = ValueDiff::new(
+                B256::from(storage_at(before, slot)),
+                B256::from(storage_at(after, slot)),
+            )?;
+            Some((B256::from(*slot), diff))
+        })
+        .collect();
+
+    AccountStateDiff {
+        balance: ValueDiff::new(before_info.balance, after_info.balance),
+        nonce: ValueDiff::new(before_info.nonce, after_info.nonce),
+        code: (before_info.code_hash != after_info.code_hash)
+            .then(|| ValueDiff { from: code(&before_info), to: code(&after_info) }),
+        storage,
+    }
 }
 
 /// Get max nonce from transaction pool by address
@@ -3,13 +3,13 @@
 use crate::eth::error::BlockchainError;
 use alloy_primitives::{keccak256, Address, B256, U256};
 use alloy_rlp::Encodable;
-use alloy_rpc_types::state::StateOverride;
+use alloy_rpc_types::{state::StateOverride, BlockOverrides};
 use alloy_trie::{HashBuilder, Nibbles};
 use foundry_evm::{
     backend::DatabaseError,
     revm::{
-        db::{CacheDB, DatabaseRef, DbAccount},
-        primitives::{AccountInfo, Bytecode, HashMap},
+        db::{AccountState, CacheDB, DatabaseRef, DbAccount},
+        primitives::{AccountInfo, BlockEnv, Bytecode, HashMap},
     },
 };
 
@@ -26,6 +26,43 @@ pub fn state_root(accounts: &HashMap<Address, DbAccount>) -> B256 {
     build_root(trie_accounts(accounts))
 }
 
+/// Builds the state root of the given accounts after the accounts of a [CacheDB] on top of them
+/// were committed
+pub fn state_root_with_overlay(
+    accounts: &HashMap<Address, DbAccount>,
+    overlay: &HashMap<Address, DbAccount>,
+) -> B256 {
+    let mut values = accounts
+        .iter()
+        .filter(|(address, _)| !overlay.contains_key(*address))
+        .map(|(address, account)| (*address, trie_account_rlp(&account.info, &account.storage)))
+        .collect::<Vec<_>>();
+    for (address, account) in overlay {
+        let data = match accounts.get(address) {
+            // the storage of accounts that were created or destroyed is replaced entirely
+            Some(base)
+                if !matches!(
+                    account.account_state,
+                    AccountState::NotExisting | AccountState::StorageCleared
+                ) =>
+            {
+                let mut storage = base.storage.clone();
+                storage.extend(account.storage.iter().map(|(slot, value)| (*slot, *value)));
+                trie_account_rlp(&account.info, &storage)
+            }
+            _ => trie_account_rlp(&account.info, &account.storage),
+        };
+        values.push((*address, data));
+    }
+    let mut values = values
+        .into_iter()
+        .map(|(address, data)| (Nibbles::unpack(keccak256(address)), data))
+        .collect::<Vec<_>>();
+    values.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+
+    build_root(values)
+}
+
 /// Builds storage root from the given storage
 pub fn storage_root(storage: &HashMap<U256, U256>) -> B256 {
     build_root(trie_storage(storage))
@@ -79,6 +116,18 @@ pub fn apply_state_override<D>(
     D: DatabaseRef<Error = DatabaseError>,
 {
     let mut cache_db = CacheDB::new(state);
+    apply_cached_db_state_override(overrides, &mut cache_db)?;
+    Ok(cache_db)
+}
+
+/// Applies the given state overrides to the given CacheDB
+pub fn apply_cached_db_state_override<D>(
+    overrides: StateOverride,
+    cache_db: &mut CacheDB<D>,
+) -> Result<(), BlockchainError>
+where
+    D: DatabaseRef<Error = DatabaseError>,
+{
     for (account, account_overrides) in overrides.iter() {
         let mut account_info = cache_db.basic_ref(*account)?.unwrap_or_default();
 
@@ -120,5 +169,52 @@ pub fn apply_state_override<D>(
             }
         };
     }
-    Ok(cache_db)
+    Ok(())
+}
+
+/// Applies the given block overrides to the env and updates overridden block hashes in the db.
+pub fn apply_block_overrides<D>(
+    overrides: BlockOverrides,
+    cache_db: &mut CacheDB<D>,
+    env: &mut BlockEnv,
+) {
+    let BlockOverrides {
+        number,
+        difficulty,
+        time,
+        gas_limit,
+        coinbase,
+        random,
+        base_fee,
+        block_hash,
+    } = overrides;
+
+    if let Some(block_hashes) = block_hash {
+        // override block hashes
+        cache_db
+            .block_hashes
+            .extend(block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)))
+    }
+
+    if let Some(number) = number {
+        env.number = number;
+    }
+    if let Some(difficulty) = difficulty {
+        env.difficulty = difficulty;
+    }
+    if let Some(time) = time {
+        env.timestamp = U256::from(time);
+    }
+    if let Some(gas_limit) = gas_limit {
+        env.gas_limit = U256::from(gas_limit);
+    }
+    if let Some(coinbase) = coinbase {
+        env.coinbase = coinbase;
+    }
+    if let Some(random) = random {
+        env.prevrandao = Some(random);
+    }
+    if let Some(base_fee) = base_fee {
+        env.basefee = base_fee;
+    }
 }
@@ -14,13 +14,13 @@
 use alloy_eips::eip7685::EMPTY_REQUESTS_HASH;
 use alloy_primitives::{
     map::{B256HashMap, HashMap},
-    Bytes, B256, U256, U64,
+    Address, Bytes, B256, U256, U64,
 };
 use alloy_rpc_types::{
     trace::{
         geth::{
-            FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
-            GethDebugTracingOptions, GethTrace, NoopFrame,
+            GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingOptions, GethTrace,
+            NoopFrame,
         },
         otterscan::{InternalOperation, OperationType},
         parity::LocalizedTransactionTrace,
@@ -35,9 +35,7 @@
 use foundry_evm::{
     backend::MemDb,
     revm::primitives::Env,
-    traces::{
-        CallKind, FourByteInspector, GethTraceBuilder, ParityTraceBuilder, TracingInspectorConfig,
-    },
+    traces::{CallKind, GethTraceBuilder, ParityTraceBuilder, TracingInspectorConfig},
 };
 use parking_lot::RwLock;
 use revm::primitives::SpecId;
@@ -267,6 +265,9 @@ pub struct BlockchainStorage {
     pub transactions: B256HashMap<MinedTransaction>,
     /// The total difficulty of the chain until this block
     pub total_difficulty: U256,
+    /// The accounts that were touched by the transactions of a mined block (block hash ->
+    /// addresses)
+    pub changed_accounts: B256HashMap<Vec<Address>>,
 }
 
 impl BlockchainStorage {
@@ -309,6 +310,7 @@ pub fn new(env: &Env, spec_id: SpecId, base_fee: Option<u64>, timestamp: u64) ->
             genesis_hash,
             transactions: Default::default(),
             total_difficulty: Default::default(),
+            changed_accounts: Default::default(),
         }
     }
 
@@ -324,6 +326,7 @@ pub fn forked(block_number: u64, block_hash: B256, total_difficulty: U256) -> Se
             genesis_hash: Default::default(),
             transactions: Default::default(),
             total_difficulty,
+            changed_accounts: Default::default(),
         }
     }
 
@@ -338,6 +341,7 @@ pub fn unwind_to(&mut self, block_number: u64, block_hash: B256) {
                 if let Some(block) = self.blocks.remove(&hash) {
                     self.remove_block_transactions_by_number(block.header.number);
                 }
+                self.changed_accounts.remove(&hash);
             }
         }
         self.best_hash = block_hash;
@@ -354,6 +358,7 @@ pub fn empty() -> Self {
             genesis_hash: Default::default(),
             transactions: Default::default(),
             total_difficulty: Default::default(),
+            changed_accounts: Default::default(),
         }
     }
 
@@ -549,10 +554,6 @@ pub fn geth_trace(&self, opts: GethDebugTracingOptions) -> Result<GethTrace, Blo
         if let Some(tracer) = tracer {
             match tracer {
                 GethDebugTracerType::BuiltInTracer(tracer) => match tracer {
-                    GethDebugBuiltInTracerType::FourByteTracer => {
-                        let inspector = FourByteInspector::default();
-                        return Ok(FourByteFrame::from(inspector).into());
-                    }
                     GethDebugBuiltInTracerType::CallTracer => {
                         return match tracer_config.into_call_config() {
                             Ok(call_config) => Ok(GethTraceBuilder::new(self.info.traces.clone())
@@ -562,6 +563,7 @@ pub fn geth_trace(&self, opts: GethDebugTracingOptions) -> Result<GethTrace, Blo
                         };
                     }
                     GethDebugBuiltInTracerType::PreStateTracer |
+                    GethDebugBuiltInTracerType::FourByteTracer |
                     GethDebugBuiltInTracerType::NoopTracer |
                     GethDebugBuiltInTracerType::MuxTracer |
                     GethDebugBuiltInTracerType::FlatCallTracer => {}
@@ -2,6 +2,7 @@
 
 use alloy_consensus::Header;
 use alloy_primitives::B256;
+use anvil_core::eth::{block::Block, transaction::TypedReceipt};
 use futures::channel::mpsc::UnboundedReceiver;
 use std::sync::Arc;
 
@@ -16,3 +17,24 @@ pub struct NewBlockNotification {
 
 /// Type alias for a receiver that receives [NewBlockNotification]
 pub type NewBlockNotifications = UnboundedReceiver<NewBlockNotification>;
+
+/// A block that was removed from the canonical chain
+#[derive(Clone, Debug)]
+pub struct RemovedBlock {
+    /// The removed block
+    pub block: Block,
+    /// The receipts of the block's transactions
+    pub receipts: Vec<TypedReceipt>,
+}
+
+/// A change of the canonical chain
+#[derive(Clone, Debug)]
+pub enum ChainNotification {
+    /// A new block was imported
+    NewBlock(NewBlockNotification),
+    /// Blocks were removed from the chain by a reorg or rollback, in ascending order
+    Removed(Arc<Vec<RemovedBlock>>),
+}
+
+/// Type alias for a receiver that receives [ChainNotification]s
+pub type ChainNotifications = UnboundedReceiver<ChainNotification>;
@@ -70,12 +70,19 @@ pub fn increase_time(&self, seconds: u64) -> i128 {
     /// Fails if it's before (or at the same time) the last timestamp
     pub fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), BlockchainError> {
         trace!(target: "time", "override next timestamp {}", timestamp);
+        self.check_next_block_timestamp(timestamp)?;
+        self.next_exact_timestamp.write().replace(timestamp);
+        Ok(())
+    }
+
+    /// Checks whether the timestamp can be used in the next block, see
+    /// [Self::set_next_block_timestamp]
+    pub fn check_next_block_timestamp(&self, timestamp: u64) -> Result<(), BlockchainError> {
         if timestamp < *self.last_timestamp.read() {
             return Err(BlockchainError::TimestampError(format!(
                 "{timestamp} is lower than previous block's timestamp"
             )))
         }
-        self.next_exact_timestamp.write().replace(timestamp);
         Ok(())
     }
 
@@ -98,6 +105,22 @@ pub fn remove_block_timestamp_interval(&self) -> bool {
         }
     }
 
+    /// Shifts the tracked offset so that [`Self::current_call_timestamp`] returns the given
+    /// `timestamp`
+    ///
+    /// Unlike [`Self::reset`], this keeps the last timestamp, and has no effect if an exact next
+    /// timestamp or an interval is set, since the current timestamp doesn't depend on the offset
+    /// then.
+    pub fn set_current_timestamp(&self, timestamp: u64) {
+        if self.next_exact_timestamp.read().is_some() || self.interval.read().is_some() {
+            return
+        }
+        let drift = (timestamp as i128) - (self.current_call_timestamp() as i128);
+        if drift != 0 {
+            self.add_offset(drift);
+        }
+    }
+
     /// Computes the next timestamp without updating internals
     fn compute_next_timestamp(&self) -> (u64, Option<i128>) {
         let current = duration_since_unix_epoch().as_secs() as i128;
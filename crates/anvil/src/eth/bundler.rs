@@ -0,0 +1,437 @@
+//! Support for ERC-4337 user operations.
+//!
+//! The bundler of a node accepts user operations for a single EntryPoint v0.7. Every operation
+//! that passes the simulation of `handleOps` is sent as its own bundle, a `handleOps` transaction
+//! of the bundler account, through the pool of the node, so it's mined like any other transaction.
+//!
+//! The EntryPoint isn't part of a fresh chain, it's either forked or predeployed at genesis from
+//! the runtime code of the canonical deployment, see [EntryPointPredeploy].
+
+use crate::eth::backend::mem::MIN_TRANSACTION_GAS;
+use alloy_genesis::GenesisAccount;
+use alloy_primitives::{address, keccak256, Address, Bytes, TxHash, B256, U256};
+use alloy_rpc_types::Log;
+use alloy_sol_types::{sol, SolCall, SolError, SolEvent, SolValue};
+use anvil_core::{
+    eth::transaction::ReceiptResponse,
+    types::{UserOperation, UserOperationReceipt},
+};
+use anvil_rpc::error::{ErrorCode, RpcError};
+use eyre::Result;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+/// The address of the canonical EntryPoint v0.7
+pub const ENTRY_POINT_V07: Address = address!("0000000071727De22E5E9d8BAf0edAc6f37da032");
+
+/// The address of the `SenderCreator` of the canonical EntryPoint v0.7, which deploys accounts via
+/// their factory
+pub const SENDER_CREATOR_V07: Address = address!("EFC2c1444eBCC4Db75e7613d20C6a62fF67A167C");
+
+/// The error code of user operations that are rejected by the EntryPoint during validation
+pub const USER_OPERATION_REJECTED: i64 = -32500;
+
+/// The error code of user operations whose execution reverts during the estimation
+pub const USER_OPERATION_REVERTED: i64 = -32521;
+
+sol! {
+    /// The user operation as it's passed to the EntryPoint v0.7
+    #[derive(Debug, PartialEq, Eq)]
+    struct PackedUserOperation {
+        address sender;
+        uint256 nonce;
+        bytes initCode;
+        bytes callData;
+        bytes32 accountGasLimits;
+        uint256 preVerificationGas;
+        bytes32 gasFees;
+        bytes paymasterAndData;
+        bytes signature;
+    }
+
+    function handleOps(PackedUserOperation[] ops, address beneficiary);
+
+    function validateUserOp(
+        PackedUserOperation userOp,
+        bytes32 userOpHash,
+        uint256 missingAccountFunds
+    ) returns (uint256 validationData);
+
+    function validatePaymasterUserOp(
+        PackedUserOperation userOp,
+        bytes32 userOpHash,
+        uint256 maxCost
+    ) returns (bytes context, uint256 validationData);
+
+    event UserOperationEvent(
+        bytes32 indexed userOpHash,
+        address indexed sender,
+        address indexed paymaster,
+        uint256 nonce,
+        bool success,
+        uint256 actualGasCost,
+        uint256 actualGasUsed
+    );
+
+    event UserOperationRevertReason(
+        bytes32 indexed userOpHash,
+        address indexed sender,
+        uint256 nonce,
+        bytes revertReason
+    );
+
+    event BeforeExecution();
+
+    error FailedOp(uint256 opIndex, string reason);
+
+    error FailedOpWithRevert(uint256 opIndex, string reason, bytes inner);
+}
+
+/// The configuration of the bundler of a node
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BundlerConfig {
+    /// The supported EntryPoint, [ENTRY_POINT_V07] if unset
+    pub entry_point: Option<Address>,
+    /// The account that receives the fees of the `handleOps` transactions, the last account the
+    /// node can sign for, which sends them, if unset
+    pub beneficiary: Option<Address>,
+    /// The EntryPoint to predeploy at genesis, if any
+    pub predeploy: Option<EntryPointPredeploy>,
+}
+
+impl BundlerConfig {
+    /// Returns the supported EntryPoint
+    pub fn entry_point(&self) -> Address {
+        self.entry_point.unwrap_or(ENTRY_POINT_V07)
+    }
+
+    /// Returns the accounts to predeploy at genesis
+    pub fn genesis_alloc(&self) -> BTreeMap<Address, GenesisAccount> {
+        self.predeploy
+            .as_ref()
+            .map(|predeploy| predeploy.genesis_alloc(self.entry_point()))
+            .unwrap_or_default()
+    }
+}
+
+/// The runtime code of the EntryPoint v0.7 and its `SenderCreator`, loaded from a JSON file
+///
+/// ```json
+/// {
+///   "entryPoint": "0x60806040...",
+///   "senderCreator": "0x60806040..."
+/// }
+/// ```
+///
+/// The code is the one of the canonical deployment, e.g. as returned by `cast code`. The
+/// `SenderCreator` is always predeployed at [SENDER_CREATOR_V07], since its address is embedded in
+/// the code of the EntryPoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryPointPredeploy {
+    pub entry_point: Bytes,
+    pub sender_creator: Bytes,
+}
+
+impl EntryPointPredeploy {
+    /// Loads the code from the JSON file at the given path
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let predeploy: Self = foundry_common::fs::read_json_file(path)?;
+        if predeploy.entry_point.is_empty() || predeploy.sender_creator.is_empty() {
+            eyre::bail!("invalid EntryPoint {}: the code must not be empty", path.display());
+        }
+        Ok(predeploy)
+    }
+
+    pub(crate) fn parse(path: &str) -> Result<Self, String> {
+        Self::load(path).map_err(|err| format!("{err:#}"))
+    }
+
+    /// Returns the genesis accounts of the EntryPoint at the given address and its `SenderCreator`
+    pub fn genesis_alloc(&self, entry_point: Address) -> BTreeMap<Address, GenesisAccount> {
+        BTreeMap::from([
+            (
+                entry_point,
+                GenesisAccount { code: Some(self.entry_point.clone()), ..Default::default() },
+            ),
+            (
+                SENDER_CREATOR_V07,
+                GenesisAccount { code: Some(self.sender_creator.clone()), ..Default::default() },
+            ),
+        ])
+    }
+}
+
+/// The bundler of a node, which tracks the transactions of the submitted user operations
+#[derive(Debug)]
+pub struct Bundler {
+    config: BundlerConfig,
+    /// The `handleOps` transaction of every submitted user operation, by operation hash
+    bundled: RwLock<HashMap<B256, TxHash>>,
+}
+
+impl Bundler {
+    pub fn new(config: BundlerConfig) -> Self {
+        Self { config, bundled: Default::default() }
+    }
+
+    /// Returns the supported EntryPoint
+    pub fn entry_point(&self) -> Address {
+        self.config.entry_point()
+    }
+
+    /// Returns the configured beneficiary, if any
+    pub fn beneficiary(&self) -> Option<Address> {
+        self.config.beneficiary
+    }
+
+    /// Records the transaction that includes the user operation
+    pub fn insert(&self, user_op_hash: B256, tx_hash: TxHash) {
+        self.bundled.write().insert(user_op_hash, tx_hash);
+    }
+
+    /// Returns the transaction that includes the user operation, if it was submitted
+    pub fn transaction(&self, user_op_hash: B256) -> Option<TxHash> {
+        self.bundled.read().get(&user_op_hash).copied()
+    }
+}
+
+/// Packs the user operation the way it's passed to the EntryPoint v0.7
+pub fn pack_user_operation(op: &UserOperation) -> PackedUserOperation {
+    let mut init_code = Vec::new();
+    if let Some(factory) = op.factory {
+        init_code.extend_from_slice(factory.as_slice());
+        if let Some(data) = &op.factory_data {
+            init_code.extend_from_slice(data);
+        }
+    }
+
+    let mut paymaster_and_data = Vec::new();
+    if let Some(paymaster) = op.paymaster {
+        paymaster_and_data.extend_from_slice(paymaster.as_slice());
+        paymaster_and_data.extend_from_slice(
+            &op.paymaster_verification_gas_limit.unwrap_or_default().to_be_bytes(),
+        );
+        paymaster_and_data
+            .extend_from_slice(&op.paymaster_post_op_gas_limit.unwrap_or_default().to_be_bytes());
+        if let Some(data) = &op.paymaster_data {
+            paymaster_and_data.extend_from_slice(data);
+        }
+    }
+
+    PackedUserOperation {
+        sender: op.sender,
+        nonce: op.nonce,
+        initCode: init_code.into(),
+        callData: op.call_data.clone(),
+        accountGasLimits: pack_u128s(op.verification_gas_limit, op.call_gas_limit),
+        preVerificationGas: op.pre_verification_gas,
+        gasFees: pack_u128s(op.max_priority_fee_per_gas, op.max_fee_per_gas),
+        paymasterAndData: paymaster_and_data.into(),
+        signature: op.signature.clone(),
+    }
+}
+
+/// Packs two 16 byte values into one word, the first one in the high bytes
+fn pack_u128s(high: u128, low: u128) -> B256 {
+    let mut word = B256::ZERO;
+    word[..16].copy_from_slice(&high.to_be_bytes());
+    word[16..].copy_from_slice(&low.to_be_bytes());
+    word
+}
+
+/// Returns the hash of the user operation, as computed by `EntryPoint.getUserOpHash`
+pub fn user_operation_hash(op: &PackedUserOperation, entry_point: Address, chain_id: u64) -> B256 {
+    let packed = (
+        op.sender,
+        op.nonce,
+        keccak256(&op.initCode),
+        keccak256(&op.callData),
+        op.accountGasLimits,
+        op.preVerificationGas,
+        op.gasFees,
+        keccak256(&op.paymasterAndData),
+    )
+        .abi_encode();
+    keccak256((keccak256(packed), entry_point, U256::from(chain_id)).abi_encode())
+}
+
+/// Returns the input of a `handleOps` call that pays the fees to the beneficiary
+pub fn handle_ops_input(ops: Vec<PackedUserOperation>, beneficiary: Address) -> Bytes {
+    handleOpsCall { ops, beneficiary }.abi_encode().into()
+}
+
+/// Returns the intrinsic gas of a call transaction with the given input, without access lists
+pub fn intrinsic_gas(input: &[u8]) -> u64 {
+    let calldata_gas: u64 = input.iter().map(|byte| if *byte == 0 { 4 } else { 16 }).sum();
+    MIN_TRANSACTION_GAS as u64 + calldata_gas
+}
+
+/// Adds a margin to the gas that a phase of a user operation used in the simulation, which covers
+/// the overhead of the EntryPoint
+pub fn with_gas_margin(gas: u64) -> u64 {
+    gas + gas / 5 + 10_000
+}
+
+/// Returns the error of a user operation that was rejected with the given revert data of
+/// `handleOps`, if the EntryPoint rejected it
+pub fn failed_op_error(data: &[u8]) -> Option<RpcError> {
+    let message = if let Ok(err) = FailedOp::abi_decode(data, true) {
+        err.reason
+    } else if let Ok(err) = FailedOpWithRevert::abi_decode(data, true) {
+        format!("{}: {}", err.reason, err.inner)
+    } else {
+        return None
+    };
+    Some(user_operation_error(USER_OPERATION_REJECTED, message))
+}
+
+/// Returns an error with one of the ERC-4337 error codes
+pub fn user_operation_error(code: i64, message: impl Into<String>) -> RpcError {
+    RpcError { code: ErrorCode::ServerError(code), message: Cow::Owned(message.into()), data: None }
+}
+
+/// Returns the receipt of the user operation from the receipt of its `handleOps` transaction, if
+/// the transaction executed the operation.
+///
+/// The logs of the operation are the ones that were emitted after the previous operation of the
+/// bundle.
+pub fn user_operation_receipt(
+    user_op_hash: B256,
+    entry_point: Address,
+    receipt: ReceiptResponse,
+) -> Option<UserOperationReceipt> {
+    let logs = &receipt.inner.as_receipt_with_bloom().receipt.logs;
+    let mut start = 0;
+    let mut reason = Bytes::new();
+    for (idx, log) in logs.iter().enumerate() {
+        let topics = log.topics();
+        let is_entry_point_event =
+            |signature: B256| log.address() == entry_point && topics.first() == Some(&signature);
+
+        if is_entry_point_event(UserOperationRevertReason::SIGNATURE_HASH) &&
+            topics.get(1) == Some(&user_op_hash)
+        {
+            if let Ok(event) = UserOperationRevertReason::decode_log_data(log.data(), true) {
+                reason = event.revertReason;
+            }
+        } else if is_entry_point_event(UserOperationEvent::SIGNATURE_HASH) {
+            if topics.get(1) != Some(&user_op_hash) {
+                start = idx + 1;
+                continue
+            }
+            let event = UserOperationEvent::decode_log_data(log.data(), true).ok()?;
+            let logs = operation_logs(&logs[start..idx], entry_point);
+            return Some(UserOperationReceipt {
+                user_op_hash,
+                entry_point,
+                sender: event.sender,
+                nonce: event.nonce,
+                paymaster: event.paymaster,
+                actual_gas_cost: event.actualGasCost,
+                actual_gas_used: event.actualGasUsed,
+                success: event.success,
+                reason,
+                logs,
+                receipt,
+            })
+        }
+    }
+    None
+}
+
+/// Returns the logs without the `BeforeExecution` marker of the EntryPoint
+fn operation_logs(logs: &[Log], entry_point: Address) -> Vec<Log> {
+    logs.iter()
+        .filter(|log| {
+            log.address() != entry_point ||
+                log.topics().first() != Some(&BeforeExecution::SIGNATURE_HASH)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_pack_user_operation() {
+        let op = UserOperation {
+            sender: Address::repeat_byte(1),
+            factory: Some(Address::repeat_byte(2)),
+            factory_data: Some(Bytes::from_static(&[0xab])),
+            call_gas_limit: 2,
+            verification_gas_limit: 1,
+            max_fee_per_gas: 4,
+            max_priority_fee_per_gas: 3,
+            paymaster: Some(Address::repeat_byte(3)),
+            paymaster_verification_gas_limit: Some(5),
+            paymaster_post_op_gas_limit: Some(6),
+            paymaster_data: Some(Bytes::from_static(&[0xcd])),
+            ..Default::default()
+        };
+        let packed = pack_user_operation(&op);
+
+        assert_eq!(packed.initCode.len(), 21);
+        assert_eq!(&packed.initCode[..20], Address::repeat_byte(2).as_slice());
+        assert_eq!(packed.initCode[20], 0xab);
+        assert_eq!(
+            U256::from_be_bytes(packed.accountGasLimits.0),
+            (U256::from(1) << 128) + U256::from(2)
+        );
+        assert_eq!(packed.gasFees, pack_u128s(3, 4));
+
+        let pm = &packed.paymasterAndData;
+        assert_eq!(pm.len(), 20 + 16 + 16 + 1);
+        assert_eq!(&pm[..20], Address::repeat_byte(3).as_slice());
+        assert_eq!(u128::from_be_bytes(pm[20..36].try_into().unwrap()), 5);
+        assert_eq!(u128::from_be_bytes(pm[36..52].try_into().unwrap()), 6);
+        assert_eq!(pm[52], 0xcd);
+
+        // accounts that are deployed have no init code
+        let packed = pack_user_operation(&UserOperation::default());
+        assert!(packed.initCode.is_empty());
+        assert!(packed.paymasterAndData.is_empty());
+    }
+
+    #[test]
+    fn user_operation_hash_depends_on_chain() {
+        let packed = pack_user_operation(&UserOperation::default());
+        let hash = user_operation_hash(&packed, ENTRY_POINT_V07, 1);
+        assert_ne!(hash, user_operation_hash(&packed, ENTRY_POINT_V07, 10));
+        assert_ne!(hash, user_operation_hash(&packed, Address::ZERO, 1));
+
+        // the signature isn't part of the hash
+        let signed = PackedUserOperation { signature: Bytes::from_static(&[1]), ..packed };
+        assert_eq!(hash, user_operation_hash(&signed, ENTRY_POINT_V07, 1));
+    }
+
+    #[test]
+    fn can_decode_failed_op() {
+        let data =
+            FailedOp { opIndex: U256::ZERO, reason: "AA21 didn't pay prefund".into() }.abi_encode();
+        let err = failed_op_error(&data).unwrap();
+        assert_eq!(err.code, ErrorCode::ServerError(USER_OPERATION_REJECTED));
+        assert_eq!(err.message, "AA21 didn't pay prefund");
+
+        assert!(failed_op_error(&[0xde, 0xad, 0xbe, 0xef]).is_none());
+    }
+
+    #[test]
+    fn can_parse_predeploy() {
+        let predeploy: EntryPointPredeploy =
+            serde_json::from_str(r#"{ "entryPoint": "0x6001", "senderCreator": "0x6002" }"#)
+                .unwrap();
+        let alloc = predeploy.genesis_alloc(ENTRY_POINT_V07);
+        assert_eq!(alloc.len(), 2);
+        assert_eq!(alloc[&ENTRY_POINT_V07].code, Some(Bytes::from_static(&[0x60, 0x01])));
+        assert_eq!(alloc[&SENDER_CREATOR_V07].code, Some(Bytes::from_static(&[0x60, 0x02])));
+    }
+}
@@ -0,0 +1,272 @@
+//! Support for the engine API, which lets a consensus client drive block production
+
+use crate::eth::{
+    backend::mem::{BlockAttributes, ImportedBlock},
+    error::BlockchainError,
+};
+use alloy_consensus::{transaction::eip4844::TxEip4844Variant, Header};
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{keccak256, B256, U256};
+use alloy_rpc_types::engine::{
+    BlobsBundleV1, ExecutionPayloadEnvelopeV2, ExecutionPayloadEnvelopeV3, ExecutionPayloadFieldV2,
+    ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, PayloadAttributes, PayloadId,
+};
+use anvil_core::eth::{block::BlockInfo, transaction::TypedTransaction};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+
+/// How many payload jobs are kept around
+const MAX_PAYLOAD_JOBS: usize = 64;
+
+/// How many blocks that are not part of the canonical chain are kept around
+const MAX_SIDE_BLOCKS: usize = 64;
+
+/// All `engine_*` methods that are supported, returned by `engine_exchangeCapabilities`
+pub const ENGINE_CAPABILITIES: &[&str] = &[
+    "engine_exchangeCapabilities",
+    "engine_forkchoiceUpdatedV1",
+    "engine_forkchoiceUpdatedV2",
+    "engine_forkchoiceUpdatedV3",
+    "engine_getPayloadV1",
+    "engine_getPayloadV2",
+    "engine_getPayloadV3",
+    "engine_newPayloadV1",
+    "engine_newPayloadV2",
+    "engine_newPayloadV3",
+];
+
+/// Keeps track of the payloads requested via `engine_forkchoiceUpdated` and of the valid blocks
+/// that are not part of the canonical chain
+#[derive(Debug, Default)]
+pub struct EngineState {
+    jobs: Mutex<VecDeque<(PayloadId, PayloadJob)>>,
+    /// Valid payloads that don't extend the best block and blocks that were discarded by a
+    /// forkchoice update, a forkchoice update can make them canonical
+    side_blocks: Mutex<VecDeque<ImportedBlock>>,
+}
+
+impl EngineState {
+    /// Adds a new payload job, replacing an existing job with the same id
+    pub fn insert(&self, id: PayloadId, job: PayloadJob) {
+        let mut jobs = self.jobs.lock();
+        jobs.retain(|(existing, _)| *existing != id);
+        if jobs.len() >= MAX_PAYLOAD_JOBS {
+            jobs.pop_front();
+        }
+        jobs.push_back((id, job));
+    }
+
+    /// Returns the payload job with the given id
+    pub fn get(&self, id: &PayloadId) -> Option<PayloadJob> {
+        self.jobs.lock().iter().find(|(existing, _)| existing == id).map(|(_, job)| job.clone())
+    }
+
+    /// Adds a valid block that is not part of the canonical chain
+    pub fn insert_side_block(&self, block: ImportedBlock) {
+        let mut blocks = self.side_blocks.lock();
+        if blocks.iter().any(|existing| existing.hash == block.hash) {
+            return;
+        }
+        if blocks.len() >= MAX_SIDE_BLOCKS {
+            blocks.pop_front();
+        }
+        blocks.push_back(block);
+    }
+
+    /// Returns the side block with the given hash and all its ancestors that are side blocks,
+    /// oldest first.
+    ///
+    /// The parent of the first block is expected to be part of the canonical chain.
+    pub fn side_chain(&self, hash: B256) -> Vec<ImportedBlock> {
+        let blocks = self.side_blocks.lock();
+        let mut chain = Vec::new();
+        let mut next = hash;
+        while let Some(block) = blocks.iter().find(|block| block.hash == next) {
+            next = block.parent_hash;
+            chain.push(block.clone());
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// Removes the side blocks with the given hashes, e.g. because they became canonical
+    pub fn remove_side_blocks(&self, hashes: &[B256]) {
+        self.side_blocks.lock().retain(|block| !hashes.contains(&block.hash));
+    }
+
+    /// Replaces the built payload of the job with the given id
+    pub fn set_payload(&self, id: &PayloadId, payload: BuiltPayload) {
+        if let Some((_, job)) = self.jobs.lock().iter_mut().find(|(existing, _)| existing == id) {
+            job.payload = payload;
+        }
+    }
+}
+
+/// A payload that was requested via `engine_forkchoiceUpdated`
+#[derive(Clone, Debug)]
+pub struct PayloadJob {
+    /// The block the payload is built on
+    pub parent_hash: B256,
+    /// The header values requested by the consensus client
+    pub attributes: BlockAttributes,
+    /// The most recently built payload
+    pub payload: BuiltPayload,
+}
+
+/// A block built for a [PayloadJob]
+#[derive(Clone, Debug)]
+pub struct BuiltPayload {
+    /// The block as execution payload
+    pub execution_payload: ExecutionPayloadV3,
+    /// Whether the block contains the `withdrawalsRoot`, i.e. shanghai is active
+    pub has_withdrawals: bool,
+    /// Priority fees earned by the fee recipient
+    pub block_value: U256,
+    /// Sidecars of all blob transactions in the block
+    pub blobs_bundle: BlobsBundleV1,
+}
+
+impl BuiltPayload {
+    /// Converts the built block with the given hash
+    pub fn new(block: &BlockInfo, block_hash: B256) -> Self {
+        let BlockInfo { block, transactions: infos, .. } = block;
+        let header = &block.header;
+        let base_fee = header.base_fee_per_gas.unwrap_or_default() as u128;
+
+        let mut block_value = U256::ZERO;
+        let mut sidecars = Vec::new();
+        let mut transactions = Vec::with_capacity(block.transactions.len());
+        for (tx, info) in block.transactions.iter().zip(infos) {
+            let essentials = tx.essentials();
+            let tip = match essentials.max_priority_fee_per_gas {
+                Some(max_priority_fee) => max_priority_fee
+                    .min(essentials.max_fee_per_gas.unwrap_or_default().saturating_sub(base_fee)),
+                None => essentials.gas_price.unwrap_or_default().saturating_sub(base_fee),
+            };
+            block_value += U256::from(tip) * U256::from(info.gas_used);
+
+            if let TypedTransaction::EIP4844(tx) = &tx.transaction {
+                if let TxEip4844Variant::TxEip4844WithSidecar(tx) = tx.tx() {
+                    sidecars.push(tx.sidecar.clone());
+                }
+            }
+            transactions.push(tx.encoded_2718().into());
+        }
+
+        Self {
+            execution_payload: payload_from_header(header, block_hash, transactions),
+            has_withdrawals: header.withdrawals_root.is_some(),
+            block_value,
+            blobs_bundle: BlobsBundleV1::new(sidecars),
+        }
+    }
+
+    /// Returns the response of `engine_getPayloadV1`
+    pub fn envelope_v1(&self) -> ExecutionPayloadV1 {
+        self.execution_payload.payload_inner.payload_inner.clone()
+    }
+
+    /// Returns the response of `engine_getPayloadV2`
+    pub fn envelope_v2(&self) -> ExecutionPayloadEnvelopeV2 {
+        let payload = self.execution_payload.payload_inner.clone();
+        ExecutionPayloadEnvelopeV2 {
+            execution_payload: if self.has_withdrawals {
+                ExecutionPayloadFieldV2::V2(payload)
+            } else {
+                ExecutionPayloadFieldV2::V1(payload.payload_inner)
+            },
+            block_value: self.block_value,
+        }
+    }
+
+    /// Returns the response of `engine_getPayloadV3`
+    pub fn envelope_v3(&self) -> ExecutionPayloadEnvelopeV3 {
+        ExecutionPayloadEnvelopeV3 {
+            execution_payload: self.execution_payload.clone(),
+            block_value: self.block_value,
+            blobs_bundle: self.blobs_bundle.clone(),
+            should_override_builder: false,
+        }
+    }
+}
+
+/// Converts the header and the encoded transactions of a block into an execution payload
+fn payload_from_header(
+    header: &Header,
+    block_hash: B256,
+    transactions: Vec<alloy_primitives::Bytes>,
+) -> ExecutionPayloadV3 {
+    ExecutionPayloadV3 {
+        payload_inner: ExecutionPayloadV2 {
+            payload_inner: ExecutionPayloadV1 {
+                parent_hash: header.parent_hash,
+                fee_recipient: header.beneficiary,
+                state_root: header.state_root,
+                receipts_root: header.receipts_root,
+                logs_bloom: header.logs_bloom,
+                prev_randao: header.mix_hash,
+                block_number: header.number,
+                gas_limit: header.gas_limit,
+                gas_used: header.gas_used,
+                timestamp: header.timestamp,
+                extra_data: header.extra_data.clone(),
+                base_fee_per_gas: U256::from(header.base_fee_per_gas.unwrap_or_default()),
+                block_hash,
+                transactions,
+            },
+            withdrawals: Vec::new(),
+        },
+        blob_gas_used: header.blob_gas_used.unwrap_or_default(),
+        excess_blob_gas: header.excess_blob_gas.unwrap_or_default(),
+    }
+}
+
+/// Returns the id of the payload built on `parent_hash` with the given attributes.
+///
+/// The same request always results in the same id.
+pub fn payload_id(parent_hash: B256, attributes: &PayloadAttributes) -> PayloadId {
+    let mut buf = Vec::with_capacity(32 * 3 + 20 + 8);
+    buf.extend_from_slice(parent_hash.as_slice());
+    buf.extend_from_slice(&attributes.timestamp.to_be_bytes());
+    buf.extend_from_slice(attributes.prev_randao.as_slice());
+    buf.extend_from_slice(attributes.suggested_fee_recipient.as_slice());
+    if let Some(root) = attributes.parent_beacon_block_root {
+        buf.extend_from_slice(root.as_slice());
+    }
+    let hash = keccak256(buf);
+    PayloadId::new(hash[..8].try_into().unwrap())
+}
+
+/// Validates the payload attributes of `engine_forkchoiceUpdatedV{version}` and converts them
+pub fn block_attributes(
+    version: u8,
+    attributes: PayloadAttributes,
+) -> Result<BlockAttributes, BlockchainError> {
+    if attributes.withdrawals.as_ref().is_some_and(|withdrawals| !withdrawals.is_empty()) {
+        return Err(BlockchainError::InvalidPayloadAttributes(
+            "withdrawals are not supported".to_string(),
+        ));
+    }
+    match (version, attributes.parent_beacon_block_root) {
+        (1 | 2, Some(_)) => {
+            return Err(BlockchainError::InvalidPayloadAttributes(format!(
+                "parentBeaconBlockRoot is not supported by V{version}"
+            )))
+        }
+        (3, None) => {
+            return Err(BlockchainError::InvalidPayloadAttributes(
+                "missing parentBeaconBlockRoot".to_string(),
+            ))
+        }
+        _ => {}
+    }
+    Ok(BlockAttributes {
+        timestamp: attributes.timestamp,
+        prev_randao: attributes.prev_randao,
+        fee_recipient: attributes.suggested_fee_recipient,
+        parent_beacon_block_root: attributes.parent_beacon_block_root,
+        extra_data: Default::default(),
+        gas_limit: None,
+        base_fee_per_gas: None,
+    })
+}
@@ -1,7 +1,7 @@
 //! Aggregated error type for this module
 
 use crate::eth::pool::transactions::PoolTransaction;
-use alloy_primitives::{Bytes, SignatureError};
+use alloy_primitives::{Bytes, SignatureError, B256};
 use alloy_rpc_types::BlockNumberOrTag;
 use alloy_signer::Error as SignerError;
 use alloy_transport::TransportError;
@@ -94,6 +94,12 @@ pub enum BlockchainError {
     UnknownTransactionType,
     #[error("Excess blob gas not set.")]
     ExcessBlobGasNotSet,
+    #[error("Unknown payload")]
+    UnknownPayload,
+    #[error("Invalid forkchoice state")]
+    InvalidForkchoiceState,
+    #[error("Invalid payload attributes: {0}")]
+    InvalidPayloadAttributes(String),
     #[error("{0}")]
     Message(String),
 }
@@ -158,6 +164,28 @@ pub enum PoolError {
     ReplacementUnderpriced(Box<PoolTransaction>),
     #[error("Tx: [{0:?}] already Imported")]
     AlreadyImported(Box<PoolTransaction>),
+    /// Thrown if the pool is full and the transaction doesn't pay more than the cheapest one
+    #[error("Tx: [{0:?}] underpriced")]
+    Underpriced(Box<PoolTransaction>),
+    /// Thrown if the pool is full and no transaction can be evicted
+    #[error("Tx: [{0:?}] txpool is full")]
+    PoolFull(Box<PoolTransaction>),
+    /// Thrown if the sender already has the maximum number of transactions in the pool
+    #[error("Tx: [{0:?}] account limit exceeded")]
+    AccountLimitExceeded(Box<PoolTransaction>),
+}
+
+/// Errors that can occur when importing a block that was built elsewhere
+#[derive(Debug, thiserror::Error)]
+pub enum BlockImportError {
+    #[error("parent block {0} is not the current best block")]
+    UnknownParent(B256),
+    #[error("state of parent block {0} is not available")]
+    MissingState(B256),
+    #[error("block contains invalid transactions")]
+    InvalidTransactions,
+    #[error("block hash mismatch: want {expected}, got {got}")]
+    HashMismatch { expected: B256, got: B256 },
 }
 
 /// Errors that can occur with `eth_feeHistory`
@@ -345,6 +373,13 @@ fn to_rpc_result(self) -> ResponseResult {
                         PoolError::AlreadyImported(_) => {
                             RpcError::transaction_rejected("transaction already imported")
                         }
+                        PoolError::Underpriced(_) => {
+                            RpcError::transaction_rejected("transaction underpriced")
+                        }
+                        PoolError::PoolFull(_) => RpcError::transaction_rejected("txpool is full"),
+                        PoolError::AccountLimitExceeded(_) => {
+                            RpcError::transaction_rejected("account limit exceeded")
+                        }
                     }
                 }
                 BlockchainError::NoSignerAvailable => {
@@ -473,6 +508,22 @@ fn to_rpc_result(self) -> ResponseResult {
                 err @ BlockchainError::ExcessBlobGasNotSet => {
                     RpcError::invalid_params(err.to_string())
                 }
+                err @ BlockchainError::UnknownPayload => RpcError {
+                    // <https://github.com/ethereum/execution-apis/blob/main/src/engine/common.md#errors>
+                    code: ErrorCode::ServerError(-38001),
+                    message: err.to_string().into(),
+                    data: None,
+                },
+                err @ BlockchainError::InvalidForkchoiceState => RpcError {
+                    code: ErrorCode::ServerError(-38002),
+                    message: err.to_string().into(),
+                    data: None,
+                },
+                err @ BlockchainError::InvalidPayloadAttributes(_) => RpcError {
+                    code: ErrorCode::ServerError(-38003),
+                    message: err.to_string().into(),
+                    data: None,
+                },
                 err @ BlockchainError::Message(_) => RpcError::internal_error_with(err.to_string()),
                 err @ BlockchainError::UnknownTransactionType => {
                     RpcError::invalid_params(err.to_string())
@@ -1,7 +1,10 @@
 //! Mines transactions
 
-use crate::eth::pool::{transactions::PoolTransaction, Pool};
-use alloy_primitives::TxHash;
+use crate::{
+    eth::pool::{bundle::Bundle, transactions::PoolTransaction, Pool},
+    metrics::MinerMetrics,
+};
+use alloy_primitives::{map::HashSet, TxHash};
 use futures::{
     channel::mpsc::Receiver,
     stream::{Fuse, Stream, StreamExt},
@@ -28,6 +31,8 @@ pub struct Miner {
     /// Transactions included into the pool before any others are.
     /// Done once on startup.
     force_transactions: Option<Vec<Arc<PoolTransaction>>>,
+    /// Stats of the mined blocks
+    metrics: Arc<MinerMetrics>,
 }
 
 impl Miner {
@@ -37,6 +42,7 @@ pub fn new(mode: MiningMode) -> Self {
             mode: Arc::new(RwLock::new(mode)),
             inner: Default::default(),
             force_transactions: None,
+            metrics: Default::default(),
         }
     }
 
@@ -53,6 +59,11 @@ pub fn with_forced_transactions(
         self
     }
 
+    /// Returns the stats of the blocks mined by this miner
+    pub fn metrics(&self) -> &MinerMetrics {
+        &self.metrics
+    }
+
     /// Returns the write lock of the mining mode
     pub fn mode_write(&self) -> RwLockWriteGuard<'_, RawRwLock, MiningMode> {
         self.mode.write()
@@ -64,6 +75,12 @@ pub fn is_auto_mine(&self) -> bool {
         matches!(*mode, MiningMode::Auto(_))
     }
 
+    /// Returns `true` if blocks are only built via the engine API
+    pub fn is_engine(&self) -> bool {
+        let mode = self.mode.read();
+        matches!(*mode, MiningMode::Engine)
+    }
+
     pub fn get_interval(&self) -> Option<u64> {
         let mode = self.mode.read();
         if let MiningMode::FixedBlockTime(ref mm) = *mode {
@@ -138,6 +155,10 @@ pub enum MiningMode {
 
     /// A minner that uses both Auto and FixedBlockTime
     Mixed(ReadyTransactionMiner, FixedBlockTimeMiner),
+
+    /// A miner that never builds blocks on its own, blocks are built and made canonical via
+    /// `engine_*` calls instead
+    Engine,
 }
 
 impl MiningMode {
@@ -167,7 +188,7 @@ pub fn poll(
         cx: &mut Context<'_>,
     ) -> Poll<Vec<Arc<PoolTransaction>>> {
         match self {
-            Self::None => Poll::Pending,
+            Self::None | Self::Engine => Poll::Pending,
             Self::Auto(miner) => miner.poll(pool, cx),
             Self::FixedBlockTime(miner) => miner.poll(pool, cx),
             Self::Mixed(auto, fixed) => {
@@ -275,3 +296,94 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .finish_non_exhaustive()
     }
 }
+
+/// An item of a block that is being built
+#[derive(Clone, Debug)]
+pub enum BlockItem {
+    /// A single transaction, that is skipped if it can't be included
+    Transaction(Arc<PoolTransaction>),
+    /// A bundle, that is only included if all of its transactions can be included
+    Bundle(Bundle),
+}
+
+impl From<Arc<PoolTransaction>> for BlockItem {
+    fn from(tx: Arc<PoolTransaction>) -> Self {
+        Self::Transaction(tx)
+    }
+}
+
+/// Assembles the items of a new block from the pending bundles and the ready transactions of the
+/// pool, in the order they are executed.
+///
+/// This is implemented for closures, so a custom ordering can be configured via
+/// [NodeConfig::with_block_builder](crate::NodeConfig::with_block_builder):
+///
+/// ```
+/// use anvil::{eth::miner::BlockItem, NodeConfig};
+///
+/// // mine the pool transactions in reverse and ignore all bundles
+/// let config = NodeConfig::test().with_block_builder(|_bundles, mut transactions: Vec<_>| {
+///     transactions.reverse();
+///     transactions.into_iter().map(BlockItem::Transaction).collect()
+/// });
+/// ```
+pub trait BlockBuilder: Send + Sync {
+    /// Returns the items of the next block.
+    ///
+    /// `transactions` are ordered according to the
+    /// [TransactionOrder](crate::eth::pool::transactions::TransactionOrder) of the pool.
+    fn build(
+        &self,
+        bundles: Vec<Bundle>,
+        transactions: Vec<Arc<PoolTransaction>>,
+    ) -> Vec<BlockItem>;
+}
+
+impl fmt::Debug for dyn BlockBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockBuilder").finish_non_exhaustive()
+    }
+}
+
+impl<F> BlockBuilder for F
+where
+    F: Fn(Vec<Bundle>, Vec<Arc<PoolTransaction>>) -> Vec<BlockItem> + Send + Sync,
+{
+    fn build(
+        &self,
+        bundles: Vec<Bundle>,
+        transactions: Vec<Arc<PoolTransaction>>,
+    ) -> Vec<BlockItem> {
+        self(bundles, transactions)
+    }
+}
+
+/// The default [BlockBuilder].
+///
+/// Puts all bundles at the top of the block, in the order they were submitted, followed by the
+/// transactions of the pool.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BundlesFirst;
+
+impl BlockBuilder for BundlesFirst {
+    fn build(
+        &self,
+        bundles: Vec<Bundle>,
+        transactions: Vec<Arc<PoolTransaction>>,
+    ) -> Vec<BlockItem> {
+        let bundled = bundles
+            .iter()
+            .flat_map(|bundle| bundle.transactions.iter().map(|tx| tx.hash()))
+            .collect::<HashSet<_>>();
+        bundles
+            .into_iter()
+            .map(BlockItem::Bundle)
+            .chain(
+                transactions
+                    .into_iter()
+                    .filter(|tx| !bundled.contains(&tx.hash()))
+                    .map(BlockItem::Transaction),
+            )
+            .collect()
+    }
+}
@@ -4,7 +4,9 @@
 pub use api::EthApi;
 
 pub mod backend;
+pub mod bundler;
 
+pub mod engine;
 pub mod error;
 
 pub mod fees;
@@ -0,0 +1,134 @@
+//! Bundles and private transactions that bypass the public pool.
+//!
+//! Bundles are never announced to other nodes or exposed via `txpool_*`, they are only handed to
+//! the [BlockBuilder](crate::eth::miner::BlockBuilder) when the node builds its next block.
+
+use crate::eth::pool::transactions::PoolTransaction;
+use alloy_primitives::{keccak256, map::HashSet, TxHash, B256};
+use std::sync::Arc;
+
+/// A group of transactions that is included atomically and in order.
+///
+/// A bundle is dropped from a block if any of its transactions can't be included or reverts,
+/// unless it is allowed to revert.
+#[derive(Clone, Debug)]
+pub struct Bundle {
+    /// The hash identifying the bundle
+    pub hash: B256,
+    /// The transactions of the bundle, in execution order
+    pub transactions: Vec<Arc<PoolTransaction>>,
+    /// The first block the bundle can be included in
+    pub min_block_number: Option<u64>,
+    /// The last block the bundle can be included in
+    pub max_block_number: Option<u64>,
+    /// The minimum timestamp of the block the bundle is included in
+    pub min_timestamp: Option<u64>,
+    /// The maximum timestamp of the block the bundle is included in
+    pub max_timestamp: Option<u64>,
+    /// Transactions that are allowed to revert without dropping the bundle
+    pub reverting_tx_hashes: HashSet<TxHash>,
+}
+
+// == impl Bundle ==
+
+impl Bundle {
+    /// Creates a bundle of the transactions that can be included in any block.
+    ///
+    /// The hash of the bundle is the keccak256 hash of the concatenated transaction hashes.
+    pub fn new(transactions: Vec<Arc<PoolTransaction>>) -> Self {
+        let hashes = transactions.iter().flat_map(|tx| tx.hash().0).collect::<Vec<_>>();
+        Self {
+            hash: keccak256(hashes),
+            transactions,
+            min_block_number: None,
+            max_block_number: None,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: Default::default(),
+        }
+    }
+
+    /// Creates a bundle for a private transaction, which is identified by the transaction hash and
+    /// is allowed to revert.
+    pub fn private(transaction: Arc<PoolTransaction>, max_block_number: Option<u64>) -> Self {
+        let hash = transaction.hash();
+        Self {
+            hash,
+            min_block_number: None,
+            max_block_number,
+            reverting_tx_hashes: HashSet::from_iter([hash]),
+            ..Self::new(vec![transaction])
+        }
+    }
+
+    /// Returns true if the bundle can be included in the block with that number
+    pub fn is_valid_for_block(&self, number: u64) -> bool {
+        self.min_block_number.is_none_or(|min| number >= min) &&
+            self.max_block_number.is_none_or(|max| number <= max)
+    }
+
+    /// Returns true if the bundle can be included in a block with that timestamp
+    pub fn is_valid_for_timestamp(&self, timestamp: u64) -> bool {
+        self.min_timestamp.is_none_or(|min| timestamp >= min) &&
+            self.max_timestamp.is_none_or(|max| timestamp <= max)
+    }
+
+    /// Returns true if the transaction is allowed to revert
+    pub fn can_revert(&self, hash: &TxHash) -> bool {
+        self.reverting_tx_hashes.contains(hash)
+    }
+}
+
+/// Holds all bundles that haven't been included yet, in the order they were submitted.
+#[derive(Debug, Default)]
+pub struct BundlePool {
+    bundles: Vec<Bundle>,
+}
+
+// == impl BundlePool ==
+
+impl BundlePool {
+    /// Returns the number of bundles
+    pub fn len(&self) -> usize {
+        self.bundles.len()
+    }
+
+    /// Returns true if there are no bundles
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty()
+    }
+
+    /// Adds the bundle, replacing a bundle with the same hash
+    pub fn add(&mut self, bundle: Bundle) {
+        self.remove(&bundle.hash);
+        self.bundles.push(bundle);
+    }
+
+    /// Removes the bundle with that hash
+    pub fn remove(&mut self, hash: &B256) -> Option<Bundle> {
+        let index = self.bundles.iter().position(|bundle| bundle.hash == *hash)?;
+        Some(self.bundles.remove(index))
+    }
+
+    /// Returns all bundles that can be included in the block with that number
+    pub fn bundles_for_block(&self, number: u64) -> Vec<Bundle> {
+        self.bundles.iter().filter(|bundle| bundle.is_valid_for_block(number)).cloned().collect()
+    }
+
+    /// Removes all bundles that were included in the block, or can't be included in any later
+    /// block.
+    ///
+    /// A bundle counts as included as soon as one of its transactions is, since its nonce is used
+    /// then.
+    pub fn on_mined_block(&mut self, number: u64, included: &HashSet<TxHash>) {
+        self.bundles.retain(|bundle| {
+            bundle.max_block_number.is_none_or(|max| max > number) &&
+                !bundle.transactions.iter().any(|tx| included.contains(&tx.hash()))
+        });
+    }
+
+    /// Removes all bundles
+    pub fn clear(&mut self) {
+        self.bundles.clear();
+    }
+}
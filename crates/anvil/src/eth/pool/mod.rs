@@ -24,6 +24,8 @@
 //! a `nonce > nonce on chain` will _require_ `(nonce -1, account)` first, before it is ready to be
 //! included in a block.
 //!
+//! Replacements, limits and eviction follow geth's rules, see [PoolConfig].
+//!
 //! This implementation is adapted from <https://github.com/paritytech/substrate/tree/master/client/transaction-pool>
 
 use crate::{
@@ -35,16 +37,41 @@
         },
     },
     mem::storage::MinedBlockOutcome,
+    metrics::PoolMetrics,
 };
 use alloy_primitives::{Address, TxHash, U64};
 use alloy_rpc_types::txpool::TxpoolStatus;
 use anvil_core::eth::transaction::PendingTransaction;
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use parking_lot::{Mutex, RwLock};
-use std::{collections::VecDeque, fmt, sync::Arc};
+use std::{collections::VecDeque, fmt, sync::Arc, time::Duration};
 
+pub mod bundle;
 pub mod transactions;
 
+/// Configures how transactions are replaced and when they are rejected or evicted.
+///
+/// All limits are unbounded by default. Unlike geth, the per-account limits are hard limits: a
+/// transaction that would exceed them is rejected even if the pool has spare capacity.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Minimum fee bump in percent to replace a transaction with the same nonce.
+    ///
+    /// A replacement must always pay a higher fee cap and tip cap than the transaction it
+    /// replaces. Defaults to 0, so any increase is enough; set it to 10 to enforce geth's rule.
+    pub price_bump: u64,
+    /// Maximum number of ready transactions per account
+    pub account_slots: Option<usize>,
+    /// Maximum number of ready transactions across all accounts
+    pub global_slots: Option<usize>,
+    /// Maximum number of queued transactions per account
+    pub account_queue: Option<usize>,
+    /// Maximum number of queued transactions across all accounts
+    pub global_queue: Option<usize>,
+    /// Maximum time a transaction is kept in the queue before it is evicted
+    pub lifetime: Option<Duration>,
+}
+
 /// Transaction pool that performs validation.
 #[derive(Default)]
 pub struct Pool {
@@ -52,11 +79,32 @@ pub struct Pool {
     inner: RwLock<PoolInner>,
     /// listeners for new ready transactions
     transaction_listener: Mutex<Vec<Sender<TxHash>>>,
+    /// counts added, rejected and mined transactions
+    metrics: PoolMetrics,
 }
 
 // == impl Pool ==
 
 impl Pool {
+    /// Creates a new pool with the given config
+    pub fn new(config: PoolConfig) -> Self {
+        Self {
+            inner: RwLock::new(PoolInner { config, ..Default::default() }),
+            transaction_listener: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+
+    /// Returns the config of this pool
+    pub fn config(&self) -> PoolConfig {
+        self.inner.read().config.clone()
+    }
+
+    /// Returns the metrics of this pool
+    pub fn metrics(&self) -> &PoolMetrics {
+        &self.metrics
+    }
+
     /// Returns an iterator that yields all transactions that are currently ready
     pub fn ready_transactions(&self) -> TransactionsIterator {
         self.inner.read().ready_transactions()
@@ -88,6 +136,7 @@ pub fn on_mined_block(&self, outcome: MinedBlockOutcome) -> PruneResult {
 
         // remove invalid transactions from the pool
         self.remove_invalid(invalid.into_iter().map(|tx| tx.hash()).collect());
+        self.metrics.mined.increment_by(included.len() as u64);
 
         // prune all the markers the mined transactions provide
         let res = self
@@ -111,7 +160,10 @@ pub fn prune_markers(
 
     /// Adds a new transaction to the pool
     pub fn add_transaction(&self, tx: PoolTransaction) -> Result<AddedTransaction, PoolError> {
-        let added = self.inner.write().add_transaction(tx)?;
+        let added = self.inner.write().add_transaction(tx).inspect_err(|_| {
+            self.metrics.rejected.increment();
+        })?;
+        self.metrics.added.increment();
         if let AddedTransaction::Ready(ref ready) = added {
             self.notify_listener(ready.hash);
             // also notify promoted transactions
@@ -166,6 +218,11 @@ pub fn drop_transaction(&self, tx: TxHash) -> Option<Arc<PoolTransaction>> {
         dropped
     }
 
+    /// Removes all queued transactions that exceeded the configured lifetime
+    pub fn evict_expired(&self) -> Vec<Arc<PoolTransaction>> {
+        self.inner.write().evict_expired()
+    }
+
     /// Removes all transactions from the pool
     pub fn clear(&self) {
         let mut pool = self.inner.write();
@@ -207,6 +264,7 @@ fn notify_listener(&self, hash: TxHash) {
 struct PoolInner {
     ready_transactions: ReadyTransactions,
     pending_transactions: PendingTransactions,
+    config: PoolConfig,
 }
 
 // == impl PoolInner ==
@@ -264,9 +322,15 @@ fn add_transaction(&mut self, tx: PoolTransaction) -> Result<AddedTransaction, P
             return Err(PoolError::AlreadyImported(Box::new(tx)))
         }
 
+        self.evict_expired();
+
         let tx = PendingPoolTransaction::new(tx, self.ready_transactions.provided_markers());
         trace!(target: "txpool", "[{:?}] {:?}", tx.transaction.hash(), tx);
 
+        if !self.check_replacement(&tx.transaction)? {
+            self.check_limits(&tx)?;
+        }
+
         // If all markers are not satisfied import to future
         if !tx.is_ready() {
             let hash = tx.transaction.hash();
@@ -276,6 +340,85 @@ fn add_transaction(&mut self, tx: PoolTransaction) -> Result<AddedTransaction, P
         self.add_ready_transaction(tx)
     }
 
+    /// Returns true if the transaction replaces a transaction with the same nonce.
+    ///
+    /// Returns an error if it doesn't pay enough to replace it.
+    fn check_replacement(&self, tx: &PoolTransaction) -> Result<bool, PoolError> {
+        let replaced = match self.pending_transactions.get_by_provides(&tx.provides) {
+            Some(pending) => Some(pending.transaction.clone()),
+            None => tx
+                .provides
+                .first()
+                .and_then(|marker| self.ready_transactions.provided_markers().get(marker))
+                .and_then(|hash| self.ready_transactions.get(hash))
+                .map(|ready| ready.transaction.transaction),
+        };
+        let Some(replaced) = replaced else { return Ok(false) };
+        if !tx.is_replacement_for(&replaced, self.config.price_bump) {
+            warn!(target: "txpool", "[{:?}] replacement transaction underpriced", tx.hash());
+            return Err(PoolError::ReplacementUnderpriced(Box::new(tx.clone())))
+        }
+        Ok(true)
+    }
+
+    /// Checks the account and global limits for a new transaction.
+    ///
+    /// If the pool is full, this evicts a transaction of another account to make room: the
+    /// cheapest ready transaction nothing depends on, or the oldest queued transaction.
+    fn check_limits(&mut self, tx: &PendingPoolTransaction) -> Result<(), PoolError> {
+        let sender = tx.transaction.sender();
+        let PoolConfig { account_slots, global_slots, account_queue, global_queue, .. } =
+            self.config;
+
+        if tx.is_ready() {
+            if account_slots
+                .is_some_and(|max| self.ready_transactions.count_by_sender(sender) >= max)
+            {
+                return Err(PoolError::AccountLimitExceeded(Box::new(
+                    tx.transaction.as_ref().clone(),
+                )))
+            }
+            if global_slots.is_some_and(|max| self.ready_transactions.len() >= max) {
+                let Some(cheapest) = self.ready_transactions.cheapest_leaf_except(sender) else {
+                    return Err(PoolError::PoolFull(Box::new(tx.transaction.as_ref().clone())))
+                };
+                if tx.transaction.gas_price() <= cheapest.gas_price() {
+                    return Err(PoolError::Underpriced(Box::new(tx.transaction.as_ref().clone())))
+                }
+                debug!(target: "txpool", "[{:?}] evicting underpriced transaction", cheapest.hash());
+                self.ready_transactions.remove_with_markers(vec![cheapest.hash()], None);
+            }
+        } else {
+            if account_queue
+                .is_some_and(|max| self.pending_transactions.count_by_sender(sender) >= max)
+            {
+                return Err(PoolError::AccountLimitExceeded(Box::new(
+                    tx.transaction.as_ref().clone(),
+                )))
+            }
+            if global_queue.is_some_and(|max| self.pending_transactions.len() >= max) {
+                let Some(oldest) = self.pending_transactions.oldest_except(sender) else {
+                    return Err(PoolError::PoolFull(Box::new(tx.transaction.as_ref().clone())))
+                };
+                let oldest = oldest.transaction.hash();
+                debug!(target: "txpool", "[{:?}] evicting queued transaction", oldest);
+                self.pending_transactions.remove(vec![oldest]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all queued transactions that exceeded the configured lifetime
+    fn evict_expired(&mut self) -> Vec<Arc<PoolTransaction>> {
+        let Some(lifetime) = self.config.lifetime else { return vec![] };
+        let expired = self.pending_transactions.expired(lifetime);
+        if expired.is_empty() {
+            return vec![]
+        }
+        trace!(target: "txpool", "Evicting expired transactions: {:?}", expired);
+        self.pending_transactions.remove(expired)
+    }
+
     /// Adds the transaction to the ready queue
     fn add_ready_transaction(
         &mut self,
@@ -6,7 +6,14 @@
 };
 use anvil_core::eth::transaction::{PendingTransaction, TypedTransaction};
 use parking_lot::RwLock;
-use std::{cmp::Ordering, collections::BTreeSet, fmt, str::FromStr, sync::Arc, time::Instant};
+use std::{
+    cmp::Ordering,
+    collections::BTreeSet,
+    fmt,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// A unique identifying marker for a transaction
 pub type TxMarker = Vec<u8>;
@@ -98,6 +105,36 @@ pub fn hash(&self) -> TxHash {
     pub fn gas_price(&self) -> u128 {
         self.pending_transaction.transaction.gas_price()
     }
+
+    /// Returns the sender of this transaction
+    pub fn sender(&self) -> Address {
+        *self.pending_transaction.sender()
+    }
+
+    /// Returns the max fee per gas and the max priority fee per gas.
+    ///
+    /// Both are the gas price for transactions without dynamic fees.
+    pub fn fee_caps(&self) -> (u128, u128) {
+        let essentials = self.pending_transaction.transaction.essentials();
+        match essentials.max_priority_fee_per_gas {
+            Some(tip) => (essentials.max_fee_per_gas.unwrap_or_default(), tip),
+            None => (self.gas_price(), self.gas_price()),
+        }
+    }
+
+    /// Returns true if this transaction can replace `other`.
+    ///
+    /// Like geth, this requires both the fee cap and the tip cap to be higher than the ones of
+    /// `other`, by at least `price_bump` percent.
+    pub fn is_replacement_for(&self, other: &Self, price_bump: u64) -> bool {
+        let (fee_cap, tip_cap) = self.fee_caps();
+        let (old_fee_cap, old_tip_cap) = other.fee_caps();
+        if fee_cap <= old_fee_cap || tip_cap <= old_tip_cap {
+            return false
+        }
+        let threshold = |old: u128| old.saturating_mul(100 + price_bump as u128) / 100;
+        fee_cap >= threshold(old_fee_cap) && tip_cap >= threshold(old_tip_cap)
+    }
 }
 
 impl fmt::Debug for PoolTransaction {
@@ -182,6 +219,9 @@ pub fn add_transaction(&mut self, tx: PendingPoolTransaction) -> Result<(), Pool
                     tx.transaction.as_ref().clone(),
                 )))
             }
+            // drop the replaced transaction
+            let replaced = replace.transaction.hash();
+            self.remove(vec![replaced]);
         }
 
         // add all missing markers
@@ -207,6 +247,33 @@ pub fn get(&self, hash: &TxHash) -> Option<&PendingPoolTransaction> {
         self.waiting_queue.get(hash)
     }
 
+    /// Returns the waiting transaction that provides exactly the given markers
+    pub fn get_by_provides(&self, provides: &[TxMarker]) -> Option<&PendingPoolTransaction> {
+        self.waiting_markers.get(provides).and_then(|hash| self.waiting_queue.get(hash))
+    }
+
+    /// Returns the number of waiting transactions sent by `sender`
+    pub fn count_by_sender(&self, sender: Address) -> usize {
+        self.waiting_queue.values().filter(|tx| tx.transaction.sender() == sender).count()
+    }
+
+    /// Returns the transaction that has been waiting the longest and wasn't sent by `sender`
+    pub fn oldest_except(&self, sender: Address) -> Option<&PendingPoolTransaction> {
+        self.waiting_queue
+            .values()
+            .filter(|tx| tx.transaction.sender() != sender)
+            .min_by_key(|tx| tx.added_at)
+    }
+
+    /// Returns the hashes of all transactions that have been waiting longer than `lifetime`
+    pub fn expired(&self, lifetime: Duration) -> Vec<TxHash> {
+        self.waiting_queue
+            .iter()
+            .filter(|(_, tx)| tx.added_at.elapsed() > lifetime)
+            .map(|(hash, _)| *hash)
+            .collect()
+    }
+
     /// This will check off the markers of pending transactions.
     ///
     /// Returns the those transactions that become unlocked (all markers checked) and can be moved
@@ -421,6 +488,36 @@ pub fn provided_markers(&self) -> &HashMap<TxMarker, TxHash> {
         &self.provided_markers
     }
 
+    /// Returns the number of ready transactions
+    pub fn len(&self) -> usize {
+        self.ready_tx.read().len()
+    }
+
+    /// Returns true if there are no ready transactions
+    pub fn is_empty(&self) -> bool {
+        self.ready_tx.read().is_empty()
+    }
+
+    /// Returns the number of ready transactions sent by `sender`
+    pub fn count_by_sender(&self, sender: Address) -> usize {
+        self.ready_tx
+            .read()
+            .values()
+            .filter(|tx| tx.transaction.transaction.sender() == sender)
+            .count()
+    }
+
+    /// Returns the cheapest transaction that no other ready transaction depends on and that
+    /// wasn't sent by `sender`
+    pub fn cheapest_leaf_except(&self, sender: Address) -> Option<Arc<PoolTransaction>> {
+        self.ready_tx
+            .read()
+            .values()
+            .filter(|tx| tx.unlocks.is_empty() && tx.transaction.transaction.sender() != sender)
+            .min_by_key(|tx| tx.gas_price())
+            .map(|tx| tx.transaction.transaction.clone())
+    }
+
     fn next_id(&mut self) -> u64 {
         let id = self.id;
         self.id = self.id.wrapping_add(1);
@@ -1,7 +1,10 @@
 //! Support for polling based filters
 use crate::{
-    eth::{backend::notifications::NewBlockNotifications, error::ToRpcResponseResult},
-    pubsub::filter_logs,
+    eth::{
+        backend::notifications::{ChainNotifications, NewBlockNotifications},
+        error::ToRpcResponseResult,
+    },
+    pubsub::chain_logs,
     StorageInfo,
 };
 use alloy_primitives::{map::HashMap, TxHash};
@@ -145,11 +148,12 @@ fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::It
     }
 }
 
-/// Listens for new blocks and matching logs emitted in that block
+/// Listens for new blocks and matching logs emitted in that block, and for logs of blocks that were
+/// removed from the chain
 #[derive(Debug)]
 pub struct LogsFilter {
-    /// listener for new blocks
-    pub blocks: NewBlockNotifications,
+    /// listener for changes of the chain
+    pub blocks: ChainNotifications,
     /// accessor for block storage
     pub storage: StorageInfo,
     /// matcher with all provided filter params
@@ -164,12 +168,8 @@ impl LogsFilter {
     /// Returns all the logs since the last time this filter was polled
     pub fn poll(&mut self, cx: &mut Context<'_>) -> Vec<Log> {
         let mut logs = self.historic.take().unwrap_or_default();
-        while let Poll::Ready(Some(block)) = self.blocks.poll_next_unpin(cx) {
-            let b = self.storage.block(block.hash);
-            let receipts = self.storage.receipts(block.hash);
-            if let (Some(receipts), Some(block)) = (receipts, b) {
-                logs.extend(filter_logs(block, receipts, &self.filter))
-            }
+        while let Poll::Ready(Some(notification)) = self.blocks.poll_next_unpin(cx) {
+            logs.extend(chain_logs(&self.storage, notification, &self.filter))
         }
         logs
     }
@@ -3,8 +3,10 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use crate::{
+    config::{anvil_tmp_dir, write_jwt_secret},
     eth::{
         backend::{info::StorageInfo, mem},
+        bundler::Bundler,
         fees::{FeeHistoryService, FeeManager},
         miner::{Miner, MiningMode},
         pool::Pool,
@@ -13,12 +15,16 @@
     },
     filter::Filters,
     logging::{LoggingManager, NodeLogLayer},
-    server::error::{NodeError, NodeResult},
+    server::{
+        error::{NodeError, NodeResult},
+        session::SessionRecorder,
+    },
     service::NodeService,
     shutdown::Signal,
     tasks::TaskManager,
 };
 use alloy_primitives::{Address, U256};
+use alloy_rpc_types::engine::JwtSecret;
 use alloy_signer_local::PrivateKeySigner;
 use eth::backend::fork::ClientFork;
 use eyre::Result;
@@ -30,6 +36,7 @@
 use std::{
     future::Future,
     net::SocketAddr,
+    path::{Path, PathBuf},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -55,10 +62,24 @@
 /// Evm related abstractions
 mod evm;
 pub use evm::{inject_precompiles, PrecompileFactory};
+/// Custom precompiles and predeploys loaded from a config file
+pub mod chain_overrides;
+pub use chain_overrides::ChainOverrides;
 /// support for polling filters
 pub mod filter;
 /// commandline output
 pub mod logging;
+/// node metrics in the Prometheus format
+pub mod metrics;
+/// local networks of nodes that gossip with each other
+pub mod network;
+pub use network::{spawn_network, try_spawn_network, NodeNetwork};
+/// several chains served from one process with a shared clock
+pub mod multichain;
+pub use multichain::{spawn_chains, try_spawn_chains, MultiChain};
+/// emulation of the bridge between an L1 chain and an OP stack L2 chain
+pub mod op_bridge;
+pub use op_bridge::{spawn_op_bridge, try_spawn_op_bridge, OpBridge};
 /// types for subscriptions
 pub mod pubsub;
 /// axum RPC server implementations
@@ -147,14 +168,23 @@ pub async fn try_spawn(mut config: NodeConfig) -> Result<(EthApi, NodeHandle)> {
         server_config,
         no_mining,
         transaction_order,
+        pool_config,
         genesis,
         mixed_mining,
+        engine_api,
+        auth_port,
+        jwt_secret,
+        metrics_port,
+        record_session,
+        bundler,
         ..
     } = config.clone();
 
-    let pool = Arc::new(Pool::default());
+    let pool = Arc::new(Pool::new(pool_config));
 
-    let mode = if let Some(block_time) = block_time {
+    let mode = if engine_api {
+        MiningMode::Engine
+    } else if let Some(block_time) = block_time {
         if mixed_mining {
             let listener = pool.add_ready_listener();
             MiningMode::mixed(max_transactions, listener, block_time)
@@ -204,7 +234,7 @@ pub async fn try_spawn(mut config: NodeConfig) -> Result<(EthApi, NodeHandle)> {
     let filters = Filters::default();
 
     // create the cloneable api wrapper
-    let api = EthApi::new(
+    let mut api = EthApi::new(
         Arc::clone(&pool),
         Arc::clone(&backend),
         Arc::new(signers),
@@ -215,6 +245,12 @@ pub async fn try_spawn(mut config: NodeConfig) -> Result<(EthApi, NodeHandle)> {
         filters.clone(),
         transaction_order,
     );
+    if let Some(path) = record_session {
+        api.set_session_recorder(SessionRecorder::create(path, api.backend.best_number())?);
+    }
+    if let Some(bundler) = bundler {
+        api.set_bundler(Bundler::new(bundler));
+    }
 
     // spawn the node service
     let node_service =
@@ -235,6 +271,44 @@ pub async fn try_spawn(mut config: NodeConfig) -> Result<(EthApi, NodeHandle)> {
         servers.push(tokio::task::spawn(srv.map_err(Into::into)));
     }
 
+    let jwt_secret = engine_api.then(|| jwt_secret.unwrap_or_else(JwtSecret::random));
+    let mut auth_addresses = Vec::new();
+    if let Some(secret) = jwt_secret {
+        for addr in &config.host {
+            let tcp_listener =
+                tokio::net::TcpListener::bind(SocketAddr::new(*addr, auth_port)).await?;
+            auth_addresses.push(tcp_listener.local_addr()?);
+
+            let srv =
+                server::serve_auth_on(tcp_listener, api.clone(), server_config.clone(), secret);
+            servers.push(tokio::task::spawn(srv.map_err(Into::into)));
+        }
+    }
+
+    // a random secret is written to a file instead of the logs
+    let jwt_secret_path = match (jwt_secret, auth_addresses.first()) {
+        (Some(secret), Some(addr)) if config.jwt_secret.is_none() => {
+            let path = anvil_tmp_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join(format!("jwtsecret-{}.hex", addr.port()));
+            write_jwt_secret(&path, &secret)?;
+            Some(path)
+        }
+        _ => None,
+    };
+
+    let mut metrics_addresses = Vec::new();
+    if let Some(metrics_port) = metrics_port {
+        for addr in &config.host {
+            let tcp_listener =
+                tokio::net::TcpListener::bind(SocketAddr::new(*addr, metrics_port)).await?;
+            metrics_addresses.push(tcp_listener.local_addr()?);
+
+            let srv = server::serve_metrics_on(tcp_listener, api.clone());
+            servers.push(tokio::task::spawn(srv.map_err(Into::into)));
+        }
+    }
+
     let tokio_handle = Handle::current();
     let (signal, on_shutdown) = shutdown::signal();
     let task_manager = TaskManager::new(tokio_handle, on_shutdown);
@@ -248,6 +322,10 @@ pub async fn try_spawn(mut config: NodeConfig) -> Result<(EthApi, NodeHandle)> {
         servers,
         ipc_task,
         addresses,
+        auth_addresses,
+        jwt_secret,
+        jwt_secret_path,
+        metrics_addresses,
         _signal: Some(signal),
         task_manager,
     };
@@ -266,6 +344,14 @@ pub struct NodeHandle {
     config: NodeConfig,
     /// The address of the running rpc server.
     addresses: Vec<SocketAddr>,
+    /// The address of the running Engine API server, if enabled.
+    auth_addresses: Vec<SocketAddr>,
+    /// The secret the Engine API requests are authenticated with, if enabled.
+    jwt_secret: Option<JwtSecret>,
+    /// The file the random secret of the Engine API was written to, if no secret was configured.
+    jwt_secret_path: Option<PathBuf>,
+    /// The address of the running metrics server, if enabled.
+    metrics_addresses: Vec<SocketAddr>,
     /// Join handle for the Node Service.
     pub node_service: JoinHandle<Result<(), NodeError>>,
     /// Join handles (one per socket) for the Anvil server.
@@ -308,6 +394,29 @@ pub(crate) fn print(&self, fork: Option<&ClientFork>) -> Result<()> {
                     .collect::<Vec<String>>()
                     .join(", ")
             )?;
+            if !self.auth_addresses.is_empty() {
+                sh_println!(
+                    "Engine API listening on {}",
+                    self.auth_addresses
+                        .iter()
+                        .map(|addr| addr.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )?;
+            }
+            if !self.metrics_addresses.is_empty() {
+                sh_println!(
+                    "Metrics listening on {}",
+                    self.metrics_addresses
+                        .iter()
+                        .map(|addr| addr.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )?;
+            }
+            if let Some(path) = &self.jwt_secret_path {
+                sh_println!("Engine API JWT secret written to {}", path.display())?;
+            }
         }
         Ok(())
     }
@@ -330,6 +439,27 @@ pub fn ws_endpoint(&self) -> String {
         format!("ws://{}", self.socket_address())
     }
 
+    /// Returns the http endpoint of the authenticated Engine API server, if enabled.
+    pub fn auth_endpoint(&self) -> Option<String> {
+        self.auth_addresses.first().map(|addr| format!("http://{addr}"))
+    }
+
+    /// Returns the secret the Engine API requests are authenticated with, if enabled.
+    pub fn jwt_secret(&self) -> Option<JwtSecret> {
+        self.jwt_secret
+    }
+
+    /// Returns the file the random secret of the Engine API was written to, if no secret was
+    /// configured.
+    pub fn jwt_secret_path(&self) -> Option<&Path> {
+        self.jwt_secret_path.as_deref()
+    }
+
+    /// Returns the url of the Prometheus metrics endpoint, if enabled.
+    pub fn metrics_endpoint(&self) -> Option<String> {
+        self.metrics_addresses.first().map(|addr| format!("http://{addr}/metrics"))
+    }
+
     /// Returns the path of the launched ipc server, if any.
     pub fn ipc_path(&self) -> Option<String> {
         self.config.get_ipc_path()
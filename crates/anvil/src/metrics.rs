@@ -0,0 +1,333 @@
+//! Metrics collected by the node components, exposed in the Prometheus text format via `/metrics`
+//! if enabled.
+
+use crate::mem::storage::MinedBlockOutcome;
+use parking_lot::Mutex;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Upper bounds of the buckets of all duration histograms, in seconds
+const DURATION_BUCKETS: [f64; 12] =
+    [0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
+
+/// A monotonically increasing counter
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    /// Increments the counter by one
+    pub fn increment(&self) {
+        self.increment_by(1);
+    }
+
+    /// Increments the counter by `value`
+    pub fn increment_by(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    /// Returns the current value
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A histogram of durations with the fixed [DURATION_BUCKETS]
+#[derive(Clone, Debug, Default)]
+pub struct Histogram {
+    /// Number of observations per bucket, the last bucket is `+Inf`
+    buckets: [u64; DURATION_BUCKETS.len() + 1],
+    /// Sum of all observations in seconds
+    sum: f64,
+    /// Number of observations
+    count: u64,
+}
+
+impl Histogram {
+    /// Records the duration
+    pub fn observe(&mut self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        let bucket = DURATION_BUCKETS.iter().position(|le| secs <= *le);
+        self.buckets[bucket.unwrap_or(DURATION_BUCKETS.len())] += 1;
+        self.sum += secs;
+        self.count += 1;
+    }
+
+    /// Returns the number of observations
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// Metrics of the RPC calls handled by the [EthApi](crate::eth::EthApi), by method
+#[derive(Debug, Default)]
+pub struct RpcMetrics {
+    methods: Mutex<BTreeMap<String, RpcMethodMetrics>>,
+}
+
+#[derive(Debug, Default)]
+struct RpcMethodMetrics {
+    errors: u64,
+    duration: Histogram,
+}
+
+impl RpcMetrics {
+    /// Records a handled call of the method
+    pub fn record(&self, method: &str, elapsed: Duration, is_error: bool) {
+        let mut methods = self.methods.lock();
+        let metrics = match methods.get_mut(method) {
+            Some(metrics) => metrics,
+            None => methods.entry(method.to_string()).or_default(),
+        };
+        metrics.duration.observe(elapsed);
+        metrics.errors += is_error as u64;
+    }
+
+    fn encode(&self, encoder: &mut MetricsEncoder) {
+        let methods = self.methods.lock();
+        encoder.header("anvil_rpc_requests_total", "Number of handled RPC calls", "counter");
+        for (method, metrics) in methods.iter() {
+            let labels = [("method", method.as_str())];
+            encoder.sample("anvil_rpc_requests_total", &labels, metrics.duration.count());
+        }
+        encoder.header("anvil_rpc_errors_total", "Number of RPC calls that failed", "counter");
+        for (method, metrics) in methods.iter() {
+            encoder.sample(
+                "anvil_rpc_errors_total",
+                &[("method", method.as_str())],
+                metrics.errors,
+            );
+        }
+        encoder.header(
+            "anvil_rpc_request_duration_seconds",
+            "Time it took to handle RPC calls",
+            "histogram",
+        );
+        for (method, metrics) in methods.iter() {
+            let labels = [("method", method.as_str())];
+            encoder.histogram("anvil_rpc_request_duration_seconds", &labels, &metrics.duration);
+        }
+    }
+}
+
+/// Metrics of the transaction pool
+#[derive(Debug, Default)]
+pub struct PoolMetrics {
+    /// Number of transactions added to the pool
+    pub added: Counter,
+    /// Number of transactions rejected by the pool
+    pub rejected: Counter,
+    /// Number of transactions removed from the pool because they were mined
+    pub mined: Counter,
+}
+
+/// Metrics of the blocks mined by the [Miner](crate::eth::miner::Miner)
+#[derive(Debug, Default)]
+pub struct MinerMetrics {
+    /// Number of mined blocks
+    pub blocks: Counter,
+    /// Number of transactions included in mined blocks
+    pub transactions: Counter,
+    /// Number of transactions that were invalid when they were about to be mined
+    pub invalid_transactions: Counter,
+    /// Time it took to build blocks
+    pub block_time: Mutex<Histogram>,
+}
+
+impl MinerMetrics {
+    /// Records a block that was mined in `elapsed`
+    pub fn on_mined_block(&self, outcome: &MinedBlockOutcome, elapsed: Duration) {
+        self.blocks.increment();
+        self.transactions.increment_by(outcome.included.len() as u64);
+        self.invalid_transactions.increment_by(outcome.invalid.len() as u64);
+        self.block_time.lock().observe(elapsed);
+    }
+}
+
+/// Metrics of the data fetched from the remote client of a
+/// [ClientFork](crate::eth::backend::fork::ClientFork)
+#[derive(Debug, Default)]
+pub struct ForkMetrics {
+    /// Number of lookups that were served from the fork's cache
+    pub cache_hits: Counter,
+    /// Number of lookups that weren't cached
+    pub cache_misses: Counter,
+    /// Number of requests sent to the remote client
+    pub remote_calls: Counter,
+}
+
+/// A snapshot of all metrics of the node
+#[derive(Debug)]
+pub struct NodeMetrics<'a> {
+    pub rpc: &'a RpcMetrics,
+    pub pool: &'a PoolMetrics,
+    pub pending_transactions: u64,
+    pub queued_transactions: u64,
+    pub miner: &'a MinerMetrics,
+    pub block_number: u64,
+    pub fork: Option<Arc<ForkMetrics>>,
+}
+
+impl NodeMetrics<'_> {
+    /// Returns the metrics in the Prometheus text format
+    pub fn encode(&self) -> String {
+        let mut encoder = MetricsEncoder::default();
+        self.rpc.encode(&mut encoder);
+
+        encoder.gauge(
+            "anvil_txpool_pending",
+            "Number of transactions that are ready to be mined",
+            self.pending_transactions,
+        );
+        encoder.gauge(
+            "anvil_txpool_queued",
+            "Number of transactions that wait for a nonce gap to be filled",
+            self.queued_transactions,
+        );
+        encoder.counter(
+            "anvil_txpool_added_total",
+            "Number of transactions added to the pool",
+            self.pool.added.get(),
+        );
+        encoder.counter(
+            "anvil_txpool_rejected_total",
+            "Number of transactions rejected by the pool",
+            self.pool.rejected.get(),
+        );
+        encoder.counter(
+            "anvil_txpool_mined_total",
+            "Number of pool transactions that were mined",
+            self.pool.mined.get(),
+        );
+
+        encoder.gauge("anvil_block_number", "Number of the latest block", self.block_number);
+        encoder.counter(
+            "anvil_miner_blocks_total",
+            "Number of blocks mined",
+            self.miner.blocks.get(),
+        );
+        encoder.counter(
+            "anvil_miner_transactions_total",
+            "Number of transactions included in mined blocks",
+            self.miner.transactions.get(),
+        );
+        encoder.counter(
+            "anvil_miner_invalid_transactions_total",
+            "Number of transactions that were invalid when they were about to be mined",
+            self.miner.invalid_transactions.get(),
+        );
+        encoder.header(
+            "anvil_miner_block_time_seconds",
+            "Time it took to build mined blocks",
+            "histogram",
+        );
+        encoder.histogram("anvil_miner_block_time_seconds", &[], &self.miner.block_time.lock());
+
+        if let Some(fork) = &self.fork {
+            encoder.counter(
+                "anvil_fork_cache_hits_total",
+                "Number of fork lookups served from the cache",
+                fork.cache_hits.get(),
+            );
+            encoder.counter(
+                "anvil_fork_cache_misses_total",
+                "Number of fork lookups that weren't cached",
+                fork.cache_misses.get(),
+            );
+            encoder.counter(
+                "anvil_fork_remote_calls_total",
+                "Number of requests sent to the forked client",
+                fork.remote_calls.get(),
+            );
+        }
+
+        encoder.0
+    }
+}
+
+/// Writes metrics in the Prometheus text exposition format
+#[derive(Debug, Default)]
+struct MetricsEncoder(String);
+
+impl MetricsEncoder {
+    fn header(&mut self, name: &str, help: &str, kind: &str) {
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            let labels = labels
+                .iter()
+                .map(|(key, value)| format!("{key}=\"{}\"", escape_label(value)))
+                .collect::<Vec<_>>();
+            let _ = write!(self.0, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.0, " {value}");
+    }
+
+    fn counter(&mut self, name: &str, help: &str, value: u64) {
+        self.header(name, help, "counter");
+        self.sample(name, &[], value);
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, value: u64) {
+        self.header(name, help, "gauge");
+        self.sample(name, &[], value);
+    }
+
+    fn histogram(&mut self, name: &str, labels: &[(&str, &str)], histogram: &Histogram) {
+        let bucket = format!("{name}_bucket");
+        let mut cumulative = 0;
+        for (le, count) in DURATION_BUCKETS.iter().zip(histogram.buckets) {
+            cumulative += count;
+            let le = le.to_string();
+            self.sample(&bucket, &[labels, &[("le", le.as_str())]].concat(), cumulative);
+        }
+        self.sample(&bucket, &[labels, &[("le", "+Inf")]].concat(), histogram.count);
+        self.sample(&format!("{name}_sum"), labels, histogram.sum);
+        self.sample(&format!("{name}_count"), labels, histogram.count);
+    }
+}
+
+/// Escapes a label value as required by the text format
+fn escape_label(value: &str) -> String {
+    value.replace('\\', r"\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_histogram() {
+        let mut histogram = Histogram::default();
+        histogram.observe(Duration::from_micros(200));
+        histogram.observe(Duration::from_millis(20));
+        histogram.observe(Duration::from_secs(5));
+
+        let mut encoder = MetricsEncoder::default();
+        encoder.histogram("duration", &[("method", "eth_call")], &histogram);
+        let lines = encoder.0.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "duration_bucket{method=\"eth_call\",le=\"0.0005\"} 1");
+        assert_eq!(lines[5], "duration_bucket{method=\"eth_call\",le=\"0.025\"} 2");
+        assert_eq!(lines[11], "duration_bucket{method=\"eth_call\",le=\"2.5\"} 2");
+        assert_eq!(lines[12], "duration_bucket{method=\"eth_call\",le=\"+Inf\"} 3");
+        assert_eq!(lines[13], "duration_sum{method=\"eth_call\"} 5.0202");
+        assert_eq!(lines[14], "duration_count{method=\"eth_call\"} 3");
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        let mut encoder = MetricsEncoder::default();
+        encoder.sample("calls", &[("method", "a\"b\\c")], 1);
+        assert_eq!(encoder.0, "calls{method=\"a\\\"b\\\\c\"} 1\n");
+    }
+}
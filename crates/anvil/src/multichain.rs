@@ -0,0 +1,332 @@
+//! Several chains served from one process.
+//!
+//! Every chain of a [MultiChain] is a separate node, forked or fresh, that's identified by its
+//! chain id. Besides its own port, every chain is served at `/<chain id>` of the
+//! [MultiChain::router]. The root of the router serves a control endpoint that applies time and
+//! mining calls to all chains together, so that all chains share a consistent clock, e.g. for
+//! testing relayers of cross-chain bridges.
+
+use crate::{
+    config::ForkChoice,
+    eth::{error::ToRpcResponseResult, EthApi},
+    server, NodeConfig, NodeHandle,
+};
+use alloy_rpc_types::anvil::MineOptions;
+use anvil_core::eth::{EthRequest, Params};
+use anvil_rpc::{error::RpcError, response::ResponseResult};
+use anvil_server::{RpcHandler, ServerConfig};
+use axum::Router;
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io, path::Path, sync::Arc};
+use tokio::net::TcpListener;
+
+/// Spawns a node for every config and serves them as one [MultiChain].
+///
+/// # Panics
+///
+/// Panics if any node fails to spawn. For a non-panicking version, use [`try_spawn_chains`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use anvil::NodeConfig;
+/// # async fn spawn() -> eyre::Result<()> {
+/// let chains = anvil::spawn_chains(vec![
+///     NodeConfig::default().with_chain_id(Some(1u64)),
+///     NodeConfig::default().with_chain_id(Some(10u64)),
+/// ])
+/// .await;
+///
+/// // serves chain 1 at `/1` and chain 10 at `/10`
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:8545").await?;
+/// chains.serve_on(listener, Default::default()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn spawn_chains(configs: impl IntoIterator<Item = NodeConfig>) -> MultiChain {
+    try_spawn_chains(configs).await.expect("failed to spawn chains")
+}
+
+/// Spawns a node for every config and serves them as one [MultiChain].
+///
+/// Fails if several nodes have the same chain id. The clocks of all chains are moved to the latest
+/// timestamp of any chain, see [MultiChain::sync_clocks].
+pub async fn try_spawn_chains(configs: impl IntoIterator<Item = NodeConfig>) -> Result<MultiChain> {
+    let mut apis = BTreeMap::new();
+    let mut handles = BTreeMap::new();
+    for config in configs {
+        let (api, handle) = crate::try_spawn(config).await?;
+        let chain_id = api.chain_id();
+        if apis.insert(chain_id, api).is_some() {
+            eyre::bail!("multiple chains have the chain id {chain_id}");
+        }
+        handles.insert(chain_id, handle);
+    }
+
+    let chains = MultiChain { apis: Arc::new(apis), handles };
+    chains.sync_clocks();
+    Ok(chains)
+}
+
+/// A set of chains that share a clock.
+///
+/// Chains are identified by their chain id.
+pub struct MultiChain {
+    apis: Arc<BTreeMap<u64, EthApi>>,
+    handles: BTreeMap<u64, NodeHandle>,
+}
+
+impl MultiChain {
+    /// Returns the number of chains
+    pub fn len(&self) -> usize {
+        self.apis.len()
+    }
+
+    /// Returns true if there are no chains
+    pub fn is_empty(&self) -> bool {
+        self.apis.is_empty()
+    }
+
+    /// Returns the chain ids of all chains, in ascending order
+    pub fn chain_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.apis.keys().copied()
+    }
+
+    /// Returns the [EthApi] of the chain with the given id, if any
+    pub fn api(&self, chain_id: u64) -> Option<&EthApi> {
+        self.apis.get(&chain_id)
+    }
+
+    /// Returns the [NodeHandle] of the chain with the given id, if any
+    pub fn handle(&self, chain_id: u64) -> Option<&NodeHandle> {
+        self.handles.get(&chain_id)
+    }
+
+    /// Moves the clocks of all chains to the latest current timestamp of any chain, and returns it
+    ///
+    /// Chains only move forward in time, so this doesn't break chains whose blocks are newer than
+    /// the time of other chains, like forks of different chains.
+    pub fn sync_clocks(&self) -> u64 {
+        let timestamp = self
+            .apis
+            .values()
+            .map(|api| api.backend.time().current_call_timestamp())
+            .max()
+            .unwrap_or_default();
+        for api in self.apis.values() {
+            api.backend.time().set_current_timestamp(timestamp);
+        }
+        timestamp
+    }
+
+    /// Executes the time or mining request on all chains, see [ControlRpcHandler]
+    pub async fn execute(&self, request: EthRequest) -> ResponseResult {
+        execute_on_all(&self.apis, request).await
+    }
+
+    /// Configures an [`axum::Router`] that serves every chain at `/<chain id>`, and the control
+    /// endpoint of all chains at `/`.
+    pub fn router(&self, config: ServerConfig) -> Router {
+        let control = ControlRpcHandler { apis: self.apis.clone() };
+        self.apis.iter().fold(
+            anvil_server::http_router(config.clone(), control),
+            |router, (chain_id, api)| {
+                router.nest(&format!("/{chain_id}"), server::router(api.clone(), config.clone()))
+            },
+        )
+    }
+
+    /// Serves the [Self::router] on the given listener.
+    pub async fn serve_on(
+        &self,
+        tcp_listener: TcpListener,
+        config: ServerConfig,
+    ) -> io::Result<()> {
+        axum::serve(tcp_listener, self.router(config).into_make_service()).await
+    }
+}
+
+impl std::fmt::Debug for MultiChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultiChain").field("chains", &self.apis.keys()).finish_non_exhaustive()
+    }
+}
+
+/// A `RpcHandler` that executes time and mining calls on all chains of a [MultiChain].
+///
+/// Supported are `evm_increaseTime`, `evm_setTime`, `evm_setNextBlockTimestamp`, `evm_mine` and
+/// `anvil_mine`, and their aliases. The result is an object with the result of every chain, keyed
+/// by its chain id.
+///
+/// A call is either applied to all chains or to none: explicit timestamps of the next block, which
+/// fail on chains whose latest block is newer, are checked on all chains first. If the call fails,
+/// the error of the first failing chain is returned.
+#[derive(Clone)]
+pub struct ControlRpcHandler {
+    apis: Arc<BTreeMap<u64, EthApi>>,
+}
+
+#[async_trait::async_trait]
+impl RpcHandler for ControlRpcHandler {
+    type Request = EthRequest;
+
+    async fn on_request(&self, request: Self::Request) -> ResponseResult {
+        execute_on_all(&self.apis, request).await
+    }
+}
+
+async fn execute_on_all(apis: &BTreeMap<u64, EthApi>, request: EthRequest) -> ResponseResult {
+    match request {
+        EthRequest::EvmIncreaseTime(_) |
+        EthRequest::EvmSetTime(_) |
+        EthRequest::EvmSetNextBlockTimeStamp(_) |
+        EthRequest::EvmMine(_) |
+        EthRequest::Mine(..) => {}
+        _ => return RpcError::method_not_found().into(),
+    }
+
+    // check the timestamp on all chains before any chain applies the call
+    if let Some(timestamp) = next_block_timestamp(&request) {
+        let checked = apis
+            .values()
+            .try_for_each(|api| api.backend.time().check_next_block_timestamp(timestamp));
+        if checked.is_err() {
+            return checked.to_rpc_result();
+        }
+    }
+
+    let mut results = serde_json::Map::new();
+    for (chain_id, api) in apis {
+        match api.execute(request.clone()).await {
+            ResponseResult::Success(result) => {
+                results.insert(chain_id.to_string(), result);
+            }
+            err => return err,
+        }
+    }
+    ResponseResult::Success(results.into())
+}
+
+/// Returns the timestamp the request sets for the next block, if any
+fn next_block_timestamp(request: &EthRequest) -> Option<u64> {
+    match request {
+        EthRequest::EvmSetNextBlockTimeStamp(timestamp) => (*timestamp).try_into().ok(),
+        EthRequest::EvmMine(Some(Params {
+            params: Some(MineOptions::Timestamp(timestamp) | MineOptions::Options { timestamp, .. }),
+        })) => *timestamp,
+        _ => None,
+    }
+}
+
+/// The chains to serve, loaded from a JSON file
+///
+/// ```json
+/// {
+///   "chains": [
+///     { "chainId": 1, "forkUrl": "https://eth.llamarpc.com", "forkBlockNumber": 21000000 },
+///     { "chainId": 10, "forkUrl": "https://mainnet.optimism.io", "port": 8546 },
+///     { "chainId": 31337 }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainsConfig {
+    pub chains: Vec<ChainConfig>,
+}
+
+/// A chain of a [ChainsConfig]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ChainConfig {
+    /// The chain id, which identifies the chain
+    pub chain_id: u64,
+    /// The endpoint to fork from, a fresh chain is created if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_url: Option<String>,
+    /// The block to fork from, the latest block if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_block_number: Option<u64>,
+    /// The port to additionally serve the chain on, a random port if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+}
+
+impl ChainConfig {
+    /// Returns the config of the chain's node, based on the given config
+    pub fn node_config(&self, config: NodeConfig) -> NodeConfig {
+        config
+            .with_chain_id(Some(self.chain_id))
+            .with_eth_rpc_url(self.fork_url.clone())
+            .with_fork_choice(self.fork_block_number.map(ForkChoice::Block))
+            .with_port(self.port.unwrap_or_default())
+            .with_ipc(None)
+    }
+}
+
+impl ChainsConfig {
+    /// Loads the chains from the JSON file at the given path
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let config: Self = foundry_common::fs::read_json_file(path)?;
+        config.validate().wrap_err_with(|| format!("invalid chains {}", path.display()))?;
+        Ok(config)
+    }
+
+    pub(crate) fn parse(path: &str) -> Result<Self, String> {
+        Self::load(path).map_err(|err| format!("{err:#}"))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.chains.is_empty() {
+            eyre::bail!("no chains are configured");
+        }
+        let mut chain_ids = std::collections::HashSet::new();
+        for chain in &self.chains {
+            if !chain_ids.insert(chain.chain_id) {
+                eyre::bail!("multiple chains have the chain id {}", chain.chain_id);
+            }
+            if chain.fork_block_number.is_some() && chain.fork_url.is_none() {
+                eyre::bail!("chain {} has a fork block number but no fork url", chain.chain_id);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_chains() {
+        let config: ChainsConfig = serde_json::from_str(
+            r#"{
+                "chains": [
+                    { "chainId": 1, "forkUrl": "http://localhost:8545", "forkBlockNumber": 100 },
+                    { "chainId": 10, "port": 8546 }
+                ]
+            }"#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.chains[0].fork_block_number, Some(100));
+        assert_eq!(config.chains[1].port, Some(8546));
+
+        let node = config.chains[0].node_config(NodeConfig::test());
+        assert_eq!(node.chain_id, Some(1));
+        assert_eq!(node.eth_rpc_url.as_deref(), Some("http://localhost:8545"));
+    }
+
+    #[test]
+    fn rejects_invalid_chains() {
+        let chain =
+            ChainConfig { chain_id: 1, fork_url: None, fork_block_number: None, port: None };
+        assert!(ChainsConfig::default().validate().is_err());
+        assert!(ChainsConfig { chains: vec![chain.clone(), chain.clone()] }.validate().is_err());
+
+        let chain = ChainConfig { fork_block_number: Some(1), ..chain };
+        assert!(ChainsConfig { chains: vec![chain] }.validate().is_err());
+    }
+}
@@ -0,0 +1,403 @@
+//! A local network of anvil nodes that gossip transactions and blocks.
+//!
+//! Every node of a [NodeNetwork] forwards its ready transactions and new blocks to all peers it
+//! is connected to. Blocks of peers are re-executed and imported, and nodes follow the longest
+//! chain, so competing chains that are built while the network is partitioned result in actual
+//! reorgs once it is healed.
+//!
+//! Besides its own port, every node is served at `/<index>` of the [NodeNetwork::router]. The root
+//! of the router serves a control endpoint that partitions and heals the network, see
+//! [NetworkRpcHandler].
+
+use crate::{eth::EthApi, server, NodeConfig, NodeHandle};
+use alloy_primitives::B256;
+use anvil_core::eth::{
+    serde_helpers::{empty_params, sequence},
+    transaction::PendingTransaction,
+};
+use anvil_rpc::{error::RpcError, response::ResponseResult};
+use anvil_server::{RpcHandler, ServerConfig};
+use axum::Router;
+use futures::StreamExt;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::{
+    cmp::Ordering,
+    io,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+/// Spawns a node for every config and connects all of them.
+///
+/// # Panics
+///
+/// Panics if any node fails to spawn. For a non-panicking version, use [`try_spawn_network`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use anvil::NodeConfig;
+/// # async fn spawn() -> eyre::Result<()> {
+/// let network = anvil::spawn_network(vec![
+///     NodeConfig::default().with_port(8545),
+///     NodeConfig::default().with_port(8546),
+/// ])
+/// .await;
+///
+/// // build competing chains
+/// network.partition(&[&[0], &[1]]);
+/// network.api(0).mine_one().await;
+/// network.api(1).mine_one().await;
+/// network.api(1).mine_one().await;
+///
+/// // node 0 reorgs to the longer chain of node 1
+/// network.heal().await;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn spawn_network(configs: impl IntoIterator<Item = NodeConfig>) -> NodeNetwork {
+    try_spawn_network(configs).await.expect("failed to spawn network")
+}
+
+/// Spawns a node for every config and connects all of them.
+///
+/// Configs without a genesis timestamp use the same one, so all nodes start with the same genesis
+/// block.
+pub async fn try_spawn_network(
+    configs: impl IntoIterator<Item = NodeConfig>,
+) -> eyre::Result<NodeNetwork> {
+    let genesis_timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    let mut apis = Vec::new();
+    let mut handles = Vec::new();
+    for mut config in configs {
+        config.genesis_timestamp.get_or_insert(genesis_timestamp);
+        let (api, handle) = crate::try_spawn(config).await?;
+        apis.push(api);
+        handles.push(handle);
+    }
+
+    let peers = Arc::new(Peers {
+        partitions: RwLock::new(vec![0; apis.len()]),
+        import_locks: apis.iter().map(|_| Default::default()).collect(),
+        apis,
+    });
+    let tasks = (0..handles.len()).map(|node| tokio::spawn(gossip(peers.clone(), node))).collect();
+
+    Ok(NodeNetwork { peers, handles, tasks })
+}
+
+/// A set of nodes that are connected to each other.
+///
+/// Nodes are identified by their index in the order they were spawned.
+pub struct NodeNetwork {
+    peers: Arc<Peers>,
+    handles: Vec<NodeHandle>,
+    /// The gossip tasks of all nodes
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl NodeNetwork {
+    /// Returns the number of nodes
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Returns true if the network has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Returns the [EthApi] of the given node
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no node with this index
+    pub fn api(&self, node: usize) -> &EthApi {
+        &self.peers.apis[node]
+    }
+
+    /// Returns the [NodeHandle] of the given node
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no node with this index
+    pub fn handle(&self, node: usize) -> &NodeHandle {
+        &self.handles[node]
+    }
+
+    /// Returns true if the two nodes can reach each other
+    pub fn is_connected(&self, a: usize, b: usize) -> bool {
+        self.peers.is_connected(a, b)
+    }
+
+    /// Splits the network into the given groups.
+    ///
+    /// Nodes only gossip with nodes of their own group, nodes that aren't part of any group are
+    /// isolated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a group contains a node that doesn't exist
+    pub fn partition(&self, groups: &[&[usize]]) {
+        self.peers.partition(groups)
+    }
+
+    /// Reconnects all nodes and syncs their chains, see [Self::sync].
+    pub async fn heal(&self) {
+        self.peers.heal().await
+    }
+
+    /// Announces the best block of every node to all of its peers.
+    ///
+    /// Once this returns, all connected nodes follow the same chain.
+    pub async fn sync(&self) {
+        self.peers.sync().await
+    }
+
+    /// Configures an [`axum::Router`] that serves every node at `/<index>`, and the control
+    /// endpoint of the network at `/`.
+    pub fn router(&self, config: ServerConfig) -> Router {
+        let control = NetworkRpcHandler { peers: self.peers.clone() };
+        self.peers.apis.iter().enumerate().fold(
+            anvil_server::http_router(config.clone(), control),
+            |router, (node, api)| {
+                router.nest(&format!("/{node}"), server::router(api.clone(), config.clone()))
+            },
+        )
+    }
+
+    /// Serves the [Self::router] on the given listener.
+    pub async fn serve_on(
+        &self,
+        tcp_listener: TcpListener,
+        config: ServerConfig,
+    ) -> io::Result<()> {
+        axum::serve(tcp_listener, self.router(config).into_make_service()).await
+    }
+}
+
+impl Drop for NodeNetwork {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl std::fmt::Debug for NodeNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeNetwork")
+            .field("nodes", &self.len())
+            .field("partitions", &*self.peers.partitions.read())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The nodes of a network and how they are connected
+struct Peers {
+    apis: Vec<EthApi>,
+    /// The partition each node belongs to
+    partitions: RwLock<Vec<usize>>,
+    /// Serializes the chain updates of each node
+    import_locks: Vec<tokio::sync::Mutex<()>>,
+}
+
+impl Peers {
+    /// See [NodeNetwork::partition]
+    fn partition(&self, groups: &[&[usize]]) {
+        let mut partitions = self.partitions.write();
+        for (node, partition) in partitions.iter_mut().enumerate() {
+            // isolated nodes get a group of their own
+            *partition = groups.len() + node;
+        }
+        for (group, nodes) in groups.iter().enumerate() {
+            for node in nodes.iter() {
+                partitions[*node] = group;
+            }
+        }
+    }
+
+    /// See [NodeNetwork::heal]
+    async fn heal(&self) {
+        self.partitions.write().fill(0);
+        self.sync().await;
+    }
+
+    /// See [NodeNetwork::sync]
+    async fn sync(&self) {
+        for source in 0..self.apis.len() {
+            let head = self.apis[source].backend.best_hash();
+            for target in self.connected_peers(source) {
+                self.sync_chain(source, target, head).await;
+            }
+        }
+    }
+
+    fn is_connected(&self, a: usize, b: usize) -> bool {
+        let partitions = self.partitions.read();
+        partitions[a] == partitions[b]
+    }
+
+    /// Returns all other nodes the given node is connected to
+    fn connected_peers(&self, node: usize) -> Vec<usize> {
+        (0..self.apis.len())
+            .filter(|peer| *peer != node && self.is_connected(node, *peer))
+            .collect()
+    }
+
+    /// Forwards a transaction to all peers of the node
+    async fn broadcast_transaction(&self, source: usize, transaction: PendingTransaction) {
+        for target in self.connected_peers(source) {
+            if let Err(err) = self.apis[target].add_peer_transaction(transaction.clone()).await {
+                trace!(target: "network", node = target, ?err, "rejected transaction");
+            }
+        }
+    }
+
+    /// Makes `target` follow the chain of `source` that ends in `head`, if it's better than the
+    /// chain `target` currently follows.
+    ///
+    /// The longest chain wins, ties are broken by the lower block hash.
+    async fn sync_chain(&self, source: usize, target: usize, head: B256) {
+        let _lock = self.import_locks[target].lock().await;
+        let source_api = &self.apis[source];
+        let target_api = &self.apis[target];
+
+        // collect all blocks target doesn't have yet, newest first
+        let mut blocks = Vec::new();
+        let mut hash = head;
+        let ancestor = loop {
+            if let Some(block) = target_api.canonical_block(hash) {
+                break block;
+            }
+            let Some(block) = source_api.backend.get_block_by_hash(hash) else {
+                debug!(target: "network", node = target, ?hash, "no common ancestor");
+                return;
+            };
+            hash = block.header.parent_hash;
+            blocks.push(block);
+        };
+        let Some(new_head) = blocks.first() else { return };
+
+        let best_number = target_api.backend.best_number();
+        let best_hash = target_api.backend.best_hash();
+        let is_better = match new_head.header.number.cmp(&best_number) {
+            Ordering::Greater => true,
+            Ordering::Equal => head < best_hash,
+            Ordering::Less => false,
+        };
+        if !is_better {
+            return;
+        }
+
+        // transactions of the blocks that are replaced
+        let mut dropped = Vec::new();
+        if ancestor.header.number < best_number {
+            for number in ancestor.header.number + 1..=best_number {
+                if let Some(block) = target_api.backend.get_block(number) {
+                    dropped.extend(block.transactions);
+                }
+            }
+            debug!(
+                target: "network",
+                node = target,
+                from = best_number,
+                to = ancestor.header.number,
+                "reorg"
+            );
+            if let Err(err) = target_api.backend.rewind_to(ancestor).await {
+                warn!(target: "network", node = target, %err, "failed to rewind");
+                return;
+            }
+        }
+
+        for block in blocks.iter().rev() {
+            if let Err(err) = target_api.import_block(block).await {
+                warn!(
+                    target: "network",
+                    node = target,
+                    number = block.header.number,
+                    %err,
+                    "failed to import block"
+                );
+                break;
+            }
+        }
+
+        // like a real node, return the transactions that are no longer included to the pool
+        for transaction in dropped {
+            if let Ok(transaction) = PendingTransaction::from_mined(transaction) {
+                let _ = target_api.add_peer_transaction(transaction).await;
+            }
+        }
+    }
+}
+
+/// The calls of the control endpoint of a [NodeNetwork]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "method", content = "params")]
+pub enum NetworkRequest {
+    /// Splits the network into groups of node indices, see [NodeNetwork::partition]
+    #[serde(rename = "anvil_partitionNetwork", with = "sequence")]
+    Partition(Vec<Vec<usize>>),
+    /// Reconnects all nodes and syncs their chains, see [NodeNetwork::heal]
+    #[serde(rename = "anvil_healNetwork", with = "empty_params")]
+    Heal(()),
+}
+
+/// A `RpcHandler` that partitions and heals a [NodeNetwork].
+///
+/// Supported are `anvil_partitionNetwork`, which takes the groups of node indices, e.g.
+/// `[[0], [1, 2]]`, and `anvil_healNetwork`.
+#[derive(Clone)]
+pub struct NetworkRpcHandler {
+    peers: Arc<Peers>,
+}
+
+#[async_trait::async_trait]
+impl RpcHandler for NetworkRpcHandler {
+    type Request = NetworkRequest;
+
+    async fn on_request(&self, request: Self::Request) -> ResponseResult {
+        match request {
+            NetworkRequest::Partition(groups) => {
+                let nodes = self.peers.apis.len();
+                if let Some(node) = groups.iter().flatten().find(|node| **node >= nodes) {
+                    return RpcError::invalid_params(format!(
+                        "node {node} does not exist, the network has {nodes} nodes"
+                    ))
+                    .into();
+                }
+                let groups = groups.iter().map(Vec::as_slice).collect::<Vec<_>>();
+                self.peers.partition(&groups);
+            }
+            NetworkRequest::Heal(()) => self.peers.heal().await,
+        }
+        ResponseResult::success(())
+    }
+}
+
+/// Forwards the ready transactions and new blocks of the node to its peers
+async fn gossip(peers: Arc<Peers>, node: usize) {
+    let api = &peers.apis[node];
+    let mut blocks = api.new_block_notifications();
+    let mut transactions = api.new_ready_transactions();
+    loop {
+        tokio::select! {
+            Some(notification) = blocks.next() => {
+                for target in peers.connected_peers(node) {
+                    peers.sync_chain(node, target, notification.hash).await;
+                }
+            }
+            Some(hash) = transactions.next() => {
+                if let Some(transaction) = api.pool_transaction(hash) {
+                    peers.broadcast_transaction(node, transaction).await;
+                }
+            }
+            else => break,
+        }
+    }
+}
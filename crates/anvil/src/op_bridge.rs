@@ -0,0 +1,297 @@
+//! Emulation of the bridge between an L1 chain and an OP stack L2 chain.
+//!
+//! An [OpBridge] pairs an L1 node with an L2 node that runs in optimism mode. Like the op-node
+//! derives deposits from L1 blocks, every `TransactionDeposited` event that the `OptimismPortal`
+//! emits on L1 is turned into a deposit transaction and added to the pool of the L2 node.
+//! Withdrawals are initiated on L2 via the `L2ToL1MessagePasser` predeploy, and
+//! `anvil_getWithdrawalProofs` of the L2 node returns everything that's needed to prove them on L1
+//! with `OptimismPortal.proveWithdrawalTransaction`.
+//!
+//! Only the relaying of messages is emulated: the contracts of the bridge must be deployed by the
+//! user, e.g. the message passer via the predeploys of `--chain-overrides`, and the output roots of
+//! L2 blocks must be made known to the portal by the test. Reorgs of L1 aren't followed.
+
+use crate::{eth::EthApi, multichain::ChainConfig, NodeConfig, NodeHandle};
+use alloy_primitives::{address, keccak256, Address, Bytes, TxKind, B256, U256};
+use alloy_rpc_types::{Filter, Log};
+use alloy_sol_types::{sol, SolEvent};
+use anvil_core::eth::transaction::optimism::DepositTransaction;
+use eyre::{Result, WrapErr};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::task::JoinHandle;
+
+/// The address of the `L2ToL1MessagePasser` predeploy
+pub const L2_TO_L1_MESSAGE_PASSER: Address = address!("4200000000000000000000000000000000000016");
+
+sol! {
+    /// Emitted by the `OptimismPortal` on L1 for every deposit
+    event TransactionDeposited(
+        address indexed from,
+        address indexed to,
+        uint256 indexed version,
+        bytes opaqueData
+    );
+
+    /// Emitted by the `L2ToL1MessagePasser` on L2 for every withdrawal
+    event MessagePassed(
+        uint256 indexed nonce,
+        address indexed sender,
+        address indexed target,
+        uint256 value,
+        uint256 gasLimit,
+        bytes data,
+        bytes32 withdrawalHash
+    );
+}
+
+/// Spawns an L1 and an L2 node and relays the deposits of the portal on L1 to L2.
+///
+/// # Panics
+///
+/// Panics if any node fails to spawn. For a non-panicking version, use [`try_spawn_op_bridge`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use anvil::NodeConfig;
+/// # async fn spawn() -> eyre::Result<()> {
+/// let portal = "0xbEb5Fc579115071764c7423A4f12eDde41f106Ed".parse()?;
+/// let bridge = anvil::spawn_op_bridge(
+///     NodeConfig::default().with_chain_id(Some(1u64)).with_port(8545),
+///     NodeConfig::default().with_chain_id(Some(10u64)).with_port(9545),
+///     portal,
+/// )
+/// .await;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn spawn_op_bridge(l1: NodeConfig, l2: NodeConfig, portal: Address) -> OpBridge {
+    try_spawn_op_bridge(l1, l2, portal).await.expect("failed to spawn bridge")
+}
+
+/// Spawns an L1 and an L2 node and relays the deposits of the portal on L1 to L2.
+///
+/// The L2 node always runs in optimism mode.
+pub async fn try_spawn_op_bridge(
+    l1: NodeConfig,
+    l2: NodeConfig,
+    portal: Address,
+) -> Result<OpBridge> {
+    let (l1_api, l1_handle) = crate::try_spawn(l1).await?;
+    let (l2_api, l2_handle) = crate::try_spawn(l2.with_optimism(true)).await?;
+    if l1_api.chain_id() == l2_api.chain_id() {
+        eyre::bail!("L1 and L2 have the same chain id {}", l1_api.chain_id());
+    }
+
+    let relay = tokio::spawn(relay_deposits(l1_api.clone(), l2_api.clone(), portal));
+    Ok(OpBridge { portal, l1: (l1_api, l1_handle), l2: (l2_api, l2_handle), relay })
+}
+
+/// An L1 and an L2 node that are connected by the bridge of an OP stack chain.
+pub struct OpBridge {
+    portal: Address,
+    l1: (EthApi, NodeHandle),
+    l2: (EthApi, NodeHandle),
+    /// Relays the deposits of L1 blocks to L2
+    relay: JoinHandle<()>,
+}
+
+impl OpBridge {
+    /// Returns the address of the `OptimismPortal` on L1
+    pub fn portal(&self) -> Address {
+        self.portal
+    }
+
+    /// Returns the [EthApi] of the L1 node
+    pub fn l1_api(&self) -> &EthApi {
+        &self.l1.0
+    }
+
+    /// Returns the [NodeHandle] of the L1 node
+    pub fn l1_handle(&self) -> &NodeHandle {
+        &self.l1.1
+    }
+
+    /// Returns the [EthApi] of the L2 node
+    pub fn l2_api(&self) -> &EthApi {
+        &self.l2.0
+    }
+
+    /// Returns the [NodeHandle] of the L2 node
+    pub fn l2_handle(&self) -> &NodeHandle {
+        &self.l2.1
+    }
+}
+
+impl Drop for OpBridge {
+    fn drop(&mut self) {
+        self.relay.abort();
+    }
+}
+
+impl std::fmt::Debug for OpBridge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpBridge")
+            .field("portal", &self.portal)
+            .field("l1", &self.l1.0.chain_id())
+            .field("l2", &self.l2.0.chain_id())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Adds a deposit transaction to L2 for every deposit of the portal in a new L1 block
+async fn relay_deposits(l1: EthApi, l2: EthApi, portal: Address) {
+    let mut blocks = l1.new_block_notifications();
+    while let Some(notification) = blocks.next().await {
+        let filter = Filter::new()
+            .address(portal)
+            .event_signature(TransactionDeposited::SIGNATURE_HASH)
+            .at_block_hash(notification.hash);
+        let logs = match l1.logs(filter).await {
+            Ok(logs) => logs,
+            Err(err) => {
+                warn!(target: "op_bridge", hash = ?notification.hash, %err, "failed to get deposits");
+                continue
+            }
+        };
+        for log in logs {
+            let deposit = match deposit_transaction(&log) {
+                Ok(deposit) => deposit,
+                Err(err) => {
+                    warn!(target: "op_bridge", tx = ?log.transaction_hash, %err, "invalid deposit");
+                    continue
+                }
+            };
+            match l2.send_deposit_transaction(deposit).await {
+                Ok(hash) => trace!(target: "op_bridge", ?hash, "relayed deposit"),
+                Err(err) => warn!(target: "op_bridge", %err, "failed to relay deposit"),
+            }
+        }
+    }
+}
+
+/// Returns the L2 deposit transaction of a `TransactionDeposited` event of the portal
+pub fn deposit_transaction(log: &Log) -> Result<DepositTransaction> {
+    let event = TransactionDeposited::decode_log_data(log.data(), true)?;
+    if event.version != U256::ZERO {
+        eyre::bail!("unsupported deposit version {}", event.version);
+    }
+    let (Some(block_hash), Some(log_index)) = (log.block_hash, log.log_index) else {
+        eyre::bail!("deposit is not mined");
+    };
+
+    // packed `mint`, `value`, `gasLimit`, `isCreation` and `data`
+    let data = &event.opaqueData;
+    if data.len() < 73 {
+        eyre::bail!("deposit data is too short");
+    }
+    let gas_limit = u64::from_be_bytes(data[64..72].try_into().expect("8 bytes; qed"));
+    let kind = if data[72] == 0 { TxKind::Call(event.to) } else { TxKind::Create };
+
+    Ok(DepositTransaction {
+        nonce: 0,
+        source_hash: user_deposit_source_hash(block_hash, log_index),
+        from: event.from,
+        kind,
+        mint: U256::from_be_slice(&data[..32]),
+        value: U256::from_be_slice(&data[32..64]),
+        gas_limit,
+        is_system_tx: false,
+        input: Bytes::copy_from_slice(&data[73..]),
+    })
+}
+
+/// Returns the source hash of a user deposit, which makes the hashes of deposits unique
+///
+/// See <https://specs.optimism.io/protocol/deposits.html#source-hash-computation>
+pub fn user_deposit_source_hash(l1_block_hash: B256, log_index: u64) -> B256 {
+    let deposit_id =
+        keccak256([l1_block_hash.as_slice(), &U256::from(log_index).to_be_bytes::<32>()].concat());
+    keccak256([B256::ZERO.as_slice(), deposit_id.as_slice()].concat())
+}
+
+/// Returns the slot of the withdrawal in the `sentMessages` mapping of the `L2ToL1MessagePasser`
+pub fn withdrawal_slot(withdrawal_hash: B256) -> B256 {
+    keccak256([withdrawal_hash.as_slice(), B256::ZERO.as_slice()].concat())
+}
+
+/// The nodes and the portal of an [OpBridge], loaded from a JSON file
+///
+/// ```json
+/// {
+///   "portal": "0xbEb5Fc579115071764c7423A4f12eDde41f106Ed",
+///   "l1": { "chainId": 1, "port": 8545 },
+///   "l2": { "chainId": 10, "port": 9545 }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpBridgeConfig {
+    /// The address of the `OptimismPortal` on L1
+    pub portal: Address,
+    pub l1: ChainConfig,
+    pub l2: ChainConfig,
+}
+
+impl OpBridgeConfig {
+    /// Loads the bridge from the JSON file at the given path
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let config: Self = foundry_common::fs::read_json_file(path)?;
+        config.validate().wrap_err_with(|| format!("invalid bridge {}", path.display()))?;
+        Ok(config)
+    }
+
+    pub(crate) fn parse(path: &str) -> Result<Self, String> {
+        Self::load(path).map_err(|err| format!("{err:#}"))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.l1.chain_id == self.l2.chain_id {
+            eyre::bail!("L1 and L2 have the same chain id {}", self.l1.chain_id);
+        }
+        for chain in [&self.l1, &self.l2] {
+            if chain.fork_block_number.is_some() && chain.fork_url.is_none() {
+                eyre::bail!("chain {} has a fork block number but no fork url", chain.chain_id);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_decode_deposit() {
+        let from = Address::repeat_byte(1);
+        let to = Address::repeat_byte(2);
+        let mut opaque_data = Vec::new();
+        opaque_data.extend_from_slice(&U256::from(100).to_be_bytes::<32>());
+        opaque_data.extend_from_slice(&U256::from(10).to_be_bytes::<32>());
+        opaque_data.extend_from_slice(&100_000u64.to_be_bytes());
+        opaque_data.push(0);
+        opaque_data.extend_from_slice(&[0xab, 0xcd]);
+        let event =
+            TransactionDeposited { from, to, version: U256::ZERO, opaqueData: opaque_data.into() };
+
+        let block_hash = B256::repeat_byte(3);
+        let log = Log {
+            inner: alloy_primitives::Log { address: Address::ZERO, data: event.encode_log_data() },
+            block_hash: Some(block_hash),
+            log_index: Some(1),
+            ..Default::default()
+        };
+        let deposit = deposit_transaction(&log).unwrap();
+        assert_eq!(deposit.from, from);
+        assert_eq!(deposit.kind, TxKind::Call(to));
+        assert_eq!(deposit.mint, U256::from(100));
+        assert_eq!(deposit.value, U256::from(10));
+        assert_eq!(deposit.gas_limit, 100_000);
+        assert_eq!(deposit.input, Bytes::from_static(&[0xab, 0xcd]));
+        assert_eq!(deposit.source_hash, user_deposit_source_hash(block_hash, 1));
+    }
+}
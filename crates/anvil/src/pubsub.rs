@@ -1,5 +1,8 @@
 use crate::{
-    eth::{backend::notifications::NewBlockNotifications, error::to_rpc_result},
+    eth::{
+        backend::notifications::{ChainNotification, ChainNotifications, NewBlockNotifications},
+        error::to_rpc_result,
+    },
     StorageInfo,
 };
 use alloy_primitives::{TxHash, B256};
@@ -14,10 +17,11 @@
     task::{Context, Poll},
 };
 
-/// Listens for new blocks and matching logs emitted in that block
+/// Listens for new blocks and matching logs emitted in that block, and for logs of blocks that were
+/// removed from the chain
 #[derive(Debug)]
 pub struct LogsSubscription {
-    pub blocks: NewBlockNotifications,
+    pub blocks: ChainNotifications,
     pub storage: StorageInfo,
     pub filter: FilteredParams,
     pub queued: VecDeque<Log>,
@@ -35,19 +39,15 @@ fn poll(&mut self, cx: &mut Context<'_>) -> Poll<Option<EthSubscriptionResponse>
                 return Poll::Ready(Some(EthSubscriptionResponse::new(params)));
             }
 
-            if let Some(block) = ready!(self.blocks.poll_next_unpin(cx)) {
-                let b = self.storage.block(block.hash);
-                let receipts = self.storage.receipts(block.hash);
-                if let (Some(receipts), Some(block)) = (receipts, b) {
-                    let logs = filter_logs(block, receipts, &self.filter);
-                    if logs.is_empty() {
-                        // this ensures we poll the receiver until it is pending, in which case the
-                        // underlying `UnboundedReceiver` will register the new waker, see
-                        // [`futures::channel::mpsc::UnboundedReceiver::poll_next()`]
-                        continue;
-                    }
-                    self.queued.extend(logs)
+            if let Some(notification) = ready!(self.blocks.poll_next_unpin(cx)) {
+                let logs = chain_logs(&self.storage, notification, &self.filter);
+                if logs.is_empty() {
+                    // this ensures we poll the receiver until it is pending, in which case the
+                    // underlying `UnboundedReceiver` will register the new waker, see
+                    // [`futures::channel::mpsc::UnboundedReceiver::poll_next()`]
+                    continue;
                 }
+                self.queued.extend(logs)
             } else {
                 return Poll::Ready(None);
             }
@@ -136,6 +136,31 @@ fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::It
     }
 }
 
+/// Returns all the logs of the chain change that match the given filter.
+///
+/// Logs of blocks that were removed from the chain are marked as `removed`.
+pub fn chain_logs(
+    storage: &StorageInfo,
+    notification: ChainNotification,
+    filter: &FilteredParams,
+) -> Vec<Log> {
+    match notification {
+        ChainNotification::NewBlock(block) => {
+            match (storage.block(block.hash), storage.receipts(block.hash)) {
+                (Some(block), Some(receipts)) => filter_logs(block, receipts, filter),
+                _ => vec![],
+            }
+        }
+        ChainNotification::Removed(blocks) => blocks
+            .iter()
+            .flat_map(|removed| {
+                filter_logs(removed.block.clone(), removed.receipts.clone(), filter)
+            })
+            .map(|log| Log { removed: true, ..log })
+            .collect(),
+    }
+}
+
 /// Returns all the logs that match the given filter
 pub fn filter_logs(block: Block, receipts: Vec<TypedReceipt>, filter: &FilteredParams) -> Vec<Log> {
     /// Determines whether to add this log
@@ -0,0 +1,499 @@
+//! A minimal stand-in for the beacon node HTTP API, so the blob sidecars of mined transactions
+//! can be fetched like from a consensus client.
+//!
+//! Every block gets its own slot, derived from its timestamp as
+//! `(timestamp - genesis_time) / SECONDS_PER_SLOT`. If blocks are mined faster than that, so that
+//! a block would share the slot of its parent, it's moved to the slot after the parent's. In fork
+//! mode the beacon chain starts at the forked block, whose sidecars aren't available.
+//!
+//! The beacon blocks are stubs that only exist to serve the sidecars: the body only commits to the
+//! KZG commitments of the blobs, all its other fields are zero. The body root is computed like
+//! the SSZ `hash_tree_root` of a Deneb `BeaconBlockBody`, so the `kzg_commitment_inclusion_proof`
+//! of every sidecar verifies against the `body_root` of its header, and blocks are identified by
+//! the `hash_tree_root` of their header. The `state_root` is the state root of the execution
+//! block, the proposer index is zero and the signature is empty.
+
+use crate::eth::{backend::mem::Backend, EthApi};
+use alloy_consensus::transaction::eip4844::TxEip4844Variant;
+use alloy_eips::{
+    eip4844::{Blob, Bytes48},
+    BlockNumberOrTag,
+};
+use alloy_primitives::{map::B256HashMap, FixedBytes, B256};
+use anvil_core::eth::{block::Block, transaction::TypedTransaction};
+use axum::{
+    extract::{Path, RawQuery, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Depth of the merkle tree of the `blob_kzg_commitments` list, whose limit is
+/// `MAX_BLOB_COMMITMENTS_PER_BLOCK = 4096`
+const BLOB_KZG_COMMITMENTS_DEPTH: usize = 12;
+
+/// Depth of the merkle tree of the 12 fields of a Deneb block body
+const BLOCK_BODY_DEPTH: usize = 4;
+
+/// Position of the `blob_kzg_commitments` field in the block body
+const BLOB_KZG_COMMITMENTS_INDEX: usize = 11;
+
+/// Depth of the merkle tree of the 5 fields of a block header
+const BLOCK_HEADER_DEPTH: usize = 3;
+
+/// The state shared by all handlers of the beacon API
+#[derive(Clone)]
+struct BeaconState {
+    api: EthApi,
+    index: Arc<Mutex<BlockIndex>>,
+}
+
+/// Returns the routes of the beacon API
+pub fn router(api: EthApi) -> Router {
+    Router::new()
+        .route("/eth/v1/beacon/genesis", get(genesis))
+        .route("/eth/v1/config/spec", get(spec))
+        .route("/eth/v1/beacon/headers/:block_id", get(header))
+        .route("/eth/v1/beacon/blob_sidecars/:block_id", get(blob_sidecars))
+        .with_state(BeaconState { api, index: Default::default() })
+}
+
+/// The response of all beacon API endpoints
+#[derive(Serialize)]
+struct BeaconResponse<T> {
+    data: T,
+}
+
+#[derive(Serialize)]
+struct Genesis {
+    #[serde(with = "alloy_serde::displayfromstr")]
+    genesis_time: u64,
+    genesis_validators_root: B256,
+    genesis_fork_version: FixedBytes<4>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct Spec {
+    #[serde(with = "alloy_serde::displayfromstr")]
+    seconds_per_slot: u64,
+    #[serde(with = "alloy_serde::displayfromstr")]
+    slots_per_epoch: u64,
+    #[serde(with = "alloy_serde::displayfromstr")]
+    deposit_chain_id: u64,
+    #[serde(with = "alloy_serde::displayfromstr")]
+    deposit_network_id: u64,
+}
+
+#[derive(Serialize)]
+struct BlockHeader {
+    root: B256,
+    canonical: bool,
+    header: SignedBeaconBlockHeader,
+}
+
+#[derive(Serialize)]
+struct BlobSidecar {
+    #[serde(with = "alloy_serde::displayfromstr")]
+    index: u64,
+    blob: Box<Blob>,
+    kzg_commitment: Bytes48,
+    kzg_proof: Bytes48,
+    signed_block_header: SignedBeaconBlockHeader,
+    kzg_commitment_inclusion_proof: Vec<B256>,
+}
+
+#[derive(Clone, Serialize)]
+struct SignedBeaconBlockHeader {
+    message: BeaconBlockHeader,
+    signature: FixedBytes<96>,
+}
+
+#[derive(Clone, Serialize)]
+struct BeaconBlockHeader {
+    #[serde(with = "alloy_serde::displayfromstr")]
+    slot: u64,
+    #[serde(with = "alloy_serde::displayfromstr")]
+    proposer_index: u64,
+    parent_root: B256,
+    state_root: B256,
+    body_root: B256,
+}
+
+impl BeaconBlockHeader {
+    /// Returns the SSZ `hash_tree_root` of the header
+    fn root(&self) -> B256 {
+        let leaves = [
+            uint_chunk(self.slot),
+            uint_chunk(self.proposer_index),
+            self.parent_root,
+            self.state_root,
+            self.body_root,
+        ];
+        merkle_proof(&leaves, BLOCK_HEADER_DEPTH, 0).0
+    }
+}
+
+/// An error response of the beacon API
+struct BeaconError {
+    code: StatusCode,
+    message: String,
+}
+
+impl BeaconError {
+    fn not_found(message: impl Into<String>) -> Self {
+        Self { code: StatusCode::NOT_FOUND, message: message.into() }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self { code: StatusCode::BAD_REQUEST, message: message.into() }
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self { code: StatusCode::INTERNAL_SERVER_ERROR, message: message.into() }
+    }
+}
+
+impl IntoResponse for BeaconError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({ "code": self.code.as_u16(), "message": self.message });
+        (self.code, Json(body)).into_response()
+    }
+}
+
+/// Handler for `GET /eth/v1/beacon/genesis`
+async fn genesis(
+    State(state): State<BeaconState>,
+) -> Result<Json<BeaconResponse<Genesis>>, BeaconError> {
+    let genesis_time = ChainStart::of(&state.api.backend).await?.timestamp;
+    Ok(Json(BeaconResponse {
+        data: Genesis {
+            genesis_time,
+            genesis_validators_root: B256::ZERO,
+            genesis_fork_version: Default::default(),
+        },
+    }))
+}
+
+/// Handler for `GET /eth/v1/config/spec`
+async fn spec(State(state): State<BeaconState>) -> Json<BeaconResponse<Spec>> {
+    let chain_id = state.api.chain_id();
+    Json(BeaconResponse {
+        data: Spec {
+            seconds_per_slot: state.api.backend.seconds_per_slot(),
+            slots_per_epoch: state.api.backend.slots_in_an_epoch(),
+            deposit_chain_id: chain_id,
+            deposit_network_id: chain_id,
+        },
+    })
+}
+
+/// Handler for `GET /eth/v1/beacon/headers/{block_id}`
+async fn header(
+    State(state): State<BeaconState>,
+    Path(block_id): Path<String>,
+) -> Result<Json<BeaconResponse<BlockHeader>>, BeaconError> {
+    let (_, block) = state.find_block(&block_id).await?;
+    Ok(Json(BeaconResponse {
+        data: BlockHeader {
+            root: block.root,
+            canonical: true,
+            header: SignedBeaconBlockHeader {
+                message: block.header,
+                signature: Default::default(),
+            },
+        },
+    }))
+}
+
+/// Handler for `GET /eth/v1/beacon/blob_sidecars/{block_id}`
+///
+/// The block can be identified by `head`, `genesis`, `finalized`, `justified`, a slot or the
+/// block root, the sidecars can be filtered with the `indices` query parameter.
+async fn blob_sidecars(
+    State(state): State<BeaconState>,
+    Path(block_id): Path<String>,
+    RawQuery(query): RawQuery,
+) -> Result<Json<BeaconResponse<Vec<BlobSidecar>>>, BeaconError> {
+    let indices = parse_indices(query.as_deref())?;
+    let (number, indexed) = state.find_block(&block_id).await?;
+    let block = state.api.backend.get_block(number);
+
+    let block_header =
+        SignedBeaconBlockHeader { message: indexed.header, signature: Default::default() };
+    let sidecars = block.iter().flat_map(blob_sidecars_of).collect::<Vec<_>>();
+    let commitments = sidecars.iter().map(|(_, commitment, _)| **commitment).collect::<Vec<_>>();
+    let sidecars = sidecars
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| {
+            indices.as_ref().is_none_or(|indices| indices.contains(&(*index as u64)))
+        })
+        .map(|(index, (blob, commitment, proof))| BlobSidecar {
+            index: index as u64,
+            blob: Box::new(*blob),
+            kzg_commitment: *commitment,
+            kzg_proof: *proof,
+            signed_block_header: block_header.clone(),
+            kzg_commitment_inclusion_proof: body_root_and_proof(&commitments, index).1,
+        })
+        .collect();
+
+    Ok(Json(BeaconResponse { data: sidecars }))
+}
+
+impl BeaconState {
+    /// Returns the number of the execution block and the beacon block identified by a beacon API
+    /// block id
+    async fn find_block(&self, block_id: &str) -> Result<(u64, IndexedBlock), BeaconError> {
+        let backend = &self.api.backend;
+        let start = ChainStart::of(backend).await?;
+        let mut index = self.index.lock();
+        index.update(backend, &start)?;
+
+        let number = match block_id {
+            "head" => index.blocks.len().checked_sub(1).map(|offset| index.first + offset as u64),
+            "genesis" => Some(index.first),
+            "finalized" | "justified" => {
+                let tag = if block_id == "finalized" {
+                    BlockNumberOrTag::Finalized
+                } else {
+                    BlockNumberOrTag::Safe
+                };
+                Some(backend.convert_block_number(Some(tag)).max(index.first))
+            }
+            root if root.starts_with("0x") => {
+                let root = root.parse::<B256>().map_err(|_| {
+                    BeaconError::bad_request(format!("invalid block id: {block_id}"))
+                })?;
+                index.roots.get(&root).copied()
+            }
+            slot => {
+                let slot = slot.parse::<u64>().map_err(|_| {
+                    BeaconError::bad_request(format!("invalid block id: {block_id}"))
+                })?;
+                index
+                    .blocks
+                    .binary_search_by_key(&slot, |block| block.header.slot)
+                    .ok()
+                    .map(|offset| index.first + offset as u64)
+            }
+        };
+
+        number
+            .and_then(|number| Some((number, index.get(number)?.clone())))
+            .ok_or_else(|| BeaconError::not_found(format!("block not found: {block_id}")))
+    }
+}
+
+/// The first block of the chain: the genesis block, or the forked block in fork mode
+struct ChainStart {
+    number: u64,
+    hash: B256,
+    timestamp: u64,
+    state_root: B256,
+}
+
+impl ChainStart {
+    async fn of(backend: &Backend) -> Result<Self, BeaconError> {
+        let not_found = || BeaconError::not_found("genesis block not found");
+        if let Some(fork) = backend.get_fork() {
+            // the forked block isn't stored locally
+            let block = fork
+                .block_by_number(fork.block_number())
+                .await
+                .map_err(|err| BeaconError::internal(err.to_string()))?
+                .ok_or_else(not_found)?;
+            return Ok(Self {
+                number: fork.block_number(),
+                hash: fork.block_hash(),
+                timestamp: fork.timestamp(),
+                state_root: block.header.state_root,
+            });
+        }
+
+        let block = backend.get_block(BlockNumberOrTag::Earliest).ok_or_else(not_found)?;
+        Ok(Self {
+            number: block.header.number,
+            hash: block.header.hash_slow(),
+            timestamp: block.header.timestamp,
+            state_root: block.header.state_root,
+        })
+    }
+}
+
+/// The beacon blocks of the canonical chain, one for every execution block
+#[derive(Default)]
+struct BlockIndex {
+    /// The number of the first indexed block, the start of the chain
+    first: u64,
+    /// The beacon blocks by block number, starting at `first`, their slots are strictly
+    /// increasing
+    blocks: Vec<IndexedBlock>,
+    /// Block numbers by the root of their beacon block
+    roots: B256HashMap<u64>,
+}
+
+#[derive(Clone)]
+struct IndexedBlock {
+    /// The hash of the execution block
+    hash: B256,
+    /// The root of the beacon block
+    root: B256,
+    header: BeaconBlockHeader,
+}
+
+impl BlockIndex {
+    fn get(&self, number: u64) -> Option<&IndexedBlock> {
+        self.blocks.get(usize::try_from(number.checked_sub(self.first)?).ok()?)
+    }
+
+    /// Removes the blocks that are no longer canonical and indexes new blocks
+    fn update(&mut self, backend: &Backend, start: &ChainStart) -> Result<(), BeaconError> {
+        if self.first != start.number || self.blocks.first().is_some_and(|b| b.hash != start.hash) {
+            // the chain was reset
+            *self = Self { first: start.number, ..Default::default() };
+        }
+        while self.blocks.len() > 1 {
+            let last = &self.blocks[self.blocks.len() - 1];
+            let number = self.first + self.blocks.len() as u64 - 1;
+            if backend.get_block(number).is_some_and(|block| block.header.hash_slow() == last.hash)
+            {
+                break;
+            }
+            self.roots.remove(&last.root);
+            self.blocks.pop();
+        }
+
+        for number in self.first + self.blocks.len() as u64..=backend.best_number() {
+            let (hash, timestamp, state_root, commitments) = if number == start.number {
+                (start.hash, start.timestamp, start.state_root, Vec::new())
+            } else {
+                let Some(block) = backend.get_block(number) else { break };
+                let commitments = blob_sidecars_of(&block)
+                    .map(|(_, commitment, _)| *commitment)
+                    .collect::<Vec<_>>();
+                (
+                    block.header.hash_slow(),
+                    block.header.timestamp,
+                    block.header.state_root,
+                    commitments,
+                )
+            };
+            let mut slot = timestamp.saturating_sub(start.timestamp) / backend.seconds_per_slot();
+            let mut parent_root = B256::ZERO;
+            if let Some(parent) = self.blocks.last() {
+                slot = slot.max(parent.header.slot + 1);
+                parent_root = parent.root;
+            }
+
+            let header = BeaconBlockHeader {
+                slot,
+                proposer_index: 0,
+                parent_root,
+                state_root,
+                body_root: body_root_and_proof(&commitments, 0).0,
+            };
+            let root = header.root();
+            self.roots.insert(root, number);
+            self.blocks.push(IndexedBlock { hash, root, header });
+        }
+        Ok(())
+    }
+}
+
+/// Returns the blobs, commitments and proofs of all blob transactions of the block
+fn blob_sidecars_of(block: &Block) -> impl Iterator<Item = (&Blob, &Bytes48, &Bytes48)> {
+    block
+        .transactions
+        .iter()
+        .filter_map(|tx| match &tx.transaction {
+            TypedTransaction::EIP4844(tx) => match tx.tx() {
+                TxEip4844Variant::TxEip4844WithSidecar(tx) => Some(&tx.sidecar),
+                TxEip4844Variant::TxEip4844(_) => None,
+            },
+            _ => None,
+        })
+        .flat_map(|sidecar| sidecar.blobs.iter().zip(&sidecar.commitments).zip(&sidecar.proofs))
+        .map(|((blob, commitment), proof)| (blob, commitment, proof))
+}
+
+/// Returns the root of a block body that only contains the given commitments, and the inclusion
+/// proof of the commitment at `index`
+fn body_root_and_proof(commitments: &[Bytes48], index: usize) -> (B256, Vec<B256>) {
+    let leaves = commitments
+        .iter()
+        .map(|commitment| {
+            let mut chunk = B256::ZERO;
+            chunk[..16].copy_from_slice(&commitment[32..]);
+            hash_pair(&B256::from_slice(&commitment[..32]), &chunk)
+        })
+        .collect::<Vec<_>>();
+    let (commitments_root, mut proof) = merkle_proof(&leaves, BLOB_KZG_COMMITMENTS_DEPTH, index);
+
+    // the root of a list commits to its length
+    let length = uint_chunk(commitments.len() as u64);
+    proof.push(length);
+
+    let mut fields = [B256::ZERO; BLOB_KZG_COMMITMENTS_INDEX + 1];
+    fields[BLOB_KZG_COMMITMENTS_INDEX] = hash_pair(&commitments_root, &length);
+    let (body_root, body_proof) =
+        merkle_proof(&fields, BLOCK_BODY_DEPTH, BLOB_KZG_COMMITMENTS_INDEX);
+    proof.extend(body_proof);
+
+    (body_root, proof)
+}
+
+/// Returns the root of the merkle tree of the given depth, padded with zero leaves, and the proof
+/// of the leaf at `index`
+fn merkle_proof(leaves: &[B256], depth: usize, mut index: usize) -> (B256, Vec<B256>) {
+    let mut layer = leaves.to_vec();
+    let mut zero = B256::ZERO;
+    let mut proof = Vec::with_capacity(depth);
+    for _ in 0..depth {
+        if layer.len() % 2 == 1 {
+            layer.push(zero);
+        }
+        proof.push(layer.get(index ^ 1).copied().unwrap_or(zero));
+        layer = layer.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+        zero = hash_pair(&zero, &zero);
+        index /= 2;
+    }
+    (layer.first().copied().unwrap_or(zero), proof)
+}
+
+fn hash_pair(left: &B256, right: &B256) -> B256 {
+    B256::from_slice(&Sha256::new().chain_update(left).chain_update(right).finalize())
+}
+
+/// Returns the SSZ chunk of an integer
+fn uint_chunk(value: u64) -> B256 {
+    let mut chunk = B256::ZERO;
+    chunk[..8].copy_from_slice(&value.to_le_bytes());
+    chunk
+}
+
+/// Parses the `indices` query parameter, either as list (`indices=0,1`) or repeated
+/// (`indices=0&indices=1`)
+fn parse_indices(query: Option<&str>) -> Result<Option<Vec<u64>>, BeaconError> {
+    let mut indices: Option<Vec<u64>> = None;
+    let query = query.unwrap_or_default().as_bytes();
+    for (key, values) in url::form_urlencoded::parse(query) {
+        if key != "indices" {
+            continue;
+        }
+        let indices = indices.get_or_insert_with(Vec::new);
+        for value in values.split(',').filter(|value| !value.is_empty()) {
+            let index = value
+                .parse()
+                .map_err(|_| BeaconError::bad_request(format!("invalid index: {value}")))?;
+            indices.push(index);
+        }
+    }
+    Ok(indices)
+}
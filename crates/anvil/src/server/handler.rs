@@ -2,15 +2,19 @@
 use crate::{
     eth::error::to_rpc_result,
     pubsub::{EthSubscription, LogsSubscription},
+    server::session::SessionCall,
     EthApi,
 };
 use alloy_rpc_types::{
     pubsub::{Params, SubscriptionKind},
     FilteredParams,
 };
-use anvil_core::eth::{subscription::SubscriptionId, EthPubSub, EthRequest, EthRpcCall};
+use anvil_core::eth::{
+    subscription::SubscriptionId, EngineRpcCall, EthPubSub, EthRequest, EthRpcCall,
+};
 use anvil_rpc::{error::RpcError, response::ResponseResult};
 use anvil_server::{PubSubContext, PubSubRpcHandler, RpcHandler};
+use std::time::Duration;
 
 /// A `RpcHandler` that expects `EthRequest` rpc calls via http
 #[derive(Clone)]
@@ -28,10 +32,50 @@ pub fn new(api: EthApi) -> Self {
 
 #[async_trait::async_trait]
 impl RpcHandler for HttpEthRpcHandler {
-    type Request = EthRequest;
+    type Request = SessionCall<EthRequest>;
 
     async fn on_request(&self, request: Self::Request) -> ResponseResult {
-        self.api.execute(request).await
+        self.api.execute_session_call(request).await
+    }
+
+    fn on_handled(&self, method: &str, elapsed: Duration, response: &ResponseResult) {
+        self.api.on_rpc_call_handled(method, elapsed, response)
+    }
+}
+
+/// A `RpcHandler` that expects `EngineRequest` and `EthRequest` rpc calls via the authenticated
+/// http endpoint
+#[derive(Clone)]
+pub struct AuthEthRpcHandler {
+    /// Access to the node
+    api: EthApi,
+}
+
+impl AuthEthRpcHandler {
+    /// Creates a new instance of the handler using the given `EthApi`
+    pub fn new(api: EthApi) -> Self {
+        Self { api }
+    }
+}
+
+#[async_trait::async_trait]
+impl RpcHandler for AuthEthRpcHandler {
+    type Request = SessionCall<EngineRpcCall>;
+
+    async fn on_request(&self, request: Self::Request) -> ResponseResult {
+        let SessionCall { method, params, request } = request;
+        match request {
+            EngineRpcCall::Engine(request) => self.api.execute_engine(*request).await,
+            EngineRpcCall::Request(request) => {
+                self.api
+                    .execute_session_call(SessionCall { method, params, request: *request })
+                    .await
+            }
+        }
+    }
+
+    fn on_handled(&self, method: &str, elapsed: Duration, response: &ResponseResult) {
+        self.api.on_rpc_call_handled(method, elapsed, response)
     }
 }
 
@@ -73,7 +117,7 @@ async fn on_pub_sub(&self, pubsub: EthPubSub, cx: PubSubContext<Self>) -> Respon
                 let subscription = match kind {
                     SubscriptionKind::Logs => {
                         trace!(target: "rpc::ws", "received logs subscription {:?}", params);
-                        let blocks = self.api.new_block_notifications();
+                        let blocks = self.api.chain_notifications();
                         let storage = self.api.storage_info();
                         EthSubscription::Logs(Box::new(LogsSubscription {
                             blocks,
@@ -112,15 +156,24 @@ async fn on_pub_sub(&self, pubsub: EthPubSub, cx: PubSubContext<Self>) -> Respon
 
 #[async_trait::async_trait]
 impl PubSubRpcHandler for PubSubEthRpcHandler {
-    type Request = EthRpcCall;
+    type Request = SessionCall<EthRpcCall>;
     type SubscriptionId = SubscriptionId;
     type Subscription = EthSubscription;
 
     async fn on_request(&self, request: Self::Request, cx: PubSubContext<Self>) -> ResponseResult {
+        let SessionCall { method, params, request } = request;
         trace!(target: "rpc", "received pubsub request {:?}", request);
         match request {
-            EthRpcCall::Request(request) => self.api.execute(*request).await,
+            EthRpcCall::Request(request) => {
+                self.api
+                    .execute_session_call(SessionCall { method, params, request: *request })
+                    .await
+            }
             EthRpcCall::PubSub(pubsub) => self.on_pub_sub(pubsub, cx).await,
         }
     }
+
+    fn on_handled(&self, method: &str, elapsed: Duration, response: &ResponseResult) {
+        self.api.on_rpc_call_handled(method, elapsed, response)
+    }
 }
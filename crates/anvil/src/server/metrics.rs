@@ -0,0 +1,13 @@
+//! Serves the metrics of the node in the Prometheus text format.
+
+use crate::eth::EthApi;
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+
+/// Returns the route of the metrics endpoint
+pub fn router(api: EthApi) -> Router {
+    Router::new().route("/metrics", get(metrics)).with_state(api)
+}
+
+async fn metrics(State(api): State<EthApi>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], api.metrics())
+}
@@ -1,15 +1,19 @@
 //! Contains the code to launch an Ethereum RPC server.
 
 use crate::{EthApi, IpcTask};
+use alloy_rpc_types::engine::JwtSecret;
 use anvil_server::{ipc::IpcEndpoint, ServerConfig};
 use axum::Router;
 use futures::StreamExt;
-use handler::{HttpEthRpcHandler, PubSubEthRpcHandler};
+use handler::{AuthEthRpcHandler, HttpEthRpcHandler, PubSubEthRpcHandler};
 use std::{future::Future, io, net::SocketAddr, pin::pin};
 use tokio::net::TcpListener;
 
+pub mod beacon;
 pub mod error;
 mod handler;
+pub mod metrics;
+pub mod session;
 
 /// Configures a server that handles [`EthApi`] related JSON-RPC calls via HTTP and WS.
 ///
@@ -33,11 +37,34 @@ pub async fn serve_on(
     axum::serve(tcp_listener, router(api, config).into_make_service()).await
 }
 
-/// Configures an [`axum::Router`] that handles [`EthApi`] related JSON-RPC calls via HTTP and WS.
+/// Configures an [`axum::Router`] that handles [`EthApi`] related JSON-RPC calls via HTTP and WS,
+/// and serves the [beacon] API.
 pub fn router(api: EthApi, config: ServerConfig) -> Router {
     let http = HttpEthRpcHandler::new(api.clone());
-    let ws = PubSubEthRpcHandler::new(api);
-    anvil_server::http_ws_router(config, http, ws)
+    let ws = PubSubEthRpcHandler::new(api.clone());
+    anvil_server::http_ws_router(config, http, ws).merge(beacon::router(api))
+}
+
+/// Configures a server that serves the [metrics] of the node.
+pub async fn serve_metrics_on(tcp_listener: TcpListener, api: EthApi) -> io::Result<()> {
+    axum::serve(tcp_listener, metrics::router(api).into_make_service()).await
+}
+
+/// Configures a server that handles the Engine API and [`EthApi`] related JSON-RPC calls via HTTP,
+/// authenticated with the given [`JwtSecret`].
+pub async fn serve_auth_on(
+    tcp_listener: TcpListener,
+    api: EthApi,
+    config: ServerConfig,
+    secret: JwtSecret,
+) -> io::Result<()> {
+    axum::serve(tcp_listener, auth_router(api, config, secret).into_make_service()).await
+}
+
+/// Configures an [`axum::Router`] that handles the Engine API and [`EthApi`] related JSON-RPC calls
+/// via HTTP, authenticated with the given [`JwtSecret`].
+pub fn auth_router(api: EthApi, config: ServerConfig, secret: JwtSecret) -> Router {
+    anvil_server::auth_router(config, AuthEthRpcHandler::new(api), secret)
 }
 
 /// Launches an ipc server at the given path in a new task
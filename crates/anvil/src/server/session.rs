@@ -0,0 +1,410 @@
+//! Record and replay of JSON-RPC sessions.
+//!
+//! A session is a file with one handled call per line, in the order the calls were received. Every
+//! line contains the method, the params, the block number and timestamp of the node when the call
+//! was received, the timestamps of the blocks mined since the previous call was received, how long
+//! it took to handle the call, and its response.
+//!
+//! Replaying a session executes all calls against a fresh node and stops at the first call that
+//! behaves differently. The replayed node doesn't mine blocks on its own. Instead, before every
+//! call, blocks are mined with their recorded timestamps until the node reaches the recorded block
+//! number, and the clock of the node is moved to the recorded timestamp, so that blocks are mined
+//! with the same transactions at the same time, also if they were mined by `--block-time`. Replays
+//! are only deterministic if the node is launched with the same options that the session was
+//! recorded with, e.g. `--timestamp` and `--state`.
+
+use crate::eth::EthApi;
+use anvil_core::eth::EthRequest;
+use anvil_rpc::response::ResponseResult;
+use eyre::{Result, WrapErr};
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    fs::{self, File},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+/// A line of the session file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEntry {
+    /// The method of the call
+    pub method: String,
+    /// The params of the call
+    #[serde(default)]
+    pub params: serde_json::Value,
+    /// The number of the best block when the call was received
+    pub block_number: u64,
+    /// The timestamp of the node when the call was received
+    pub timestamp: u64,
+    /// The timestamps of the blocks that were mined since the previous call was received, up to
+    /// the block of this call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_timestamps: Vec<u64>,
+    /// How long it took to handle the call
+    pub elapsed_micros: u64,
+    /// The response to the call
+    pub response: ResponseResult,
+}
+
+/// Appends all handled calls to a session file, in the order they were received
+#[derive(Debug)]
+pub struct SessionRecorder {
+    path: PathBuf,
+    state: Mutex<RecorderState>,
+}
+
+#[derive(Debug)]
+struct RecorderState {
+    file: File,
+    /// The id of the next received call
+    next_id: u64,
+    /// The id of the next call to append to the file
+    next_write: u64,
+    /// The lines of handled calls that wait for calls received before them, `None` if the call
+    /// wasn't handled
+    pending: BTreeMap<u64, Option<Vec<u8>>>,
+    /// The number of the best block when the last call was received
+    block_number: u64,
+}
+
+impl SessionRecorder {
+    /// Creates the session file at `path`, overwriting any existing session, for a node at the
+    /// given block
+    pub fn create(path: impl Into<PathBuf>, block_number: u64) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = File::create(&path)
+            .wrap_err_with(|| format!("failed to create session {}", path.display()))?;
+        let state = RecorderState {
+            file,
+            next_id: 0,
+            next_write: 0,
+            pending: BTreeMap::new(),
+            block_number,
+        };
+        Ok(Self { path, state: Mutex::new(state) })
+    }
+
+    /// The path of the session file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Starts recording a call that was received by the node of the `api`
+    pub fn receive(&self, api: &EthApi) -> ReceivedCall<'_> {
+        let mut state = self.state.lock();
+        let block_number = api.backend.best_number();
+        let timestamp = api.backend.time().current_call_timestamp();
+        let block_timestamps = (state.block_number + 1..=block_number)
+            .filter_map(|number| api.backend.get_block(number))
+            .map(|block| block.header.timestamp)
+            .collect();
+        state.block_number = state.block_number.max(block_number);
+        let id = state.next_id;
+        state.next_id += 1;
+
+        ReceivedCall {
+            recorder: self,
+            id,
+            block_number,
+            timestamp,
+            block_timestamps,
+            start: Instant::now(),
+            recorded: false,
+        }
+    }
+
+    /// Appends the line of the call with the given id, and of all handled calls received after it,
+    /// once all calls received before it were handled
+    fn write(&self, id: u64, line: Option<Vec<u8>>) -> Result<()> {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        state.pending.insert(id, line);
+        while let Some(line) = state.pending.remove(&state.next_write) {
+            state.next_write += 1;
+            if let Some(line) = line {
+                state.file.write_all(&line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A call that was received by a [SessionRecorder], recorded once it was handled
+///
+/// Calls that are dropped without being recorded are skipped, so that they don't hold back the
+/// calls received after them.
+#[derive(Debug)]
+pub struct ReceivedCall<'a> {
+    recorder: &'a SessionRecorder,
+    id: u64,
+    block_number: u64,
+    timestamp: u64,
+    block_timestamps: Vec<u64>,
+    start: Instant,
+    recorded: bool,
+}
+
+impl ReceivedCall<'_> {
+    /// Records the handled call and returns its response
+    pub fn record(
+        mut self,
+        method: String,
+        params: serde_json::Value,
+        response: ResponseResult,
+    ) -> ResponseResult {
+        let entry = SessionEntry {
+            method,
+            params,
+            block_number: self.block_number,
+            timestamp: self.timestamp,
+            block_timestamps: std::mem::take(&mut self.block_timestamps),
+            elapsed_micros: self.start.elapsed().as_micros() as u64,
+            response,
+        };
+        let result = serde_json::to_vec(&entry).map_err(Into::into).and_then(|mut line| {
+            line.push(b'\n');
+            self.recorded = true;
+            self.recorder.write(self.id, Some(line))
+        });
+        if let Err(err) = result {
+            error!(target: "rpc", ?err, path = ?self.recorder.path(), "failed to record call");
+        }
+        entry.response
+    }
+}
+
+impl Drop for ReceivedCall<'_> {
+    fn drop(&mut self) {
+        if !self.recorded {
+            let _ = self.recorder.write(self.id, None);
+        }
+    }
+}
+
+/// A request of an RPC call that keeps the method and params it was deserialized from, so that the
+/// call can be recorded
+///
+/// The request is deserialized from the params by reference, so the params are neither parsed nor
+/// cloned twice.
+#[derive(Clone, Debug)]
+pub struct SessionCall<T> {
+    /// The method of the call
+    pub method: String,
+    /// The params of the call
+    pub params: serde_json::Value,
+    /// The deserialized request
+    pub request: T,
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for SessionCall<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Call {
+            method: String,
+            #[serde(default)]
+            params: serde_json::Value,
+        }
+
+        let Call { method, params } = Call::deserialize(deserializer)?;
+        let mut call = serde_json::json!({ "method": &method, "params": params });
+        let request = T::deserialize(&call).map_err(serde::de::Error::custom)?;
+        Ok(Self { method, params: call["params"].take(), request })
+    }
+}
+
+/// The outcome of a replayed session
+#[derive(Clone, Debug)]
+pub struct ReplayReport {
+    /// The number of calls that were replayed
+    pub replayed: usize,
+    /// The first call that behaved differently than when it was recorded, if any
+    pub divergence: Option<Divergence>,
+}
+
+/// A call of a replayed session that behaved differently than when it was recorded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Divergence {
+    /// The node was at a different block when the call was replayed
+    BlockNumber { line: usize, method: String, recorded: u64, replayed: u64 },
+    /// The call returned a different response
+    Response {
+        line: usize,
+        method: String,
+        recorded: Box<ResponseResult>,
+        replayed: Box<ResponseResult>,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlockNumber { line, method, recorded, replayed } => {
+                write!(f, "line {line}: `{method}` was recorded at block {recorded}, ")?;
+                write!(f, "but replayed at block {replayed}")
+            }
+            Self::Response { line, method, recorded, replayed } => {
+                let to_json =
+                    |response: &ResponseResult| serde_json::to_string(response).unwrap_or_default();
+                writeln!(f, "line {line}: `{method}` returned a different response")?;
+                writeln!(f, "  recorded: {}", to_json(recorded))?;
+                write!(f, "  replayed: {}", to_json(replayed))
+            }
+        }
+    }
+}
+
+/// Replays the session at `path` against the node of the `api`, stopping at the first call that
+/// diverges
+///
+/// The node must not mine blocks on its own, see [crate::NodeConfig::with_no_mining].
+///
+/// The ids of filters differ between nodes, so ids returned by `eth_new*Filter` calls are mapped to
+/// the ids of the replayed filters instead of being compared.
+pub async fn replay_session(api: &EthApi, path: &Path) -> Result<ReplayReport> {
+    let file =
+        File::open(path).wrap_err_with(|| format!("failed to open session {}", path.display()))?;
+
+    let mut filter_ids = HashMap::new();
+    let mut replayed = 0;
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line_number = idx + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue
+        }
+        let SessionEntry {
+            method,
+            mut params,
+            block_number,
+            timestamp,
+            block_timestamps,
+            response,
+            ..
+        } = serde_json::from_str(&line)
+            .wrap_err_with(|| format!("invalid session entry at line {line_number}"))?;
+
+        // blocks are mined right after the call that triggered them, so they're mined before the
+        // clock is moved to the timestamp of this call
+        let first_block = (block_number + 1).saturating_sub(block_timestamps.len() as u64);
+        let mut current = api.backend.best_number();
+        while current < block_number {
+            let next = current + 1;
+            if let Some(timestamp) = next
+                .checked_sub(first_block)
+                .and_then(|offset| block_timestamps.get(offset as usize))
+            {
+                api.backend.time().set_next_block_timestamp(*timestamp).wrap_err_with(|| {
+                    format!("invalid timestamp of block {next} at line {line_number}")
+                })?;
+            }
+            api.mine_one().await;
+            current = api.backend.best_number();
+        }
+        if current != block_number {
+            let divergence = Divergence::BlockNumber {
+                line: line_number,
+                method,
+                recorded: block_number,
+                replayed: current,
+            };
+            return Ok(ReplayReport { replayed, divergence: Some(divergence) })
+        }
+        api.backend.time().set_current_timestamp(timestamp);
+
+        replace_filter_ids(&mut params, &filter_ids);
+        let request: EthRequest =
+            serde_json::from_value(serde_json::json!({ "method": &method, "params": params }))
+                .wrap_err_with(|| format!("invalid `{method}` call at line {line_number}"))?;
+
+        let replayed_response = api.execute(request).await;
+        replayed += 1;
+
+        if method.starts_with("eth_new") && method.ends_with("Filter") {
+            if let (ResponseResult::Success(recorded), ResponseResult::Success(new)) =
+                (&response, &replayed_response)
+            {
+                if let (Some(recorded), Some(new)) = (recorded.as_str(), new.as_str()) {
+                    filter_ids.insert(recorded.to_string(), new.to_string());
+                    continue
+                }
+            }
+        }
+
+        if replayed_response != response {
+            let divergence = Divergence::Response {
+                line: line_number,
+                method,
+                recorded: Box::new(response),
+                replayed: Box::new(replayed_response),
+            };
+            return Ok(ReplayReport { replayed, divergence: Some(divergence) })
+        }
+    }
+
+    Ok(ReplayReport { replayed, divergence: None })
+}
+
+/// Replaces all recorded filter ids in the params with the ids of the replayed filters
+fn replace_filter_ids(params: &mut serde_json::Value, ids: &HashMap<String, String>) {
+    match params {
+        serde_json::Value::String(id) => {
+            if let Some(new) = ids.get(id.as_str()) {
+                *id = new.clone();
+            }
+        }
+        serde_json::Value::Array(values) => {
+            values.iter_mut().for_each(|value| replace_filter_ids(value, ids))
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_deserialize_session_call() {
+        let call = serde_json::json!({
+            "method": "eth_getBalance",
+            "params": ["0x0000000000000000000000000000000000000000", "latest"]
+        });
+        let call: SessionCall<EthRequest> = serde_json::from_value(call).unwrap();
+        assert_eq!(call.method, "eth_getBalance");
+        assert_eq!(call.params[1], "latest");
+        assert!(matches!(call.request, EthRequest::EthGetBalance(..)));
+
+        let call = serde_json::json!({ "method": "eth_unknown", "params": [] });
+        let err = serde_json::from_value::<SessionCall<EthRequest>>(call).unwrap_err();
+        assert!(err.to_string().contains("unknown variant"), "{err}");
+    }
+
+    #[test]
+    fn writes_calls_in_receive_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let recorder = SessionRecorder::create(&path, 0).unwrap();
+
+        recorder.write(2, Some(b"2\n".to_vec())).unwrap();
+        recorder.write(1, None).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+        recorder.write(0, Some(b"0\n".to_vec())).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "0\n2\n");
+    }
+
+    #[test]
+    fn can_replace_filter_ids() {
+        let ids = HashMap::from([("0x1".to_string(), "0x2".to_string())]);
+        let mut params = serde_json::json!(["0x1", "0x3"]);
+        replace_filter_ids(&mut params, &ids);
+        assert_eq!(params, serde_json::json!(["0x2", "0x3"]));
+    }
+}
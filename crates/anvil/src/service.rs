@@ -17,6 +17,7 @@
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::{task::JoinHandle, time::Interval};
 
@@ -39,6 +40,8 @@ pub struct NodeService {
     filters: Filters,
     /// The interval at which to check for filters that need to be evicted
     filter_eviction_interval: Interval,
+    /// The interval at which to check for queued transactions that exceeded their lifetime
+    txpool_eviction_interval: Option<Interval>,
 }
 
 impl NodeService {
@@ -51,12 +54,17 @@ pub fn new(
     ) -> Self {
         let start = tokio::time::Instant::now() + filters.keep_alive();
         let filter_eviction_interval = tokio::time::interval_at(start, filters.keep_alive());
+        let txpool_eviction_interval = pool.config().lifetime.map(|lifetime| {
+            let period = lifetime.clamp(Duration::from_secs(1), Duration::from_secs(60));
+            tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+        });
         Self {
             pool,
             block_producer: BlockProducer::new(backend),
             miner,
             fee_history,
             filter_eviction_interval,
+            txpool_eviction_interval,
             filters,
         }
     }
@@ -71,8 +79,10 @@ fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         // this drives block production and feeds new sets of ready transactions to the block
         // producer
         loop {
-            while let Poll::Ready(Some(outcome)) = pin.block_producer.poll_next_unpin(cx) {
+            while let Poll::Ready(Some((outcome, elapsed))) = pin.block_producer.poll_next_unpin(cx)
+            {
                 trace!(target: "node", "mined block {}", outcome.block_number);
+                pin.miner.metrics().on_mined_block(&outcome, elapsed);
                 // prune the transactions from the pool
                 pin.pool.on_mined_block(outcome);
             }
@@ -97,6 +107,13 @@ fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
             tokio::task::spawn(async move { filters.evict().await });
         }
 
+        if let Some(interval) = &mut pin.txpool_eviction_interval {
+            while interval.poll_tick(cx).is_ready() {
+                // evict queued transactions that exceeded their lifetime
+                pin.pool.evict_expired();
+            }
+        }
+
         Poll::Pending
     }
 }
@@ -107,7 +124,7 @@ struct BlockProducer {
     /// Holds the backend if no block is being mined
     idle_backend: Option<Arc<Backend>>,
     /// Single active future that mines a new block
-    block_mining: Option<JoinHandle<(MinedBlockOutcome, Arc<Backend>)>>,
+    block_mining: Option<JoinHandle<(MinedBlockOutcome, Duration, Arc<Backend>)>>,
     /// backlog of sets of transactions ready to be mined
     queued: VecDeque<Vec<Arc<PoolTransaction>>>,
 }
@@ -119,7 +136,8 @@ fn new(backend: Arc<Backend>) -> Self {
 }
 
 impl Stream for BlockProducer {
-    type Item = MinedBlockOutcome;
+    /// The mined block and the time it took to mine it
+    type Item = (MinedBlockOutcome, Duration);
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let pin = self.get_mut();
@@ -134,9 +152,10 @@ fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::It
                 let mining = tokio::task::spawn_blocking(move || {
                     handle.block_on(async move {
                         trace!(target: "miner", "creating new block");
+                        let start = Instant::now();
                         let block = backend.mine_block(transactions).await;
                         trace!(target: "miner", "created new block: {}", block.block_number);
-                        (block, backend)
+                        (block, start.elapsed(), backend)
                     })
                 });
                 pin.block_mining = Some(mining);
@@ -146,9 +165,9 @@ fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::It
         if let Some(mut mining) = pin.block_mining.take() {
             if let Poll::Ready(res) = mining.poll_unpin(cx) {
                 return match res {
-                    Ok((outcome, backend)) => {
+                    Ok((outcome, elapsed, backend)) => {
                         pin.idle_backend = Some(backend);
-                        Poll::Ready(Some(outcome))
+                        Poll::Ready(Some((outcome, elapsed)))
                     }
                     Err(err) => {
                         panic!("miner task failed: {err}");
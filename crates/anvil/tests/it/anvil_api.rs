@@ -7,7 +7,7 @@
 };
 use alloy_consensus::{SignableTransaction, TxEip1559};
 use alloy_network::{EthereumWallet, TransactionBuilder, TxSignerSync};
-use alloy_primitives::{address, fixed_bytes, utils::Unit, Address, Bytes, TxKind, U256};
+use alloy_primitives::{address, fixed_bytes, utils::Unit, Address, Bytes, TxKind, B256, U256};
 use alloy_provider::{ext::TxPoolApi, Provider};
 use alloy_rpc_types::{
     anvil::{
@@ -28,7 +28,7 @@
         wallet::{Capabilities, DelegationCapability, WalletCapabilities},
         EthRequest,
     },
-    types::{ReorgOptions, TransactionData},
+    types::{ReorgOptions, StateDiffOptions, TransactionData, ValueDiff},
 };
 use foundry_evm::revm::primitives::SpecId;
 use std::{
@@ -1061,3 +1061,172 @@ async fn test_mine_first_block_with_interval() {
     let second_block = api.block_by_number(2.into()).await.unwrap().unwrap();
     assert_eq!(second_block.header.timestamp, init_timestamp + 120);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_state_diff() {
+    let (api, handle) = spawn(NodeConfig::test().with_no_mining(true)).await;
+    let accounts: Vec<Address> = handle.dev_wallets().map(|w| w.address()).collect();
+    let (alice, bob) = (accounts[0], accounts[1]);
+
+    let state_snapshot = api.evm_snapshot().await.unwrap();
+    let tx = TransactionRequest::default().from(alice).to(bob).value(U256::from(100));
+    api.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    api.mine_one().await;
+
+    let diff = api
+        .anvil_state_diff(StateDiffOptions {
+            from_block: Some(BlockId::number(0)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let alice_diff = &diff.accounts[&alice];
+    assert_eq!(alice_diff.nonce, Some(ValueDiff { from: 0, to: 1 }));
+    assert!(alice_diff.code.is_none());
+    assert!(alice_diff.storage.is_empty());
+    let bob_balance = diff.accounts[&bob].balance.clone().unwrap();
+    assert_eq!(bob_balance.to - bob_balance.from, U256::from(100));
+    assert!(!diff.accounts.contains_key(&accounts[2]));
+
+    let snapshot_diff = api
+        .anvil_state_diff(StateDiffOptions {
+            from_snapshot: Some(state_snapshot),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(snapshot_diff, diff);
+
+    // manual changes are included in the diff against the current state
+    let state_snapshot = api.evm_snapshot().await.unwrap();
+    let contract = Address::random();
+    let code = Bytes::from_static(&[0x60, 0x00]);
+    api.anvil_set_code(contract, code.clone()).await.unwrap();
+    api.anvil_set_storage_at(contract, U256::from(1), B256::with_last_byte(1)).await.unwrap();
+
+    let diff = api
+        .anvil_state_diff(StateDiffOptions {
+            from_snapshot: Some(state_snapshot),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(diff.accounts.len(), 1);
+    let contract_diff = &diff.accounts[&contract];
+    assert_eq!(contract_diff.code, Some(ValueDiff { from: Bytes::new(), to: code }));
+    assert_eq!(
+        contract_diff.storage[&B256::with_last_byte(1)],
+        ValueDiff { from: B256::ZERO, to: B256::with_last_byte(1) }
+    );
+
+    assert!(api.anvil_state_diff(StateDiffOptions::default()).await.is_err());
+    assert!(api
+        .anvil_state_diff(StateDiffOptions {
+            from_block: Some(BlockId::number(0)),
+            from_snapshot: Some(state_snapshot),
+            ..Default::default()
+        })
+        .await
+        .is_err());
+    assert!(api
+        .anvil_state_diff(StateDiffOptions {
+            from_snapshot: Some(U256::from(100)),
+            ..Default::default()
+        })
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_state_diff_in_fork_mode() {
+    let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    let (carol, dave, contract) = (Address::random(), Address::random(), Address::random());
+    let balance = U256::from(5).pow(U256::from(18));
+    origin_api.anvil_set_balance(carol, balance).await.unwrap();
+    origin_api.anvil_set_balance(dave, balance).await.unwrap();
+    origin_api.anvil_set_code(contract, Bytes::from_static(&[0x60, 0x00])).await.unwrap();
+    for slot in [1, 2] {
+        origin_api
+            .anvil_set_storage_at(contract, U256::from(slot), B256::with_last_byte(slot))
+            .await
+            .unwrap();
+    }
+
+    let (api, handle) = spawn(
+        NodeConfig::test()
+            .with_no_mining(true)
+            .with_eth_rpc_url(Some(origin_handle.http_endpoint())),
+    )
+    .await;
+    let alice = handle.dev_accounts().next().unwrap();
+    let state_snapshot = api.evm_snapshot().await.unwrap();
+
+    // accounts and slots that are only read after the snapshot aren't part of the diff
+    assert_eq!(api.balance(dave, None).await.unwrap(), balance);
+    api.storage_at(contract, U256::from(1), None).await.unwrap();
+    api.anvil_set_storage_at(contract, U256::from(2), B256::with_last_byte(3)).await.unwrap();
+    let tx = TransactionRequest::default().from(alice).to(carol).value(U256::from(100));
+    api.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    api.mine_one().await;
+
+    let diff = api
+        .anvil_state_diff(StateDiffOptions {
+            from_snapshot: Some(state_snapshot),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(!diff.accounts.contains_key(&dave));
+    assert_eq!(
+        diff.accounts[&carol].balance,
+        Some(ValueDiff { from: balance, to: balance + U256::from(100) })
+    );
+    let contract_diff = &diff.accounts[&contract];
+    assert!(contract_diff.code.is_none());
+    assert_eq!(contract_diff.storage.len(), 1);
+    assert_eq!(
+        contract_diff.storage[&B256::with_last_byte(2)],
+        ValueDiff { from: B256::with_last_byte(2), to: B256::with_last_byte(3) }
+    );
+
+    let from_block = api.block_number().unwrap().to::<u64>() - 1;
+    let block_diff = api
+        .anvil_state_diff(StateDiffOptions {
+            from_block: Some(BlockId::number(from_block)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(!block_diff.accounts.contains_key(&dave));
+    assert_eq!(block_diff.accounts[&carol], diff.accounts[&carol]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_state_diff_of_deployed_code() {
+    let (api, handle) = spawn(NodeConfig::test().with_no_mining(true)).await;
+    let alice = handle.dev_accounts().next().unwrap();
+
+    // init code that deploys the runtime code 0x6000
+    let init_code =
+        Bytes::from_static(&[0x61, 0x60, 0x00, 0x60, 0x00, 0x52, 0x60, 0x02, 0x60, 0x1e, 0xf3]);
+    let tx = TransactionRequest::default().from(alice).with_deploy_code(init_code);
+    api.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    api.mine_one().await;
+
+    // old states are moved to disk, which stores the code of their accounts by hash
+    api.anvil_mine(Some(U256::from(600)), None).await.unwrap();
+
+    let contract = alice.create(0);
+    let diff = api
+        .anvil_state_diff(StateDiffOptions {
+            from_block: Some(BlockId::number(0)),
+            to_block: Some(BlockId::number(1)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        diff.accounts[&contract].code,
+        Some(ValueDiff { from: Bytes::new(), to: Bytes::from_static(&[0x60, 0x00]) })
+    );
+}
@@ -6,18 +6,23 @@
 };
 use alloy_network::{EthereumWallet, TransactionBuilder};
 use alloy_primitives::{
+    keccak256,
     map::{AddressHashMap, B256HashMap, HashMap},
-    Address, ChainId, B256, U256,
+    Address, Bytes, ChainId, B256, U256,
 };
 use alloy_provider::Provider;
 use alloy_rpc_types::{
-    request::TransactionRequest, state::AccountOverride, BlockId, BlockNumberOrTag,
-    BlockTransactions,
+    request::TransactionRequest,
+    simulate::{SimBlock, SimulatePayload},
+    state::AccountOverride,
+    BlockId, BlockNumberOrTag, BlockOverrides, BlockTransactions,
 };
 use alloy_serde::WithOtherFields;
+use alloy_sol_types::SolCall;
 use anvil::{eth::api::CLIENT_VERSION, spawn, NodeConfig, CHAIN_ID};
+use foundry_evm::traces::TRANSFER_LOG_EMITTER;
 use futures::join;
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_get_block_number() {
@@ -378,6 +383,127 @@ async fn can_call_with_state_override() {
     assert_eq!(value, "");
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_simulate_v1() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let signer: EthereumWallet = wallet.clone().into();
+    let account = wallet.address();
+
+    let provider = http_provider_with_signer(&handle.http_endpoint(), signer);
+
+    let simple_storage_contract =
+        SimpleStorage::deploy(&provider, "init value".to_string()).await.unwrap();
+    let latest = api.block_number().unwrap().to::<u64>();
+
+    let recipient = Address::random();
+    let set_value = simple_storage_contract
+        .setValue("sim".to_string())
+        .from(account)
+        .into_transaction_request();
+    let get_value = simple_storage_contract.getValue().from(account).into_transaction_request();
+    let payload =
+        SimulatePayload::default()
+            .extend(SimBlock::default().call(set_value.inner).call(
+                TransactionRequest::default().from(account).to(recipient).value(U256::from(1)),
+            ))
+            .extend(
+                SimBlock::default()
+                    .with_block_overrides(BlockOverrides {
+                        time: Some(1_000_000_000_000),
+                        ..Default::default()
+                    })
+                    .call(get_value.inner),
+            )
+            .with_trace_transfers();
+
+    let blocks = provider.simulate(&payload).await.unwrap();
+    assert_eq!(blocks.len(), 2);
+
+    assert_eq!(blocks[0].inner.header.number, latest + 1);
+    assert_eq!(blocks[0].calls.len(), 2);
+    assert!(blocks[0].calls.iter().all(|call| call.status));
+    let transfer_log = &blocks[0].calls[1].logs[0];
+    assert_eq!(transfer_log.address(), TRANSFER_LOG_EMITTER);
+    assert_eq!(transfer_log.topics()[2], recipient.into_word());
+    assert_eq!(transfer_log.block_hash, Some(blocks[0].inner.header.hash));
+
+    // the second block observes the state changes of the first one
+    assert_eq!(blocks[1].inner.header.number, latest + 2);
+    assert_eq!(blocks[1].inner.header.timestamp, 1_000_000_000_000);
+    assert_eq!(blocks[1].inner.header.parent_hash, blocks[0].inner.header.hash);
+    let value =
+        SimpleStorage::getValueCall::abi_decode_returns(&blocks[1].calls[0].return_data, true)
+            .unwrap()
+            ._0;
+    assert_eq!(value, "sim");
+
+    // nothing was committed
+    assert_eq!(api.block_number().unwrap().to::<u64>(), latest);
+    assert_eq!(provider.get_balance(recipient).await.unwrap(), U256::ZERO);
+    assert_eq!(simple_storage_contract.getValue().call().await.unwrap()._0, "init value");
+
+    // an empty account can't pay for the transfer if validation is enabled, which fails only
+    // that call
+    let transfer = TransactionRequest::default().from(account).to(recipient).value(U256::from(1));
+    let payload = SimulatePayload::default()
+        .extend(
+            SimBlock::default()
+                .call(
+                    TransactionRequest::default().from(recipient).to(account).value(U256::from(1)),
+                )
+                .call(transfer.clone()),
+        )
+        .with_validation();
+    let blocks = provider.simulate(&payload).await.unwrap();
+    assert!(!blocks[0].calls[0].status);
+    assert_eq!(blocks[0].calls[0].error.as_ref().unwrap().code, -38014);
+    assert!(blocks[0].calls[1].status);
+    assert_eq!(blocks[0].inner.transactions.len(), 1);
+
+    // the calls of a block share its gas limit, the gas used by the first transfer leaves too
+    // little for the second one
+    let gas_limit = api.gas_limit().to::<u64>();
+    let call = transfer.gas_limit(gas_limit - 10_000);
+    let payload =
+        SimulatePayload::default().extend(SimBlock::default().call(call.clone()).call(call));
+    let blocks = provider.simulate(&payload).await.unwrap();
+    assert!(blocks[0].calls[0].status);
+    assert_eq!(blocks[0].calls[1].error.as_ref().unwrap().code, -38015);
+
+    // reverted calls return their revert data in the error
+    let reverter = Address::random();
+    // PUSH1 0x2a PUSH0 MSTORE PUSH1 0x20 PUSH0 REVERT
+    let code = Bytes::from_static(&[0x60, 0x2a, 0x5f, 0x52, 0x60, 0x20, 0x5f, 0xfd]);
+    api.anvil_set_code(reverter, code).await.unwrap();
+    let payload = SimulatePayload::default()
+        .extend(SimBlock::default().call(TransactionRequest::default().to(reverter)));
+    let blocks = api.simulate_v1(payload, None).await.unwrap();
+    let error = blocks[0].calls[0].error.clone().unwrap();
+    assert_eq!(error.code, -32000);
+    assert_eq!(error.data, Some(U256::from(0x2a).to_be_bytes_vec().into()));
+
+    // block numbers have to be strictly increasing
+    let payload = SimulatePayload::default().extend(SimBlock::default()).extend(
+        SimBlock::default().with_block_overrides(BlockOverrides {
+            number: Some(U256::from(latest + 1)),
+            ..Default::default()
+        }),
+    );
+    assert!(provider.simulate(&payload).await.is_err());
+
+    // the base fee is only zeroed without validation if it isn't overridden
+    let payload = SimulatePayload::default().extend(SimBlock::default()).extend(
+        SimBlock::default().with_block_overrides(BlockOverrides {
+            base_fee: Some(U256::from(7)),
+            ..Default::default()
+        }),
+    );
+    let blocks = provider.simulate(&payload).await.unwrap();
+    assert_eq!(blocks[0].inner.header.base_fee_per_gas, Some(0));
+    assert_eq!(blocks[1].inner.header.base_fee_per_gas, Some(7));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_mine_while_mining() {
     let (api, _) = spawn(NodeConfig::test()).await;
@@ -406,3 +532,135 @@ async fn can_mine_while_mining() {
     let block = api.block_by_number(BlockNumberOrTag::Number(block_number)).await.unwrap().unwrap();
     assert_eq!(block.header.number, total_blocks);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_storage_range_at() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let signer: EthereumWallet = wallet.clone().into();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), signer);
+
+    let contract = SimpleStorage::deploy(&provider, "init".to_string()).await.unwrap();
+    let address = *contract.address();
+
+    // mine two updates of the storage into the same block
+    api.anvil_set_auto_mine(false).await.unwrap();
+    for value in ["first", "second"] {
+        let tx = TransactionRequest::default()
+            .from(wallet.address())
+            .to(address)
+            .with_input(SimpleStorage::setValueCall { value: value.to_string() }.abi_encode());
+        api.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    }
+    api.mine_one().await;
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 2);
+
+    let parent = api
+        .debug_storage_range_at(
+            BlockId::number(block.header.number - 1),
+            1,
+            address,
+            B256::ZERO,
+            10,
+        )
+        .await
+        .unwrap();
+    let before_first = api
+        .debug_storage_range_at(block.header.hash.into(), 0, address, B256::ZERO, 10)
+        .await
+        .unwrap();
+    let before_second = api
+        .debug_storage_range_at(block.header.hash.into(), 1, address, B256::ZERO, 10)
+        .await
+        .unwrap();
+    let after =
+        api.debug_storage_range_at(BlockId::latest(), 2, address, B256::ZERO, 10).await.unwrap();
+    assert_eq!(parent, before_first);
+    assert_ne!(before_first, before_second);
+    assert_ne!(before_second, after);
+    assert!(after.next_key.is_none());
+    for (hash, entry) in &after.storage {
+        assert_eq!(*hash, keccak256(entry.key.unwrap()));
+    }
+
+    // pages through the storage one slot at a time
+    let mut storage = BTreeMap::new();
+    let mut next_key = Some(B256::ZERO);
+    while let Some(start_key) = next_key {
+        let page =
+            api.debug_storage_range_at(BlockId::latest(), 2, address, start_key, 1).await.unwrap();
+        assert_eq!(page.storage.len(), 1);
+        storage.extend(page.storage);
+        next_key = page.next_key;
+    }
+    assert_eq!(storage, after.storage);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_account_range_and_modified_accounts() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let accounts: Vec<Address> = handle.dev_wallets().map(|w| w.address()).collect();
+    let (alice, bob) = (accounts[0], accounts[1]);
+
+    let contract = Address::random();
+    api.anvil_set_storage_at(contract, U256::from(1), B256::with_last_byte(1)).await.unwrap();
+
+    // mine the transaction explicitly, so the block number isn't read before it's mined
+    api.anvil_set_auto_mine(false).await.unwrap();
+    let tx = TransactionRequest::default().from(alice).to(bob).value(U256::from(100));
+    api.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    api.mine_one().await;
+    let block_number = api.block_number().unwrap().to::<u64>();
+
+    let modified = api.debug_get_modified_accounts_by_number(block_number, None).await.unwrap();
+    assert!(modified.contains(&alice));
+    assert!(modified.contains(&bob));
+    assert!(!modified.contains(&contract));
+    assert_eq!(
+        api.debug_get_modified_accounts_by_number(0, Some(block_number)).await.unwrap(),
+        modified
+    );
+    assert!(api.debug_get_modified_accounts_by_number(block_number, Some(0)).await.is_err());
+    assert!(api.debug_get_modified_accounts_by_number(block_number + 1, None).await.is_err());
+
+    // only accounts touched by the transactions of the block are considered
+    api.anvil_set_balance(bob, U256::from(1)).await.unwrap();
+    api.evm_mine(None).await.unwrap();
+    assert!(api
+        .debug_get_modified_accounts_by_number(block_number + 1, None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let range = api
+        .debug_account_range(BlockId::latest(), Default::default(), 0, false, false, false)
+        .await
+        .unwrap();
+    assert!(range.next.is_none());
+    for account in &accounts {
+        let entry = &range.accounts[account];
+        assert_eq!(entry.balance, api.balance(*account, None).await.unwrap());
+        assert_eq!(entry.key, keccak256(account));
+    }
+    let storage = range.accounts[&contract].storage.clone().unwrap();
+    assert_eq!(storage[&B256::with_last_byte(1)], B256::with_last_byte(1));
+
+    // pages through the accounts one at a time
+    let mut paged = BTreeMap::new();
+    let mut start = Some(B256::ZERO);
+    while let Some(key) = start {
+        let page = api
+            .debug_account_range(BlockId::latest(), key.into(), 1, true, true, false)
+            .await
+            .unwrap();
+        assert_eq!(page.accounts.len(), 1);
+        assert!(page
+            .accounts
+            .values()
+            .all(|entry| entry.code.is_none() && entry.storage.is_none()));
+        paged.extend(page.accounts);
+        start = page.next;
+    }
+    assert_eq!(paged.keys().collect::<Vec<_>>(), range.accounts.keys().collect::<Vec<_>>());
+}
@@ -0,0 +1,205 @@
+//! Tests for bundles, private transactions and custom block builders
+
+use alloy_eips::eip2718::Encodable2718;
+use alloy_network::{EthereumWallet, ReceiptResponse, TransactionBuilder};
+use alloy_primitives::{Address, Bytes, TxHash, TxKind, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockNumberOrTag, TransactionRequest};
+use anvil::{
+    eth::{miner::BlockItem, EthApi},
+    spawn, NodeConfig, NodeHandle,
+};
+use anvil_core::types::{
+    BundleHash, CancelPrivateTransactionRequest, PrivateTransactionRequest, SendBundleRequest,
+};
+
+/// Init code that reverts on deployment
+const REVERTING_INIT_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+/// Signs a transaction of the dev account with the given index and returns the raw transaction
+/// and its hash
+async fn sign(handle: &NodeHandle, account: usize, nonce: u64, to: TxKind) -> (Bytes, TxHash) {
+    let signer = handle.dev_wallets().nth(account).unwrap();
+    let mut tx = TransactionRequest::default()
+        .with_chain_id(31337)
+        .with_nonce(nonce)
+        .with_value(U256::from(1))
+        .with_gas_limit(100_000)
+        .with_max_fee_per_gas(20_000_000_000)
+        .with_max_priority_fee_per_gas(1_000_000_000);
+    tx.to = Some(to);
+    if to.is_create() {
+        tx = tx.with_input(REVERTING_INIT_CODE);
+    }
+    let envelope = tx.build(&EthereumWallet::from(signer)).await.unwrap();
+    (envelope.encoded_2718().into(), *envelope.tx_hash())
+}
+
+async fn block_transactions(api: &EthApi, number: u64) -> Vec<TxHash> {
+    let block = api.block_by_number(BlockNumberOrTag::Number(number)).await.unwrap().unwrap();
+    block.transactions.hashes().collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bundle_is_included_first_and_in_order() {
+    let (api, handle) = spawn(NodeConfig::test().with_no_mining(true)).await;
+    let to = TxKind::Call(Address::random());
+
+    let pool_tx = api.send_raw_transaction(sign(&handle, 0, 0, to).await.0).await.unwrap();
+    let (first, first_hash) = sign(&handle, 1, 0, to).await;
+    let (second, second_hash) = sign(&handle, 2, 0, to).await;
+    let request =
+        SendBundleRequest { txs: vec![first, second], block_number: 1, ..Default::default() };
+    api.send_bundle(request).await.unwrap();
+
+    // bundles are not part of the public pool
+    assert_eq!(api.txpool_status().await.unwrap().pending, 1);
+
+    api.mine_one().await;
+    assert_eq!(block_transactions(&api, 1).await, vec![first_hash, second_hash, pool_tx]);
+    assert_eq!(api.backend.pending_bundles(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reverting_bundle_is_not_included() {
+    let (api, handle) = spawn(NodeConfig::test().with_no_mining(true)).await;
+    let to = TxKind::Call(Address::random());
+
+    let (transfer, transfer_hash) = sign(&handle, 0, 0, to).await;
+    let (revert, revert_hash) = sign(&handle, 0, 1, TxKind::Create).await;
+    let txs = vec![transfer, revert];
+    api.send_bundle(SendBundleRequest { txs: txs.clone(), block_number: 1, ..Default::default() })
+        .await
+        .unwrap();
+
+    api.mine_one().await;
+    assert!(block_transactions(&api, 1).await.is_empty());
+    let nonce = api.transaction_count(handle.dev_accounts().next().unwrap(), None).await.unwrap();
+    assert_eq!(nonce, U256::ZERO);
+
+    // the same bundle lands if the transaction is allowed to revert
+    let request = SendBundleRequest {
+        txs,
+        block_number: 2,
+        reverting_tx_hashes: vec![revert_hash],
+        ..Default::default()
+    };
+    api.send_bundle(request).await.unwrap();
+    api.mine_one().await;
+    assert_eq!(block_transactions(&api, 2).await, vec![transfer_hash, revert_hash]);
+    let receipt =
+        handle.http_provider().get_transaction_receipt(revert_hash).await.unwrap().unwrap();
+    assert!(!receipt.status());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bundle_exceeding_block_gas_limit_is_not_included() {
+    let config = NodeConfig::test().with_no_mining(true).with_gas_limit(Some(120_000));
+    let (api, handle) = spawn(config).await;
+    let recipient = Address::random();
+
+    // the first transaction fits into the block, the second one doesn't after it used 21000 gas
+    let (first, _) = sign(&handle, 0, 0, TxKind::Call(recipient)).await;
+    let (second, _) = sign(&handle, 1, 0, TxKind::Call(recipient)).await;
+    let request =
+        SendBundleRequest { txs: vec![first, second], block_number: 1, ..Default::default() };
+    api.send_bundle(request).await.unwrap();
+
+    // none of the transactions is committed
+    api.mine_one().await;
+    assert!(block_transactions(&api, 1).await.is_empty());
+    assert_eq!(api.balance(recipient, None).await.unwrap(), U256::ZERO);
+    let nonce = api.transaction_count(handle.dev_accounts().next().unwrap(), None).await.unwrap();
+    assert_eq!(nonce, U256::ZERO);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bundle_is_only_included_in_target_block() {
+    let (api, handle) = spawn(NodeConfig::test().with_no_mining(true)).await;
+    let (tx, hash) = sign(&handle, 0, 0, TxKind::Call(Address::random())).await;
+    api.send_bundle(SendBundleRequest { txs: vec![tx], block_number: 2, ..Default::default() })
+        .await
+        .unwrap();
+
+    api.mine_one().await;
+    assert!(block_transactions(&api, 1).await.is_empty());
+    api.mine_one().await;
+    assert_eq!(block_transactions(&api, 2).await, vec![hash]);
+
+    let err = api
+        .send_bundle(SendBundleRequest {
+            txs: vec![Bytes::new()],
+            block_number: 1,
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("in the past"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_bundle_via_rpc_with_automine() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let (tx, hash) = sign(&handle, 0, 0, TxKind::Call(Address::random())).await;
+
+    let params = serde_json::json!([{ "txs": [tx], "blockNumber": "0x1" }]);
+    let response: BundleHash = provider.raw_request("eth_sendBundle".into(), params).await.unwrap();
+    assert!(!response.bundle_hash.is_zero());
+
+    assert_eq!(provider.get_block_number().await.unwrap(), 1);
+    assert_eq!(block_transactions(&api, 1).await, vec![hash]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn private_transactions_bypass_the_pool() {
+    let (api, handle) = spawn(NodeConfig::test().with_no_mining(true)).await;
+    let to = TxKind::Call(Address::random());
+
+    let (tx, hash) = sign(&handle, 0, 0, to).await;
+    let request = PrivateTransactionRequest { tx, max_block_number: None };
+    assert_eq!(api.send_private_transaction(request).await.unwrap(), hash);
+    assert_eq!(api.txpool_status().await.unwrap().pending, 0);
+    assert!(api.pool_transaction(hash).is_none());
+
+    api.mine_one().await;
+    assert_eq!(block_transactions(&api, 1).await, vec![hash]);
+
+    // cancelled transactions are not included
+    let (tx, hash) = sign(&handle, 0, 1, to).await;
+    api.send_private_transaction(PrivateTransactionRequest { tx, max_block_number: None })
+        .await
+        .unwrap();
+    let cancel = CancelPrivateTransactionRequest { tx_hash: hash };
+    assert!(api.cancel_private_transaction(cancel.clone()).await.unwrap());
+    assert!(!api.cancel_private_transaction(cancel).await.unwrap());
+    api.mine_one().await;
+    assert!(block_transactions(&api, 2).await.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_use_custom_block_builder() {
+    let config = NodeConfig::test().with_no_mining(true).with_block_builder(
+        |_bundles, mut transactions: Vec<_>| {
+            transactions.reverse();
+            transactions.into_iter().map(BlockItem::Transaction).collect()
+        },
+    );
+    let (api, handle) = spawn(config).await;
+    let to = TxKind::Call(Address::random());
+
+    let first = api.send_raw_transaction(sign(&handle, 0, 0, to).await.0).await.unwrap();
+    let second = api.send_raw_transaction(sign(&handle, 1, 0, to).await.0).await.unwrap();
+    let (bundled, _) = sign(&handle, 2, 0, to).await;
+    api.send_bundle(SendBundleRequest {
+        txs: vec![bundled],
+        block_number: 1,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    // the builder ignores bundles and reverses the pool order
+    api.mine_one().await;
+    assert_eq!(block_transactions(&api, 1).await, vec![second, first]);
+}
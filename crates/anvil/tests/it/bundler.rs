@@ -0,0 +1,188 @@
+//! Tests for the ERC-4337 bundler
+
+use alloy_consensus::Transaction;
+use alloy_primitives::{address, Address, Bytes, B256, U256};
+use alloy_provider::Provider;
+use alloy_sol_types::{SolCall, SolError, SolEvent, SolValue};
+use anvil::{
+    eth::bundler::{
+        handleOpsCall, pack_user_operation, user_operation_hash, with_gas_margin, BundlerConfig,
+        FailedOp, UserOperationEvent, ENTRY_POINT_V07,
+    },
+    spawn, NodeConfig,
+};
+use anvil_core::types::{UserOperation, UserOperationGasEstimation, UserOperationReceipt};
+use std::time::Duration;
+
+const SENDER: Address = address!("00000000000000000000000000000000000000aa");
+
+/// Runtime code that emits `UserOperationEvent(userOpHash, SENDER, 0, 0, true, 0, 0)`, like the
+/// EntryPoint does for an operation that succeeded.
+fn entry_point_code(user_op_hash: B256) -> Bytes {
+    let data = (U256::ZERO, true, U256::ZERO, U256::ZERO).abi_encode();
+    // PUSH1 <size> PUSH1 <offset> PUSH0 CODECOPY PUSH0
+    let mut code = vec![0x60, data.len() as u8, 0x60, 0x6f, 0x5f, 0x39, 0x5f];
+    // PUSH32 <topic> for the sender, the hash and the signature
+    for topic in [SENDER.into_word(), user_op_hash, UserOperationEvent::SIGNATURE_HASH] {
+        code.push(0x7f);
+        code.extend_from_slice(topic.as_slice());
+    }
+    // PUSH1 <size> PUSH0 LOG4 STOP <data>
+    code.extend([0x60, data.len() as u8, 0x5f, 0xa4, 0x00]);
+    assert_eq!(code.len(), 0x6f);
+    code.extend(data);
+    code.into()
+}
+
+/// Runtime code that reverts with the given data
+fn revert_code(data: &[u8]) -> Bytes {
+    // PUSH1 <size> PUSH1 <offset> PUSH0 CODECOPY PUSH1 <size> PUSH0 REVERT <data>
+    let mut code = vec![0x60, data.len() as u8, 0x60, 0x0a, 0x5f, 0x39, 0x60, data.len() as u8];
+    code.extend([0x5f, 0xfd]);
+    code.extend_from_slice(data);
+    code.into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_user_operation() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_bundler(Some(BundlerConfig::default()))).await;
+    let provider = handle.http_provider();
+
+    let entry_points: Vec<Address> =
+        provider.raw_request("eth_supportedEntryPoints".into(), ()).await.unwrap();
+    assert_eq!(entry_points, vec![ENTRY_POINT_V07]);
+
+    let op = UserOperation { sender: SENDER, nonce: U256::from(1), ..Default::default() };
+    let hash = user_operation_hash(&pack_user_operation(&op), ENTRY_POINT_V07, api.chain_id());
+    api.anvil_set_code(ENTRY_POINT_V07, entry_point_code(hash)).await.unwrap();
+
+    let sent: B256 = provider
+        .raw_request("eth_sendUserOperation".into(), (op.clone(), ENTRY_POINT_V07))
+        .await
+        .unwrap();
+    assert_eq!(sent, hash);
+
+    // the receipt is available once the `handleOps` transaction is mined
+    let mut attempts = 0;
+    let receipt = loop {
+        let receipt: Option<UserOperationReceipt> =
+            provider.raw_request("eth_getUserOperationReceipt".into(), [hash]).await.unwrap();
+        if let Some(receipt) = receipt {
+            break receipt
+        }
+        attempts += 1;
+        assert!(attempts < 50, "user operation was not mined");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    };
+    assert_eq!(receipt.user_op_hash, hash);
+    assert_eq!(receipt.sender, SENDER);
+    assert!(receipt.success);
+    assert!(receipt.logs.is_empty());
+    assert_eq!(receipt.receipt.to, Some(ENTRY_POINT_V07));
+
+    // operations are only accepted once
+    provider
+        .raw_request::<_, B256>("eth_sendUserOperation".into(), (op, ENTRY_POINT_V07))
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_pay_fees_to_beneficiary() {
+    let beneficiary = address!("00000000000000000000000000000000000000bb");
+    let config = BundlerConfig { beneficiary: Some(beneficiary), ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_bundler(Some(config))).await;
+    let provider = handle.http_provider();
+
+    let op = UserOperation { sender: SENDER, ..Default::default() };
+    let hash = user_operation_hash(&pack_user_operation(&op), ENTRY_POINT_V07, api.chain_id());
+    api.anvil_set_code(ENTRY_POINT_V07, entry_point_code(hash)).await.unwrap();
+    provider
+        .raw_request::<_, B256>("eth_sendUserOperation".into(), (op, ENTRY_POINT_V07))
+        .await
+        .unwrap();
+    api.mine_one().await;
+
+    // the beneficiary isn't a dev account, the transaction is sent by the last one
+    let receipt: UserOperationReceipt =
+        provider.raw_request("eth_getUserOperationReceipt".into(), [hash]).await.unwrap();
+    assert_eq!(receipt.receipt.from, handle.dev_accounts().last().unwrap());
+    let tx = provider.get_transaction_by_hash(receipt.receipt.transaction_hash).await.unwrap();
+    let call = handleOpsCall::abi_decode(tx.unwrap().input(), true).unwrap();
+    assert_eq!(call.beneficiary, beneficiary);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_failed_user_operation() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_bundler(Some(BundlerConfig::default()))).await;
+    let provider = handle.http_provider();
+
+    // calls to an EntryPoint without code would succeed without executing the operation
+    let op = UserOperation { sender: SENDER, ..Default::default() };
+    let err = provider
+        .raw_request::<_, B256>("eth_sendUserOperation".into(), (op.clone(), ENTRY_POINT_V07))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("is not deployed"), "{err}");
+
+    let failed = FailedOp { opIndex: U256::ZERO, reason: "AA23 reverted".into() }.abi_encode();
+    api.anvil_set_code(ENTRY_POINT_V07, revert_code(&failed)).await.unwrap();
+
+    let err = provider
+        .raw_request::<_, B256>("eth_sendUserOperation".into(), (op.clone(), ENTRY_POINT_V07))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("-32500") && err.contains("AA23 reverted"), "{err}");
+    assert_eq!(api.backend.best_number(), 0);
+
+    // only the configured EntryPoint is supported
+    let err = provider
+        .raw_request::<_, B256>("eth_sendUserOperation".into(), (op, Address::ZERO))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unsupported EntryPoint"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_estimate_user_operation_gas() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_bundler(Some(BundlerConfig::default()))).await;
+    let provider = handle.http_provider();
+
+    // an EntryPoint and an account that accept every operation and call
+    api.anvil_set_code(ENTRY_POINT_V07, Bytes::from_static(&[0x00])).await.unwrap();
+    api.anvil_set_code(SENDER, Bytes::from_static(&[0x00])).await.unwrap();
+    let op = UserOperation { sender: SENDER, ..Default::default() };
+    let estimation: UserOperationGasEstimation = provider
+        .raw_request("eth_estimateUserOperationGas".into(), (op.clone(), ENTRY_POINT_V07))
+        .await
+        .unwrap();
+    assert_eq!(estimation.call_gas_limit, with_gas_margin(0));
+    assert_eq!(estimation.verification_gas_limit, with_gas_margin(0));
+    assert!(estimation.pre_verification_gas > 21_000);
+    assert_eq!(estimation.paymaster_verification_gas_limit, None);
+
+    // an account that rejects every operation
+    api.anvil_set_code(SENDER, revert_code(&[])).await.unwrap();
+    let err = provider
+        .raw_request::<_, UserOperationGasEstimation>(
+            "eth_estimateUserOperationGas".into(),
+            (op, ENTRY_POINT_V07),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("account validation reverted"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bundler_is_disabled_by_default() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    provider
+        .raw_request::<_, Vec<Address>>("eth_supportedEntryPoints".into(), ())
+        .await
+        .unwrap_err();
+}
@@ -0,0 +1,146 @@
+//! custom precompiles and predeploys tests
+
+use alloy_genesis::GenesisAccount;
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{address, bytes, Address, Bytes, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use anvil::{
+    chain_overrides::{PrecompileCall, PrecompileConfig, PrecompileKind},
+    spawn, ChainOverrides, NodeConfig,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+    sync::mpsc,
+};
+
+const BYTECODE_PRECOMPILE: Address = address!("0000000000000000000000000000000000000100");
+const HANDLER_PRECOMPILE: Address = address!("0000000000000000000000000000000000000101");
+const PREDEPLOY: Address = address!("4200000000000000000000000000000000000015");
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(unix)]
+async fn can_use_chain_overrides() {
+    let response = r#"{"jsonrpc":"2.0","id":0,"result":{"output":"0x2a","gasUsed":"0x64"}}"#;
+    let overrides = ChainOverrides {
+        precompiles: vec![
+            PrecompileConfig {
+                address: BYTECODE_PRECOMPILE,
+                // PUSH1 0x2a PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 RETURN
+                kind: PrecompileKind::Predeploy(bytes!("602a60005260206000f3")),
+            },
+            PrecompileConfig {
+                address: HANDLER_PRECOMPILE,
+                kind: PrecompileKind::Command(vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    format!("while read -r _; do echo '{response}'; done"),
+                ]),
+            },
+        ],
+        predeploys: [(
+            PREDEPLOY,
+            GenesisAccount {
+                balance: U256::from(1),
+                storage: Some([(B256::ZERO, B256::with_last_byte(1))].into()),
+                ..Default::default()
+            },
+        )]
+        .into(),
+    };
+    let (_api, handle) = spawn(NodeConfig::test().with_chain_overrides(Some(overrides))).await;
+    let provider = handle.http_provider();
+
+    let tx = WithOtherFields::new(TransactionRequest::default().with_to(BYTECODE_PRECOMPILE));
+    let output = provider.call(&tx).await.unwrap();
+    assert_eq!(output, Bytes::from(B256::with_last_byte(0x2a)));
+
+    let tx = WithOtherFields::new(TransactionRequest::default().with_to(HANDLER_PRECOMPILE));
+    let output = provider.call(&tx).await.unwrap();
+    assert_eq!(output, bytes!("2a"));
+
+    assert_eq!(provider.get_balance(PREDEPLOY).await.unwrap(), U256::from(1));
+    assert_eq!(provider.get_storage_at(PREDEPLOY, U256::ZERO).await.unwrap(), U256::from(1));
+}
+
+/// Serves `precompile_call` requests over HTTP with the given result, and forwards the calls
+///
+/// Returns the URL of the handler.
+async fn spawn_http_handler(
+    result: serde_json::Value,
+) -> (String, mpsc::UnboundedReceiver<PrecompileCall>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (calls_tx, calls_rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                stream.read_line(&mut line).await.unwrap();
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                if line.trim().is_empty() {
+                    break
+                }
+            }
+            let mut body = vec![0; content_length];
+            stream.read_exact(&mut body).await.unwrap();
+            let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(request["method"], "precompile_call");
+            let _ = calls_tx.send(serde_json::from_value(request["params"][0].clone()).unwrap());
+
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": result,
+            })
+            .to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{response}",
+                response.len()
+            );
+            stream.get_mut().write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    (url, calls_rx)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_use_http_precompile_handler() {
+    let (url, mut calls) =
+        spawn_http_handler(serde_json::json!({ "output": "0x2a", "gasUsed": "0x64" })).await;
+    let overrides = ChainOverrides {
+        precompiles: vec![PrecompileConfig {
+            address: HANDLER_PRECOMPILE,
+            kind: PrecompileKind::Url(url),
+        }],
+        predeploys: Default::default(),
+    };
+    let (api, handle) = spawn(NodeConfig::test().with_chain_overrides(Some(overrides))).await;
+    let provider = handle.http_provider();
+
+    let from = handle.dev_accounts().next().unwrap();
+    let tx = WithOtherFields::new(
+        TransactionRequest::default()
+            .with_from(from)
+            .with_to(HANDLER_PRECOMPILE)
+            .with_input(bytes!("c0ffee")),
+    );
+    let output = provider.call(&tx).await.unwrap();
+    assert_eq!(output, bytes!("2a"));
+
+    let call = calls.recv().await.unwrap();
+    assert_eq!(call.address, HANDLER_PRECOMPILE);
+    assert_eq!(call.input, bytes!("c0ffee"));
+    assert_eq!(call.origin, from);
+    assert_eq!(call.chain_id.to::<u64>(), api.chain_id());
+}
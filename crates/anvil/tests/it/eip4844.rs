@@ -2,11 +2,12 @@
 use alloy_consensus::{SidecarBuilder, SimpleCoder, Transaction};
 use alloy_eips::eip4844::{BLOB_TX_MIN_BLOB_GASPRICE, DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK};
 use alloy_network::{EthereumWallet, TransactionBuilder, TransactionBuilder4844};
-use alloy_primitives::U256;
+use alloy_primitives::{B256, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, BlockTransactionsKind, TransactionRequest};
 use alloy_serde::WithOtherFields;
 use anvil::{spawn, EthereumHardfork, NodeConfig};
+use sha2::{Digest, Sha256};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_send_eip4844_transaction() {
@@ -299,3 +300,187 @@ async fn can_correctly_estimate_blob_gas_with_recommended_fillers_with_signer()
         DATA_GAS_PER_BLOB
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_blob_sidecars_from_beacon_api() {
+    let node_config = NodeConfig::test().with_hardfork(Some(EthereumHardfork::Cancun.into()));
+    let (api, handle) = spawn(node_config).await;
+
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let provider = http_provider(&handle.http_endpoint());
+
+    let data = vec![1u8; DATA_GAS_PER_BLOB as usize + 1];
+    let sidecar: SidecarBuilder<SimpleCoder> = SidecarBuilder::from_slice(&data);
+    let sidecar = sidecar.build().unwrap();
+    assert_eq!(sidecar.blobs.len(), 2);
+
+    let gas_price = provider.get_gas_price().await.unwrap();
+    let tx = TransactionRequest::default()
+        .with_from(wallets[0].address())
+        .with_to(wallets[1].address())
+        .with_max_fee_per_blob_gas(gas_price + 1)
+        .with_blob_sidecar(sidecar.clone());
+    let mut tx = WithOtherFields::new(tx);
+    tx.populate_blob_hashes();
+    let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+    let block_hash = receipt.block_hash.unwrap();
+
+    let client = reqwest::Client::new();
+    let get = |path: String| {
+        let client = client.clone();
+        let url = format!("{}{path}", handle.http_endpoint());
+        async move { client.get(url).send().await.unwrap() }
+    };
+
+    let genesis: serde_json::Value =
+        get("/eth/v1/beacon/genesis".to_string()).await.json().await.unwrap();
+    let genesis_time = api.backend.get_block(0).unwrap().header.timestamp;
+    assert_eq!(genesis["data"]["genesis_time"], genesis_time.to_string());
+
+    let spec: serde_json::Value =
+        get("/eth/v1/config/spec".to_string()).await.json().await.unwrap();
+    assert_eq!(spec["data"]["SECONDS_PER_SLOT"], "1");
+
+    let sidecars: serde_json::Value =
+        get("/eth/v1/beacon/blob_sidecars/head".to_string()).await.json().await.unwrap();
+    let sidecars = sidecars["data"].as_array().unwrap();
+    assert_eq!(sidecars.len(), 2);
+    for (index, item) in sidecars.iter().enumerate() {
+        assert_eq!(item["index"], index.to_string());
+        assert_eq!(item["blob"], serde_json::to_value(sidecar.blobs[index]).unwrap());
+        assert_eq!(
+            item["kzg_commitment"],
+            serde_json::to_value(sidecar.commitments[index]).unwrap()
+        );
+        assert_eq!(item["kzg_proof"], serde_json::to_value(sidecar.proofs[index]).unwrap());
+    }
+
+    // the inclusion proofs verify against the body root of the header
+    let header = &sidecars[0]["signed_block_header"]["message"];
+    let body_root: B256 = serde_json::from_value(header["body_root"].clone()).unwrap();
+    for (index, item) in sidecars.iter().enumerate() {
+        let commitment = sidecar.commitments[index];
+        let mut chunk = [0u8; 64];
+        chunk[..48].copy_from_slice(commitment.as_slice());
+        let mut node = B256::from_slice(&Sha256::digest(chunk));
+        let proof: Vec<B256> =
+            serde_json::from_value(item["kzg_commitment_inclusion_proof"].clone()).unwrap();
+        assert_eq!(proof.len(), 17);
+        // the position of the commitment in the `blob_kzg_commitments` field of the body
+        let position = (11 << 13) | index;
+        for (depth, sibling) in proof.iter().enumerate() {
+            let mut hasher = Sha256::new();
+            if (position >> depth) & 1 == 1 {
+                hasher.update(sibling);
+                hasher.update(node);
+            } else {
+                hasher.update(node);
+                hasher.update(sibling);
+            }
+            node = B256::from_slice(&hasher.finalize());
+        }
+        assert_eq!(node, body_root);
+    }
+
+    // look up the block by root and by slot
+    let head: serde_json::Value =
+        get("/eth/v1/beacon/headers/head".to_string()).await.json().await.unwrap();
+    assert_eq!(head["data"]["header"]["message"], *header);
+    let root = head["data"]["root"].as_str().unwrap().to_string();
+    let block = api.backend.get_block_by_hash(block_hash).unwrap();
+    let slot: u64 = header["slot"].as_str().unwrap().parse().unwrap();
+    assert!(slot >= block.header.timestamp - genesis_time);
+    for block_id in [root.clone(), slot.to_string()] {
+        let sidecars: serde_json::Value =
+            get(format!("/eth/v1/beacon/blob_sidecars/{block_id}?indices=1"))
+                .await
+                .json()
+                .await
+                .unwrap();
+        let sidecars = sidecars["data"].as_array().unwrap();
+        assert_eq!(sidecars.len(), 1);
+        assert_eq!(sidecars[0]["index"], "1");
+        assert_eq!(sidecars[0]["signed_block_header"]["message"]["slot"], slot.to_string());
+    }
+
+    // blocks are identified by their beacon block root, not the execution block hash
+    let response = get(format!("/eth/v1/beacon/blob_sidecars/{block_hash}")).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    // a block in the slot of its parent is moved to the next slot
+    api.evm_set_next_block_timestamp(block.header.timestamp).unwrap();
+    api.evm_mine(None).await.unwrap();
+    let next: serde_json::Value =
+        get(format!("/eth/v1/beacon/headers/{}", slot + 1)).await.json().await.unwrap();
+    let next = &next["data"]["header"]["message"];
+    assert_eq!(next["parent_root"], root.as_str());
+    let next_block = api.backend.get_block(block.header.number + 1).unwrap();
+    assert_eq!(next_block.header.timestamp, block.header.timestamp);
+
+    let sidecars: serde_json::Value =
+        get("/eth/v1/beacon/blob_sidecars/genesis".to_string()).await.json().await.unwrap();
+    assert!(sidecars["data"].as_array().unwrap().is_empty());
+
+    let response = get("/eth/v1/beacon/blob_sidecars/latest".to_string()).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_blob_sidecars_from_beacon_api_in_fork_mode() {
+    let node_config = NodeConfig::test().with_hardfork(Some(EthereumHardfork::Cancun.into()));
+    let (origin_api, origin_handle) = spawn(node_config.clone()).await;
+    origin_api.evm_mine(None).await.unwrap();
+    origin_api.evm_mine(None).await.unwrap();
+    let fork_block = origin_api.backend.get_block(2).unwrap();
+
+    let (api, handle) =
+        spawn(node_config.with_eth_rpc_url(Some(origin_handle.http_endpoint()))).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let provider = http_provider(&handle.http_endpoint());
+
+    let sidecar: SidecarBuilder<SimpleCoder> =
+        SidecarBuilder::from_slice(&vec![1u8; DATA_GAS_PER_BLOB as usize + 1]);
+    let sidecar = sidecar.build().unwrap();
+    let gas_price = provider.get_gas_price().await.unwrap();
+    let tx = TransactionRequest::default()
+        .with_from(wallets[0].address())
+        .with_to(wallets[1].address())
+        .with_max_fee_per_blob_gas(gas_price + 1)
+        .with_blob_sidecar(sidecar.clone());
+    let mut tx = WithOtherFields::new(tx);
+    tx.populate_blob_hashes();
+    provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+
+    let client = reqwest::Client::new();
+    let get = |path: &str| {
+        let client = client.clone();
+        let url = format!("{}{path}", handle.http_endpoint());
+        async move { client.get(url).send().await.unwrap().json::<serde_json::Value>().await }
+    };
+
+    // the beacon chain starts at the forked block
+    let genesis = get("/eth/v1/beacon/genesis").await.unwrap();
+    assert_eq!(genesis["data"]["genesis_time"], fork_block.header.timestamp.to_string());
+    let genesis = get("/eth/v1/beacon/headers/genesis").await.unwrap();
+    let genesis = &genesis["data"]["header"]["message"];
+    assert_eq!(genesis["slot"], "0");
+    assert_eq!(genesis["state_root"], fork_block.header.state_root.to_string());
+    let sidecars = get("/eth/v1/beacon/blob_sidecars/genesis").await.unwrap();
+    assert!(sidecars["data"].as_array().unwrap().is_empty());
+
+    let head = get("/eth/v1/beacon/headers/head").await.unwrap();
+    let head = &head["data"]["header"]["message"];
+    let block = api.backend.get_block(api.backend.best_number()).unwrap();
+    assert_eq!(block.header.number, fork_block.header.number + 1);
+    let slot = (block.header.timestamp - fork_block.header.timestamp).max(1);
+    assert_eq!(head["slot"], slot.to_string());
+
+    // the query is percent-decoded
+    let sidecars = get("/eth/v1/beacon/blob_sidecars/head?indices=1%2c0").await.unwrap();
+    let sidecars = sidecars["data"].as_array().unwrap();
+    assert_eq!(sidecars.len(), 2);
+    assert_eq!(
+        sidecars[1]["kzg_commitment"],
+        serde_json::to_value(sidecar.commitments[1]).unwrap()
+    );
+}
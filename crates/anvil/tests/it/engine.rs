@@ -0,0 +1,305 @@
+//! Engine API tests
+
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{address, hex, Address, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{
+    engine::{
+        Claims, ExecutionPayloadEnvelopeV3, ForkchoiceState, ForkchoiceUpdated, PayloadAttributes,
+        PayloadStatus, PayloadStatusEnum,
+    },
+    BlockId, TransactionRequest,
+};
+use alloy_serde::WithOtherFields;
+use anvil::{spawn, NodeConfig, NodeHandle};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// Sends a JSON-RPC request to the authenticated endpoint and returns the result
+async fn engine_call<T: DeserializeOwned>(handle: &NodeHandle, method: &str, params: Value) -> T {
+    let token = handle.jwt_secret().unwrap().encode(&Claims::default()).unwrap();
+    let response: Value = reqwest::Client::new()
+        .post(handle.auth_endpoint().unwrap())
+        .bearer_auth(token)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    match response.get("result") {
+        Some(result) => serde_json::from_value(result.clone()).unwrap(),
+        None => panic!("{method} failed: {response}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_build_blocks_via_engine_api() {
+    let (_api, handle) = spawn(NodeConfig::test().with_engine_api(true)).await;
+    let provider = handle.http_provider();
+
+    let from = handle.dev_accounts().next().unwrap();
+    let to = Address::random();
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(1));
+    let tx_hash = *provider.send_transaction(WithOtherFields::new(tx)).await.unwrap().tx_hash();
+
+    // transactions are not mined on their own
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    assert_eq!(provider.get_block_number().await.unwrap(), 0);
+
+    let genesis = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    let fee_recipient = address!("0x00000000000000000000000000000000000000fe");
+    let attributes = PayloadAttributes {
+        timestamp: genesis.header.timestamp + 12,
+        prev_randao: B256::with_last_byte(1),
+        suggested_fee_recipient: fee_recipient,
+        withdrawals: Some(vec![]),
+        parent_beacon_block_root: Some(B256::with_last_byte(2)),
+    };
+    let state = ForkchoiceState {
+        head_block_hash: genesis.header.hash,
+        safe_block_hash: B256::ZERO,
+        finalized_block_hash: B256::ZERO,
+    };
+    let updated: ForkchoiceUpdated =
+        engine_call(&handle, "engine_forkchoiceUpdatedV3", json!([state, attributes])).await;
+    assert!(updated.is_valid());
+    let payload_id = updated.payload_id.unwrap();
+
+    let envelope: ExecutionPayloadEnvelopeV3 =
+        engine_call(&handle, "engine_getPayloadV3", json!([payload_id])).await;
+    let payload = envelope.execution_payload;
+    let block_hash = payload.payload_inner.payload_inner.block_hash;
+    assert_eq!(payload.payload_inner.payload_inner.block_number, 1);
+    assert_eq!(payload.payload_inner.payload_inner.transactions.len(), 1);
+    assert_eq!(payload.payload_inner.payload_inner.fee_recipient, fee_recipient);
+    // building the payload doesn't change the chain or its state
+    assert_eq!(provider.get_block_number().await.unwrap(), 0);
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::ZERO);
+
+    // a payload that doesn't match its hash is rejected
+    let mut tampered = payload.clone();
+    tampered.payload_inner.payload_inner.extra_data = vec![1].into();
+    let status: PayloadStatus = engine_call(
+        &handle,
+        "engine_newPayloadV3",
+        json!([tampered, Vec::<B256>::new(), B256::with_last_byte(2)]),
+    )
+    .await;
+    assert!(status.is_invalid());
+    assert_eq!(status.latest_valid_hash, Some(genesis.header.hash));
+
+    let status: PayloadStatus = engine_call(
+        &handle,
+        "engine_newPayloadV3",
+        json!([payload, Vec::<B256>::new(), B256::with_last_byte(2)]),
+    )
+    .await;
+    assert_eq!(status.status, PayloadStatusEnum::Valid);
+    assert_eq!(status.latest_valid_hash, Some(block_hash));
+
+    let block = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(block.header.hash, block_hash);
+    assert_eq!(block.header.timestamp, attributes.timestamp);
+    assert_eq!(block.header.beneficiary, fee_recipient);
+    assert_eq!(block.header.mix_hash, Some(attributes.prev_randao));
+    let receipt = provider.get_transaction_receipt(tx_hash).await.unwrap().unwrap();
+    assert_eq!(receipt.block_hash, Some(block_hash));
+
+    // payloads extending a block that isn't the head are validated as well
+    let mut side = tampered;
+    side.payload_inner.payload_inner.block_hash = B256::with_last_byte(3);
+    let status: PayloadStatus = engine_call(
+        &handle,
+        "engine_newPayloadV3",
+        json!([side, Vec::<B256>::new(), B256::with_last_byte(2)]),
+    )
+    .await;
+    assert!(status.is_invalid());
+    assert_eq!(provider.get_block_number().await.unwrap(), 1);
+
+    let state = ForkchoiceState { head_block_hash: block_hash, ..state };
+    let updated: ForkchoiceUpdated =
+        engine_call(&handle, "engine_forkchoiceUpdatedV3", json!([state])).await;
+    assert!(updated.is_valid());
+    assert!(updated.payload_id.is_none());
+
+    // moving the head back to the genesis block discards the new block
+    let state = ForkchoiceState { head_block_hash: genesis.header.hash, ..state };
+    let updated: ForkchoiceUpdated =
+        engine_call(&handle, "engine_forkchoiceUpdatedV3", json!([state])).await;
+    assert_eq!(updated.payload_status.latest_valid_hash, Some(genesis.header.hash));
+    assert_eq!(provider.get_block_number().await.unwrap(), 0);
+
+    // unknown heads can't be applied
+    let state = ForkchoiceState { head_block_hash: B256::random(), ..state };
+    let updated: ForkchoiceUpdated =
+        engine_call(&handle, "engine_forkchoiceUpdatedV3", json!([state])).await;
+    assert!(updated.is_syncing());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_import_payload_with_different_gas_limit_and_base_fee() {
+    let config = NodeConfig::test().with_engine_api(true).with_genesis_timestamp(Some(1_000u64));
+    let (_api, builder) = spawn(config.clone()).await;
+    let (api, importer) = spawn(config).await;
+
+    // the importing node would pick different values for the next block
+    api.evm_set_block_gas_limit(U256::from(20_000_000)).unwrap();
+    api.anvil_set_next_block_base_fee_per_gas(U256::from(7)).await.unwrap();
+
+    let provider = builder.http_provider();
+    let genesis = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    let state = ForkchoiceState {
+        head_block_hash: genesis.header.hash,
+        safe_block_hash: B256::ZERO,
+        finalized_block_hash: B256::ZERO,
+    };
+    let attributes = PayloadAttributes {
+        timestamp: genesis.header.timestamp + 12,
+        prev_randao: B256::with_last_byte(1),
+        suggested_fee_recipient: Address::ZERO,
+        withdrawals: Some(vec![]),
+        parent_beacon_block_root: Some(B256::with_last_byte(2)),
+    };
+    let updated: ForkchoiceUpdated =
+        engine_call(&builder, "engine_forkchoiceUpdatedV3", json!([state, attributes])).await;
+    let envelope: ExecutionPayloadEnvelopeV3 =
+        engine_call(&builder, "engine_getPayloadV3", json!([updated.payload_id.unwrap()])).await;
+    let payload = envelope.execution_payload;
+
+    let status: PayloadStatus = engine_call(
+        &importer,
+        "engine_newPayloadV3",
+        json!([payload, Vec::<B256>::new(), B256::with_last_byte(2)]),
+    )
+    .await;
+    assert_eq!(status.status, PayloadStatusEnum::Valid);
+
+    let block =
+        importer.http_provider().get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(block.header.gas_limit, payload.payload_inner.payload_inner.gas_limit);
+    assert_eq!(
+        block.header.base_fee_per_gas.map(U256::from),
+        Some(payload.payload_inner.payload_inner.base_fee_per_gas)
+    );
+}
+
+/// Requests a payload on top of `head` from the node and returns it
+async fn build_payload(handle: &NodeHandle, head: B256, attributes: PayloadAttributes) -> Value {
+    let state = ForkchoiceState {
+        head_block_hash: head,
+        safe_block_hash: B256::ZERO,
+        finalized_block_hash: B256::ZERO,
+    };
+    let updated: ForkchoiceUpdated =
+        engine_call(handle, "engine_forkchoiceUpdatedV3", json!([state, attributes])).await;
+    let envelope: ExecutionPayloadEnvelopeV3 =
+        engine_call(handle, "engine_getPayloadV3", json!([updated.payload_id.unwrap()])).await;
+    json!([envelope.execution_payload, Vec::<B256>::new(), attributes.parent_beacon_block_root])
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reorg_onto_side_payloads() {
+    let config = NodeConfig::test().with_engine_api(true).with_genesis_timestamp(Some(1_000u64));
+    let (_api, builder) = spawn(config.clone()).await;
+    let (_api, importer) = spawn(config).await;
+    let provider = importer.http_provider();
+
+    let genesis = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    let attributes = |timestamp: u64, prev_randao: u8| PayloadAttributes {
+        timestamp,
+        prev_randao: B256::with_last_byte(prev_randao),
+        suggested_fee_recipient: Address::ZERO,
+        withdrawals: Some(vec![]),
+        parent_beacon_block_root: Some(B256::with_last_byte(1)),
+    };
+
+    // the importer follows an empty block
+    let a1 = build_payload(&builder, genesis.header.hash, attributes(1_012, 1)).await;
+    let a1_hash: B256 = serde_json::from_value(a1[0]["blockHash"].clone()).unwrap();
+    let status: PayloadStatus = engine_call(&importer, "engine_newPayloadV3", a1).await;
+    assert_eq!(status.status, PayloadStatusEnum::Valid);
+
+    // while the builder continues on another branch with a transfer
+    let from = builder.dev_accounts().next().unwrap();
+    let to = Address::random();
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(1));
+    let _ = builder.http_provider().send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    let b1 = build_payload(&builder, genesis.header.hash, attributes(1_012, 2)).await;
+    let b1_hash: B256 = serde_json::from_value(b1[0]["blockHash"].clone()).unwrap();
+    let status: PayloadStatus = engine_call(&builder, "engine_newPayloadV3", b1.clone()).await;
+    assert_eq!(status.status, PayloadStatusEnum::Valid);
+    let b2 = build_payload(&builder, b1_hash, attributes(1_024, 3)).await;
+    let b2_hash: B256 = serde_json::from_value(b2[0]["blockHash"].clone()).unwrap();
+
+    // the side payloads are validated without changing the chain of the importer
+    for payload in [b1, b2] {
+        let status: PayloadStatus = engine_call(&importer, "engine_newPayloadV3", payload).await;
+        assert_eq!(status.status, PayloadStatusEnum::Valid);
+    }
+    let latest = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(latest.header.hash, a1_hash);
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::ZERO);
+
+    // a forkchoice update reorgs onto the side chain
+    let state = ForkchoiceState {
+        head_block_hash: b2_hash,
+        safe_block_hash: B256::ZERO,
+        finalized_block_hash: B256::ZERO,
+    };
+    let updated: ForkchoiceUpdated =
+        engine_call(&importer, "engine_forkchoiceUpdatedV3", json!([state])).await;
+    assert!(updated.is_valid());
+    let latest = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(latest.header.hash, b2_hash);
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(1));
+
+    // and back onto the discarded block
+    let state = ForkchoiceState { head_block_hash: a1_hash, ..state };
+    let updated: ForkchoiceUpdated =
+        engine_call(&importer, "engine_forkchoiceUpdatedV3", json!([state])).await;
+    assert!(updated.is_valid());
+    let latest = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(latest.header.hash, a1_hash);
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::ZERO);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn engine_api_requires_jwt() {
+    let (_api, handle) = spawn(NodeConfig::test().with_engine_api(true)).await;
+
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "engine_exchangeCapabilities",
+        "params": [[]]
+    });
+    let response = reqwest::Client::new()
+        .post(handle.auth_endpoint().unwrap())
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let capabilities: Vec<String> =
+        engine_call(&handle, "engine_exchangeCapabilities", json!([[]])).await;
+    assert!(capabilities.contains(&"engine_newPayloadV3".to_string()));
+
+    // regular requests are served as well
+    let chain_id: U256 = engine_call(&handle, "eth_chainId", json!([])).await;
+    assert_eq!(chain_id, U256::from(handle.config().get_chain_id()));
+
+    // the random secret is only written to a file the current user can access
+    let path = handle.jwt_secret_path().unwrap();
+    let secret = std::fs::read_to_string(path).unwrap();
+    assert_eq!(secret, hex::encode(handle.jwt_secret().unwrap().as_bytes()));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
@@ -2,18 +2,27 @@
 mod anvil;
 mod anvil_api;
 mod api;
+mod bundle;
+mod bundler;
+mod chain_overrides;
 mod eip4844;
 mod eip7702;
+mod engine;
 mod fork;
 mod gas;
 mod genesis;
 mod ipc;
 mod logs;
+mod metrics;
+mod multichain;
+mod network;
+mod op_bridge;
 mod optimism;
 mod otterscan;
 mod proof;
 mod pubsub;
 mod revert;
+mod session;
 mod sign;
 mod state;
 mod traces;
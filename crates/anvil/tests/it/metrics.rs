@@ -0,0 +1,62 @@
+//! Tests for the Prometheus metrics endpoint
+
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use anvil::{spawn, NodeConfig};
+
+async fn scrape(endpoint: &str) -> String {
+    let response = reqwest::get(endpoint).await.unwrap();
+    assert!(response.status().is_success());
+    response.text().await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn metrics_are_disabled_by_default() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    assert!(handle.metrics_endpoint().is_none());
+
+    let response = reqwest::get(format!("{}/metrics", handle.http_endpoint())).await.unwrap();
+    assert!(!response.status().is_success());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_scrape_metrics() {
+    let (_api, handle) = spawn(NodeConfig::test().with_metrics_port(Some(0))).await;
+    let endpoint = handle.metrics_endpoint().unwrap();
+    let provider = handle.http_provider();
+
+    provider.get_block_number().await.unwrap();
+    provider.get_chain_id().await.unwrap();
+    provider.get_block_number().await.unwrap();
+    let _ = provider.send_raw_transaction(&Bytes::from_static(&[0x01])).await.unwrap_err();
+
+    let metrics = scrape(&endpoint).await;
+    assert!(metrics.contains("# TYPE anvil_rpc_requests_total counter"), "{metrics}");
+    assert!(metrics.contains("anvil_rpc_requests_total{method=\"eth_blockNumber\"} 2"));
+    assert!(metrics.contains("anvil_rpc_requests_total{method=\"eth_chainId\"} 1"));
+    assert!(metrics.contains("anvil_rpc_errors_total{method=\"eth_sendRawTransaction\"} 1"));
+    assert!(metrics.contains("anvil_rpc_errors_total{method=\"eth_blockNumber\"} 0"));
+    assert!(metrics.contains(
+        "anvil_rpc_request_duration_seconds_bucket{method=\"eth_blockNumber\",le=\"+Inf\"} 2"
+    ));
+    assert!(metrics.contains("anvil_miner_blocks_total 0"));
+    // fork metrics are only reported in forking mode
+    assert!(!metrics.contains("anvil_fork_"));
+
+    let from = handle.dev_accounts().next().unwrap();
+    let tx = TransactionRequest::default().with_from(from).with_to(Address::random());
+    let tx = WithOtherFields::new(tx.with_value(U256::from(1)));
+    provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+
+    let metrics = scrape(&endpoint).await;
+    assert!(metrics.contains("anvil_txpool_added_total 1"), "{metrics}");
+    assert!(metrics.contains("anvil_txpool_mined_total 1"));
+    assert!(metrics.contains("anvil_txpool_pending 0"));
+    assert!(metrics.contains("anvil_block_number 1"));
+    assert!(metrics.contains("anvil_miner_blocks_total 1"));
+    assert!(metrics.contains("anvil_miner_transactions_total 1"));
+    assert!(metrics.contains("anvil_miner_block_time_seconds_count 1"));
+}
@@ -0,0 +1,92 @@
+//! Tests for serving several chains from one process
+
+use crate::utils::http_provider;
+use alloy_primitives::U256;
+use alloy_provider::Provider;
+use alloy_rpc_types::{anvil::MineOptions, BlockNumberOrTag, BlockTransactionsKind};
+use anvil::{spawn_chains, NodeConfig};
+use anvil_core::eth::EthRequest;
+use anvil_rpc::response::ResponseResult;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_serve_chains_with_shared_clock() {
+    let chains = spawn_chains(vec![
+        NodeConfig::test().with_chain_id(Some(1u64)).with_genesis_timestamp(Some(1_700_000_000u64)),
+        NodeConfig::test().with_chain_id(Some(10u64)),
+    ])
+    .await;
+    assert_eq!(chains.chain_ids().collect::<Vec<_>>(), vec![1, 10]);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let router = chains.router(Default::default());
+    tokio::spawn(async move { axum::serve(listener, router.into_make_service()).await });
+
+    let control = http_provider(&format!("http://{addr}"));
+    let providers = [1u64, 10].map(|chain_id| http_provider(&format!("http://{addr}/{chain_id}")));
+    for (provider, chain_id) in providers.iter().zip([1, 10]) {
+        assert_eq!(provider.get_chain_id().await.unwrap(), chain_id);
+    }
+
+    // the genesis of chain 1 is older, but its clock was moved to the time of chain 10
+    let timestamp = chains.sync_clocks();
+    for chain_id in [1, 10] {
+        let api = chains.api(chain_id).unwrap();
+        assert_eq!(api.backend.time().current_call_timestamp(), timestamp);
+    }
+
+    let next: serde_json::Value =
+        control.raw_request("evm_setNextBlockTimestamp".into(), [timestamp + 100]).await.unwrap();
+    assert_eq!(next.as_object().unwrap().len(), 2);
+    control.raw_request::<_, serde_json::Value>("evm_mine".into(), ()).await.unwrap();
+    for provider in &providers {
+        let block = provider
+            .get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.header.number, 1);
+        assert_eq!(block.header.timestamp, timestamp + 100);
+    }
+
+    control.raw_request::<_, serde_json::Value>("evm_increaseTime".into(), [3600]).await.unwrap();
+    for chain_id in [1, 10] {
+        let api = chains.api(chain_id).unwrap();
+        assert!(api.backend.time().current_call_timestamp() >= timestamp + 3700);
+    }
+
+    // only time and mining calls are supported by the control endpoint
+    control.get_block_number().await.unwrap_err();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn control_calls_are_applied_to_all_chains_or_none() {
+    let chains = spawn_chains(vec![
+        NodeConfig::test().with_chain_id(Some(1u64)),
+        NodeConfig::test().with_chain_id(Some(10u64)),
+    ])
+    .await;
+
+    // chain 10 is ahead of chain 1
+    let newer = chains.api(10).unwrap();
+    let timestamp = newer.backend.time().current_call_timestamp() + 1000;
+    newer.evm_mine(Some(MineOptions::Timestamp(Some(timestamp)))).await.unwrap();
+
+    let request = serde_json::from_value::<EthRequest>(
+        serde_json::json!({ "method": "evm_mine", "params": [timestamp - 500] }),
+    )
+    .unwrap();
+    assert!(matches!(chains.execute(request).await, ResponseResult::Error(_)));
+
+    // the call failed on chain 10, so chain 1 didn't mine either
+    assert_eq!(chains.api(1).unwrap().block_number().unwrap(), U256::ZERO);
+    assert_eq!(chains.api(10).unwrap().block_number().unwrap(), U256::from(1));
+
+    let request = serde_json::from_value::<EthRequest>(
+        serde_json::json!({ "method": "evm_mine", "params": [timestamp + 500] }),
+    )
+    .unwrap();
+    assert!(matches!(chains.execute(request).await, ResponseResult::Success(_)));
+    assert_eq!(chains.api(1).unwrap().block_number().unwrap(), U256::from(1));
+    assert_eq!(chains.api(10).unwrap().block_number().unwrap(), U256::from(2));
+}
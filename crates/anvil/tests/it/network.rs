@@ -0,0 +1,161 @@
+//! Tests for networks of multiple nodes
+
+use crate::utils::http_provider;
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{Address, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use anvil::{eth::EthApi, spawn_network, NodeConfig, NodeNetwork};
+use std::time::Duration;
+
+async fn spawn_nodes(num: usize) -> NodeNetwork {
+    spawn_network((0..num).map(|_| NodeConfig::test().with_no_mining(true))).await
+}
+
+/// Waits until the condition holds for all nodes
+async fn wait_for(network: &NodeNetwork, condition: impl Fn(&EthApi) -> bool) {
+    for _ in 0..500 {
+        if (0..network.len()).all(|node| condition(network.api(node))) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("network did not converge: {network:?}");
+}
+
+async fn send_transfer(network: &NodeNetwork, node: usize) -> B256 {
+    let handle = network.handle(node);
+    let from = handle.dev_accounts().next().unwrap();
+    let tx = TransactionRequest::default()
+        .with_from(from)
+        .with_to(Address::random())
+        .with_value(U256::from(1337));
+    let provider = handle.http_provider();
+    *provider.send_transaction(WithOtherFields::new(tx)).await.unwrap().tx_hash()
+}
+
+async fn pending_transactions(api: &EthApi) -> u64 {
+    api.txpool_status().await.unwrap().pending
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_gossip_transactions_and_blocks() {
+    let network = spawn_nodes(3).await;
+    let genesis = network.api(0).backend.best_hash();
+    wait_for(&network, |api| api.backend.best_hash() == genesis).await;
+
+    send_transfer(&network, 0).await;
+    for node in 0..network.len() {
+        for _ in 0..500 {
+            if pending_transactions(network.api(node)).await == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(pending_transactions(network.api(node)).await, 1);
+    }
+
+    // a block mined by any node is imported by all others
+    network.api(1).mine_one().await;
+    let head = network.api(1).backend.best_hash();
+    wait_for(&network, |api| api.backend.best_hash() == head).await;
+    for node in 0..network.len() {
+        assert_eq!(pending_transactions(network.api(node)).await, 0);
+        let block = network.api(node).backend.get_block(1).unwrap();
+        assert_eq!(block.transactions.len(), 1);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn partitioned_nodes_reorg_on_heal() {
+    let network = spawn_nodes(3).await;
+    network.partition(&[&[0, 1], &[2]]);
+    assert!(network.is_connected(0, 1));
+    assert!(!network.is_connected(0, 2));
+
+    // node 0 and 1 build a chain with one block, node 2 a chain with two blocks
+    let tx_hash = send_transfer(&network, 0).await;
+    network.api(0).mine_one().await;
+    network.api(2).mine_one().await;
+    network.api(2).mine_one().await;
+    network.sync().await;
+
+    let minority_head = network.api(0).backend.best_hash();
+    let majority_head = network.api(2).backend.best_hash();
+    assert_eq!(network.api(1).backend.best_hash(), minority_head);
+    assert_eq!(network.api(2).backend.best_number(), 2);
+    assert!(network.api(0).backend.transaction_receipt(tx_hash).await.unwrap().is_some());
+
+    // the longer chain wins
+    network.heal().await;
+    for node in 0..network.len() {
+        let api = network.api(node);
+        assert_eq!(api.backend.best_hash(), majority_head);
+        assert!(api.backend.get_block_by_hash(minority_head).is_none());
+        assert!(api.backend.transaction_receipt(tx_hash).await.unwrap().is_none());
+    }
+
+    // the dropped transaction is back in the pool and included in the next block
+    assert_eq!(pending_transactions(network.api(0)).await, 1);
+    network.api(0).mine_one().await;
+    network.sync().await;
+    for node in 0..network.len() {
+        let api = network.api(node);
+        assert_eq!(api.backend.best_number(), 3);
+        assert!(api.backend.transaction_receipt(tx_hash).await.unwrap().is_some());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn equal_length_chains_converge() {
+    let network = spawn_nodes(2).await;
+    network.partition(&[]);
+    send_transfer(&network, 0).await;
+    send_transfer(&network, 1).await;
+    network.api(0).mine_one().await;
+    network.api(1).mine_one().await;
+    assert_ne!(network.api(0).backend.best_hash(), network.api(1).backend.best_hash());
+
+    network.heal().await;
+    let head = network.api(0).backend.best_hash();
+    assert_eq!(network.api(1).backend.best_hash(), head);
+    assert_eq!(network.api(1).backend.best_number(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_partition_and_heal_over_rpc() {
+    let network = spawn_nodes(2).await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let router = network.router(Default::default());
+    tokio::spawn(async move { axum::serve(listener, router.into_make_service()).await });
+
+    let control = http_provider(&format!("http://{addr}"));
+    let providers = [0, 1].map(|node| http_provider(&format!("http://{addr}/{node}")));
+
+    control
+        .raw_request::<_, serde_json::Value>("anvil_partitionNetwork".into(), [[[0], [1]]])
+        .await
+        .unwrap();
+    assert!(!network.is_connected(0, 1));
+    control
+        .raw_request::<_, serde_json::Value>("anvil_partitionNetwork".into(), [[[0], [2]]])
+        .await
+        .unwrap_err();
+
+    providers[0].raw_request::<_, serde_json::Value>("evm_mine".into(), ()).await.unwrap();
+    for _ in 0..2 {
+        providers[1].raw_request::<_, serde_json::Value>("evm_mine".into(), ()).await.unwrap();
+    }
+    assert_eq!(providers[0].get_block_number().await.unwrap(), 1);
+
+    control.raw_request::<_, serde_json::Value>("anvil_healNetwork".into(), ()).await.unwrap();
+    assert!(network.is_connected(0, 1));
+    let head = network.api(1).backend.best_hash();
+    assert_eq!(network.api(0).backend.best_hash(), head);
+    assert_eq!(providers[0].get_block_number().await.unwrap(), 2);
+
+    // only network calls are supported by the control endpoint
+    control.get_block_number().await.unwrap_err();
+}
@@ -0,0 +1,164 @@
+//! Tests for the bridge between an L1 and an OP stack L2 chain
+
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{address, keccak256, Address, Bytes, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockNumberOrTag, BlockTransactionsKind, TransactionRequest};
+use alloy_serde::WithOtherFields;
+use alloy_sol_types::{SolEvent, SolValue};
+use anvil::{
+    op_bridge::{withdrawal_slot, MessagePassed, TransactionDeposited, L2_TO_L1_MESSAGE_PASSER},
+    spawn_op_bridge, NodeConfig,
+};
+use futures::StreamExt;
+use std::time::Duration;
+
+/// Runtime code that copies the calldata to memory, optionally sets `slot` to 1, and emits the
+/// memory as the data of a log with the event signature and `topics`, in this order.
+///
+/// Every topic is either the caller or zero.
+fn emitter_code(signature: B256, slot: Option<B256>, caller_topics: [bool; 3]) -> Bytes {
+    // CALLDATASIZE PUSH0 PUSH0 CALLDATACOPY
+    let mut code = vec![0x36, 0x5f, 0x5f, 0x37];
+    if let Some(slot) = slot {
+        // PUSH1 1 PUSH32 <slot> SSTORE
+        code.extend([0x60, 0x01, 0x7f]);
+        code.extend_from_slice(slot.as_slice());
+        code.push(0x55);
+    }
+    // topics are pushed in reverse order, CALLER or PUSH0
+    for caller in caller_topics.iter().rev() {
+        code.push(if *caller { 0x33 } else { 0x5f });
+    }
+    // PUSH32 <signature> CALLDATASIZE PUSH0 LOG4 STOP
+    code.push(0x7f);
+    code.extend_from_slice(signature.as_slice());
+    code.extend([0x36, 0x5f, 0xa4, 0x00]);
+    code.into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_relay_deposits() {
+    let portal: Address = address!("bEb5Fc579115071764c7423A4f12eDde41f106Ed");
+    let bridge = spawn_op_bridge(
+        NodeConfig::test().with_chain_id(Some(1u64)),
+        NodeConfig::test().with_chain_id(Some(10u64)).with_no_mining(true),
+        portal,
+    )
+    .await;
+
+    // a portal that emits `TransactionDeposited(caller, caller, 0, opaqueData)`
+    let code = emitter_code(TransactionDeposited::SIGNATURE_HASH, None, [true, true, false]);
+    bridge.l1_api().anvil_set_code(portal, code).await.unwrap();
+
+    let from = bridge.l1_handle().dev_accounts().next().unwrap();
+    let l2 = bridge.l2_handle().http_provider();
+    let before = l2.get_balance(from).await.unwrap();
+
+    // a pending L2 transaction that pays a high tip
+    let other = bridge.l2_handle().dev_accounts().nth(1).unwrap();
+    let tx = TransactionRequest::default()
+        .with_from(other)
+        .with_to(other)
+        .with_max_fee_per_gas(100_000_000_000)
+        .with_max_priority_fee_per_gas(100_000_000_000);
+    let pending = *l2.send_transaction(WithOtherFields::new(tx)).await.unwrap().tx_hash();
+    let mut ready = bridge.l2_api().new_ready_transactions();
+
+    let mint = U256::from(10).pow(U256::from(18));
+    let mut opaque_data = Vec::new();
+    opaque_data.extend_from_slice(&mint.to_be_bytes::<32>());
+    opaque_data.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
+    opaque_data.extend_from_slice(&100_000u64.to_be_bytes());
+    opaque_data.push(0);
+    let tx = TransactionRequest::default()
+        .with_from(from)
+        .with_to(portal)
+        .with_input(Bytes::from(opaque_data).abi_encode());
+    let l1 = bridge.l1_handle().http_provider();
+    l1.send_transaction(WithOtherFields::new(tx)).await.unwrap().get_receipt().await.unwrap();
+
+    // the deposit is relayed to L2, and mined before the pending transaction
+    let deposit = tokio::time::timeout(Duration::from_secs(10), ready.next())
+        .await
+        .expect("deposit was not relayed")
+        .unwrap();
+    bridge.l2_api().evm_mine(None).await.unwrap();
+    let block = l2
+        .get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(block.transactions.hashes().collect::<Vec<_>>(), vec![deposit, pending]);
+    assert_eq!(l2.get_balance(from).await.unwrap(), before + mint);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_prove_withdrawals() {
+    let bridge = spawn_op_bridge(
+        NodeConfig::test().with_chain_id(Some(1u64)),
+        NodeConfig::test().with_chain_id(Some(10u64)),
+        Address::ZERO,
+    )
+    .await;
+    let api = bridge.l2_api();
+
+    // a message passer that emits `MessagePassed(0, caller, caller, ...)` and marks the
+    // withdrawal as sent
+    let withdrawal_hash = keccak256("withdrawal");
+    let code = emitter_code(
+        MessagePassed::SIGNATURE_HASH,
+        Some(withdrawal_slot(withdrawal_hash)),
+        [false, true, true],
+    );
+    api.anvil_set_code(L2_TO_L1_MESSAGE_PASSER, code).await.unwrap();
+
+    let from = bridge.l2_handle().dev_accounts().next().unwrap();
+    let data = (U256::from(1), U256::from(100_000), Bytes::new(), withdrawal_hash);
+    let tx = TransactionRequest::default()
+        .with_from(from)
+        .with_to(L2_TO_L1_MESSAGE_PASSER)
+        .with_input(data.abi_encode_params());
+    let provider = bridge.l2_handle().http_provider();
+    let receipt = provider
+        .send_transaction(WithOtherFields::new(tx))
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+
+    let proofs = api.anvil_get_withdrawal_proofs(receipt.transaction_hash).await.unwrap();
+    assert_eq!(proofs.len(), 1);
+    let proof = &proofs[0];
+    assert_eq!(proof.withdrawal_hash, withdrawal_hash);
+    assert_eq!(proof.withdrawal.sender, from);
+    assert_eq!(proof.withdrawal.value, U256::from(1));
+    assert_eq!(proof.l2_block_number, receipt.block_number.unwrap());
+    assert_eq!(proof.output_root, proof.output_root_proof.output_root());
+    assert_eq!(proof.output_root_proof.latest_blockhash, receipt.block_hash.unwrap());
+    assert!(!proof.withdrawal_proof.is_empty());
+
+    let account = api
+        .get_proof(
+            L2_TO_L1_MESSAGE_PASSER,
+            vec![withdrawal_slot(withdrawal_hash)],
+            Some(proof.l2_block_number.into()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(proof.output_root_proof.message_passer_storage_root, account.storage_hash);
+    assert_eq!(account.storage_proof[0].value, U256::from(1));
+
+    // transactions without withdrawals have no proofs
+    let tx = TransactionRequest::default().with_from(from).with_to(from);
+    let receipt = provider
+        .send_transaction(WithOtherFields::new(tx))
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    let proofs = api.anvil_get_withdrawal_proofs(receipt.transaction_hash).await.unwrap();
+    assert!(proofs.is_empty());
+}
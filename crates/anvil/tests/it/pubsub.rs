@@ -5,10 +5,11 @@
 use alloy_primitives::{Address, U256};
 use alloy_provider::Provider;
 use alloy_pubsub::Subscription;
-use alloy_rpc_types::{Block as AlloyBlock, Filter, TransactionRequest};
+use alloy_rpc_types::{Block as AlloyBlock, Filter, Log, TransactionRequest};
 use alloy_serde::WithOtherFields;
 use alloy_sol_types::sol;
 use anvil::{spawn, NodeConfig};
+use anvil_core::types::{ReorgOptions, TransactionData};
 use futures::StreamExt;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -271,3 +272,63 @@ async fn test_sub_new_heads_fast() {
     let numbers = (1..=num).collect::<Vec<_>>();
     assert_eq!(block_numbers, numbers);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_logs_removed_on_rollback() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = connect_pubsub(&handle.ws_endpoint()).await;
+
+    let contract = EmitLogs::deploy(provider.clone(), "First Message".to_string()).await.unwrap();
+    let filter = Filter::new().address(contract.address().to_owned());
+    let mut logs_sub = provider.subscribe_logs(&filter).await.unwrap().into_stream();
+
+    let receipt = contract
+        .setValue("Next Message".to_string())
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    let log = logs_sub.next().await.unwrap();
+    assert_eq!(receipt.inner.logs()[0], log);
+    assert!(!log.removed);
+
+    // the block with the log is dropped
+    api.anvil_rollback(None).await.unwrap();
+
+    let removed = logs_sub.next().await.unwrap();
+    assert_eq!(removed, Log { removed: true, ..log });
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_filter_logs_removed_on_reorg() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let provider = handle.http_provider();
+
+    let contract = EmitLogs::deploy(provider.clone(), "First Message".to_string()).await.unwrap();
+    let filter = Filter::new().address(contract.address().to_owned());
+    let id = provider.new_filter(&filter).await.unwrap();
+
+    contract.setValue("Next Message".to_string()).send().await.unwrap().watch().await.unwrap();
+    let logs = provider.get_filter_changes::<Log>(id).await.unwrap();
+    assert_eq!(logs.len(), 1);
+    let old_log = logs[0].clone();
+
+    // replace the block with one that emits a different log
+    let tx = TransactionRequest::default()
+        .from(wallet.address())
+        .to(*contract.address())
+        .with_input(contract.setValue("Reorged Message".to_string()).calldata().clone());
+    let options = ReorgOptions { depth: 1, tx_block_pairs: vec![(TransactionData::JSON(tx), 0)] };
+    api.anvil_reorg(options).await.unwrap();
+
+    let logs = provider.get_filter_changes::<Log>(id).await.unwrap();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0], Log { removed: true, ..old_log.clone() });
+    assert!(!logs[1].removed);
+    assert_eq!(logs[1].block_number, old_log.block_number);
+    assert_ne!(logs[1].block_hash, old_log.block_hash);
+    assert_ne!(logs[1].data(), old_log.data());
+}
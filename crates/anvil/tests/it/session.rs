@@ -0,0 +1,97 @@
+//! Tests for recording and replaying JSON-RPC sessions
+
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{address, Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockNumberOrTag, BlockTransactionsKind, TransactionRequest};
+use alloy_serde::WithOtherFields;
+use anvil::{
+    server::session::{replay_session, Divergence, SessionEntry},
+    spawn, NodeConfig,
+};
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_record_and_replay_session() {
+    let dir = tempfile::tempdir().unwrap();
+    let session = dir.path().join("session.jsonl");
+    let config = NodeConfig::test().with_genesis_timestamp(Some(1_700_000_000u64));
+
+    let (_api, handle) = spawn(config.clone().with_record_session(Some(session.clone()))).await;
+    let provider = handle.http_provider();
+
+    let to: Address = address!("00000000000000000000000000000000000000aa");
+    let from = handle.dev_accounts().next().unwrap();
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(1));
+    provider.send_transaction(WithOtherFields::new(tx)).await.unwrap().get_receipt().await.unwrap();
+    provider
+        .get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(1));
+
+    let entries = std::fs::read_to_string(&session)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<SessionEntry>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert!(entries.iter().any(|entry| entry.method == "eth_sendTransaction"));
+    let last = entries.last().unwrap();
+    assert_eq!(last.method, "eth_getBalance");
+    assert_eq!(last.block_number, 1);
+
+    let (api, _handle) = spawn(config.clone().with_no_mining(true)).await;
+    let report = replay_session(&api, &session).await.unwrap();
+    assert_eq!(report.divergence, None);
+    assert_eq!(report.replayed, entries.len());
+
+    // the transaction can't be replayed with another chain id
+    let (api, _handle) = spawn(config.with_chain_id(Some(1u64)).with_no_mining(true)).await;
+    let report = replay_session(&api, &session).await.unwrap();
+    assert!(matches!(report.divergence, Some(Divergence::Response { .. })), "{report:?}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_replay_session_with_block_time() {
+    let dir = tempfile::tempdir().unwrap();
+    let session = dir.path().join("session.jsonl");
+    let config = NodeConfig::test().with_genesis_timestamp(Some(1_700_000_000u64));
+
+    let (_api, handle) = spawn(
+        config
+            .clone()
+            .with_blocktime(Some(Duration::from_secs(1)))
+            .with_record_session(Some(session.clone())),
+    )
+    .await;
+    let provider = handle.http_provider();
+
+    let to: Address = address!("00000000000000000000000000000000000000aa");
+    let from = handle.dev_accounts().next().unwrap();
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(1));
+    provider.send_transaction(WithOtherFields::new(tx)).await.unwrap().get_receipt().await.unwrap();
+    let block = provider
+        .get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(block.header.number > 0);
+
+    // the blocks were mined by the interval miner, their timestamps are part of the session
+    let entries = std::fs::read_to_string(&session)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<SessionEntry>(line).unwrap())
+        .collect::<Vec<_>>();
+    let last = entries.last().unwrap();
+    assert_eq!(last.method, "eth_getBlockByNumber");
+    let recorded_blocks: u64 =
+        entries.iter().map(|entry| entry.block_timestamps.len() as u64).sum();
+    assert_eq!(last.block_number, recorded_blocks);
+
+    let (api, _handle) = spawn(config.with_no_mining(true)).await;
+    let report = replay_session(&api, &session).await.unwrap();
+    assert_eq!(report.divergence, None);
+    assert_eq!(report.replayed, entries.len());
+}
@@ -6,8 +6,9 @@
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, TransactionRequest};
 use alloy_serde::WithOtherFields;
-use anvil::{spawn, NodeConfig};
+use anvil::{eth::backend::mem::chain_db::ChainDb, spawn, NodeConfig};
 use foundry_test_utils::rpc::next_http_rpc_endpoint;
+use std::time::Duration;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_load_state() {
@@ -108,6 +109,167 @@ async fn test_make_sure_historical_state_is_not_cleared_on_dump() {
     assert_ne!(code, Bytes::new());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_continue_chain_from_chain_db() {
+    let tmp = tempfile::tempdir().unwrap();
+    let chain_db = tmp.path().join("chain.db");
+
+    let (api, handle) = spawn(NodeConfig::test().with_chain_db(Some(chain_db.clone()))).await;
+    let provider = handle.http_provider();
+
+    let greeter = Greeter::deploy(&provider, "Hello".to_string()).await.unwrap();
+    let address = *greeter.address();
+    let receipt = greeter
+        .setGreeting("World!".to_string())
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+
+    let alice = address!("0x00000000000000000000000000000000000a11ce");
+    api.anvil_set_balance(alice, U256::from(1337)).await.unwrap();
+
+    let block = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+
+    // shut the node down and wait until the chain db is released
+    handle.node_service.abort();
+    handle.servers.iter().for_each(|server| server.abort());
+    drop((provider, api, handle));
+    wait_for_chain_db(&chain_db).await;
+
+    let (api, handle) = spawn(NodeConfig::test().with_chain_db(Some(chain_db))).await;
+    let provider = handle.http_provider();
+
+    // blocks, receipts and state are restored
+    let restored = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(restored.header.hash, block.header.hash);
+    let restored_receipt =
+        provider.get_transaction_receipt(receipt.transaction_hash).await.unwrap().unwrap();
+    assert_eq!(restored_receipt.block_hash, receipt.block_hash);
+    assert_eq!(provider.get_balance(alice).await.unwrap(), U256::from(1337));
+    let greeter = Greeter::new(address, &provider);
+    assert_eq!(greeter.greet().call().await.unwrap()._0, "World!");
+
+    // the chain continues on top of the restored one
+    api.mine_one().await;
+    let next = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(next.header.number, block.header.number + 1);
+    assert_eq!(next.header.parent_hash, block.header.hash);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_rewind_chain_db() {
+    let tmp = tempfile::tempdir().unwrap();
+    let chain_db = tmp.path().join("chain.db");
+
+    let (api, handle) = spawn(NodeConfig::test().with_chain_db(Some(chain_db.clone()))).await;
+    let provider = handle.http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+    let alice = address!("0x00000000000000000000000000000000000a11ce");
+    let bob = address!("0x0000000000000000000000000000000000000b0b");
+    let carol = address!("0x00000000000000000000000000000000000ca201");
+
+    let send = |to, value| {
+        let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(value);
+        let provider = provider.clone();
+        async move {
+            provider
+                .send_transaction(WithOtherFields::new(tx))
+                .await
+                .unwrap()
+                .get_receipt()
+                .await
+                .unwrap()
+        }
+    };
+
+    let kept = send(alice, U256::from(100)).await;
+    let snapshot = api.evm_snapshot().await.unwrap();
+    api.anvil_set_balance(carol, U256::from(1337)).await.unwrap();
+    let reverted = send(bob, U256::from(200)).await;
+    assert!(api.evm_revert(snapshot).await.unwrap());
+    send(alice, U256::from(300)).await;
+    let rolled_back = send(bob, U256::from(400)).await;
+    api.anvil_rollback(Some(1)).await.unwrap();
+
+    let block = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(block.header.number, 2);
+
+    handle.node_service.abort();
+    handle.servers.iter().for_each(|server| server.abort());
+    drop((provider, api, handle));
+    wait_for_chain_db(&chain_db).await;
+
+    let (_api, handle) = spawn(NodeConfig::test().with_chain_db(Some(chain_db))).await;
+    let provider = handle.http_provider();
+
+    // the discarded blocks and their transactions are gone
+    let restored = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(restored.header.hash, block.header.hash);
+    assert!(provider.get_block(BlockId::number(3), false.into()).await.unwrap().is_none());
+    assert!(provider.get_transaction_receipt(kept.transaction_hash).await.unwrap().is_some());
+    for tx in [reverted.transaction_hash, rolled_back.transaction_hash] {
+        assert!(provider.get_transaction_receipt(tx).await.unwrap().is_none());
+    }
+    assert_eq!(provider.get_balance(alice).await.unwrap(), U256::from(400));
+    assert_eq!(provider.get_balance(bob).await.unwrap(), U256::ZERO);
+    assert_eq!(provider.get_balance(carol).await.unwrap(), U256::ZERO);
+
+    // historical states are not persisted
+    assert!(provider.get_balance(alice).block_id(BlockId::number(1)).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_prune_transactions_in_chain_db() {
+    let tmp = tempfile::tempdir().unwrap();
+    let chain_db = tmp.path().join("chain.db");
+    let config = || {
+        NodeConfig::test()
+            .with_chain_db(Some(chain_db.clone()))
+            .with_transaction_block_keeper(Some(1usize))
+    };
+
+    let (api, handle) = spawn(config()).await;
+    let provider = handle.http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+    let mut hashes = Vec::new();
+    for _ in 0..2 {
+        let tx = TransactionRequest::default().with_from(from).with_to(from);
+        let receipt = provider
+            .send_transaction(WithOtherFields::new(tx))
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        hashes.push(receipt.transaction_hash);
+    }
+    assert!(provider.get_transaction_receipt(hashes[0]).await.unwrap().is_none());
+
+    handle.node_service.abort();
+    handle.servers.iter().for_each(|server| server.abort());
+    drop((provider, api, handle));
+    wait_for_chain_db(&chain_db).await;
+
+    let (_api, handle) = spawn(config()).await;
+    let provider = handle.http_provider();
+    assert!(provider.get_transaction_receipt(hashes[0]).await.unwrap().is_none());
+    assert!(provider.get_transaction_receipt(hashes[1]).await.unwrap().is_some());
+}
+
+/// Waits until the chain db was released by a node that was shut down
+async fn wait_for_chain_db(path: &std::path::Path) {
+    for _ in 0..50 {
+        if ChainDb::open(path).is_ok() {
+            return
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("chain db at {} was not released", path.display());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_preserve_historical_states_between_dump_and_load() {
     let tmp = tempfile::tempdir().unwrap();
@@ -7,7 +7,7 @@
 use alloy_network::{EthereumWallet, TransactionBuilder};
 use alloy_primitives::{
     hex::{self, FromHex},
-    Address, Bytes, U256,
+    Address, Bytes, B256, U256,
 };
 use alloy_provider::{
     ext::{DebugApi, TraceApi},
@@ -16,17 +16,19 @@
 use alloy_rpc_types::{
     state::StateOverride,
     trace::{
+        common::TraceResult,
         filter::{TraceFilter, TraceFilterMode},
         geth::{
-            CallConfig, GethDebugBuiltInTracerType, GethDebugTracerType,
-            GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
+            mux::MuxConfig, CallConfig, GethDebugBuiltInTracerType, GethDebugTracerType,
+            GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, PreStateConfig,
+            PreStateFrame,
         },
-        parity::{Action, LocalizedTransactionTrace},
+        parity::{Action, ChangedType, Delta, LocalizedTransactionTrace, TraceType},
     },
     TransactionRequest,
 };
 use alloy_serde::WithOtherFields;
-use alloy_sol_types::sol;
+use alloy_sol_types::{sol, SolCall};
 use anvil::{spawn, EthereumHardfork, NodeConfig};
 
 #[tokio::test(flavor = "multi_thread")]
@@ -307,6 +309,376 @@ async fn test_debug_trace_call_state_override() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_prestate_tracer_debug_trace_transaction() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let sender = wallets[0].address();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), wallets[0].clone().into());
+
+    let contract = SimpleStorage::deploy(&provider, "init value".to_string()).await.unwrap();
+    let tx =
+        contract.setValue("bar".to_string()).send().await.unwrap().get_receipt().await.unwrap();
+
+    let prestate = handle
+        .http_provider()
+        .debug_trace_transaction(
+            tx.transaction_hash,
+            GethDebugTracingOptions::default()
+                .with_tracer(GethDebugTracerType::from(GethDebugBuiltInTracerType::PreStateTracer))
+                .with_prestate_config(PreStateConfig::default()),
+        )
+        .await
+        .unwrap();
+
+    match prestate {
+        GethTrace::PreStateTracer(PreStateFrame::Default(mode)) => {
+            let account = mode.0.get(contract.address()).unwrap();
+            assert!(account.code.is_some());
+            assert!(!account.storage.is_empty());
+            assert!(mode.0.contains_key(&sender));
+        }
+        _ => unreachable!(),
+    }
+
+    let diff = handle
+        .http_provider()
+        .debug_trace_transaction(
+            tx.transaction_hash,
+            GethDebugTracingOptions::default()
+                .with_tracer(GethDebugTracerType::from(GethDebugBuiltInTracerType::PreStateTracer))
+                .with_prestate_config(PreStateConfig {
+                    diff_mode: Some(true),
+                    ..Default::default()
+                }),
+        )
+        .await
+        .unwrap();
+
+    match diff {
+        GethTrace::PreStateTracer(PreStateFrame::Diff(diff)) => {
+            let pre = diff.pre.get(contract.address()).unwrap();
+            let post = diff.post.get(contract.address()).unwrap();
+            assert_ne!(pre.storage, post.storage);
+            assert!(diff.post.get(&sender).unwrap().nonce > diff.pre.get(&sender).unwrap().nonce);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_four_byte_and_mux_tracer_debug_trace_transaction() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), wallets[0].clone().into());
+
+    let multicall_contract = Multicall::deploy(&provider).await.unwrap();
+    let simple_storage_contract =
+        SimpleStorage::deploy(&provider, "init value".to_string()).await.unwrap();
+
+    let set_value_calldata =
+        simple_storage_contract.setValue("bar".to_string()).calldata().to_owned();
+    let tx = multicall_contract
+        .aggregate(vec![Multicall::Call {
+            target: *simple_storage_contract.address(),
+            callData: set_value_calldata,
+        }])
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+
+    let four_byte = handle
+        .http_provider()
+        .debug_trace_transaction(
+            tx.transaction_hash,
+            GethDebugTracingOptions::default()
+                .with_tracer(GethDebugTracerType::from(GethDebugBuiltInTracerType::FourByteTracer)),
+        )
+        .await
+        .unwrap();
+
+    match four_byte {
+        GethTrace::FourByteTracer(frame) => {
+            // the `aggregate` call and the internal `setValue` call
+            assert_eq!(frame.0.len(), 2);
+        }
+        _ => unreachable!(),
+    }
+
+    let mux_config: MuxConfig = serde_json::from_value(serde_json::json!({
+        "callTracer": {},
+        "flatCallTracer": {},
+        "4byteTracer": null,
+    }))
+    .unwrap();
+    let mux = handle
+        .http_provider()
+        .debug_trace_transaction(
+            tx.transaction_hash,
+            GethDebugTracingOptions::default()
+                .with_tracer(GethDebugTracerType::from(GethDebugBuiltInTracerType::MuxTracer))
+                .with_config(mux_config),
+        )
+        .await
+        .unwrap();
+
+    match mux {
+        GethTrace::MuxTracer(frame) => {
+            assert_eq!(frame.0.len(), 3);
+            match frame.0.get(&GethDebugBuiltInTracerType::FlatCallTracer).unwrap() {
+                GethTrace::FlatCallTracer(traces) => {
+                    assert_eq!(traces.len(), 2);
+                    assert_eq!(traces[0].transaction_hash, Some(tx.transaction_hash));
+                }
+                _ => unreachable!(),
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_flat_call_tracer_debug_trace_call() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), wallets[0].clone().into());
+
+    let multicall_contract = Multicall::deploy(&provider).await.unwrap();
+    let simple_storage_contract =
+        SimpleStorage::deploy(&provider, "init value".to_string()).await.unwrap();
+
+    let set_value_calldata =
+        simple_storage_contract.setValue("bar".to_string()).calldata().to_owned();
+    let calldata = multicall_contract
+        .aggregate(vec![Multicall::Call {
+            target: *simple_storage_contract.address(),
+            callData: set_value_calldata,
+        }])
+        .calldata()
+        .to_owned();
+
+    let tx = TransactionRequest::default()
+        .from(wallets[1].address())
+        .to(*multicall_contract.address())
+        .with_input(calldata);
+
+    let traces = handle
+        .http_provider()
+        .debug_trace_call(
+            tx,
+            BlockId::latest(),
+            GethDebugTracingCallOptions::default().with_tracing_options(
+                GethDebugTracingOptions::default().with_tracer(GethDebugTracerType::from(
+                    GethDebugBuiltInTracerType::FlatCallTracer,
+                )),
+            ),
+        )
+        .await
+        .unwrap();
+
+    match traces {
+        GethTrace::FlatCallTracer(traces) => {
+            assert_eq!(traces.len(), 2);
+            assert_eq!(traces[1].trace.trace_address, vec![0]);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_block() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), wallets[0].clone().into());
+
+    let contract = SimpleStorage::deploy(&provider, "init value".to_string()).await.unwrap();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+    let first = contract
+        .setValue("foo".to_string())
+        .nonce(1)
+        .send()
+        .await
+        .unwrap()
+        .register()
+        .await
+        .unwrap();
+    let second = contract
+        .setValue("bar".to_string())
+        .nonce(2)
+        .send()
+        .await
+        .unwrap()
+        .register()
+        .await
+        .unwrap();
+    api.mine_one().await;
+
+    let block = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 2);
+
+    let traces = handle
+        .http_provider()
+        .debug_trace_block_by_number(
+            block.header.number.into(),
+            GethDebugTracingOptions::default()
+                .with_tracer(GethDebugTracerType::from(GethDebugBuiltInTracerType::CallTracer))
+                .with_call_config(CallConfig::default()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(traces.len(), 2);
+    for (trace, hash) in traces.into_iter().zip([first.tx_hash(), second.tx_hash()]) {
+        match trace {
+            TraceResult::Success { result: GethTrace::CallTracer(frame), tx_hash } => {
+                assert_eq!(tx_hash, Some(*hash));
+                assert_eq!(frame.to, Some(*contract.address()));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    let traces = handle
+        .http_provider()
+        .debug_trace_block_by_hash(
+            block.header.hash,
+            GethDebugTracingOptions::default()
+                .with_tracer(GethDebugTracerType::from(GethDebugBuiltInTracerType::PreStateTracer))
+                .with_prestate_config(PreStateConfig {
+                    diff_mode: Some(true),
+                    ..Default::default()
+                }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(traces.len(), 2);
+
+    // the second transaction must observe the state left behind by the first one
+    let diffs = traces
+        .into_iter()
+        .map(|trace| match trace {
+            TraceResult::Success {
+                result: GethTrace::PreStateTracer(PreStateFrame::Diff(diff)),
+                ..
+            } => diff,
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    let value_slot = B256::with_last_byte(1);
+    assert_eq!(
+        diffs[0].post.get(contract.address()).unwrap().storage.get(&value_slot),
+        diffs[1].pre.get(contract.address()).unwrap().storage.get(&value_slot)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trace_replay_transaction() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let sender = wallets[0].address();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), wallets[0].clone().into());
+
+    let contract = SimpleStorage::deploy(&provider, "init value".to_string()).await.unwrap();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+    let first = contract
+        .setValue("foo".to_string())
+        .nonce(1)
+        .send()
+        .await
+        .unwrap()
+        .register()
+        .await
+        .unwrap();
+    let second = contract
+        .setValue("bar".to_string())
+        .nonce(2)
+        .send()
+        .await
+        .unwrap()
+        .register()
+        .await
+        .unwrap();
+    api.mine_one().await;
+
+    let trace = handle
+        .http_provider()
+        .trace_replay_transaction(
+            *second.tx_hash(),
+            &[TraceType::Trace, TraceType::StateDiff, TraceType::VmTrace],
+        )
+        .await
+        .unwrap();
+    assert_eq!(trace.trace.len(), 1);
+    assert!(trace.vm_trace.is_some());
+
+    let state_diff = trace.state_diff.unwrap();
+    let sender_diff = state_diff.get(&sender).unwrap();
+    match sender_diff.nonce {
+        Delta::Changed(ChangedType { from, to }) => {
+            assert_eq!(from.to::<u64>(), 2);
+            assert_eq!(to.to::<u64>(), 3);
+        }
+        _ => unreachable!(),
+    }
+    // the replayed transaction observes the value written by the first transaction of the block
+    let value_slot = B256::with_last_byte(1);
+    match state_diff.get(contract.address()).unwrap().storage.get(&value_slot).unwrap() {
+        Delta::Changed(ChangedType { from, .. }) => {
+            assert!(from.starts_with(b"foo"));
+        }
+        _ => unreachable!(),
+    }
+
+    let traces = handle
+        .http_provider()
+        .trace_replay_block_transactions(BlockId::latest(), &[TraceType::Trace])
+        .await
+        .unwrap();
+    assert_eq!(traces.len(), 2);
+    assert_eq!(traces[0].transaction_hash, *first.tx_hash());
+    assert_eq!(traces[1].transaction_hash, *second.tx_hash());
+    assert!(traces.iter().all(|trace| trace.full_trace.state_diff.is_none()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trace_call_many() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let sender = wallets[0].address();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), wallets[0].clone().into());
+
+    let contract = SimpleStorage::deploy(&provider, "init value".to_string()).await.unwrap();
+    let set_value = contract.setValue("foo".to_string()).from(sender).into_transaction_request();
+    let get_value = contract.getValue().from(sender).into_transaction_request();
+
+    let trace = handle
+        .http_provider()
+        .trace_call(&set_value, &[TraceType::Trace, TraceType::StateDiff])
+        .await
+        .unwrap();
+    assert_eq!(trace.trace.len(), 1);
+    assert!(trace.state_diff.unwrap().contains_key(contract.address()));
+
+    // `trace_call` doesn't modify the state
+    assert_eq!(contract.getValue().call().await.unwrap()._0, "init value");
+
+    let traces = handle
+        .http_provider()
+        .trace_call_many(&[(set_value, &[TraceType::StateDiff]), (get_value, &[TraceType::Trace])])
+        .await
+        .unwrap();
+    assert_eq!(traces.len(), 2);
+    assert!(traces[0].trace.is_empty());
+    assert!(traces[1].state_diff.is_none());
+    let value =
+        SimpleStorage::getValueCall::abi_decode_returns(&traces[1].output, true).unwrap()._0;
+    assert_eq!(value, "foo");
+}
+
 // <https://github.com/foundry-rs/foundry/issues/2656>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_trace_address_fork() {
@@ -1,11 +1,12 @@
 //! txpool related tests
 
-use alloy_network::TransactionBuilder;
+use alloy_network::{TransactionBuilder, TransactionResponse};
 use alloy_primitives::U256;
 use alloy_provider::{ext::TxPoolApi, Provider};
 use alloy_rpc_types::TransactionRequest;
 use alloy_serde::WithOtherFields;
-use anvil::{spawn, NodeConfig};
+use anvil::{eth::pool::PoolConfig, spawn, NodeConfig};
+use std::time::Duration;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn geth_txpool() {
@@ -56,3 +57,150 @@ async fn geth_txpool() {
         assert!(content.contains_key(&nonce.to_string()));
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replacement_requires_price_bump() {
+    let pool_config = PoolConfig { price_bump: 10, ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(pool_config)).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap().remove(0);
+    let gas_price = provider.get_gas_price().await.unwrap();
+    let tx = TransactionRequest::default().with_from(account).with_to(account).with_nonce(0);
+
+    let _ = provider
+        .send_transaction(WithOtherFields::new(tx.clone().with_gas_price(gas_price)))
+        .await
+        .unwrap();
+
+    let err = provider
+        .send_transaction(WithOtherFields::new(tx.clone().with_gas_price(gas_price + 1)))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("replacement transaction underpriced"), "{err}");
+
+    let replacement = provider
+        .send_transaction(WithOtherFields::new(tx.with_gas_price(gas_price * 110 / 100)))
+        .await
+        .unwrap();
+    let content = provider.txpool_content().await.unwrap();
+    let pending = content.pending.get(&account).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending["0"].tx_hash(), *replacement.tx_hash());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_replace_queued_transaction() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap().remove(0);
+    let gas_price = provider.get_gas_price().await.unwrap();
+    let tx = TransactionRequest::default().with_from(account).with_to(account).with_nonce(1);
+
+    let _ = provider
+        .send_transaction(WithOtherFields::new(tx.clone().with_gas_price(gas_price)))
+        .await
+        .unwrap();
+    let _ = provider
+        .send_transaction(WithOtherFields::new(tx.with_gas_price(gas_price + 1)))
+        .await
+        .unwrap();
+
+    let status = provider.txpool_status().await.unwrap();
+    assert_eq!(status.queued, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_transactions_over_account_limits() {
+    let pool_config =
+        PoolConfig { account_slots: Some(2), account_queue: Some(1), ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(pool_config)).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap().remove(0);
+    let tx = |nonce| {
+        WithOtherFields::new(
+            TransactionRequest::default().with_from(account).with_to(account).with_nonce(nonce),
+        )
+    };
+
+    let _ = provider.send_transaction(tx(0)).await.unwrap();
+    let _ = provider.send_transaction(tx(1)).await.unwrap();
+    let err = provider.send_transaction(tx(2)).await.unwrap_err();
+    assert!(err.to_string().contains("account limit exceeded"), "{err}");
+
+    let _ = provider.send_transaction(tx(5)).await.unwrap();
+    let err = provider.send_transaction(tx(6)).await.unwrap_err();
+    assert!(err.to_string().contains("account limit exceeded"), "{err}");
+
+    let status = provider.txpool_status().await.unwrap();
+    assert_eq!(status.pending, 2);
+    assert_eq!(status.queued, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn full_pool_evicts_cheapest_transaction() {
+    let pool_config = PoolConfig { global_slots: Some(1), ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(pool_config)).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = provider.get_accounts().await.unwrap();
+    let gas_price = provider.get_gas_price().await.unwrap();
+    let tx = |from, gas_price| {
+        WithOtherFields::new(
+            TransactionRequest::default().with_from(from).with_to(from).with_gas_price(gas_price),
+        )
+    };
+
+    let _ = provider.send_transaction(tx(accounts[0], gas_price)).await.unwrap();
+    let err = provider.send_transaction(tx(accounts[1], gas_price)).await.unwrap_err();
+    assert!(err.to_string().contains("transaction underpriced"), "{err}");
+
+    let _ = provider.send_transaction(tx(accounts[1], gas_price * 2)).await.unwrap();
+    let content = provider.txpool_content().await.unwrap();
+    assert_eq!(content.pending.len(), 1);
+    assert!(content.pending.contains_key(&accounts[1]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn full_queue_evicts_oldest_transaction() {
+    let pool_config = PoolConfig { global_queue: Some(1), ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(pool_config)).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = provider.get_accounts().await.unwrap();
+    let tx = |from| {
+        WithOtherFields::new(
+            TransactionRequest::default().with_from(from).with_to(from).with_nonce(1),
+        )
+    };
+
+    let oldest = *provider.send_transaction(tx(accounts[0])).await.unwrap().tx_hash();
+    let newest = *provider.send_transaction(tx(accounts[1])).await.unwrap().tx_hash();
+
+    assert_eq!(provider.txpool_status().await.unwrap().queued, 1);
+    assert!(provider.get_transaction_by_hash(oldest).await.unwrap().is_none());
+    assert!(provider.get_transaction_by_hash(newest).await.unwrap().is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn evicts_expired_queued_transactions() {
+    let pool_config = PoolConfig { lifetime: Some(Duration::from_secs(1)), ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(pool_config)).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap().remove(0);
+    let tx = TransactionRequest::default().with_from(account).with_to(account).with_nonce(1);
+    let _ = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    assert_eq!(provider.txpool_status().await.unwrap().queued, 1);
+
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert_eq!(provider.txpool_status().await.unwrap().queued, 0);
+}
@@ -2,8 +2,8 @@
     access_list::AccessListArgs, artifact::ArtifactArgs, bind::BindArgs, call::CallArgs,
     constructor_args::ConstructorArgsArgs, create2::Create2Args, creation_code::CreationCodeArgs,
     estimate::EstimateArgs, find_block::FindBlockArgs, interface::InterfaceArgs, logs::LogsArgs,
-    mktx::MakeTxArgs, rpc::RpcArgs, run::RunArgs, send::SendTxArgs, storage::StorageArgs,
-    wallet::WalletSubcommands,
+    mktx::MakeTxArgs, rpc::RpcArgs, run::RunArgs, send::SendTxArgs, state_diff::StateDiffArgs,
+    storage::StorageArgs, wallet::WalletSubcommands,
 };
 use alloy_primitives::{Address, B256, U256};
 use alloy_rpc_types::BlockId;
@@ -841,6 +841,10 @@ pub enum CastSubcommand {
     #[command(visible_alias = "st")]
     Storage(StorageArgs),
 
+    /// Get all balance, nonce, code and storage changes between two blocks or a snapshot and a
+    /// block of an Anvil node.
+    StateDiff(StateDiffArgs),
+
     /// Generate a storage proof for a given storage slot.
     #[command(visible_alias = "pr")]
     Proof {
@@ -20,5 +20,6 @@
 pub mod rpc;
 pub mod run;
 pub mod send;
+pub mod state_diff;
 pub mod storage;
 pub mod wallet;
@@ -0,0 +1,229 @@
+use crate::cmd::storage::{add_storage_layout_output, StorageValue};
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::BlockId;
+use clap::Parser;
+use eyre::Result;
+use foundry_cli::{
+    opts::{BuildOpts, RpcOpts},
+    utils,
+    utils::LoadConfig,
+};
+use foundry_common::{compile::ProjectCompiler, shell, ContractsByArtifact};
+use foundry_compilers::artifacts::StorageLayout;
+use foundry_config::{
+    figment::{self, value::Dict, Metadata, Profile},
+    impl_figment_convert_cast, Config,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+/// CLI arguments for `cast state-diff`.
+#[derive(Clone, Debug, Parser)]
+pub struct StateDiffArgs {
+    /// The block to diff from.
+    ///
+    /// Can also be the tags earliest, finalized, safe, latest, or pending.
+    #[arg(long, required_unless_present = "from_snapshot", conflicts_with = "from_snapshot")]
+    from_block: Option<BlockId>,
+
+    /// The id of the snapshot to diff from, as returned by `evm_snapshot`.
+    #[arg(long, value_name = "ID")]
+    from_snapshot: Option<U256>,
+
+    /// The block to diff to, defaults to the latest block.
+    ///
+    /// Can also be the tags earliest, finalized, safe, latest, or pending.
+    #[arg(long)]
+    to_block: Option<BlockId>,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+
+    #[command(flatten)]
+    build: BuildOpts,
+}
+
+impl_figment_convert_cast!(StateDiffArgs);
+
+impl figment::Provider for StateDiffArgs {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("StateDiffArgs")
+    }
+
+    fn data(&self) -> Result<figment::value::Map<Profile, Dict>, figment::Error> {
+        let mut map = self.build.data()?;
+        let dict = map.get_mut(&Config::selected_profile()).unwrap();
+        dict.extend(self.rpc.dict());
+        Ok(map)
+    }
+}
+
+/// The options of an `anvil_stateDiff` request.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StateDiffOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from_block: Option<BlockId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from_snapshot: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to_block: Option<BlockId>,
+}
+
+/// The response of an `anvil_stateDiff` request.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StateDiff {
+    accounts: BTreeMap<Address, AccountStateDiff>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct AccountStateDiff {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    balance: Option<ValueDiff<U256>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nonce: Option<ValueDiff<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    code: Option<ValueDiff<Bytes>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    storage: BTreeMap<B256, ValueDiff<B256>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ValueDiff<T> {
+    from: T,
+    to: T,
+}
+
+impl StateDiffArgs {
+    pub async fn run(self) -> Result<()> {
+        let config = self.load_config()?;
+
+        let Self { from_block, from_snapshot, to_block, build, .. } = self;
+        let provider = utils::get_provider(&config)?;
+
+        let options = StateDiffOptions { from_block, from_snapshot, to_block };
+        let diff: StateDiff = provider.raw_request("anvil_stateDiff".into(), (options,)).await?;
+
+        if shell::is_json() {
+            sh_println!("{}", serde_json::to_string_pretty(&diff)?)?;
+            return Ok(());
+        }
+
+        if diff.accounts.is_empty() {
+            sh_println!("No state changes")?;
+            return Ok(());
+        }
+
+        // Check if we're in a forge project and if we can find storage layouts for the changed
+        // contracts
+        let mut contracts = ContractsByArtifact::default();
+        let mut layouts = BTreeMap::new();
+        let mut project = build.project()?;
+        if project.paths.has_input_files() &&
+            diff.accounts.values().any(|account| !account.storage.is_empty())
+        {
+            add_storage_layout_output(&mut project);
+            let out = ProjectCompiler::new().quiet(true).compile(&project)?;
+            contracts = ContractsByArtifact::new(
+                out.artifact_ids().map(|(id, artifact)| (id, artifact.clone().into())),
+            );
+            layouts = out
+                .artifact_ids()
+                .filter_map(|(id, artifact)| Some((id, artifact.storage_layout.clone()?)))
+                .collect();
+        }
+
+        for (address, account) in diff.accounts {
+            let mut contract = None;
+            if !layouts.is_empty() && !account.storage.is_empty() {
+                let code = match &account.code {
+                    Some(code) => code.to.clone(),
+                    None => {
+                        provider.get_code_at(address).block_id(to_block.unwrap_or_default()).await?
+                    }
+                };
+                // immutables differ from the compiled code, they are ignored when matching it
+                contract = contracts
+                    .find_by_deployed_code_exact(&code)
+                    .and_then(|(id, _)| Some((&id.name, layouts.get(id)?)));
+            }
+
+            match contract {
+                Some((name, _)) => sh_println!("{address} ({name})")?,
+                None => sh_println!("{address}")?,
+            }
+            if let Some(balance) = &account.balance {
+                print_change("balance", balance.from, balance.to)?;
+            }
+            if let Some(nonce) = &account.nonce {
+                print_change("nonce", nonce.from, nonce.to)?;
+            }
+            if let Some(code) = &account.code {
+                print_change(
+                    "code",
+                    format!("{} bytes", code.from.len()),
+                    format!("{} bytes", code.to.len()),
+                )?;
+            }
+            if !account.storage.is_empty() {
+                sh_println!("  storage:")?;
+                for (slot, value) in &account.storage {
+                    print_storage_change(*slot, value, contract.map(|(_, layout)| layout))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn print_change(name: &str, from: impl Display, to: impl Display) -> Result<()> {
+    sh_println!("  {name}: {from} → {to}")?;
+    Ok(())
+}
+
+/// Prints the change of a storage slot, decoding every variable of the layout stored in it.
+fn print_storage_change(
+    slot: B256,
+    value: &ValueDiff<B256>,
+    layout: Option<&StorageLayout>,
+) -> Result<()> {
+    let slot_number = U256::from_be_bytes(slot.0);
+    let variables = layout
+        .map(|layout| {
+            layout
+                .storage
+                .iter()
+                .filter(|storage| U256::from_str(&storage.slot).is_ok_and(|s| s == slot_number))
+                .map(|storage| (storage, layout.types.get(&storage.storage_type)))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    if variables.is_empty() {
+        sh_println!("    @ {slot}: {} → {}", value.from, value.to)?;
+        return Ok(());
+    }
+
+    let from = StorageValue { slot, raw_slot_value: value.from };
+    let to = StorageValue { slot, raw_slot_value: value.to };
+    for (storage, storage_type) in variables {
+        let number_of_bytes = storage_type.and_then(|t| t.number_of_bytes.parse::<usize>().ok());
+        let (from, to) = (
+            from.value(storage.offset, number_of_bytes),
+            to.value(storage.offset, number_of_bytes),
+        );
+        if from == to {
+            continue;
+        }
+        sh_println!(
+            "    @ {slot_number} ({}: {}): {} → {}",
+            storage.label,
+            storage_type.map_or("?", |t| &t.label),
+            U256::from_be_bytes(from.0),
+            U256::from_be_bytes(to.0),
+        )?;
+    }
+    Ok(())
+}
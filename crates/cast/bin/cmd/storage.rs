@@ -191,16 +191,16 @@ pub async fn run(self) -> Result<()> {
 
 /// Represents the value of a storage slot `eth_getStorageAt` call.
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct StorageValue {
+pub(crate) struct StorageValue {
     /// The slot number.
-    slot: B256,
+    pub slot: B256,
     /// The value as returned by `eth_getStorageAt`.
-    raw_slot_value: B256,
+    pub raw_slot_value: B256,
 }
 
 impl StorageValue {
     /// Returns the value of the storage slot, applying the offset if necessary.
-    fn value(&self, offset: i64, number_of_bytes: Option<usize>) -> B256 {
+    pub fn value(&self, offset: i64, number_of_bytes: Option<usize>) -> B256 {
         let offset = offset as usize;
         let mut end = 32;
         if let Some(number_of_bytes) = number_of_bytes {
@@ -324,7 +324,9 @@ fn print_storage(layout: StorageLayout, values: Vec<StorageValue>, pretty: bool)
     Ok(())
 }
 
-fn add_storage_layout_output<C: Compiler<CompilerContract = Contract>>(project: &mut Project<C>) {
+pub(crate) fn add_storage_layout_output<C: Compiler<CompilerContract = Contract>>(
+    project: &mut Project<C>,
+) {
     project.artifacts.additional_values.storage_layout = true;
     project.update_output_selection(|selection| {
         selection.0.values_mut().for_each(|contract_selection| {
@@ -466,6 +466,7 @@ async fn main_args(args: CastArgs) -> Result<()> {
         }
         CastSubcommand::Rpc(cmd) => cmd.run().await?,
         CastSubcommand::Storage(cmd) => cmd.run().await?,
+        CastSubcommand::StateDiff(cmd) => cmd.run().await?,
 
         // Calls & transactions
         CastSubcommand::Call(cmd) => cmd.run().await?,
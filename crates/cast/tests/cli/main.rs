@@ -1844,6 +1844,52 @@
 "#]]);
 });
 
+// tests cast can print the state diff between two blocks with decoded storage labels
+forgetest_async!(state_diff_with_storage_layout, |prj, cmd| {
+    let (_api, handle) = anvil::spawn(NodeConfig::test()).await;
+
+    foundry_test_utils::util::initialize(prj.root());
+    // Deploy counter contract.
+    cmd.args([
+        "script",
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "--rpc-url",
+        &handle.http_endpoint(),
+        "--broadcast",
+        "CounterScript",
+    ])
+    .assert_success();
+
+    // Send tx to change counter storage value.
+    cmd.cast_fuse()
+        .args([
+            "send",
+            "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "setNumber(uint256)",
+            "111",
+            "--private-key",
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+            "--rpc-url",
+            &handle.http_endpoint(),
+        ])
+        .assert_success();
+
+    cmd.cast_fuse()
+        .args(["state-diff", "--from-block", "1", "--rpc-url", &handle.http_endpoint()])
+        .assert_success()
+        .stdout_eq(str![[r#"
+...
+0x5FbDB2315678afecb367f032d93F642f64180aa3 (Counter)
+  storage:
+    @ 0 (number: uint256): 0 → 111
+0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+  balance: [..] → [..]
+  nonce: 1 → 2
+
+"#]]);
+});
+
 // tests cast can decode external libraries traces with project cached selectors
 forgetest_async!(decode_external_libraries_with_cached_selectors, |prj, cmd| {
     let (api, handle) = anvil::spawn(NodeConfig::test()).await;
@@ -7,7 +7,7 @@
 use alloy_provider::Provider;
 use alloy_rpc_types::Filter;
 use alloy_sol_types::SolValue;
-use foundry_common::provider::ProviderBuilder;
+use foundry_common::provider::{cassette::CassetteConfig, ProviderBuilder};
 use foundry_evm_core::fork::CreateFork;
 
 impl Cheatcode for activeForkCall {
@@ -219,7 +219,7 @@ fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
         let Self { method, params } = self;
         let url =
             ccx.ecx.db.active_fork_url().ok_or_else(|| fmt_err!("no active fork URL found"))?;
-        rpc_call(&url, method, params)
+        rpc_call(&url, ccx.state.config.evm_opts.fork_cassette.clone(), method, params)
     }
 }
 
@@ -227,7 +227,7 @@ impl Cheatcode for rpc_1Call {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { urlOrAlias, method, params } = self;
         let url = state.config.rpc_endpoint(urlOrAlias)?.url()?;
-        rpc_call(&url, method, params)
+        rpc_call(&url, state.config.evm_opts.fork_cassette.clone(), method, params)
     }
 }
 
@@ -245,7 +245,9 @@ fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
 
         let url =
             ccx.ecx.db.active_fork_url().ok_or_else(|| fmt_err!("no active fork URL found"))?;
-        let provider = ProviderBuilder::new(&url).build()?;
+        let provider = ProviderBuilder::new(&url)
+            .maybe_cassette(ccx.state.config.evm_opts.fork_cassette.clone())
+            .build()?;
         let mut filter = Filter::new().address(*target).from_block(from_block).to_block(to_block);
         for (i, &topic) in topics.iter().enumerate() {
             filter.topics[i] = topic.into();
@@ -378,8 +380,8 @@ fn persist_caller(ccx: &mut CheatsCtxt) {
 }
 
 /// Performs an Ethereum JSON-RPC request to the given endpoint.
-fn rpc_call(url: &str, method: &str, params: &str) -> Result {
-    let provider = ProviderBuilder::new(url).build()?;
+fn rpc_call(url: &str, cassette: Option<CassetteConfig>, method: &str, params: &str) -> Result {
+    let provider = ProviderBuilder::new(url).maybe_cassette(cassette).build()?;
     let params_json: serde_json::Value = serde_json::from_str(params)?;
     let result =
         foundry_common::block_on(provider.raw_request(method.to_string().into(), params_json))
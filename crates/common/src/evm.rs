@@ -13,8 +13,9 @@
     Chain, Config,
 };
 use serde::Serialize;
+use std::path::PathBuf;
 
-use crate::shell;
+use crate::{provider::cassette::CassetteConfig, shell};
 
 /// Map keyed by breakpoints char to their location (contract address, pc)
 pub type Breakpoints = HashMap<char, (Address, usize)>;
@@ -128,6 +129,21 @@ pub struct EvmArgs {
     #[serde(skip)]
     pub no_rpc_rate_limit: bool,
 
+    /// Records all requests to the fork endpoint and their responses to a cassette file.
+    ///
+    /// See also --fork-replay.
+    #[arg(long, value_name = "PATH", help_heading = "Fork config", conflicts_with = "fork_replay")]
+    #[serde(skip)]
+    pub fork_record: Option<PathBuf>,
+
+    /// Serves all requests to the fork endpoint from a cassette file written with --fork-record,
+    /// without any network access.
+    ///
+    /// Requests that aren't recorded in the cassette fail.
+    #[arg(long, value_name = "PATH", help_heading = "Fork config")]
+    #[serde(skip)]
+    pub fork_replay: Option<PathBuf>,
+
     /// All ethereum environment related arguments
     #[command(flatten)]
     #[serde(flatten)]
@@ -146,6 +162,16 @@ pub struct EvmArgs {
     pub odyssey: bool,
 }
 
+impl EvmArgs {
+    /// Returns the cassette to record fork requests to or replay them from, if any.
+    pub fn fork_cassette(&self) -> Option<CassetteConfig> {
+        self.fork_record
+            .clone()
+            .map(CassetteConfig::record)
+            .or_else(|| self.fork_replay.clone().map(CassetteConfig::replay))
+    }
+}
+
 // Make this set of options a `figment::Provider` so that it can be merged into the `Config`
 impl Provider for EvmArgs {
     fn metadata(&self) -> Metadata {
@@ -189,6 +215,10 @@ fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
             dict.insert("no_rpc_rate_limit".to_string(), self.no_rpc_rate_limit.into());
         }
 
+        if let Some(cassette) = self.fork_cassette() {
+            dict.insert("fork_cassette".to_string(), Value::serialize(cassette)?);
+        }
+
         if let Some(fork_url) = &self.fork_url {
             dict.insert("eth_rpc_url".to_string(), fork_url.clone().into());
         }
@@ -332,4 +362,21 @@ fn test_chain_id() {
         let config = Config::from_provider(Config::figment().merge(args)).unwrap();
         assert_eq!(config.chain, Some(Chain::mainnet()));
     }
+
+    #[test]
+    fn can_parse_fork_cassette() {
+        let args = EvmArgs::parse_from(["foundry-common", "--fork-replay", "fork.jsonl"]);
+        let cassette =
+            Config::figment().merge(args).extract_inner::<CassetteConfig>("fork_cassette").unwrap();
+        assert_eq!(cassette, CassetteConfig::replay("fork.jsonl"));
+
+        let args = EvmArgs::try_parse_from([
+            "foundry-common",
+            "--fork-record",
+            "fork.jsonl",
+            "--fork-replay",
+            "fork.jsonl",
+        ]);
+        assert!(args.is_err());
+    }
 }
@@ -0,0 +1,422 @@
+//! Record and replay of JSON-RPC requests, which allows running forks without network access.
+//!
+//! A cassette is a file with one recorded request and its response per line. In
+//! [CassetteMode::Record] all requests are forwarded to the endpoint and every response that isn't
+//! recorded yet is appended to the file. In [CassetteMode::Replay] the endpoint is never contacted
+//! and requests that aren't recorded fail.
+//!
+//! Requests are matched by the host of the endpoint, the method and the params. The rest of the URL
+//! is ignored, so that API keys are neither written to the cassette nor required to replay it.
+
+use alloy_json_rpc::{
+    ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload, SerializedRequest,
+};
+use alloy_transport::{TransportError, TransportErrorKind, TransportFut};
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+use url::Url;
+
+/// Whether a cassette records or replays requests
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteMode {
+    /// Forwards requests to the endpoint and records all responses that aren't recorded yet
+    Record,
+    /// Serves all requests from the cassette without ever contacting the endpoint
+    Replay,
+}
+
+/// The file and mode of a cassette
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CassetteConfig {
+    /// The cassette file
+    pub path: PathBuf,
+    /// Whether to record or replay requests
+    pub mode: CassetteMode,
+}
+
+impl CassetteConfig {
+    /// Records requests to the file at `path`
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), mode: CassetteMode::Record }
+    }
+
+    /// Replays requests from the file at `path`
+    pub fn replay(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), mode: CassetteMode::Replay }
+    }
+}
+
+/// Identifies a request in the cassette
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct RequestKey {
+    endpoint: String,
+    method: String,
+    params: String,
+}
+
+impl RequestKey {
+    fn new(endpoint: &str, request: &SerializedRequest) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            method: request.method().to_string(),
+            params: request.params().map(|params| params.get().to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+/// A line of the cassette file
+#[derive(Debug, Serialize, Deserialize)]
+struct CassetteEntry {
+    endpoint: String,
+    method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    params: Option<Box<RawValue>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<Box<RawValue>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorPayload>,
+}
+
+impl CassetteEntry {
+    fn into_parts(self) -> (RequestKey, ResponsePayload) {
+        let key = RequestKey {
+            endpoint: self.endpoint,
+            method: self.method,
+            params: self.params.map(|params| params.get().to_string()).unwrap_or_default(),
+        };
+        let payload = match self.error {
+            Some(error) => ResponsePayload::Failure(error),
+            None => {
+                ResponsePayload::Success(self.result.unwrap_or_else(|| RawValue::NULL.to_owned()))
+            }
+        };
+        (key, payload)
+    }
+}
+
+/// Recorded responses, keyed by their request
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    responses: Mutex<HashMap<RequestKey, ResponsePayload>>,
+    /// The file new responses are appended to, only set in [CassetteMode::Record]
+    file: Option<Mutex<File>>,
+}
+
+impl Cassette {
+    /// Loads the cassette and, in [CassetteMode::Record], creates the file if it doesn't exist
+    pub fn open(config: &CassetteConfig) -> Result<Self> {
+        let CassetteConfig { path, mode } = config;
+        let file = match mode {
+            CassetteMode::Record => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .wrap_err_with(|| format!("failed to open cassette {}", path.display()))?;
+                Some(Mutex::new(file))
+            }
+            CassetteMode::Replay => None,
+        };
+        let responses = Self::load(path)
+            .wrap_err_with(|| format!("failed to load cassette {}", path.display()))?;
+        Ok(Self { path: path.clone(), mode: *mode, responses: Mutex::new(responses), file })
+    }
+
+    fn load(path: &Path) -> Result<HashMap<RequestKey, ResponsePayload>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut responses = HashMap::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: CassetteEntry = serde_json::from_str(&line)?;
+            let (key, payload) = entry.into_parts();
+            responses.insert(key, payload);
+        }
+        Ok(responses)
+    }
+
+    /// Returns the cassette file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns whether requests are recorded or replayed
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Returns the number of recorded responses
+    pub fn len(&self) -> usize {
+        self.responses.lock().unwrap().len()
+    }
+
+    /// Returns `true` if no responses are recorded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the recorded responses to all requests, if every one of them is recorded, or the
+    /// first request that isn't
+    fn lookup<'a>(
+        &self,
+        requests: &'a [SerializedRequest],
+        keys: &[RequestKey],
+    ) -> Result<Vec<Response>, &'a SerializedRequest> {
+        let responses = self.responses.lock().unwrap();
+        requests
+            .iter()
+            .zip(keys)
+            .map(|(request, key)| match responses.get(key) {
+                Some(payload) => {
+                    Ok(Response { id: request.id().clone(), payload: payload.clone() })
+                }
+                None => Err(request),
+            })
+            .collect()
+    }
+
+    /// Records the response to the request, unless it's already recorded or shouldn't be replayed
+    fn record(&self, request: &SerializedRequest, key: RequestKey, payload: &ResponsePayload) {
+        if payload.as_error().is_some_and(|err| err.is_retry_err()) {
+            return;
+        }
+        let Some(file) = &self.file else { return };
+        let mut responses = self.responses.lock().unwrap();
+        if responses.contains_key(&key) {
+            return;
+        }
+
+        let (result, error) = match payload {
+            ResponsePayload::Success(result) => (Some(result.clone()), None),
+            ResponsePayload::Failure(error) => (None, Some(error.clone())),
+        };
+        let entry = CassetteEntry {
+            endpoint: key.endpoint.clone(),
+            method: key.method.clone(),
+            params: request.params().map(ToOwned::to_owned),
+            result,
+            error,
+        };
+        // entries are written with a single call, so that concurrent recorders of the same
+        // cassette don't interleave lines
+        let mut line = serde_json::to_vec(&entry).expect("cassette entries are valid JSON");
+        line.push(b'\n');
+        if let Err(err) = file.lock().unwrap().write_all(&line) {
+            warn!(target: "cassette", ?err, path = %self.path.display(), "failed to record response");
+            return;
+        }
+        responses.insert(key, payload.clone());
+    }
+
+    fn miss(&self, endpoint: &str, request: &SerializedRequest) -> TransportError {
+        let params = request.params().map(RawValue::get).unwrap_or("[]");
+        TransportErrorKind::custom_str(&format!(
+            "no response to `{}` with params {params} from {endpoint} is recorded in cassette {}",
+            request.method(),
+            self.path.display()
+        ))
+    }
+}
+
+/// A [Layer] that records or replays all requests with a [Cassette]
+#[derive(Clone, Debug)]
+pub struct CassetteLayer {
+    cassette: Arc<Cassette>,
+    endpoint: String,
+}
+
+impl CassetteLayer {
+    /// Creates a layer for requests to the given endpoint
+    pub fn new(cassette: Arc<Cassette>, url: &Url) -> Self {
+        let endpoint = url.host_str().unwrap_or_else(|| url.path()).to_string();
+        Self { cassette, endpoint }
+    }
+}
+
+impl<S> Layer<S> for CassetteLayer {
+    type Service = CassetteService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CassetteService { inner, cassette: self.cassette.clone(), endpoint: self.endpoint.clone() }
+    }
+}
+
+/// A transport that serves recorded responses and, in [CassetteMode::Record], forwards all other
+/// requests to the inner transport and records their responses
+#[derive(Clone, Debug)]
+pub struct CassetteService<S> {
+    inner: S,
+    cassette: Arc<Cassette>,
+    endpoint: String,
+}
+
+impl<S> CassetteService<S>
+where
+    S: Service<RequestPacket, Response = ResponsePacket, Error = TransportError> + Send + 'static,
+    S::Future: Send,
+{
+    async fn request(mut self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let requests = match &request {
+            RequestPacket::Single(request) => std::slice::from_ref(request),
+            RequestPacket::Batch(requests) => requests.as_slice(),
+        };
+        let keys =
+            requests.iter().map(|req| RequestKey::new(&self.endpoint, req)).collect::<Vec<_>>();
+
+        let missing = match self.cassette.lookup(requests, &keys) {
+            Ok(responses) => return Ok(into_packet(&request, responses)),
+            Err(missing) => missing,
+        };
+        if self.cassette.mode == CassetteMode::Replay {
+            return Err(self.cassette.miss(&self.endpoint, missing));
+        }
+
+        let response = self.inner.call(request.clone()).await?;
+        let responses = match &response {
+            ResponsePacket::Single(response) => std::slice::from_ref(response),
+            ResponsePacket::Batch(responses) => responses.as_slice(),
+        };
+        for response in responses {
+            if let Some((request, key)) =
+                requests.iter().zip(&keys).find(|(request, _)| *request.id() == response.id)
+            {
+                self.cassette.record(request, key.clone(), &response.payload);
+            }
+        }
+        Ok(response)
+    }
+}
+
+impl<S> Service<RequestPacket> for CassetteService<S>
+where
+    S: Service<RequestPacket, Response = ResponsePacket, Error = TransportError>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.cassette.mode {
+            CassetteMode::Record => self.inner.poll_ready(cx),
+            CassetteMode::Replay => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().request(request))
+    }
+}
+
+/// Returns the responses in the shape of the request
+fn into_packet(request: &RequestPacket, mut responses: Vec<Response>) -> ResponsePacket {
+    match request {
+        RequestPacket::Single(_) => ResponsePacket::Single(responses.remove(0)),
+        RequestPacket::Batch(_) => ResponsePacket::Batch(responses),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_json_rpc::{Id, Request};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A transport that responds with the number of requests it received so far
+    #[derive(Clone, Default)]
+    struct CountingTransport(Arc<AtomicUsize>);
+
+    impl Service<RequestPacket> for CountingTransport {
+        type Response = ResponsePacket;
+        type Error = TransportError;
+        type Future = TransportFut<'static>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: RequestPacket) -> Self::Future {
+            let RequestPacket::Single(request) = request else { unreachable!() };
+            let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            let payload = RawValue::from_string(count.to_string()).unwrap();
+            let response =
+                Response { id: request.id().clone(), payload: ResponsePayload::Success(payload) };
+            Box::pin(async move { Ok(ResponsePacket::Single(response)) })
+        }
+    }
+
+    fn request(id: u64, method: &'static str, params: &str) -> RequestPacket {
+        let params = RawValue::from_string(params.to_string()).unwrap();
+        Request::new(method, Id::Number(id), params).serialize().unwrap().into()
+    }
+
+    async fn send<S>(service: &mut S, request: RequestPacket) -> Result<String, TransportError>
+    where
+        S: Service<RequestPacket, Response = ResponsePacket, Error = TransportError>,
+    {
+        let ResponsePacket::Single(response) = service.call(request).await? else { unreachable!() };
+        Ok(response.payload.as_success().unwrap().get().to_string())
+    }
+
+    #[tokio::test]
+    async fn can_record_and_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fork").join("cassette.jsonl");
+        let url = Url::parse("https://eth.example.com/v2/secret").unwrap();
+
+        let transport = CountingTransport::default();
+        let cassette = Arc::new(Cassette::open(&CassetteConfig::record(&path)).unwrap());
+        let mut recorder = CassetteLayer::new(cassette, &url).layer(transport.clone());
+        let balance = r#"["0x0000000000000000000000000000000000000001","latest"]"#;
+        assert_eq!(send(&mut recorder, request(1, "eth_getBalance", balance)).await.unwrap(), "1");
+        assert_eq!(send(&mut recorder, request(2, "eth_blockNumber", "[]")).await.unwrap(), "2");
+        // recorded responses are served from the cassette
+        assert_eq!(send(&mut recorder, request(3, "eth_getBalance", balance)).await.unwrap(), "1");
+        assert_eq!(transport.0.load(Ordering::SeqCst), 2);
+
+        let recorded = fs::read_to_string(&path).unwrap();
+        assert_eq!(recorded.lines().count(), 2);
+        assert!(!recorded.contains("secret"));
+
+        // replays from another URL of the same host and without contacting the endpoint
+        let url = Url::parse("https://eth.example.com/v2/other").unwrap();
+        let cassette = Arc::new(Cassette::open(&CassetteConfig::replay(&path)).unwrap());
+        assert_eq!(cassette.len(), 2);
+        let mut replayer = CassetteLayer::new(cassette, &url).layer(transport.clone());
+        assert_eq!(send(&mut replayer, request(7, "eth_getBalance", balance)).await.unwrap(), "1");
+        assert_eq!(send(&mut replayer, request(8, "eth_blockNumber", "[]")).await.unwrap(), "2");
+
+        let err = send(&mut replayer, request(9, "eth_chainId", "[]")).await.unwrap_err();
+        assert!(err.to_string().contains("no response to `eth_chainId`"), "{err}");
+        assert_eq!(transport.0.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn replaying_missing_cassette_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = CassetteConfig::replay(dir.path().join("missing.jsonl"));
+        let err = Cassette::open(&config).unwrap_err();
+        assert!(err.to_string().contains("failed to load cassette"), "{err}");
+    }
+}
@@ -1,16 +1,21 @@
 //! Provider-related instantiation and usage utilities.
 
+pub mod cassette;
 pub mod runtime_transport;
 
 use crate::{
-    provider::runtime_transport::RuntimeTransportBuilder, ALCHEMY_FREE_TIER_CUPS, REQUEST_TIMEOUT,
+    provider::{
+        cassette::{Cassette, CassetteConfig, CassetteLayer},
+        runtime_transport::{RuntimeTransport, RuntimeTransportBuilder},
+    },
+    ALCHEMY_FREE_TIER_CUPS, REQUEST_TIMEOUT,
 };
 use alloy_provider::{
     fillers::{ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller, WalletFiller},
     network::{AnyNetwork, EthereumWallet},
     Identity, ProviderBuilder as AlloyProviderBuilder, RootProvider,
 };
-use alloy_rpc_client::ClientBuilder;
+use alloy_rpc_client::{ClientBuilder, RpcClient};
 use alloy_transport::{layers::RetryBackoffLayer, utils::guess_local_url};
 use eyre::{Result, WrapErr};
 use foundry_config::NamedChain;
@@ -19,6 +24,7 @@
     net::SocketAddr,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
     time::Duration,
 };
 use url::ParseError;
@@ -96,6 +102,8 @@ pub struct ProviderBuilder {
     jwt: Option<String>,
     headers: Vec<String>,
     is_local: bool,
+    /// Records or replays all requests
+    cassette: Option<CassetteConfig>,
 }
 
 impl ProviderBuilder {
@@ -145,6 +153,7 @@ pub fn new(url_str: &str) -> Self {
             jwt: None,
             headers: vec![],
             is_local,
+            cassette: None,
         }
     }
 
@@ -242,6 +251,15 @@ pub fn maybe_headers(mut self, headers: Option<Vec<String>>) -> Self {
         self
     }
 
+    /// Records all requests to or replays them from a cassette. If `None`, defaults to the
+    /// already-set value.
+    ///
+    /// See [`cassette`] for more details.
+    pub fn maybe_cassette(mut self, cassette: Option<CassetteConfig>) -> Self {
+        self.cassette = cassette.or(self.cassette);
+        self
+    }
+
     /// Constructs the `RetryProvider` taking all configs into account.
     pub fn build(self) -> Result<RetryProvider> {
         let Self {
@@ -254,18 +272,24 @@ pub fn build(self) -> Result<RetryProvider> {
             jwt,
             headers,
             is_local,
+            cassette,
         } = self;
         let url = url?;
 
         let retry_layer =
             RetryBackoffLayer::new(max_retry, initial_backoff, compute_units_per_second);
+        let cassette = cassette
+            .map(|cassette| {
+                Cassette::open(&cassette).map(|c| CassetteLayer::new(Arc::new(c), &url))
+            })
+            .transpose()?;
 
         let transport = RuntimeTransportBuilder::new(url)
             .with_timeout(timeout)
             .with_headers(headers)
             .with_jwt(jwt)
             .build();
-        let client = ClientBuilder::default().layer(retry_layer).transport(transport, is_local);
+        let client = rpc_client(retry_layer, cassette, transport, is_local);
 
         if !is_local {
             client.set_poll_interval(
@@ -297,11 +321,17 @@ pub fn build_with_wallet(self, wallet: EthereumWallet) -> Result<RetryProviderWi
             jwt,
             headers,
             is_local,
+            cassette,
         } = self;
         let url = url?;
 
         let retry_layer =
             RetryBackoffLayer::new(max_retry, initial_backoff, compute_units_per_second);
+        let cassette = cassette
+            .map(|cassette| {
+                Cassette::open(&cassette).map(|c| CassetteLayer::new(Arc::new(c), &url))
+            })
+            .transpose()?;
 
         let transport = RuntimeTransportBuilder::new(url)
             .with_timeout(timeout)
@@ -309,7 +339,7 @@ pub fn build_with_wallet(self, wallet: EthereumWallet) -> Result<RetryProviderWi
             .with_jwt(jwt)
             .build();
 
-        let client = ClientBuilder::default().layer(retry_layer).transport(transport, is_local);
+        let client = rpc_client(retry_layer, cassette, transport, is_local);
 
         if !is_local {
             client.set_poll_interval(
@@ -329,6 +359,20 @@ pub fn build_with_wallet(self, wallet: EthereumWallet) -> Result<RetryProviderWi
     }
 }
 
+/// Creates the client with the retry layer and, if set, the cassette layer on top of the transport.
+fn rpc_client(
+    retry_layer: RetryBackoffLayer,
+    cassette: Option<CassetteLayer>,
+    transport: RuntimeTransport,
+    is_local: bool,
+) -> RpcClient {
+    let builder = ClientBuilder::default().layer(retry_layer);
+    match cassette {
+        Some(cassette) => builder.layer(cassette).transport(transport, is_local),
+        None => builder.transport(transport, is_local),
+    }
+}
+
 #[cfg(not(windows))]
 fn resolve_path(path: &Path) -> Result<PathBuf, ()> {
     if path.is_absolute() {
@@ -26,10 +26,23 @@ pub struct FuzzConfig {
     pub failure_persist_dir: Option<PathBuf>,
     /// Name of the file to record fuzz failures, defaults to `failures`.
     pub failure_persist_file: Option<String>,
+    /// Whether counterexamples of fuzz and invariant tests are recorded to and replayed from the
+    /// regression corpora, defaults to `true`.
+    pub regressions: bool,
+    /// Path where counterexamples of fuzz and invariant tests are appended to the regression
+    /// corpus of each test, to be replayed before fuzzing. Counterexamples are not recorded if
+    /// unset.
+    pub regression_persist_dir: Option<PathBuf>,
+    /// Path of the checked-in regression corpus, replayed before fuzzing. Recorded
+    /// counterexamples are promoted to it with `forge regressions promote`. Disabled if unset.
+    pub regressions_dir: Option<PathBuf>,
     /// show `console.log` in fuzz test, defaults to `false`
     pub show_logs: bool,
     /// Optional timeout (in seconds) for each property test
     pub timeout: Option<u32>,
+    /// Path where the corpus of coverage-guided fuzzing is kept, coverage-guided fuzzing is
+    /// disabled if unset.
+    pub corpus_dir: Option<PathBuf>,
 }
 
 impl Default for FuzzConfig {
@@ -42,8 +55,12 @@ fn default() -> Self {
             gas_report_samples: 256,
             failure_persist_dir: None,
             failure_persist_file: None,
+            regressions: true,
+            regression_persist_dir: None,
+            regressions_dir: None,
             show_logs: false,
             timeout: None,
+            corpus_dir: None,
         }
     }
 }
@@ -57,6 +74,14 @@ pub fn new(cache_dir: PathBuf) -> Self {
             ..Default::default()
         }
     }
+
+    /// Returns path to the corpus dir of the given fuzz test, if coverage-guided fuzzing is
+    /// enabled.
+    pub fn test_corpus_dir(&self, contract_name: &str, test_name: &str) -> Option<PathBuf> {
+        self.corpus_dir
+            .as_ref()
+            .map(|dir| dir.join(contract_name.split(':').next_back().unwrap()).join(test_name))
+    }
 }
 
 /// Contains for fuzz testing
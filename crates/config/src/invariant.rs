@@ -34,6 +34,13 @@ pub struct InvariantConfig {
     pub timeout: Option<u32>,
     /// Display counterexample as solidity calls.
     pub show_solidity: bool,
+    /// Path where the corpus of coverage-guided fuzzing is kept, coverage-guided fuzzing is
+    /// disabled if unset.
+    pub corpus_dir: Option<PathBuf>,
+    /// Number of threads the runs of each invariant test are distributed across, runs are
+    /// executed sequentially if unset and on all available threads if set to 0. Each thread
+    /// persists new corpus entries in its own subdirectory of the corpus dir.
+    pub threads: Option<usize>,
 }
 
 impl Default for InvariantConfig {
@@ -51,6 +58,8 @@ fn default() -> Self {
             show_metrics: false,
             timeout: None,
             show_solidity: false,
+            corpus_dir: None,
+            threads: None,
         }
     }
 }
@@ -71,6 +80,8 @@ pub fn new(cache_dir: PathBuf) -> Self {
             show_metrics: false,
             timeout: None,
             show_solidity: false,
+            corpus_dir: None,
+            threads: None,
         }
     }
 
@@ -81,4 +92,12 @@ pub fn failure_dir(self, contract_name: &str) -> PathBuf {
             .join("failures")
             .join(contract_name.split(':').next_back().unwrap())
     }
+
+    /// Returns path to the corpus dir of the given invariant test, if coverage-guided fuzzing is
+    /// enabled.
+    pub fn test_corpus_dir(&self, contract_name: &str, test_name: &str) -> Option<PathBuf> {
+        self.corpus_dir
+            .as_ref()
+            .map(|dir| dir.join(contract_name.split(':').next_back().unwrap()).join(test_name))
+    }
 }
@@ -1065,7 +1065,8 @@ pub fn cleanup<C: Compiler, T: ArtifactOutput<CompilerContract = C::CompilerCont
         // Remove last test run failures file.
         let _ = fs::remove_file(&self.test_failures_file);
 
-        // Remove fuzz and invariant cache directories.
+        // Remove fuzz and invariant cache directories, including the recorded regression corpus.
+        // Promoted counterexamples are kept in the checked-in regression corpus.
         let remove_test_dir = |test_dir: &Option<PathBuf>| {
             if let Some(test_dir) = test_dir {
                 let path = project.root().join(test_dir);
@@ -1076,6 +1077,7 @@ pub fn cleanup<C: Compiler, T: ArtifactOutput<CompilerContract = C::CompilerCont
         };
         remove_test_dir(&self.fuzz.failure_persist_dir);
         remove_test_dir(&self.invariant.failure_persist_dir);
+        remove_test_dir(&self.fuzz.regression_persist_dir);
 
         Ok(())
     }
@@ -2353,7 +2355,11 @@ fn default() -> Self {
             test_failures_file: "cache/test-failures".into(),
             threads: None,
             show_progress: false,
-            fuzz: FuzzConfig::new("cache/fuzz".into()),
+            fuzz: FuzzConfig {
+                regression_persist_dir: Some("cache/regressions".into()),
+                regressions_dir: Some("regressions".into()),
+                ..FuzzConfig::new("cache/fuzz".into())
+            },
             invariant: InvariantConfig::new("cache/invariant".into()),
             always_use_create_2_factory: false,
             ffi: false,
@@ -3,7 +3,10 @@
 use alloy_primitives::{Address, B256, U256};
 use alloy_provider::{network::AnyRpcBlock, Provider};
 use eyre::WrapErr;
-use foundry_common::{provider::ProviderBuilder, ALCHEMY_FREE_TIER_CUPS};
+use foundry_common::{
+    provider::{cassette::CassetteConfig, ProviderBuilder},
+    ALCHEMY_FREE_TIER_CUPS,
+};
 use foundry_config::{Chain, Config, GasLimit};
 use revm::primitives::{BlockEnv, CfgEnv, TxEnv};
 use serde::{Deserialize, Serialize};
@@ -32,6 +35,9 @@ pub struct EvmOpts {
     /// Headers to use with `fork_url`
     pub fork_headers: Option<Vec<String>>,
 
+    /// Records all fork requests to or replays them from a cassette.
+    pub fork_cassette: Option<CassetteConfig>,
+
     /// The available compute units per second.
     ///
     /// See also <https://docs.alchemy.com/reference/compute-units#what-are-cups-compute-units-per-second>
@@ -84,6 +90,7 @@ fn default() -> Self {
             fork_retries: None,
             fork_retry_backoff: None,
             fork_headers: None,
+            fork_cassette: None,
             compute_units_per_second: None,
             no_rpc_rate_limit: false,
             no_storage_caching: false,
@@ -122,6 +129,7 @@ pub async fn fork_evm_env(
     ) -> eyre::Result<(revm::primitives::Env, AnyRpcBlock)> {
         let provider = ProviderBuilder::new(fork_url)
             .compute_units_per_second(self.get_compute_units_per_second())
+            .maybe_cassette(self.fork_cassette.clone())
             .build()?;
         environment(
             &provider,
@@ -233,6 +241,7 @@ pub async fn get_remote_chain_id(&self) -> Option<Chain> {
             trace!(?url, "retrieving chain via eth_chainId");
             let provider = ProviderBuilder::new(url.as_str())
                 .compute_units_per_second(self.get_compute_units_per_second())
+                .maybe_cassette(self.fork_cassette.clone())
                 .build()
                 .ok()
                 .unwrap_or_else(|| panic!("Failed to establish provider to {url}"));
@@ -11,13 +11,15 @@
 };
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_fuzz::{
+    invariant::{BasicTxDetails, CallDetails},
     strategies::{fuzz_calldata, fuzz_calldata_from_state, EvmFuzzState},
-    BaseCounterExample, CounterExample, FuzzCase, FuzzError, FuzzFixtures, FuzzTestResult,
+    BaseCounterExample, Corpus, CorpusCallStrategy, CounterExample, FuzzCase, FuzzError,
+    FuzzFixtures, FuzzTestResult,
 };
 use foundry_evm_traces::SparsedTraceArena;
 use indicatif::ProgressBar;
 use proptest::test_runner::{TestCaseError, TestError, TestRunner};
-use std::{cell::RefCell, collections::BTreeMap};
+use std::{cell::RefCell, collections::BTreeMap, path::PathBuf};
 
 mod types;
 pub use types::{CaseOutcome, CounterExampleOutcome, FuzzOutcome};
@@ -59,17 +61,28 @@ pub struct FuzzedExecutor {
     sender: Address,
     /// The fuzz configuration
     config: FuzzConfig,
+    /// The corpus dir of the test, if coverage-guided fuzzing is enabled
+    corpus_dir: Option<PathBuf>,
+    /// Calldata of the regression corpus of the test, replayed before fuzzing new inputs
+    regressions: Vec<Bytes>,
 }
 
 impl FuzzedExecutor {
     /// Instantiates a fuzzed executor given a testrunner
+    ///
+    /// Coverage is collected to guide the fuzzer if a corpus dir is given.
     pub fn new(
-        executor: Executor,
+        mut executor: Executor,
         runner: TestRunner,
         sender: Address,
         config: FuzzConfig,
+        corpus_dir: Option<PathBuf>,
+        regressions: Vec<Bytes>,
     ) -> Self {
-        Self { executor, runner, sender, config }
+        if corpus_dir.is_some() {
+            executor.inspector_mut().collect_coverage(true);
+        }
+        Self { executor, runner, sender, config, corpus_dir, regressions }
     }
 
     /// Fuzzes the provided function, assuming it is available at the contract at `address`
@@ -91,10 +104,19 @@ pub fn fuzz(
         let execution_data = RefCell::new(FuzzTestData::default());
         let state = self.build_fuzz_state(deployed_libs);
         let dictionary_weight = self.config.dictionary.dictionary_weight.min(100);
-        let strategy = proptest::prop_oneof![
-            100 - dictionary_weight => fuzz_calldata(func.clone(), fuzz_fixtures),
-            dictionary_weight => fuzz_calldata_from_state(func.clone(), &state),
-        ];
+        let corpus =
+            self.corpus_dir.clone().map(|dir| RefCell::new(self.load_corpus(dir, address)));
+        // Inputs are derived from the corpus within the strategy, so proptest sees the executed
+        // calldata.
+        let strategy = CorpusCallStrategy::new(
+            proptest::prop_oneof![
+                100 - dictionary_weight => fuzz_calldata(func.clone(), fuzz_fixtures),
+                dictionary_weight => fuzz_calldata_from_state(func.clone(), &state),
+            ],
+            corpus.as_ref(),
+            func,
+            &state,
+        );
         // We want to collect at least one trace which will be displayed to user.
         let max_traces_to_collect = std::cmp::max(1, self.config.gas_report_samples) as usize;
         let show_logs = self.config.show_logs;
@@ -102,63 +124,83 @@ pub fn fuzz(
         // Start timer for this fuzz test.
         let timer = FuzzTestTimer::new(self.config.timeout);
 
-        let run_result = self.runner.clone().run(&strategy, |calldata| {
-            // Check if the timeout has been reached.
-            if timer.is_timed_out() {
-                return Err(TestCaseError::fail(TEST_TIMEOUT));
-            }
+        // Replay the regression corpus before fuzzing new inputs, the campaign is not run if one
+        // of the counterexamples still breaks the test.
+        let run_result = match self.replay_regressions(address, rd, &execution_data) {
+            Some(failure) => Err(failure),
+            None => self.runner.clone().run(&strategy, |calldata| {
+                // Check if the timeout has been reached.
+                if timer.is_timed_out() {
+                    return Err(TestCaseError::fail(TEST_TIMEOUT));
+                }
 
-            let fuzz_res = self.single_fuzz(address, calldata)?;
+                let fuzz_res = self.single_fuzz(address, calldata)?;
 
-            // If running with progress then increment current run.
-            if let Some(progress) = progress {
-                progress.inc(1);
-            };
+                // If running with progress then increment current run.
+                if let Some(progress) = progress {
+                    progress.inc(1);
+                };
 
-            match fuzz_res {
-                FuzzOutcome::Case(case) => {
-                    let mut data = execution_data.borrow_mut();
-                    data.gas_by_case.push((case.case.gas, case.case.stipend));
+                match fuzz_res {
+                    FuzzOutcome::Case(case) => {
+                        // Keep the input in the corpus if it reached new coverage.
+                        if let Some(corpus) = &corpus {
+                            let mut corpus = corpus.borrow_mut();
+                            if corpus.merge_coverage(case.coverage.as_ref()) {
+                                corpus.insert(vec![BasicTxDetails {
+                                    sender: self.sender,
+                                    call_details: CallDetails {
+                                        target: address,
+                                        calldata: case.case.calldata.clone(),
+                                    },
+                                }]);
+                            }
+                        }
 
-                    if data.first_case.is_none() {
-                        data.first_case.replace(case.case);
-                    }
+                        let mut data = execution_data.borrow_mut();
+                        data.gas_by_case.push((case.case.gas, case.case.stipend));
 
-                    if let Some(call_traces) = case.traces {
-                        if data.traces.len() == max_traces_to_collect {
-                            data.traces.pop();
+                        if data.first_case.is_none() {
+                            data.first_case.replace(case.case);
                         }
-                        data.traces.push(call_traces);
-                        data.breakpoints.replace(case.breakpoints);
-                    }
 
-                    if show_logs {
-                        data.logs.extend(case.logs);
-                    }
+                        if let Some(call_traces) = case.traces {
+                            if data.traces.len() == max_traces_to_collect {
+                                data.traces.pop();
+                            }
+                            data.traces.push(call_traces);
+                            data.breakpoints.replace(case.breakpoints);
+                        }
 
-                    HitMaps::merge_opt(&mut data.coverage, case.coverage);
+                        if show_logs {
+                            data.logs.extend(case.logs);
+                        }
 
-                    data.deprecated_cheatcodes = case.deprecated_cheatcodes;
+                        HitMaps::merge_opt(&mut data.coverage, case.coverage);
 
-                    Ok(())
-                }
-                FuzzOutcome::CounterExample(CounterExampleOutcome {
-                    exit_reason: status,
-                    counterexample: outcome,
-                    ..
-                }) => {
-                    // We cannot use the calldata returned by the test runner in `TestError::Fail`,
-                    // since that input represents the last run case, which may not correspond with
-                    // our failure - when a fuzz case fails, proptest will try to run at least one
-                    // more case to find a minimal failure case.
-                    let reason = rd.maybe_decode(&outcome.1.result, Some(status));
-                    execution_data.borrow_mut().logs.extend(outcome.1.logs.clone());
-                    execution_data.borrow_mut().counterexample = outcome;
-                    // HACK: we have to use an empty string here to denote `None`.
-                    Err(TestCaseError::fail(reason.unwrap_or_default()))
+                        data.deprecated_cheatcodes = case.deprecated_cheatcodes;
+
+                        Ok(())
+                    }
+                    FuzzOutcome::CounterExample(CounterExampleOutcome {
+                        exit_reason: status,
+                        counterexample: outcome,
+                        ..
+                    }) => {
+                        // We cannot use the calldata returned by the test runner in
+                        // `TestError::Fail`, since that input represents
+                        // the last run case, which may not correspond with
+                        // our failure - when a fuzz case fails, proptest will try to run at least
+                        // one more case to find a minimal failure case.
+                        let reason = rd.maybe_decode(&outcome.1.result, Some(status));
+                        execution_data.borrow_mut().logs.extend(outcome.1.logs.clone());
+                        execution_data.borrow_mut().counterexample = outcome;
+                        // HACK: we have to use an empty string here to denote `None`.
+                        Err(TestCaseError::fail(reason.unwrap_or_default()))
+                    }
                 }
-            }
-        });
+            }),
+        };
 
         let fuzz_result = execution_data.into_inner();
         let (calldata, call) = fuzz_result.counterexample;
@@ -232,6 +274,42 @@ pub fn fuzz(
         result
     }
 
+    /// Replays the calldata of the regression corpus of the test, and returns the failure of the
+    /// first counterexample that still breaks it, if any.
+    fn replay_regressions(
+        &self,
+        address: Address,
+        rd: &RevertDecoder,
+        execution_data: &RefCell<FuzzTestData>,
+    ) -> Option<TestError<Bytes>> {
+        for calldata in &self.regressions {
+            match self.single_fuzz(address, calldata.clone()) {
+                Ok(FuzzOutcome::Case(_)) => {}
+                Ok(FuzzOutcome::CounterExample(CounterExampleOutcome {
+                    exit_reason: status,
+                    counterexample: outcome,
+                    ..
+                })) => {
+                    let reason = rd.maybe_decode(&outcome.1.result, Some(status));
+                    let mut data = execution_data.borrow_mut();
+                    data.logs.extend(outcome.1.logs.clone());
+                    data.counterexample = outcome;
+                    return Some(TestError::Fail(
+                        reason.unwrap_or_default().into(),
+                        calldata.clone(),
+                    ))
+                }
+                // Counterexamples rejected by `vm.assume` no longer apply to the test.
+                Err(TestCaseError::Reject(_)) => {}
+                Err(TestCaseError::Fail(reason)) => {
+                    execution_data.borrow_mut().counterexample.0 = calldata.clone();
+                    return Some(TestError::Fail(reason, calldata.clone()))
+                }
+            }
+        }
+        None
+    }
+
     /// Granular and single-step function that runs only one fuzz and returns either a `CaseOutcome`
     /// or a `CounterExampleOutcome`
     pub fn single_fuzz(
@@ -273,6 +351,23 @@ pub fn single_fuzz(
         }
     }
 
+    /// Loads the corpus of the test from the given dir, and merges the coverage of its entries so
+    /// that only inputs that reach new coverage are added.
+    fn load_corpus(&self, dir: PathBuf, address: Address) -> Corpus {
+        let mut corpus = Corpus::load(dir);
+        let calldatas = corpus
+            .entries()
+            .iter()
+            .map(|calls| calls[0].call_details.calldata.clone())
+            .collect::<Vec<_>>();
+        for calldata in calldatas {
+            if let Ok(call) = self.executor.call_raw(self.sender, address, calldata, U256::ZERO) {
+                corpus.merge_coverage(call.coverage.as_ref());
+            }
+        }
+        corpus
+    }
+
     /// Stores fuzz state for use with [fuzz_calldata_from_state]
     pub fn build_fuzz_state(&self, deployed_libs: &[Address]) -> EvmFuzzState {
         if let Some(fork_db) = self.executor.backend().active_fork_db() {
@@ -20,22 +20,28 @@
         RandomCallGenerator, SenderFilters, TargetedContract, TargetedContracts,
     },
     strategies::{invariant_strat, override_call_strat, EvmFuzzState},
-    FuzzCase, FuzzFixtures, FuzzedCases,
+    Corpus, FuzzCase, FuzzFixtures, FuzzedCases,
 };
 use foundry_evm_traces::{CallTraceArena, SparsedTraceArena};
 use indicatif::ProgressBar;
 use parking_lot::RwLock;
 use proptest::{
+    prelude::RngCore,
     strategy::{Strategy, ValueTree},
-    test_runner::{TestCaseError, TestRunner},
+    test_runner::{RngAlgorithm, TestCaseError, TestRng, TestRunner},
 };
+use rayon::prelude::*;
 use result::{assert_after_invariant, assert_invariants, can_continue};
 use revm::primitives::HashMap;
 use shrink::shrink_sequence;
 use std::{
     cell::RefCell,
     collections::{btree_map::Entry, HashMap as Map},
-    sync::Arc,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 mod error;
@@ -134,6 +140,8 @@ pub struct InvariantTestData {
     pub coverage: Option<HitMaps>,
     // Metrics for each fuzzed selector.
     pub metrics: Map<String, InvariantMetrics>,
+    // Call sequences that reached new coverage, if coverage-guided fuzzing is enabled.
+    pub corpus: Option<Corpus>,
 
     // Proptest runner to query for random values.
     // The strategy only comes with the first `input`. We fill the rest of the `inputs`
@@ -159,6 +167,7 @@ pub fn new(
         targeted_contracts: FuzzRunIdentifiedContracts,
         failures: InvariantFailures,
         last_call_results: Option<RawCallResult>,
+        corpus: Option<Corpus>,
         branch_runner: TestRunner,
     ) -> Self {
         let mut fuzz_cases = vec![];
@@ -173,6 +182,7 @@ pub fn new(
             last_call_results,
             coverage: None,
             metrics: Map::default(),
+            corpus,
             branch_runner,
         });
         Self { fuzz_state, targeted_contracts, execution_data }
@@ -208,6 +218,33 @@ pub fn merge_coverage(&self, new_coverage: Option<HitMaps>) {
         HitMaps::merge_opt(&mut self.execution_data.borrow_mut().coverage, new_coverage);
     }
 
+    /// Merge the coverage of the last fuzzed call into the corpus coverage, returns `true` if it
+    /// reached new coverage.
+    pub fn merge_corpus_coverage(&self, coverage: Option<&HitMaps>) -> bool {
+        self.execution_data
+            .borrow_mut()
+            .corpus
+            .as_mut()
+            .is_some_and(|corpus| corpus.merge_coverage(coverage))
+    }
+
+    /// Returns the call sequence derived from the corpus to start the next run with, empty if the
+    /// run should only use generated calls.
+    pub fn corpus_sequence(&self) -> Vec<BasicTxDetails> {
+        let mut invariant_data = self.execution_data.borrow_mut();
+        let InvariantTestData { corpus, branch_runner, .. } = &mut *invariant_data;
+        let Some(corpus) = corpus.as_ref() else { return vec![] };
+        if !corpus.should_mutate(branch_runner.rng()) {
+            return vec![];
+        }
+        let targets = self.targeted_contracts.targets.lock();
+        corpus.mutate_sequence(
+            |tx| targets.fuzzed_artifacts(tx).1,
+            &self.fuzz_state,
+            branch_runner.rng(),
+        )
+    }
+
     /// Update metrics for a fuzzed selector, extracted from tx details.
     /// Always increments number of calls; discarded runs (through assume cheatcodes) are tracked
     /// separated from reverts.
@@ -240,6 +277,15 @@ pub fn end_run(&self, run: InvariantTestRun, gas_samples: usize) {
         }
         invariant_data.fuzz_cases.push(FuzzedCases::new(run.fuzz_runs));
 
+        // Keep the executed calls in the corpus if the run reached new coverage.
+        if run.new_coverage {
+            if let Some(corpus) = invariant_data.corpus.as_mut() {
+                let mut inputs = run.inputs;
+                inputs.truncate(run.depth as usize);
+                corpus.insert(inputs);
+            }
+        }
+
         // Revert state to not persist values between runs.
         self.fuzz_state.revert();
     }
@@ -261,6 +307,8 @@ pub struct InvariantTestRun {
     pub depth: u32,
     // Current assume rejects of the invariant run.
     pub assume_rejects_counter: u32,
+    // Whether any call of the invariant run reached new coverage of the corpus.
+    pub new_coverage: bool,
 }
 
 impl InvariantTestRun {
@@ -274,6 +322,7 @@ pub fn new(first_input: BasicTxDetails, executor: Executor, depth: usize) -> Sel
             run_traces: vec![],
             depth: 0,
             assume_rejects_counter: 0,
+            new_coverage: false,
         }
     }
 }
@@ -297,17 +346,27 @@ pub struct InvariantExecutor<'a> {
     project_contracts: &'a ContractsByArtifact,
     /// Filters contracts to be fuzzed through their artifact identifiers.
     artifact_filters: ArtifactFilters,
+    /// The corpus dir of the test, if coverage-guided fuzzing is enabled.
+    corpus_dir: Option<PathBuf>,
+    /// The index of the worker, if the executor runs a share of a parallel campaign.
+    worker: Option<usize>,
 }
 
 impl<'a> InvariantExecutor<'a> {
     /// Instantiates a fuzzed executor EVM given a testrunner
+    ///
+    /// Coverage is collected to guide the fuzzer if a corpus dir is given.
     pub fn new(
-        executor: Executor,
+        mut executor: Executor,
         runner: TestRunner,
         config: InvariantConfig,
         setup_contracts: &'a ContractsByAddress,
         project_contracts: &'a ContractsByArtifact,
+        corpus_dir: Option<PathBuf>,
     ) -> Self {
+        if corpus_dir.is_some() {
+            executor.inspector_mut().collect_coverage(true);
+        }
         Self {
             executor,
             runner,
@@ -315,10 +374,17 @@ pub fn new(
             setup_contracts,
             project_contracts,
             artifact_filters: ArtifactFilters::default(),
+            corpus_dir,
+            worker: None,
         }
     }
 
     /// Fuzzes any deployed contract and checks any broken invariant at `invariant_address`.
+    ///
+    /// If configured, the runs are distributed across workers, each fuzzing with its own executor
+    /// cloned from the `setUp` state and its own runner seeded from the test runner. Results and
+    /// fuzz dictionaries of workers are merged in order up to the first failing worker, so that the
+    /// same seed always reports the same failure.
     pub fn invariant_fuzz(
         &mut self,
         invariant_contract: InvariantContract<'_>,
@@ -331,16 +397,111 @@ pub fn invariant_fuzz(
             return Err(eyre!("Invariant test function should have no inputs"))
         }
 
-        let (invariant_test, invariant_strategy) =
-            self.prepare_test(&invariant_contract, fuzz_fixtures, deployed_libs)?;
-
         // Start timer for this invariant test.
         let timer = FuzzTestTimer::new(self.config.timeout);
 
+        let threads = match self.config.threads {
+            Some(0) => rayon::current_num_threads(),
+            Some(threads) => threads,
+            None => 1,
+        }
+        .min(self.config.runs as usize);
+        let failed_worker = AtomicUsize::new(usize::MAX);
+        if threads <= 1 {
+            let (result, fuzz_state) = self.run_campaign(
+                &invariant_contract,
+                fuzz_fixtures,
+                deployed_libs,
+                progress,
+                &timer,
+                0,
+                &failed_worker,
+            )?;
+            fuzz_state.log_stats();
+            return Ok(result)
+        }
+
+        let workers = (0..threads).map(|worker| self.worker(worker, threads)).collect::<Vec<_>>();
+        let campaigns = workers
+            .into_par_iter()
+            .enumerate()
+            .map(|(worker, mut executor)| {
+                executor.run_campaign(
+                    &invariant_contract,
+                    fuzz_fixtures,
+                    deployed_libs,
+                    progress,
+                    &timer,
+                    worker,
+                    &failed_worker,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Campaigns of workers after the first failing one may have been stopped early.
+        let mut campaigns =
+            campaigns.into_iter().take(failed_worker.into_inner().saturating_add(1));
+        let (mut result, fuzz_state) = campaigns.next().expect("at least one worker");
+        for (worker_result, worker_fuzz_state) in campaigns {
+            result.merge(worker_result, self.config.gas_report_samples as usize);
+            fuzz_state.merge(&worker_fuzz_state, self.config.depth);
+        }
+        fuzz_state.log_stats();
+        Ok(result)
+    }
+
+    /// Returns the executor of a worker of a parallel invariant campaign, with its share of runs.
+    fn worker(&mut self, worker: usize, threads: usize) -> Self {
+        let mut seed = [0u8; 32];
+        self.runner.rng().fill_bytes(&mut seed);
+        let runs = self.config.runs as usize;
+        let mut config = self.runner.config().clone();
+        config.cases = (runs / threads + usize::from(worker < runs % threads)) as u32;
+
+        Self {
+            executor: self.executor.clone(),
+            runner: TestRunner::new_with_rng(
+                config,
+                TestRng::from_seed(RngAlgorithm::ChaCha, &seed),
+            ),
+            config: self.config.clone(),
+            setup_contracts: self.setup_contracts,
+            project_contracts: self.project_contracts,
+            artifact_filters: ArtifactFilters::default(),
+            corpus_dir: self.corpus_dir.clone(),
+            worker: Some(worker),
+        }
+    }
+
+    /// Executes the runs of the runner, returns the result and the fuzz state of the campaign.
+    ///
+    /// Runs are stopped once a worker before the given one failed, as the result of this worker is
+    /// discarded then.
+    #[allow(clippy::too_many_arguments)]
+    fn run_campaign(
+        &mut self,
+        invariant_contract: &InvariantContract<'_>,
+        fuzz_fixtures: &FuzzFixtures,
+        deployed_libs: &[Address],
+        progress: Option<&ProgressBar>,
+        timer: &FuzzTestTimer,
+        worker: usize,
+        failed_worker: &AtomicUsize,
+    ) -> Result<(InvariantFuzzTestResult, EvmFuzzState)> {
+        let (invariant_test, invariant_strategy) =
+            self.prepare_test(invariant_contract, fuzz_fixtures, deployed_libs)?;
+
         let _ = self.runner.run(&invariant_strategy, |first_input| {
+            if failed_worker.load(Ordering::Relaxed) < worker {
+                return Err(TestCaseError::fail("previous worker failed"))
+            }
+
+            // Start the run with a call sequence derived from the corpus, if any.
+            let mut corpus_calls = invariant_test.corpus_sequence().into_iter();
+
             // Create current invariant run data.
             let mut current_run = InvariantTestRun::new(
-                first_input,
+                corpus_calls.next().unwrap_or(first_input),
                 // Before each run, we must reset the backend state.
                 self.executor.clone(),
                 self.config.depth as usize,
@@ -397,6 +558,9 @@ pub fn invariant_fuzz(
                         ));
                     }
                 } else {
+                    current_run.new_coverage |=
+                        invariant_test.merge_corpus_coverage(call_result.coverage.as_ref());
+
                     // Commit executed call result.
                     current_run.executor.commit(&mut call_result);
 
@@ -439,7 +603,7 @@ pub fn invariant_fuzz(
 
                     // Determine if test can continue or should exit.
                     let result = can_continue(
-                        &invariant_contract,
+                        invariant_contract,
                         &invariant_test,
                         &mut current_run,
                         &self.config,
@@ -460,19 +624,21 @@ pub fn invariant_fuzz(
                 }
 
                 // Generates the next call from the run using the recently updated
-                // dictionary.
-                current_run.inputs.push(
-                    invariant_strategy
+                // dictionary, unless calls derived from the corpus are left.
+                let next_input = match corpus_calls.next() {
+                    Some(tx) => tx,
+                    None => invariant_strategy
                         .new_tree(&mut invariant_test.execution_data.borrow_mut().branch_runner)
                         .map_err(|_| TestCaseError::Fail("Could not generate case".into()))?
                         .current(),
-                );
+                };
+                current_run.inputs.push(next_input);
             }
 
             // Call `afterInvariant` only if it is declared and test didn't fail already.
             if invariant_contract.call_after_invariant && !invariant_test.has_errors() {
                 assert_after_invariant(
-                    &invariant_contract,
+                    invariant_contract,
                     &invariant_test,
                     &current_run,
                     &self.config,
@@ -492,18 +658,23 @@ pub fn invariant_fuzz(
         });
 
         trace!(?fuzz_fixtures);
-        invariant_test.fuzz_state.log_stats();
 
         let result = invariant_test.execution_data.into_inner();
-        Ok(InvariantFuzzTestResult {
-            error: result.failures.error,
-            cases: result.fuzz_cases,
-            reverts: result.failures.reverts,
-            last_run_inputs: result.last_run_inputs,
-            gas_report_traces: result.gas_report_traces,
-            coverage: result.coverage,
-            metrics: result.metrics,
-        })
+        if result.failures.error.is_some() {
+            failed_worker.fetch_min(worker, Ordering::Relaxed);
+        }
+        Ok((
+            InvariantFuzzTestResult {
+                error: result.failures.error,
+                cases: result.fuzz_cases,
+                reverts: result.failures.reverts,
+                last_run_inputs: result.last_run_inputs,
+                gas_report_traces: result.gas_report_traces,
+                coverage: result.coverage,
+                metrics: result.metrics,
+            },
+            invariant_test.fuzz_state,
+        ))
     }
 
     /// Prepares certain structures to execute the invariant tests:
@@ -556,6 +727,31 @@ fn prepare_test(
             ));
         }
 
+        // Loads the corpus and replays its call sequences, so that only sequences that reach new
+        // coverage are added.
+        let corpus = self.corpus_dir.clone().map(|dir| {
+            let mut corpus = Corpus::load(dir);
+            if let Some(worker) = self.worker {
+                corpus.persist_to_subdir(&format!("worker-{worker}"));
+            }
+            for calls in corpus.entries().to_vec() {
+                let mut executor = self.executor.clone();
+                for tx in calls {
+                    let Ok(mut call_result) = executor.call_raw(
+                        tx.sender,
+                        tx.call_details.target,
+                        tx.call_details.calldata,
+                        U256::ZERO,
+                    ) else {
+                        break
+                    };
+                    corpus.merge_coverage(call_result.coverage.as_ref());
+                    executor.commit(&mut call_result);
+                }
+            }
+            corpus
+        });
+
         self.executor.inspector_mut().fuzzer =
             Some(Fuzzer { call_generator, fuzz_state: fuzz_state.clone(), collect: true });
 
@@ -582,6 +778,7 @@ fn prepare_test(
                 targeted_contracts,
                 failures,
                 last_call_results,
+                corpus,
                 self.runner.clone(),
             ),
             strategy,
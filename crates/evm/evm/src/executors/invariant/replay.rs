@@ -124,6 +124,7 @@ pub fn replay_error(
                 calls,
                 &executor,
                 invariant_contract.call_after_invariant,
+                &ided_contracts,
                 progress,
             )?;
 
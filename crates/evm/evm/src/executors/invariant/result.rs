@@ -34,6 +34,25 @@ pub struct InvariantFuzzTestResult {
     pub metrics: HashMap<String, InvariantMetrics>,
 }
 
+impl InvariantFuzzTestResult {
+    /// Merges the result of the next worker of a parallel invariant campaign into this result.
+    pub(crate) fn merge(&mut self, other: Self, gas_report_samples: usize) {
+        self.error = self.error.take().or(other.error);
+        self.cases.extend(other.cases);
+        self.reverts += other.reverts;
+        self.last_run_inputs = other.last_run_inputs;
+        self.gas_report_traces.extend(other.gas_report_traces);
+        self.gas_report_traces.truncate(gas_report_samples);
+        HitMaps::merge_opt(&mut self.coverage, other.coverage);
+        for (selector, metrics) in other.metrics {
+            let merged = self.metrics.entry(selector).or_default();
+            merged.calls += metrics.calls;
+            merged.reverts += metrics.reverts;
+            merged.discards += metrics.discards;
+        }
+    }
+}
+
 /// Enriched results of an invariant run check.
 ///
 /// Contains the success condition and call results of the last run
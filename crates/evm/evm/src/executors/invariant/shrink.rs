@@ -4,9 +4,12 @@
     },
     Executor,
 };
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, Bytes, B256, I256, U256};
+use foundry_common::ContractsByAddress;
 use foundry_evm_core::constants::MAGIC_ASSUME;
-use foundry_evm_fuzz::invariant::BasicTxDetails;
+use foundry_evm_fuzz::invariant::{BasicTxDetails, CallDetails};
 use indicatif::ProgressBar;
 use proptest::bits::{BitSetLike, VarBitSet};
 use std::cmp::min;
@@ -78,7 +81,8 @@ fn complicate(&mut self) -> bool {
     }
 }
 
-/// Shrinks the failure case to its smallest sequence of calls.
+/// Shrinks the failure case to its smallest sequence of calls, then shrinks the arguments and the
+/// senders of the remaining calls, see [`CallArgsShrinker`].
 ///
 /// Maximal shrinkage is guaranteed if the shrink_run_limit is not set to a value lower than the
 /// length of failed call sequence.
@@ -90,6 +94,7 @@ pub(crate) fn shrink_sequence(
     calls: &[BasicTxDetails],
     executor: &Executor,
     call_after_invariant: bool,
+    contracts: &ContractsByAddress,
     progress: Option<&ProgressBar>,
 ) -> eyre::Result<Vec<BasicTxDetails>> {
     trace!(target: "forge::test", "Shrinking sequence of {} calls.", calls.len());
@@ -110,7 +115,9 @@ pub(crate) fn shrink_sequence(
     }
 
     let mut shrinker = CallSequenceShrinker::new(calls.len());
-    for _ in 0..failed_case.shrink_run_limit {
+    let mut runs = 0;
+    while runs < failed_case.shrink_run_limit {
+        runs += 1;
         // Check candidate sequence result.
         match check_sequence(
             executor.clone(),
@@ -134,7 +141,247 @@ pub(crate) fn shrink_sequence(
         }
     }
 
-    Ok(shrinker.current().map(|idx| &calls[idx]).cloned().collect())
+    let mut calls = shrinker.current().map(|idx| &calls[idx]).cloned().collect::<Vec<_>>();
+    let mut args_shrinker = CallArgsShrinker {
+        failed_case,
+        executor,
+        call_after_invariant,
+        progress,
+        runs_left: failed_case.shrink_run_limit - runs,
+    };
+    args_shrinker.shrink(&mut calls, contracts);
+    Ok(calls)
+}
+
+/// Shrinker for the calls of a failing sequence, once no call can be removed.
+///
+/// Senders are unified with the senders of earlier calls, and the arguments of calls to identified
+/// contracts are simplified toward zero, shorter arrays, bytes and strings, and smaller addresses
+/// among the addresses of the sequence and of the identified contracts. A simplification is only
+/// kept if the sequence still fails, until the remaining shrink runs are used up.
+struct CallArgsShrinker<'a> {
+    failed_case: &'a FailedInvariantCaseData,
+    executor: &'a Executor,
+    call_after_invariant: bool,
+    progress: Option<&'a ProgressBar>,
+    /// Number of candidate sequences that can still be checked.
+    runs_left: u32,
+}
+
+impl CallArgsShrinker<'_> {
+    /// Shrinks the calls in place, stops when the run limit is reached.
+    fn shrink(&mut self, calls: &mut [BasicTxDetails], contracts: &ContractsByAddress) {
+        let _ = self.shrink_senders(calls).and_then(|()| self.shrink_args(calls, contracts));
+    }
+
+    fn shrink_senders(&mut self, calls: &mut [BasicTxDetails]) -> Option<()> {
+        // Senders in the order of their first call.
+        let mut senders: Vec<Address> = Vec::new();
+        for call in calls.iter() {
+            if !senders.contains(&call.sender) {
+                senders.push(call.sender);
+            }
+        }
+
+        for index in 0..calls.len() {
+            let sender = calls[index].sender;
+            for &candidate in senders.iter().take_while(|&&earlier| earlier != sender) {
+                let call = BasicTxDetails { sender: candidate, ..calls[index].clone() };
+                if self.try_replace(calls, index, call)? {
+                    break;
+                }
+            }
+        }
+        Some(())
+    }
+
+    fn shrink_args(
+        &mut self,
+        calls: &mut [BasicTxDetails],
+        contracts: &ContractsByAddress,
+    ) -> Option<()> {
+        let mut addresses = calls
+            .iter()
+            .flat_map(|call| [call.sender, call.call_details.target])
+            .chain(contracts.keys().copied())
+            .chain([Address::ZERO])
+            .collect::<Vec<_>>();
+        addresses.sort_unstable();
+        addresses.dedup();
+
+        for index in 0..calls.len() {
+            let BasicTxDetails { sender, call_details: CallDetails { target, ref calldata } } =
+                calls[index];
+            let Some(func) = contracts.get(&target).and_then(|(_, abi)| {
+                abi.functions().find(|func| calldata.get(..4) == Some(func.selector().as_slice()))
+            }) else {
+                continue
+            };
+            let Ok(mut args) = func.abi_decode_input(&calldata[4..], false) else { continue };
+
+            for arg in 0..args.len() {
+                // Restart from the simplest candidates every time the argument is simplified.
+                'simplify: loop {
+                    for candidate in simplifications(&args[arg], &addresses) {
+                        let mut candidate_args = args.clone();
+                        candidate_args[arg] = candidate;
+                        let call = call_with_args(sender, target, func, &candidate_args);
+                        if self.try_replace(calls, index, call)? {
+                            args = candidate_args;
+                            continue 'simplify;
+                        }
+                    }
+                    break
+                }
+            }
+        }
+        Some(())
+    }
+
+    /// Replaces the call at the given index if the sequence still fails with the candidate call.
+    ///
+    /// Returns whether the call was replaced, or `None` if the run limit is reached.
+    fn try_replace(
+        &mut self,
+        calls: &mut [BasicTxDetails],
+        index: usize,
+        candidate: BasicTxDetails,
+    ) -> Option<bool> {
+        if self.runs_left == 0 {
+            return None;
+        }
+        self.runs_left -= 1;
+
+        let call = std::mem::replace(&mut calls[index], candidate);
+        let fails = matches!(
+            check_sequence(
+                self.executor.clone(),
+                calls,
+                (0..calls.len()).collect(),
+                self.failed_case.addr,
+                self.failed_case.calldata.clone(),
+                self.failed_case.fail_on_revert,
+                self.call_after_invariant,
+            ),
+            Ok((false, _))
+        );
+        if !fails {
+            calls[index] = call;
+        }
+
+        if let Some(progress) = self.progress {
+            progress.inc_length(1);
+            progress.inc(1);
+        }
+        Some(fails)
+    }
+}
+
+fn call_with_args(
+    sender: Address,
+    target: Address,
+    func: &Function,
+    args: &[DynSolValue],
+) -> BasicTxDetails {
+    BasicTxDetails {
+        sender,
+        call_details: CallDetails {
+            target,
+            calldata: func.abi_encode_input(args).expect("simplified argument of same type").into(),
+        },
+    }
+}
+
+/// Returns the simplifications of a value, simplest first.
+fn simplifications(value: &DynSolValue, addresses: &[Address]) -> Vec<DynSolValue> {
+    match value {
+        DynSolValue::Bool(true) => vec![DynSolValue::Bool(false)],
+        DynSolValue::Uint(value, size) => {
+            toward_zero(*value).map(|value| DynSolValue::Uint(value, *size)).collect()
+        }
+        DynSolValue::Int(value, size) => {
+            let (sign, abs) = value.into_sign_and_abs();
+            toward_zero(abs)
+                .filter_map(|abs| I256::checked_from_sign_and_abs(sign, abs))
+                .map(|value| DynSolValue::Int(value, *size))
+                .collect()
+        }
+        DynSolValue::Address(address) => addresses
+            .iter()
+            .take_while(|&candidate| candidate < address)
+            .map(|&candidate| DynSolValue::Address(candidate))
+            .collect(),
+        DynSolValue::FixedBytes(word, size) if !word.is_zero() => {
+            vec![DynSolValue::FixedBytes(B256::ZERO, *size)]
+        }
+        DynSolValue::Bytes(bytes) => {
+            let mut simplifications = shorter_lengths(bytes.len())
+                .map(|len| DynSolValue::Bytes(bytes[..len].to_vec()))
+                .collect::<Vec<_>>();
+            if bytes.iter().any(|&byte| byte != 0) {
+                simplifications.push(DynSolValue::Bytes(vec![0; bytes.len()]));
+            }
+            simplifications
+        }
+        DynSolValue::String(string) => shorter_lengths(string.len())
+            .filter(|&len| string.is_char_boundary(len))
+            .map(|len| DynSolValue::String(string[..len].to_string()))
+            .collect(),
+        DynSolValue::Array(values) => shorter_lengths(values.len())
+            .map(|len| DynSolValue::Array(values[..len].to_vec()))
+            .chain(element_simplifications(values, addresses, DynSolValue::Array))
+            .collect(),
+        DynSolValue::FixedArray(values) => {
+            element_simplifications(values, addresses, DynSolValue::FixedArray).collect()
+        }
+        DynSolValue::Tuple(values) => {
+            element_simplifications(values, addresses, DynSolValue::Tuple).collect()
+        }
+        DynSolValue::CustomStruct { name, prop_names, tuple } => {
+            element_simplifications(tuple, addresses, |tuple| DynSolValue::CustomStruct {
+                name: name.clone(),
+                prop_names: prop_names.clone(),
+                tuple,
+            })
+            .collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Returns the values with one element replaced by one of its simplifications.
+fn element_simplifications<'a>(
+    values: &'a [DynSolValue],
+    addresses: &'a [Address],
+    wrap: impl Fn(Vec<DynSolValue>) -> DynSolValue + 'a,
+) -> impl Iterator<Item = DynSolValue> + 'a {
+    values.iter().enumerate().flat_map(move |(index, value)| {
+        simplifications(value, addresses)
+            .into_iter()
+            .map(|simplified| {
+                let mut values = values.to_vec();
+                values[index] = simplified;
+                values
+            })
+            .map(&wrap)
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Returns the values between zero and the given value, zero first: `0`, `v - v / 2`,
+/// `v - v / 4`, ..., `v - 1`, so that greedy shrinking converges like a binary search.
+fn toward_zero(value: U256) -> impl Iterator<Item = U256> {
+    let zero = (!value.is_zero()).then_some(U256::ZERO);
+    let steps = (1..256usize).map_while(move |shift| {
+        let step = value >> shift;
+        (!step.is_zero()).then(|| value - step)
+    });
+    zero.into_iter().chain(steps)
+}
+
+/// Returns the lengths shorter than the given length, see [`toward_zero`].
+fn shorter_lengths(len: usize) -> impl Iterator<Item = usize> {
+    toward_zero(U256::from(len)).map(|len| len.to())
 }
 
 /// Checks if the given call sequence breaks the invariant.
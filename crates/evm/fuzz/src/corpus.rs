@@ -0,0 +1,594 @@
+//! Corpus of coverage-guided fuzzing.
+//!
+//! Inputs that reach new coverage are kept in an on-disk corpus per test, a single call for fuzz
+//! tests and a call sequence for invariant tests. New inputs are derived from the corpus by
+//! splicing entries, flipping bits and mutating ABI-decoded arguments, and the corpus is reused by
+//! later campaigns of the same test.
+
+use crate::{invariant::BasicTxDetails, strategies::EvmFuzzState};
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{hex, keccak256, map::HashSet, Address, Bytes, B256, I256, U256};
+use foundry_evm_coverage::HitMaps;
+use proptest::{
+    strategy::{NewTree, Strategy, ValueTree},
+    test_runner::TestRunner,
+};
+use rand::Rng;
+use std::{cell::RefCell, path::PathBuf};
+
+/// The percentage of inputs that are derived from the corpus instead of being generated.
+const CORPUS_MUTATION_WEIGHT: u32 = 50;
+
+/// A coverage feature: a program counter of a bytecode and the bucket of its hit count.
+///
+/// The hit program counters include the destinations and the fall-throughs of jumps, so both
+/// outcomes of a `JUMPI` are distinct features, like the edges of AFL.
+type Feature = (B256, u32, u8);
+
+/// Returns the AFL-style bucket of a hit count, so that only meaningful changes of loop counts are
+/// new coverage.
+fn hit_bucket(hits: u32) -> u8 {
+    match hits {
+        0..=3 => hits as u8,
+        4..=7 => 4,
+        8..=15 => 5,
+        16..=31 => 6,
+        32..=127 => 7,
+        _ => 8,
+    }
+}
+
+/// The coverage features reached by a fuzz campaign.
+#[derive(Clone, Debug, Default)]
+pub struct CoverageFeedback {
+    features: HashSet<Feature>,
+}
+
+impl CoverageFeedback {
+    /// Merges the coverage of an input, returns `true` if it reached any new feature.
+    pub fn merge(&mut self, coverage: &HitMaps) -> bool {
+        let mut new_coverage = false;
+        for (code_hash, hit_map) in coverage.iter() {
+            for (pc, hits) in hit_map.iter() {
+                new_coverage |= self.features.insert((*code_hash, pc, hit_bucket(hits)));
+            }
+        }
+        new_coverage
+    }
+
+    /// Returns the number of reached features.
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    /// Returns `true` if no feature was reached.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+}
+
+/// Inputs of a test that reached new coverage, persisted as one JSON file per entry.
+#[derive(Debug)]
+pub struct Corpus {
+    /// Directory of the corpus, created when the first entry is added.
+    dir: PathBuf,
+    /// Call sequences of the corpus, a single call for fuzz tests.
+    entries: Vec<Vec<BasicTxDetails>>,
+    /// Coverage reached by the current campaign.
+    feedback: CoverageFeedback,
+}
+
+impl Corpus {
+    /// Loads the corpus of the given directory and its subdirectories, which may not exist yet.
+    pub fn load(dir: PathBuf) -> Self {
+        let mut entries = Vec::new();
+        // Files are sorted, so the same inputs are derived for the same seed.
+        for file in foundry_common::fs::json_files(&dir) {
+            match foundry_common::fs::read_json_file::<Vec<BasicTxDetails>>(&file) {
+                Ok(calls)
+                    if !calls.is_empty() &&
+                        calls.iter().all(|call| call.call_details.calldata.len() >= 4) =>
+                {
+                    entries.push(calls)
+                }
+                Ok(_) => warn!(?file, "ignoring invalid corpus entry"),
+                Err(err) => warn!(?file, %err, "failed to load corpus entry"),
+            }
+        }
+        trace!(?dir, entries = entries.len(), "loaded corpus");
+        Self { dir, entries, feedback: CoverageFeedback::default() }
+    }
+
+    /// Persists new entries in the given subdirectory, e.g. so that the workers of a parallel
+    /// campaign never write the same file. The corpus is loaded including all subdirectories.
+    pub fn persist_to_subdir(&mut self, name: &str) {
+        self.dir.push(name);
+    }
+
+    /// Returns the entries of the corpus.
+    pub fn entries(&self) -> &[Vec<BasicTxDetails>] {
+        &self.entries
+    }
+
+    /// Returns the coverage reached by the current campaign.
+    pub fn feedback(&self) -> &CoverageFeedback {
+        &self.feedback
+    }
+
+    /// Merges the coverage of an input, returns `true` if it reached new coverage.
+    pub fn merge_coverage(&mut self, coverage: Option<&HitMaps>) -> bool {
+        coverage.is_some_and(|coverage| self.feedback.merge(coverage))
+    }
+
+    /// Adds an input that reached new coverage and persists it.
+    pub fn insert(&mut self, calls: Vec<BasicTxDetails>) {
+        if calls.is_empty() {
+            return;
+        }
+        let mut preimage = Vec::new();
+        for call in &calls {
+            preimage.extend_from_slice(call.sender.as_slice());
+            preimage.extend_from_slice(call.call_details.target.as_slice());
+            preimage.extend_from_slice(&call.call_details.calldata);
+        }
+        let file = self.dir.join(format!("{}.json", hex::encode(keccak256(preimage))));
+        if let Err(err) = foundry_common::fs::create_dir_all(&self.dir)
+            .and_then(|()| foundry_common::fs::write_json_file(&file, &calls))
+        {
+            warn!(?file, %err, "failed to persist corpus entry");
+        }
+        self.entries.push(calls);
+    }
+
+    /// Returns `true` if the next input should be derived from the corpus.
+    ///
+    /// The randomness is taken from the test runner, so the same seed derives the same inputs.
+    pub fn should_mutate(&self, rng: &mut impl Rng) -> bool {
+        !self.entries.is_empty() && rng.gen_ratio(CORPUS_MUTATION_WEIGHT, 100)
+    }
+
+    /// Derives the calldata of a fuzz test call from a random entry, by splicing it with another
+    /// entry, flipping bits or mutating an argument.
+    ///
+    /// The corpus must not be empty.
+    pub fn mutate_call(&self, func: &Function, state: &EvmFuzzState, rng: &mut impl Rng) -> Bytes {
+        let calldata = &self.random_entry(rng)[0].call_details.calldata;
+        match rng.gen_range(0..3) {
+            0 => {
+                let other = &self.random_entry(rng)[0].call_details.calldata;
+                splice_calldata(calldata, other, rng)
+            }
+            1 => flip_bits(calldata, rng),
+            _ => {
+                mutate_args(func, calldata, state, rng).unwrap_or_else(|| flip_bits(calldata, rng))
+            }
+        }
+    }
+
+    /// Derives the call sequence of an invariant run from a random entry, by splicing it with
+    /// another entry, or by flipping bits or mutating an argument of one of its calls.
+    ///
+    /// `function` returns the function of a call, if it's known. The corpus must not be empty.
+    pub fn mutate_sequence<'a>(
+        &self,
+        function: impl Fn(&BasicTxDetails) -> Option<&'a Function>,
+        state: &EvmFuzzState,
+        rng: &mut impl Rng,
+    ) -> Vec<BasicTxDetails> {
+        let mut calls = self.random_entry(rng).clone();
+        if rng.gen_bool(0.5) {
+            // Continue a prefix of the entry with a suffix of another entry.
+            let other = self.random_entry(rng);
+            calls.truncate(rng.gen_range(1..=calls.len()));
+            calls.extend_from_slice(&other[rng.gen_range(0..other.len())..]);
+        } else {
+            let index = rng.gen_range(0..calls.len());
+            let func = function(&calls[index]);
+            let call = &mut calls[index].call_details;
+            call.calldata = func
+                .and_then(|func| mutate_args(func, &call.calldata, state, rng))
+                .unwrap_or_else(|| flip_bits(&call.calldata, rng));
+        }
+        calls
+    }
+
+    fn random_entry(&self, rng: &mut impl Rng) -> &Vec<BasicTxDetails> {
+        &self.entries[rng.gen_range(0..self.entries.len())]
+    }
+}
+
+/// Strategy that derives the calldata of fuzz test calls from the corpus, if any, and otherwise
+/// generates it with the wrapped strategy.
+///
+/// Deriving inputs within the strategy makes proptest report and persist the calldata that was
+/// actually executed.
+#[derive(Debug)]
+pub struct CorpusCallStrategy<'a, S> {
+    strategy: S,
+    corpus: Option<&'a RefCell<Corpus>>,
+    func: &'a Function,
+    state: &'a EvmFuzzState,
+}
+
+impl<'a, S> CorpusCallStrategy<'a, S> {
+    /// Wraps the strategy that generates calldata of `func`.
+    pub fn new(
+        strategy: S,
+        corpus: Option<&'a RefCell<Corpus>>,
+        func: &'a Function,
+        state: &'a EvmFuzzState,
+    ) -> Self {
+        Self { strategy, corpus, func, state }
+    }
+}
+
+impl<S: Strategy<Value = Bytes>> Strategy for CorpusCallStrategy<'_, S> {
+    type Tree = CorpusCallValueTree<S::Tree>;
+    type Value = Bytes;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        if let Some(corpus) = self.corpus {
+            let corpus = corpus.borrow();
+            if corpus.should_mutate(runner.rng()) {
+                let calldata = corpus.mutate_call(self.func, self.state, runner.rng());
+                return Ok(CorpusCallValueTree::Corpus(calldata));
+            }
+        }
+        self.strategy.new_tree(runner).map(CorpusCallValueTree::Generated)
+    }
+}
+
+/// Value tree of [CorpusCallStrategy], inputs derived from the corpus are not shrunk.
+#[derive(Clone, Debug)]
+pub enum CorpusCallValueTree<T> {
+    /// Calldata generated by the wrapped strategy
+    Generated(T),
+    /// Calldata derived from the corpus
+    Corpus(Bytes),
+}
+
+impl<T: ValueTree<Value = Bytes>> ValueTree for CorpusCallValueTree<T> {
+    type Value = Bytes;
+
+    fn current(&self) -> Bytes {
+        match self {
+            Self::Generated(tree) => tree.current(),
+            Self::Corpus(calldata) => calldata.clone(),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        match self {
+            Self::Generated(tree) => tree.simplify(),
+            Self::Corpus(_) => false,
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self {
+            Self::Generated(tree) => tree.complicate(),
+            Self::Corpus(_) => false,
+        }
+    }
+}
+
+/// Continues a prefix of the calldata with the suffix of another calldata, cutting both at the
+/// same ABI word so that the arguments stay aligned.
+fn splice_calldata(calldata: &Bytes, other: &Bytes, rng: &mut impl Rng) -> Bytes {
+    let words = calldata.len().min(other.len()).saturating_sub(4) / 32;
+    let cut = 4 + 32 * rng.gen_range(0..=words);
+    [&calldata[..cut], &other[cut.min(other.len())..]].concat().into()
+}
+
+/// Flips up to 4 random bits of the calldata, the selector is kept.
+fn flip_bits(calldata: &Bytes, rng: &mut impl Rng) -> Bytes {
+    let mut calldata = calldata.to_vec();
+    if calldata.len() > 4 {
+        for _ in 0..rng.gen_range(1..=4) {
+            let index = rng.gen_range(4..calldata.len());
+            calldata[index] ^= 1 << rng.gen_range(0..8);
+        }
+    }
+    calldata.into()
+}
+
+/// Mutates a random argument of the calldata, returns `None` if it can't be decoded or the
+/// function has no arguments.
+fn mutate_args(
+    func: &Function,
+    calldata: &Bytes,
+    state: &EvmFuzzState,
+    rng: &mut impl Rng,
+) -> Option<Bytes> {
+    let mut args = func.abi_decode_input(calldata.get(4..)?, false).ok()?;
+    if args.is_empty() {
+        return None;
+    }
+    let index = rng.gen_range(0..args.len());
+    mutate_value(&mut args[index], state, rng);
+    func.abi_encode_input(&args).ok().map(Into::into)
+}
+
+/// Mutates a value in place according to its type, nested values are mutated recursively.
+fn mutate_value(value: &mut DynSolValue, state: &EvmFuzzState, rng: &mut impl Rng) {
+    match value {
+        DynSolValue::Bool(value) => *value = !*value,
+        DynSolValue::Uint(value, size) => *value = mutate_word(*value, *size, state, rng),
+        DynSolValue::Int(value, size) => {
+            let raw = mutate_word(value.into_raw(), *size, state, rng);
+            // Sign-extend the mutated bits.
+            let raw = if *size < 256 && raw.bit(*size - 1) { raw | !mask(*size) } else { raw };
+            *value = I256::from_raw(raw);
+        }
+        DynSolValue::Address(address) => {
+            let dictionary = state.dictionary_read();
+            let addresses = dictionary.addresses();
+            *address = if addresses.is_empty() || rng.gen_bool(0.2) {
+                let mut bytes = address.0 .0;
+                bytes[rng.gen_range(0..20)] ^= 1 << rng.gen_range(0..8);
+                Address::from(bytes)
+            } else {
+                *addresses.get_index(rng.gen_range(0..addresses.len())).unwrap()
+            };
+        }
+        DynSolValue::FixedBytes(word, size) => {
+            if *size > 0 {
+                word[rng.gen_range(0..*size)] ^= 1 << rng.gen_range(0..8);
+            }
+        }
+        DynSolValue::Bytes(bytes) => mutate_bytes(bytes, rng, |rng| rng.gen()),
+        DynSolValue::String(string) => {
+            // Only ASCII characters are inserted and removed to keep the string valid UTF-8.
+            let mut bytes = std::mem::take(string).into_bytes();
+            if bytes.is_ascii() {
+                mutate_bytes(&mut bytes, rng, |rng| rng.gen_range(0x20..0x7f));
+            } else {
+                bytes.push(rng.gen_range(0x20..0x7f));
+            }
+            *string = String::from_utf8(bytes).unwrap_or_default();
+        }
+        DynSolValue::Array(values) if !values.is_empty() && rng.gen_bool(0.2) => {
+            let index = rng.gen_range(0..values.len());
+            if rng.gen_bool(0.5) {
+                values.insert(index, values[index].clone());
+            } else {
+                values.remove(index);
+            }
+        }
+        DynSolValue::Array(values) |
+        DynSolValue::FixedArray(values) |
+        DynSolValue::Tuple(values) |
+        DynSolValue::CustomStruct { tuple: values, .. } => {
+            if !values.is_empty() {
+                let index = rng.gen_range(0..values.len());
+                mutate_value(&mut values[index], state, rng);
+            }
+        }
+        DynSolValue::Function(_) => {}
+    }
+}
+
+/// Mutates the lowest `size` bits of a word: by a small delta, to a boundary, to a dictionary value
+/// or by flipping a bit.
+fn mutate_word(word: U256, size: usize, state: &EvmFuzzState, rng: &mut impl Rng) -> U256 {
+    let mutated = match rng.gen_range(0..4) {
+        0 => {
+            let delta = U256::from(rng.gen_range(1..=16u8));
+            if rng.gen_bool(0.5) {
+                word.wrapping_add(delta)
+            } else {
+                word.wrapping_sub(delta)
+            }
+        }
+        1 => match rng.gen_range(0..4) {
+            0 => U256::ZERO,
+            1 => U256::from(1),
+            // The maximum of unsigned and -1 of signed integers.
+            2 => U256::MAX,
+            // The maximum of signed integers.
+            _ => mask(size) >> 1,
+        },
+        2 => {
+            let dictionary = state.dictionary_read();
+            let values = dictionary.values();
+            if values.is_empty() {
+                word ^ (U256::from(1) << rng.gen_range(0..size))
+            } else {
+                U256::from_be_bytes(values.get_index(rng.gen_range(0..values.len())).unwrap().0)
+            }
+        }
+        _ => word ^ (U256::from(1) << rng.gen_range(0..size)),
+    };
+    mutated & mask(size)
+}
+
+/// Returns a word with the lowest `size` bits set.
+fn mask(size: usize) -> U256 {
+    if size >= 256 {
+        U256::MAX
+    } else {
+        (U256::from(1) << size) - U256::from(1)
+    }
+}
+
+/// Replaces, inserts or removes a random byte.
+fn mutate_bytes<R: Rng>(bytes: &mut Vec<u8>, rng: &mut R, byte: impl Fn(&mut R) -> u8) {
+    match rng.gen_range(0..3) {
+        0 if !bytes.is_empty() => {
+            let index = rng.gen_range(0..bytes.len());
+            bytes[index] = byte(rng);
+        }
+        1 if !bytes.is_empty() => {
+            bytes.remove(rng.gen_range(0..bytes.len()));
+        }
+        _ => {
+            let index = rng.gen_range(0..=bytes.len());
+            bytes.insert(index, byte(rng));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundry_config::FuzzDictionaryConfig;
+    use foundry_evm_coverage::HitMap;
+    use rand::{rngs::StdRng, SeedableRng};
+    use revm::db::{CacheDB, EmptyDB};
+
+    fn state() -> EvmFuzzState {
+        EvmFuzzState::new(&CacheDB::new(EmptyDB::default()), FuzzDictionaryConfig::default(), &[])
+    }
+
+    #[test]
+    fn buckets_hit_counts() {
+        assert_eq!(hit_bucket(1), 1);
+        assert_eq!(hit_bucket(3), 3);
+        assert_eq!(hit_bucket(4), hit_bucket(7));
+        assert_ne!(hit_bucket(7), hit_bucket(8));
+        assert_eq!(hit_bucket(128), hit_bucket(u32::MAX));
+    }
+
+    #[test]
+    fn detects_new_coverage() {
+        let code_hash = B256::repeat_byte(1);
+        let coverage = |hits: &[(u32, u32)]| {
+            let mut hit_map = HitMap::new(Bytes::new());
+            for (pc, count) in hits {
+                hit_map.hits(*pc, *count);
+            }
+            HitMaps(std::iter::once((code_hash, hit_map)).collect())
+        };
+
+        let mut feedback = CoverageFeedback::default();
+        assert!(feedback.merge(&coverage(&[(0, 1), (10, 1)])));
+        assert!(!feedback.merge(&coverage(&[(0, 1)])));
+        // The other outcome of a branch.
+        assert!(feedback.merge(&coverage(&[(0, 1), (12, 1)])));
+        // More iterations of a loop.
+        assert!(feedback.merge(&coverage(&[(0, 5)])));
+        assert!(!feedback.merge(&coverage(&[(0, 6)])));
+        assert_eq!(feedback.len(), 4);
+    }
+
+    #[test]
+    fn mutates_values_of_the_same_type() {
+        let state = state();
+        let mut rng = StdRng::seed_from_u64(0);
+        for mut value in [
+            DynSolValue::Uint(U256::from(1), 8),
+            DynSolValue::Int(I256::MINUS_ONE, 16),
+            DynSolValue::Int(I256::ZERO, 256),
+            DynSolValue::Address(Address::ZERO),
+            DynSolValue::FixedBytes(B256::ZERO, 4),
+            DynSolValue::Bytes(vec![]),
+            DynSolValue::String("foo".to_string()),
+            DynSolValue::Array(vec![DynSolValue::Tuple(vec![
+                DynSolValue::Uint(U256::ZERO, 256),
+                DynSolValue::Bool(false),
+            ])]),
+            DynSolValue::FixedArray(vec![DynSolValue::Int(I256::ZERO, 8); 2]),
+        ] {
+            let ty = value.as_type().unwrap();
+            for _ in 0..100 {
+                mutate_value(&mut value, &state, &mut rng);
+                assert!(ty.matches(&value), "{ty}: {value:?}");
+                assert_eq!(ty.abi_decode(&value.abi_encode()).unwrap(), value, "{ty}");
+            }
+        }
+    }
+
+    #[test]
+    fn splices_and_flips_calldata() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let calldata = Bytes::from([[1u8; 4].as_slice(), &[0; 64]].concat());
+        let other = Bytes::from([[1u8; 4].as_slice(), &[0xff; 96]].concat());
+        for _ in 0..10 {
+            let spliced = splice_calldata(&calldata, &other, &mut rng);
+            assert_eq!(spliced[..4], calldata[..4]);
+            assert_eq!(spliced.len(), other.len());
+
+            let flipped = flip_bits(&calldata, &mut rng);
+            assert_eq!(flipped.len(), calldata.len());
+            assert_eq!(flipped[..4], calldata[..4]);
+        }
+    }
+
+    #[test]
+    fn strategy_yields_the_calldata_derived_from_the_corpus() {
+        let state = state();
+        let func = Function::parse("function f(uint256 x)").unwrap();
+        let generated =
+            Bytes::from(func.abi_encode_input(&[DynSolValue::from(U256::ZERO)]).unwrap());
+        let entry = Bytes::from(func.abi_encode_input(&[DynSolValue::from(U256::MAX)]).unwrap());
+        let corpus = RefCell::new(Corpus {
+            dir: PathBuf::new(),
+            entries: vec![vec![BasicTxDetails {
+                sender: Address::ZERO,
+                call_details: crate::invariant::CallDetails {
+                    target: Address::ZERO,
+                    calldata: entry,
+                },
+            }]],
+            feedback: CoverageFeedback::default(),
+        });
+
+        let mut runner = TestRunner::deterministic();
+        let strategy = CorpusCallStrategy::new(
+            proptest::strategy::Just(generated.clone()),
+            Some(&corpus),
+            &func,
+            &state,
+        );
+        let mut derived = 0;
+        for _ in 0..100 {
+            let mut tree = strategy.new_tree(&mut runner).unwrap();
+            let calldata = tree.current();
+            assert_eq!(calldata[..4], func.selector()[..]);
+            if calldata != generated {
+                derived += 1;
+                // the derived calldata is kept while shrinking
+                assert!(!tree.simplify());
+                assert_eq!(tree.current(), calldata);
+            }
+        }
+        assert!(derived > 0);
+
+        let strategy = CorpusCallStrategy::new(
+            proptest::strategy::Just(generated.clone()),
+            None,
+            &func,
+            &state,
+        );
+        assert_eq!(strategy.new_tree(&mut runner).unwrap().current(), generated);
+    }
+
+    #[test]
+    fn loads_entries_persisted_by_workers() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = |byte| {
+            vec![BasicTxDetails {
+                sender: Address::ZERO,
+                call_details: crate::invariant::CallDetails {
+                    target: Address::ZERO,
+                    calldata: Bytes::from(vec![byte; 4]),
+                },
+            }]
+        };
+        for worker in 0..2u8 {
+            let mut corpus = Corpus::load(dir.path().into());
+            corpus.persist_to_subdir(&format!("worker-{worker}"));
+            corpus.insert(entry(worker));
+        }
+        assert_eq!(foundry_common::fs::json_files(&dir.path().join("worker-1")).count(), 1);
+
+        let corpus = Corpus::load(dir.path().into());
+        let calldata = corpus
+            .entries()
+            .iter()
+            .map(|calls| calls[0].call_details.calldata.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(calldata, [Bytes::from(vec![0; 4]), Bytes::from(vec![1; 4])]);
+    }
+}
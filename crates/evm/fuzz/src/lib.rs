@@ -22,6 +22,9 @@
 
 pub use proptest::test_runner::{Config as FuzzConfig, Reason};
 
+mod corpus;
+pub use corpus::{Corpus, CorpusCallStrategy, CorpusCallValueTree, CoverageFeedback};
+
 mod error;
 pub use error::FuzzError;
 
@@ -31,6 +34,9 @@
 mod inspector;
 pub use inspector::Fuzzer;
 
+mod regressions;
+pub use regressions::{Regression, Regressions};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum CounterExample {
     /// Call used as a counter example for fuzz tests.
@@ -0,0 +1,191 @@
+//! Regression corpus of fuzz and invariant tests.
+//!
+//! Every counterexample of a test is appended to a JSON file of the test, so that earlier
+//! counterexamples are kept when a new failure is found, and are replayed before new inputs are
+//! fuzzed. Counterexamples are recorded in a local corpus first, and can be promoted to a corpus
+//! that is checked in with the project.
+
+use crate::{BaseCounterExample, CounterExample};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// A counterexample of the regression corpus of a test.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Regression {
+    /// The reason of the test failure when the counterexample was recorded.
+    pub reason: Option<String>,
+    /// The counterexample breaking the test.
+    pub counterexample: CounterExample,
+}
+
+impl Regression {
+    /// Returns the calls of the counterexample.
+    pub fn calls(&self) -> &[BaseCounterExample] {
+        match &self.counterexample {
+            CounterExample::Single(call) => std::slice::from_ref(call),
+            CounterExample::Sequence(_, calls) => calls,
+        }
+    }
+
+    /// Returns `true` if both counterexamples make the same calls.
+    pub fn same_calls(&self, other: &Self) -> bool {
+        self.calls().len() == other.calls().len() &&
+            self.calls().iter().zip(other.calls()).all(|(call, other)| {
+                (call.sender, call.addr, &call.calldata) ==
+                    (other.sender, other.addr, &other.calldata)
+            })
+    }
+}
+
+/// The regression corpus of a single test, stored in
+/// `<dir>/<source path>/<contract name>/<test name>.json`.
+#[derive(Debug)]
+pub struct Regressions {
+    /// Path of the corpus relative to the dir of the corpora.
+    key: PathBuf,
+    path: PathBuf,
+    entries: Vec<Regression>,
+}
+
+impl Regressions {
+    /// Loads the regression corpus of the given test from the given dir, empty if none was
+    /// recorded.
+    ///
+    /// The contract is identified by `<source path>:<contract name>`, so that contracts with the
+    /// same name in different files have separate corpora.
+    pub fn load(dir: &Path, contract_id: &str, test_name: &str) -> Self {
+        let (source, contract_name) = contract_id.rsplit_once(':').unwrap_or(("", contract_id));
+        // only keep the normal components, so that the corpus is always inside the dir
+        let source = Path::new(source)
+            .components()
+            .filter(|component| matches!(component, Component::Normal(_)))
+            .collect::<PathBuf>();
+        Self::load_key(dir, source.join(contract_name).join(format!("{test_name}.json")))
+    }
+
+    /// Loads the regression corpora of all tests in the given dir, sorted by contract and test.
+    pub fn load_all(dir: &Path) -> Vec<Self> {
+        foundry_common::fs::json_files(dir)
+            .filter_map(|path| Some(Self::load_key(dir, path.strip_prefix(dir).ok()?.into())))
+            .collect()
+    }
+
+    /// Loads the regression corpus of the same test from another dir.
+    pub fn load_in(&self, dir: &Path) -> Self {
+        Self::load_key(dir, self.key.clone())
+    }
+
+    fn load_key(dir: &Path, key: PathBuf) -> Self {
+        let path = dir.join(&key);
+        let entries = if path.exists() {
+            foundry_common::fs::read_json_file(&path).unwrap_or_else(|err| {
+                warn!(%err, "Failed to read regression corpus");
+                vec![]
+            })
+        } else {
+            vec![]
+        };
+        Self { key, path, entries }
+    }
+
+    /// Returns the source path of the contract of the test.
+    pub fn source_path(&self) -> &Path {
+        self.key.parent().and_then(Path::parent).unwrap_or_else(|| Path::new(""))
+    }
+
+    /// Returns the name of the contract of the test.
+    pub fn contract_name(&self) -> &str {
+        self.key
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+    }
+
+    /// Returns the name of the test.
+    pub fn test_name(&self) -> &str {
+        self.path.file_stem().and_then(|name| name.to_str()).unwrap_or_default()
+    }
+
+    /// Returns the counterexamples of the test, in the order they were recorded.
+    pub fn entries(&self) -> &[Regression] {
+        &self.entries
+    }
+
+    /// Returns `true` if the corpus contains a counterexample making the same calls.
+    pub fn contains(&self, regression: &Regression) -> bool {
+        self.entries.iter().any(|entry| entry.same_calls(regression))
+    }
+
+    /// Appends the counterexample, unless the corpus already contains it.
+    ///
+    /// Returns `true` if the counterexample was appended.
+    pub fn append(&mut self, regression: Regression) -> bool {
+        if self.contains(&regression) {
+            return false
+        }
+        self.entries.push(regression);
+        true
+    }
+
+    /// Removes the counterexample at the given index from the corpus and returns it.
+    pub fn remove(&mut self, index: usize) -> Option<Regression> {
+        (index < self.entries.len()).then(|| self.entries.remove(index))
+    }
+
+    /// Removes all counterexamples from the corpus and returns them.
+    pub fn take(&mut self) -> Vec<Regression> {
+        std::mem::take(&mut self.entries)
+    }
+
+    /// Writes the corpus to its file, the file is removed if the corpus is empty.
+    pub fn save(&self) -> foundry_common::fs::Result<()> {
+        if self.entries.is_empty() {
+            if self.path.exists() {
+                foundry_common::fs::remove_file(&self.path)?;
+            }
+            return Ok(())
+        }
+        if let Some(dir) = self.path.parent() {
+            foundry_common::fs::create_dir_all(dir)?;
+        }
+        foundry_common::fs::write_pretty_json_file(&self.path, &self.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, bytes};
+
+    fn regression(calldata: alloy_primitives::Bytes) -> Regression {
+        let call = BaseCounterExample {
+            sender: Some(address!("0x0000000000000000000000000000000000000001")),
+            addr: Some(address!("0x0000000000000000000000000000000000000002")),
+            calldata,
+            contract_name: None,
+            func_name: None,
+            signature: None,
+            args: None,
+            raw_args: None,
+            traces: None,
+            show_solidity: false,
+        };
+        Regression { reason: None, counterexample: CounterExample::Sequence(1, vec![call]) }
+    }
+
+    #[test]
+    fn appends_new_counterexamples_only() {
+        let dir = Path::new("regressions");
+        let mut regressions = Regressions::load(dir, "test/Counter.t.sol:Counter", "invariant_a");
+        assert_eq!(regressions.path, dir.join("test/Counter.t.sol/Counter/invariant_a.json"));
+        assert_eq!(regressions.source_path(), Path::new("test/Counter.t.sol"));
+        assert_eq!(regressions.contract_name(), "Counter");
+        assert_eq!(regressions.test_name(), "invariant_a");
+
+        assert!(regressions.append(regression(bytes!("0xd09de08a"))));
+        assert!(!regressions.append(regression(bytes!("0xd09de08a"))));
+        assert!(regressions.append(regression(bytes!("0x2baeceb7"))));
+        assert_eq!(regressions.entries().len(), 2);
+    }
+}
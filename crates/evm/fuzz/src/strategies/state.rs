@@ -83,6 +83,12 @@ pub fn revert(&self) {
         self.inner.write().revert();
     }
 
+    /// Merges the sample values collected by another fuzz state of the same test, e.g. by another
+    /// worker of a parallel invariant campaign.
+    pub fn merge(&self, other: &Self, limit: u32) {
+        self.inner.write().merge(&other.inner.read(), limit);
+    }
+
     pub fn dictionary_read(&self) -> RwLockReadGuard<'_, RawRwLock, FuzzDictionary> {
         self.inner.read()
     }
@@ -345,6 +351,20 @@ pub fn insert_sample_values(
         }
     }
 
+    /// Merges the sample values and stats of another dictionary into this dictionary.
+    fn merge(&mut self, other: &Self, limit: u32) {
+        for (sample_type, values) in &other.sample_values {
+            let samples = self.sample_values.entry(sample_type.clone()).or_default();
+            for &value in values {
+                if samples.len() < limit as usize {
+                    samples.insert(value);
+                }
+            }
+        }
+        self.misses += other.misses;
+        self.hits += other.hits;
+    }
+
     pub fn values(&self) -> &B256IndexSet {
         &self.state_values
     }
@@ -29,13 +29,16 @@
 
 use alloy_primitives::map::HashMap;
 
-pub use revm_inspectors::tracing::{
-    types::{
-        CallKind, CallLog, CallTrace, CallTraceNode, DecodedCallData, DecodedCallLog,
-        DecodedCallTrace,
+pub use revm_inspectors::{
+    tracing::{
+        types::{
+            CallKind, CallLog, CallTrace, CallTraceNode, DecodedCallData, DecodedCallLog,
+            DecodedCallTrace,
+        },
+        CallTraceArena, FourByteInspector, GethTraceBuilder, MuxInspector, ParityTraceBuilder,
+        StackSnapshotType, TraceWriter, TracingInspector, TracingInspectorConfig,
     },
-    CallTraceArena, FourByteInspector, GethTraceBuilder, ParityTraceBuilder, StackSnapshotType,
-    TraceWriter, TracingInspector, TracingInspectorConfig,
+    transfer::{TransferInspector, TRANSFER_LOG_EMITTER},
 };
 
 /// Call trace address identifiers.
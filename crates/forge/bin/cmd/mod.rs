@@ -23,6 +23,8 @@
 pub mod init;
 pub mod inspect;
 pub mod install;
+pub mod mutate;
+pub mod regressions;
 pub mod remappings;
 pub mod remove;
 pub mod selectors;
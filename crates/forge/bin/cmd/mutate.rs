@@ -0,0 +1,332 @@
+use super::{
+    install,
+    test::{ProjectPathsAwareFilter, TestArgs},
+};
+use alloy_primitives::{keccak256, Keccak256, B256, U256};
+use clap::Parser;
+use eyre::Result;
+use forge::{
+    mutation::{
+        find_mutants, Mutant, MutantResult, MutantStatus, MutationCache, MutationReport,
+        MUTATION_CACHE_FILENAME,
+    },
+    opts::EvmOpts,
+    result::TestOutcome,
+    revm::primitives::Env,
+    MultiContractRunner, MultiContractRunnerBuilder, TestFilter,
+};
+use foundry_cli::utils::{LoadConfig, STATIC_FUZZ_SEED};
+use foundry_common::{
+    compile::{with_compilation_reporter, ProjectCompiler},
+    fs, shell,
+    version::SHORT_VERSION,
+};
+use foundry_compilers::{
+    artifacts::Source,
+    compilers::multi::{MultiCompiler, MultiCompilerParsedSource},
+    Graph, Project, ProjectCompileOutput,
+};
+use foundry_config::{filter::GlobMatcher, Config};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+// Loads project's figment and merges the build cli arguments into it
+foundry_config::impl_figment_convert!(MutateArgs, test);
+
+/// CLI arguments for `forge mutate`.
+#[derive(Parser)]
+pub struct MutateArgs {
+    /// Only mutate source files matching the glob pattern.
+    #[arg(long, value_name = "GLOB")]
+    mutate_path: Option<GlobMatcher>,
+
+    #[command(flatten)]
+    test: TestArgs,
+}
+
+impl MutateArgs {
+    pub async fn run(self) -> Result<()> {
+        let (mut config, evm_opts) = self.load_config_and_evm_opts()?;
+
+        // install missing dependencies
+        if install::install_missing_dependencies(&mut config) && config.auto_detect_remappings {
+            // need to re-configure here to also catch additional remappings
+            config = self.load_config()?;
+        }
+
+        // Set fuzz seed so mutants are tested against the same inputs on every run
+        config.fuzz.seed = Some(U256::from_be_bytes(STATIC_FUZZ_SEED));
+
+        // Mutants are found in the Solc AST output.
+        config.ast = true;
+
+        let project = config.ephemeral_project()?;
+        let output = ProjectCompiler::new()
+            .quiet(shell::is_json())
+            .compile(&project)?
+            .with_stripped_file_prefixes(project.root());
+
+        let filter = self.test.filter(&config);
+        let tester = MutantTester::new(project, config, evm_opts, filter).await?;
+
+        // Mutants can only be killed by tests passing against the original code.
+        if !shell::is_json() {
+            sh_println!("Running tests...")?;
+        }
+        let mut runner = tester.runner(&tester.config, &output)?;
+        let outcome = TestOutcome::new(runner.test_collect(&tester.filter), false);
+        if outcome.results.is_empty() {
+            eyre::bail!("No tests match the provided filters");
+        }
+        outcome.ensure_ok(false)?;
+
+        let all_tests = runner.contracts.keys().map(|id| id.source.clone()).collect();
+        let tests =
+            runner.matching_contracts(&tester.filter).map(|(id, _)| id.source.clone()).collect();
+        let files = self.find_mutants(&tester.project, &output, &all_tests)?;
+
+        let cache_file = tester.config.cache_path.join(MUTATION_CACHE_FILENAME);
+        let mut cache =
+            if tester.config.cache { MutationCache::read(&cache_file) } else { Default::default() };
+
+        let mut report = MutationReport::default();
+        for (path, (source, mutants)) in files {
+            let affected_tests = tester.affected_tests(&path, &tests);
+            let hash = tester.hash(&path, &affected_tests);
+
+            let results = if let Some(results) = cache.get(&path, hash) {
+                if !shell::is_json() {
+                    sh_println!(
+                        "Reusing results of {} mutants of {}",
+                        results.len(),
+                        path.display()
+                    )?;
+                }
+                results.to_vec()
+            } else {
+                if !shell::is_json() {
+                    sh_println!("Testing {} mutants of {}...", mutants.len(), path.display())?;
+                }
+                let mut results = Vec::with_capacity(mutants.len());
+                for mutant in mutants {
+                    let status = tester.test_mutant(&path, &source, &mutant, &affected_tests)?;
+                    results.push(MutantResult { mutant, status });
+                }
+                cache.insert(path.clone(), hash, results.clone());
+                results
+            };
+            report.files.insert(path, results);
+        }
+
+        if tester.config.cache {
+            cache.write(&cache_file)?;
+        }
+
+        if shell::is_json() {
+            sh_println!("{}", serde_json::to_string(&report)?)?;
+        } else {
+            sh_println!("\n{report}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Finds the mutants of the project's source files, excluding test contracts and libraries.
+    fn find_mutants(
+        &self,
+        project: &Project,
+        output: &ProjectCompileOutput,
+        all_tests: &BTreeSet<PathBuf>,
+    ) -> Result<BTreeMap<PathBuf, (String, Vec<Mutant>)>> {
+        let mut files = BTreeMap::new();
+        for (path, source_file, _) in output.output().sources.sources_with_version() {
+            if files.contains_key(path) ||
+                !project.paths.root.join(path).starts_with(&project.paths.sources) ||
+                project.paths.has_library_ancestor(path) ||
+                all_tests.contains(path) ||
+                self.mutate_path.as_ref().is_some_and(|glob| !glob.is_match(path))
+            {
+                continue;
+            }
+
+            if let Some(ast) = &source_file.ast {
+                let source = fs::read_to_string(project.paths.root.join(path))?;
+                let mutants = find_mutants(&source, ast);
+                files.insert(path.clone(), (source, mutants));
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// Compiles mutants and runs the tests against them.
+struct MutantTester {
+    project: Project,
+    graph: Graph<MultiCompilerParsedSource>,
+    /// The config of the test run against the original code.
+    config: Arc<Config>,
+    /// The config of the test runs against mutants, not persisting any failure or corpus.
+    mutant_config: Arc<Config>,
+    /// The directory failures found against a mutant are persisted in, cleared before each
+    /// mutant is tested.
+    scratch_dir: PathBuf,
+    evm_opts: EvmOpts,
+    env: Env,
+    filter: ProjectPathsAwareFilter,
+    /// The hash of the config, EVM options and environment the tests are run with.
+    config_hash: B256,
+}
+
+impl MutantTester {
+    async fn new(
+        project: Project,
+        config: Config,
+        evm_opts: EvmOpts,
+        filter: ProjectPathsAwareFilter,
+    ) -> Result<Self> {
+        let graph = Graph::resolve(&project.paths)?;
+        let env = evm_opts.evm_env().await?;
+
+        let mut mutant_config = config.clone();
+        let scratch_dir = config.cache_path.join("mutants");
+        mutant_config.fuzz.failure_persist_dir = Some(scratch_dir.join("fuzz"));
+        mutant_config.invariant.failure_persist_dir = Some(scratch_dir.join("invariant"));
+        mutant_config.fuzz.regression_persist_dir = None;
+        mutant_config.fuzz.corpus_dir = None;
+        mutant_config.invariant.corpus_dir = None;
+
+        // Compiler settings, remappings, fuzz and invariant settings and the fork all change the
+        // results of the mutants.
+        let config_hash = keccak256(serde_json::to_vec(&(&mutant_config, &evm_opts, &env))?);
+
+        Ok(Self {
+            project,
+            graph,
+            config: Arc::new(config),
+            mutant_config: Arc::new(mutant_config),
+            scratch_dir,
+            evm_opts,
+            env,
+            filter,
+            config_hash,
+        })
+    }
+
+    /// Builds the test runner of the compiled project.
+    fn runner(
+        &self,
+        config: &Arc<Config>,
+        output: &ProjectCompileOutput,
+    ) -> Result<MultiContractRunner> {
+        MultiContractRunnerBuilder::new(config.clone())
+            .initial_balance(self.evm_opts.initial_balance)
+            .evm_spec(config.evm_spec_id())
+            .sender(self.evm_opts.sender)
+            .with_fork(self.evm_opts.get_fork(config, self.env.clone()))
+            .enable_isolation(self.evm_opts.isolate)
+            .odyssey(self.evm_opts.odyssey)
+            .build::<MultiCompiler>(
+                &self.project.paths.root,
+                output,
+                self.env.clone(),
+                self.evm_opts.clone(),
+            )
+    }
+
+    /// Returns the test files importing the given source file.
+    fn affected_tests(&self, path: &Path, tests: &BTreeSet<PathBuf>) -> Vec<PathBuf> {
+        let root = &self.project.paths.root;
+        let file = root.join(path);
+        tests
+            .iter()
+            .filter(|test| self.graph.imports(&root.join(test)).contains(&file))
+            .cloned()
+            .collect()
+    }
+
+    /// Hashes what the results of the mutants of a source file depend on: the config, the source
+    /// file, the tests running against it and all their imports.
+    fn hash(&self, path: &Path, tests: &[PathBuf]) -> B256 {
+        let root = &self.project.paths.root;
+        let mut files = BTreeSet::from([root.join(path)]);
+        for test in tests {
+            let test = root.join(test);
+            files.extend(self.graph.imports(&test).into_iter().cloned());
+            files.insert(test);
+        }
+
+        let mut hasher = Keccak256::new();
+        hasher.update(SHORT_VERSION);
+        hasher.update(self.filter.to_string());
+        hasher.update(self.config_hash);
+        for file in files {
+            hasher.update(file.strip_prefix(root).unwrap_or(&file).to_string_lossy().as_bytes());
+            if let Some(&id) = self.graph.files().get(&file) {
+                hasher.update(self.graph.node(id).content());
+            }
+        }
+        hasher.finalize()
+    }
+
+    /// Compiles the mutated source together with the given tests, and runs them.
+    fn test_mutant(
+        &self,
+        path: &Path,
+        source: &str,
+        mutant: &Mutant,
+        tests: &[PathBuf],
+    ) -> Result<MutantStatus> {
+        // Mutants not reached by any test survive.
+        if tests.is_empty() {
+            return Ok(MutantStatus::Survived)
+        }
+
+        let root = &self.project.paths.root;
+        let mut sources = Source::read_all(tests.iter().map(|test| root.join(test)))?;
+        sources.insert(root.join(path), Source::new(mutant.apply(source)));
+        let output = with_compilation_reporter(true, || {
+            foundry_compilers::project::ProjectCompiler::with_sources(&self.project, sources)?
+                .compile()
+        });
+        let output = match output {
+            Ok(output) if !output.has_compiler_errors() => output,
+            _ => return Ok(MutantStatus::Invalid),
+        };
+
+        if self.scratch_dir.exists() {
+            fs::remove_dir_all(&self.scratch_dir)?;
+        }
+
+        let mut runner =
+            self.runner(&self.mutant_config, &output.with_stripped_file_prefixes(root))?;
+        let results = runner.test_collect(&AffectedTestsFilter { filter: &self.filter, tests });
+        if results.values().any(|suite| suite.failed() > 0) {
+            Ok(MutantStatus::Killed)
+        } else {
+            Ok(MutantStatus::Survived)
+        }
+    }
+}
+
+/// Filters the tests of the files affected by a mutant.
+struct AffectedTestsFilter<'a> {
+    filter: &'a ProjectPathsAwareFilter,
+    tests: &'a [PathBuf],
+}
+
+impl TestFilter for AffectedTestsFilter<'_> {
+    fn matches_test(&self, test_name: &str) -> bool {
+        self.filter.matches_test(test_name)
+    }
+
+    fn matches_contract(&self, contract_name: &str) -> bool {
+        self.filter.matches_contract(contract_name)
+    }
+
+    fn matches_path(&self, path: &Path) -> bool {
+        self.tests.iter().any(|test| test == path) && self.filter.matches_path(path)
+    }
+}
@@ -0,0 +1,154 @@
+use clap::{Parser, Subcommand, ValueHint};
+use eyre::{eyre, Result};
+use foundry_cli::utils::LoadConfig;
+use foundry_config::impl_figment_convert_basic;
+use foundry_evm::fuzz::{Regression, Regressions};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// CLI arguments for `forge regressions`.
+#[derive(Clone, Debug, Parser)]
+pub struct RegressionsArgs {
+    #[command(subcommand)]
+    pub sub: RegressionsSubcommands,
+
+    /// The project's root path.
+    ///
+    /// By default root of the Git repository, if in one,
+    /// or the current working directory.
+    #[arg(long, global = true, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    root: Option<PathBuf>,
+
+    /// Only include tests of contracts matching the specified regex pattern.
+    #[arg(long = "match-contract", visible_alias = "mc", global = true, value_name = "REGEX")]
+    contract_pattern: Option<Regex>,
+
+    /// Only include tests matching the specified regex pattern.
+    #[arg(long = "match-test", visible_alias = "mt", global = true, value_name = "REGEX")]
+    test_pattern: Option<Regex>,
+}
+impl_figment_convert_basic!(RegressionsArgs);
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum RegressionsSubcommands {
+    /// List the counterexamples of the checked-in and recorded regression corpora.
+    #[command(visible_alias = "ls")]
+    List,
+
+    /// Remove counterexamples from the recorded regression corpus.
+    Prune {
+        /// The index of the counterexample to remove from the corpus of each test.
+        ///
+        /// All counterexamples are removed if not set.
+        #[arg(long)]
+        index: Option<usize>,
+    },
+
+    /// Move counterexamples from the recorded to the checked-in regression corpus.
+    Promote {
+        /// The index of the counterexample to promote from the corpus of each test.
+        ///
+        /// All counterexamples are promoted if not set.
+        #[arg(long)]
+        index: Option<usize>,
+    },
+}
+
+impl RegressionsArgs {
+    pub fn run(self) -> Result<()> {
+        let config = self.load_config()?;
+        let recorded_dir =
+            config.fuzz.regression_persist_dir.as_ref().map(|dir| config.root.join(dir));
+        let checked_in_dir = config.fuzz.regressions_dir.as_ref().map(|dir| config.root.join(dir));
+
+        match self.sub {
+            RegressionsSubcommands::List => {
+                for (kind, dir) in [("checked-in", &checked_in_dir), ("recorded", &recorded_dir)] {
+                    let Some(dir) = dir else { continue };
+                    for regressions in self.load(dir) {
+                        sh_println!(
+                            "{}:{}::{} ({kind})",
+                            regressions.source_path().display(),
+                            regressions.contract_name(),
+                            regressions.test_name()
+                        )?;
+                        for (index, regression) in regressions.entries().iter().enumerate() {
+                            let reason = regression.reason.as_deref().unwrap_or("unknown reason");
+                            sh_println!("\t[{index}] {reason}")?;
+                            for call in regression.calls() {
+                                sh_println!("\t\t{}", call.to_string().trim_start())?;
+                            }
+                        }
+                    }
+                }
+            }
+            RegressionsSubcommands::Prune { index } => {
+                let recorded_dir = recorded_dir.ok_or_else(|| {
+                    eyre!("`regression_persist_dir` is not set in the fuzz config")
+                })?;
+                for mut regressions in self.load(&recorded_dir) {
+                    let pruned = take(&mut regressions, index).len();
+                    regressions.save()?;
+                    if pruned > 0 {
+                        sh_println!(
+                            "Pruned {pruned} counterexample(s) of {}:{}::{}",
+                            regressions.source_path().display(),
+                            regressions.contract_name(),
+                            regressions.test_name()
+                        )?;
+                    }
+                }
+            }
+            RegressionsSubcommands::Promote { index } => {
+                let recorded_dir = recorded_dir.ok_or_else(|| {
+                    eyre!("`regression_persist_dir` is not set in the fuzz config")
+                })?;
+                let checked_in_dir = checked_in_dir
+                    .ok_or_else(|| eyre!("`regressions_dir` is not set in the fuzz config"))?;
+                for mut recorded in self.load(&recorded_dir) {
+                    let mut checked_in = recorded.load_in(&checked_in_dir);
+                    let promoted = take(&mut recorded, index)
+                        .into_iter()
+                        .filter(|regression| checked_in.append(regression.clone()))
+                        .count();
+                    checked_in.save()?;
+                    recorded.save()?;
+                    if promoted > 0 {
+                        sh_println!(
+                            "Promoted {promoted} counterexample(s) of {}:{}::{}",
+                            recorded.source_path().display(),
+                            recorded.contract_name(),
+                            recorded.test_name()
+                        )?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads the regression corpora of the tests matching the filters from the given dir.
+    fn load(&self, dir: &Path) -> Vec<Regressions> {
+        Regressions::load_all(dir)
+            .into_iter()
+            .filter(|regressions| {
+                is_match(&self.contract_pattern, regressions.contract_name()) &&
+                    is_match(&self.test_pattern, regressions.test_name())
+            })
+            .collect()
+    }
+}
+
+/// Removes the counterexample at the given index from the corpus, or all of them if not set.
+fn take(regressions: &mut Regressions, index: Option<usize>) -> Vec<Regression> {
+    match index {
+        Some(index) => regressions.remove(index).into_iter().collect(),
+        None => regressions.take(),
+    }
+}
+
+/// Returns `true` if the name matches the pattern, or if no pattern is set.
+fn is_match(pattern: &Option<Regex>, name: &str) -> bool {
+    pattern.as_ref().is_none_or(|pattern| pattern.is_match(name))
+}
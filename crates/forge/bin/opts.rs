@@ -1,9 +1,9 @@
 use crate::cmd::{
     bind::BindArgs, bind_json, build::BuildArgs, cache::CacheArgs, clone::CloneArgs,
     compiler::CompilerArgs, config, coverage, create::CreateArgs, doc::DocArgs, eip712, flatten,
-    fmt::FmtArgs, geiger, generate, init::InitArgs, inspect, install::InstallArgs,
-    remappings::RemappingArgs, remove::RemoveArgs, selectors::SelectorsSubcommands, snapshot,
-    soldeer, test, tree, update,
+    fmt::FmtArgs, geiger, generate, init::InitArgs, inspect, install::InstallArgs, mutate,
+    regressions::RegressionsArgs, remappings::RemappingArgs, remove::RemoveArgs,
+    selectors::SelectorsSubcommands, snapshot, soldeer, test, tree, update,
 };
 use clap::{Parser, Subcommand, ValueHint};
 use forge_script::ScriptArgs;
@@ -43,6 +43,9 @@ pub enum ForgeSubcommand {
     /// Generate coverage reports.
     Coverage(coverage::CoverageArgs),
 
+    /// Run mutation testing to find code changes not caught by the project's tests.
+    Mutate(mutate::MutateArgs),
+
     /// Generate Rust bindings for smart contracts.
     #[command(alias = "bi")]
     Bind(BindArgs),
@@ -118,6 +121,9 @@ pub enum ForgeSubcommand {
     /// Manage the Foundry cache.
     Cache(CacheArgs),
 
+    /// Manage the regression corpus of fuzz and invariant tests.
+    Regressions(RegressionsArgs),
+
     /// Create a gas snapshot of each test's gas usage.
     #[command(visible_alias = "s")]
     Snapshot(snapshot::GasSnapshotArgs),
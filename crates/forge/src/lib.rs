@@ -12,6 +12,8 @@
 
 pub mod gas_report;
 
+pub mod mutation;
+
 pub mod multi_runner;
 pub use multi_runner::{MultiContractRunner, MultiContractRunnerBuilder};
 
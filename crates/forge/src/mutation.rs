@@ -0,0 +1,427 @@
+//! Mutation testing.
+//!
+//! Mutants are small changes to the project's source code, found in the Solc AST. A mutant is
+//! killed when at least one of the tests running against it fails, and survives otherwise.
+
+use alloy_primitives::B256;
+use foundry_common::fs;
+use foundry_compilers::artifacts::ast::{Ast, Node, NodeType};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// The name of the file caching the results of previous mutation testing runs, in the project's
+/// cache directory.
+pub const MUTATION_CACHE_FILENAME: &str = "mutation-testing.json";
+
+/// The kind of a mutation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationKind {
+    /// An arithmetic, bitwise, logical or equality operator replaced with another one.
+    Operator,
+    /// A comparison operator replaced with its inclusive or exclusive counterpart.
+    Boundary,
+    /// A `require` statement deleted.
+    DeletedRequire,
+    /// A `revert` statement deleted.
+    DeletedRevert,
+    /// Two arguments of the same type swapped in a function call.
+    SwappedArguments,
+}
+
+impl fmt::Display for MutationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Operator => "operator",
+            Self::Boundary => "boundary",
+            Self::DeletedRequire => "deleted require",
+            Self::DeletedRevert => "deleted revert",
+            Self::SwappedArguments => "swapped arguments",
+        })
+    }
+}
+
+/// A mutation of a source file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mutant {
+    /// The kind of the mutation.
+    pub kind: MutationKind,
+    /// The line of the mutated code, starting at 1.
+    pub line: usize,
+    /// The byte offset of the start of the mutated code.
+    pub start: usize,
+    /// The byte offset of the end of the mutated code.
+    pub end: usize,
+    /// The original code.
+    pub original: String,
+    /// The code replacing the original code.
+    pub replacement: String,
+}
+
+impl Mutant {
+    /// Returns the source with the mutation applied.
+    pub fn apply(&self, source: &str) -> String {
+        let mut mutated = String::with_capacity(source.len() + self.replacement.len());
+        mutated.push_str(&source[..self.start]);
+        mutated.push_str(&self.replacement);
+        mutated.push_str(&source[self.end..]);
+        mutated
+    }
+}
+
+impl fmt::Display for Mutant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Deleted statements can span multiple lines.
+        let collapse = |code: &str| code.split_whitespace().collect::<Vec<_>>().join(" ");
+        write!(
+            f,
+            "line {}: replaced `{}` with `{}` ({})",
+            self.line,
+            collapse(&self.original),
+            collapse(&self.replacement),
+            self.kind
+        )
+    }
+}
+
+/// Finds the mutants of a source file in its AST, ordered by location.
+pub fn find_mutants(source: &str, ast: &Ast) -> Vec<Mutant> {
+    let mut finder = MutantFinder { source, mutants: Vec::new() };
+    for node in &ast.nodes {
+        finder.visit(node);
+    }
+
+    let mut mutants = finder.mutants;
+    mutants.sort_by(|a, b| (a.start, a.end, &a.replacement).cmp(&(b.start, b.end, &b.replacement)));
+    mutants.dedup();
+    mutants
+}
+
+struct MutantFinder<'a> {
+    source: &'a str,
+    mutants: Vec<Mutant>,
+}
+
+impl MutantFinder<'_> {
+    fn visit(&mut self, node: &Node) {
+        match node.node_type {
+            NodeType::BinaryOperation => self.visit_binary_operation(node),
+            NodeType::ExpressionStatement => self.visit_expression_statement(node),
+            NodeType::FunctionCall => self.visit_function_call(node),
+            NodeType::RevertStatement => self.push_deletion(MutationKind::DeletedRevert, node),
+            _ => {}
+        }
+
+        for child in &node.nodes {
+            self.visit(child);
+        }
+        if let Some(body) = &node.body {
+            self.visit(body);
+        }
+        for child in attribute_nodes(node) {
+            self.visit(&child);
+        }
+    }
+
+    fn visit_binary_operation(&mut self, node: &Node) {
+        let Some(operator) = node.attribute::<String>("operator") else { return };
+        let Some((kind, replacement)) = mutate_operator(&operator) else { return };
+        let (Some(left), Some(right)) =
+            (node.attribute::<Node>("leftExpression"), node.attribute::<Node>("rightExpression"))
+        else {
+            return
+        };
+        let (Some((_, left_end)), Some((right_start, _))) = (self.span(&left), self.span(&right))
+        else {
+            return
+        };
+
+        // The operator is located between both operands.
+        let Some(offset) =
+            self.source.get(left_end..right_start).and_then(|gap| gap.find(operator.as_str()))
+        else {
+            return
+        };
+        let start = left_end + offset;
+        self.push(kind, start, start + operator.len(), replacement.to_string());
+    }
+
+    fn visit_expression_statement(&mut self, node: &Node) {
+        let Some(expression) = node.attribute::<Node>("expression") else { return };
+        match called_function_name(&expression).as_deref() {
+            Some("require") => self.push_deletion(MutationKind::DeletedRequire, node),
+            Some("revert") => self.push_deletion(MutationKind::DeletedRevert, node),
+            _ => {}
+        }
+    }
+
+    fn visit_function_call(&mut self, node: &Node) {
+        if node.attribute::<String>("kind").as_deref() != Some("functionCall") {
+            return
+        }
+        // Swapping named arguments does not change the call.
+        if node.attribute::<Vec<String>>("names").is_some_and(|names| !names.is_empty()) {
+            return
+        }
+
+        let arguments: Vec<Node> = node.attribute("arguments").unwrap_or_default();
+        for pair in arguments.windows(2) {
+            let [first, second] = pair else { continue };
+            let first_type = type_string(first);
+            if first_type.is_none() || first_type != type_string(second) {
+                continue
+            }
+            let (Some((start, first_end)), Some((second_start, end))) =
+                (self.span(first), self.span(second))
+            else {
+                continue
+            };
+
+            let first = &self.source[start..first_end];
+            let second = &self.source[second_start..end];
+            if first == second {
+                continue
+            }
+            let separator = &self.source[first_end..second_start];
+            self.push(
+                MutationKind::SwappedArguments,
+                start,
+                end,
+                format!("{second}{separator}{first}"),
+            );
+        }
+    }
+
+    /// Replaces the statement with an empty block.
+    fn push_deletion(&mut self, kind: MutationKind, node: &Node) {
+        let Some((start, mut end)) = self.span(node) else { return };
+
+        // The location of some statements does not include the trailing semicolon.
+        let rest = &self.source[end..];
+        if let Some(offset) = rest.find(|c: char| !c.is_whitespace()) {
+            if rest[offset..].starts_with(';') {
+                end += offset + 1;
+            }
+        }
+        self.push(kind, start, end, "{}".to_string());
+    }
+
+    fn push(&mut self, kind: MutationKind, start: usize, end: usize, replacement: String) {
+        let original = self.source[start..end].to_string();
+        if original == replacement {
+            return
+        }
+        let line = self.source[..start].matches('\n').count() + 1;
+        self.mutants.push(Mutant { kind, line, start, end, original, replacement });
+    }
+
+    /// Returns the byte range of the node in the source, if it is valid.
+    fn span(&self, node: &Node) -> Option<(usize, usize)> {
+        let start = node.src.start;
+        let end = start + node.src.length?;
+        self.source.get(start..end)?;
+        Some((start, end))
+    }
+}
+
+/// Returns the replacement of a binary operator, if it is mutated.
+fn mutate_operator(operator: &str) -> Option<(MutationKind, &'static str)> {
+    let mutation = match operator {
+        "+" => (MutationKind::Operator, "-"),
+        "-" => (MutationKind::Operator, "+"),
+        "*" => (MutationKind::Operator, "/"),
+        "/" => (MutationKind::Operator, "*"),
+        "%" => (MutationKind::Operator, "*"),
+        "**" => (MutationKind::Operator, "*"),
+        "==" => (MutationKind::Operator, "!="),
+        "!=" => (MutationKind::Operator, "=="),
+        "&&" => (MutationKind::Operator, "||"),
+        "||" => (MutationKind::Operator, "&&"),
+        "&" => (MutationKind::Operator, "|"),
+        "|" => (MutationKind::Operator, "&"),
+        "^" => (MutationKind::Operator, "&"),
+        "<<" => (MutationKind::Operator, ">>"),
+        ">>" => (MutationKind::Operator, "<<"),
+        "<" => (MutationKind::Boundary, "<="),
+        "<=" => (MutationKind::Boundary, "<"),
+        ">" => (MutationKind::Boundary, ">="),
+        ">=" => (MutationKind::Boundary, ">"),
+        _ => return None,
+    };
+    Some(mutation)
+}
+
+/// Returns the name of the called function, if the node is a call to an identifier.
+fn called_function_name(node: &Node) -> Option<String> {
+    if !matches!(node.node_type, NodeType::FunctionCall) {
+        return None
+    }
+    let callee = node.attribute::<Node>("expression")?;
+    if !matches!(callee.node_type, NodeType::Identifier) {
+        return None
+    }
+    callee.attribute("name")
+}
+
+/// Returns the type of the expression node.
+fn type_string(node: &Node) -> Option<String> {
+    let descriptions = node.attribute::<serde_json::Value>("typeDescriptions")?;
+    descriptions.get("typeString")?.as_str().map(str::to_string)
+}
+
+/// Returns the child nodes stored in the attributes of the node, such as the operands of an
+/// expression or the statements of a block.
+fn attribute_nodes(node: &Node) -> Vec<Node> {
+    let is_node = |value: &serde_json::Value| value.get("nodeType").is_some();
+    let mut nodes = Vec::new();
+    for value in node.other.values() {
+        let values = match value {
+            serde_json::Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        nodes.extend(
+            values
+                .into_iter()
+                .filter(|value| is_node(value))
+                .filter_map(|value| serde_json::from_value(value.clone()).ok()),
+        );
+    }
+    nodes
+}
+
+/// The outcome of testing a mutant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutantStatus {
+    /// At least one test failed against the mutant.
+    Killed,
+    /// All tests passed against the mutant, or no test runs against it.
+    Survived,
+    /// The mutated source does not compile.
+    Invalid,
+}
+
+/// A tested mutant.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MutantResult {
+    #[serde(flatten)]
+    pub mutant: Mutant,
+    pub status: MutantStatus,
+}
+
+/// The number of mutants per outcome.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MutationSummary {
+    pub killed: usize,
+    pub survived: usize,
+    pub invalid: usize,
+}
+
+impl MutationSummary {
+    /// Summarizes the given results.
+    pub fn new<'a>(results: impl IntoIterator<Item = &'a MutantResult>) -> Self {
+        let mut summary = Self::default();
+        for result in results {
+            match result.status {
+                MutantStatus::Killed => summary.killed += 1,
+                MutantStatus::Survived => summary.survived += 1,
+                MutantStatus::Invalid => summary.invalid += 1,
+            }
+        }
+        summary
+    }
+
+    /// Returns the percentage of valid mutants that were killed.
+    pub fn score(&self) -> f64 {
+        let valid = self.killed + self.survived;
+        if valid == 0 {
+            100.0
+        } else {
+            self.killed as f64 / valid as f64 * 100.0
+        }
+    }
+}
+
+impl fmt::Display for MutationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} killed, {} survived, {} invalid ({:.2}% mutation score)",
+            self.killed,
+            self.survived,
+            self.invalid,
+            self.score()
+        )
+    }
+}
+
+/// The results of a mutation testing run, by source file.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MutationReport {
+    pub files: BTreeMap<PathBuf, Vec<MutantResult>>,
+}
+
+impl MutationReport {
+    /// Summarizes the results of all source files.
+    pub fn summary(&self) -> MutationSummary {
+        MutationSummary::new(self.files.values().flatten())
+    }
+}
+
+impl fmt::Display for MutationReport {
+    /// Writes the summary of each source file, followed by its surviving mutants.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, results) in &self.files {
+            writeln!(f, "{}: {}", path.display(), MutationSummary::new(results))?;
+            for result in results.iter().filter(|result| result.status == MutantStatus::Survived) {
+                writeln!(f, "  {}", result.mutant)?;
+            }
+        }
+        write!(f, "\nTotal: {}", self.summary())
+    }
+}
+
+/// The results of previous mutation testing runs.
+///
+/// Results are reused for a source file as long as the hash of the code they depend on does not
+/// change.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MutationCache {
+    files: BTreeMap<PathBuf, CachedResults>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResults {
+    hash: B256,
+    results: Vec<MutantResult>,
+}
+
+impl MutationCache {
+    /// Reads the cache from the given file, empty if it does not exist or cannot be read.
+    pub fn read(path: &Path) -> Self {
+        fs::read_json_file(path).unwrap_or_default()
+    }
+
+    /// Writes the cache to the given file.
+    pub fn write(&self, path: &Path) -> fs::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write_json_file(path, self)
+    }
+
+    /// Returns the cached results of the source file, if they were computed for the same hash.
+    pub fn get(&self, path: &Path, hash: B256) -> Option<&[MutantResult]> {
+        self.files.get(path).filter(|cached| cached.hash == hash).map(|cached| &cached.results[..])
+    }
+
+    /// Caches the results of the source file.
+    pub fn insert(&mut self, path: PathBuf, hash: B256, results: Vec<MutantResult>) {
+        self.files.insert(path, CachedResults { hash, results });
+    }
+}
@@ -26,7 +26,7 @@
     fuzz::{
         fixture_name,
         invariant::{CallDetails, InvariantContract},
-        CounterExample, FuzzFixtures,
+        CounterExample, FuzzFixtures, Regression, Regressions,
     },
     traces::{load_contracts, TraceKind, TraceMode},
 };
@@ -579,6 +579,7 @@ fn run_invariant_test(
             invariant_config.clone(),
             identified_contracts,
             &self.cr.mcr.known_contracts,
+            invariant_config.test_corpus_dir(self.cr.name, &func.name),
         );
         let invariant_contract = InvariantContract {
             address: self.address,
@@ -591,9 +592,19 @@ fn run_invariant_test(
         let failure_file = failure_dir.join(&invariant_contract.invariant_function.name);
         let show_solidity = invariant_config.clone().show_solidity;
 
-        // Try to replay recorded failure if any.
-        if let Ok(mut call_sequence) =
+        // Try to replay recorded failure and the regression corpus of the test, if any.
+        let recorded_failure =
             foundry_common::fs::read_json_file::<Vec<BaseCounterExample>>(failure_file.as_path())
+                .ok()
+                .map(|call_sequence| (true, call_sequence));
+        let regressions = load_regressions(&self.config, self.cr.name, &func.name)
+            .into_iter()
+            .filter_map(|regression| match regression.counterexample {
+                CounterExample::Sequence(_, call_sequence) => Some((false, call_sequence)),
+                CounterExample::Single(_) => None,
+            });
+        for (is_recorded_failure, mut call_sequence) in
+            recorded_failure.into_iter().chain(regressions)
         {
             // Create calls from failed sequence and check if invariant still broken.
             let txes = call_sequence
@@ -619,11 +630,18 @@ fn run_invariant_test(
                 invariant_contract.call_after_invariant,
             ) {
                 if !success {
-                    let _= sh_warn!("\
-                            Replayed invariant failure from {:?} file. \
-                            Run `forge clean` or remove file to ignore failure and to continue invariant test campaign.",
-                        failure_file.as_path()
-                    );
+                    if is_recorded_failure {
+                        let _= sh_warn!("\
+                                Replayed invariant failure from {:?} file. \
+                                Run `forge clean` or remove file to ignore failure and to continue invariant test campaign.",
+                            failure_file.as_path()
+                        );
+                    } else {
+                        let _= sh_warn!("\
+                                Replayed invariant failure from the regression corpus. \
+                                Run `forge regressions prune` or remove the checked-in counterexample to continue invariant test campaign."
+                        );
+                    }
                     // If sequence still fails then replay error to collect traces and
                     // exit without executing new runs.
                     let _ = replay_run(
@@ -708,6 +726,19 @@ fn run_invariant_test(
                                         call_sequence.len()
                                     };
 
+                                record_regression(
+                                    &self.config,
+                                    self.cr.name,
+                                    &func.name,
+                                    Regression {
+                                        reason: reason.clone(),
+                                        counterexample: CounterExample::Sequence(
+                                            original_seq_len,
+                                            call_sequence.clone(),
+                                        ),
+                                    },
+                                );
+
                                 counterexample =
                                     Some(CounterExample::Sequence(original_seq_len, call_sequence))
                             }
@@ -773,9 +804,28 @@ fn run_fuzz_test(mut self, func: &Function) -> TestResult {
         let progress =
             start_fuzz_progress(self.cr.progress, self.cr.name, &func.name, fuzz_config.runs);
 
+        // Replay the regression corpus of the test before fuzzing new inputs.
+        let config = self.config.clone();
+        let regressions = load_regressions(&config, self.cr.name, &func.name)
+            .into_iter()
+            .filter_map(|regression| match regression.counterexample {
+                CounterExample::Single(call) if call.calldata.starts_with(&func.selector()[..]) => {
+                    Some(call.calldata)
+                }
+                _ => None,
+            })
+            .collect();
+
         // Run fuzz test.
-        let fuzzed_executor =
-            FuzzedExecutor::new(self.executor.into_owned(), runner, self.tcfg.sender, fuzz_config);
+        let corpus_dir = fuzz_config.test_corpus_dir(self.cr.name, &func.name);
+        let fuzzed_executor = FuzzedExecutor::new(
+            self.executor.into_owned(),
+            runner,
+            self.tcfg.sender,
+            fuzz_config,
+            corpus_dir,
+            regressions,
+        );
         let result = fuzzed_executor.fuzz(
             func,
             &self.setup.fuzz_fixtures,
@@ -784,6 +834,19 @@ fn run_fuzz_test(mut self, func: &Function) -> TestResult {
             &self.cr.mcr.revert_decoder,
             progress.as_ref(),
         );
+        if !result.success && !result.skipped {
+            if let Some(counterexample) = &result.counterexample {
+                record_regression(
+                    &config,
+                    self.cr.name,
+                    &func.name,
+                    Regression {
+                        reason: result.reason.clone(),
+                        counterexample: counterexample.clone(),
+                    },
+                );
+            }
+        }
         self.result.fuzz_result(result);
         self.result
     }
@@ -869,6 +932,43 @@ fn clone_executor(&self) -> Executor {
     }
 }
 
+/// Returns the counterexamples of the regression corpora of the test, checked-in ones first.
+///
+/// The contract is identified by `<source path>:<contract name>`.
+fn load_regressions(config: &Config, contract_id: &str, test_name: &str) -> Vec<Regression> {
+    if !config.fuzz.regressions {
+        return vec![]
+    }
+    [&config.fuzz.regressions_dir, &config.fuzz.regression_persist_dir]
+        .into_iter()
+        .flatten()
+        .flat_map(|dir| Regressions::load(&config.root.join(dir), contract_id, test_name).take())
+        .collect()
+}
+
+/// Appends the counterexample to the recorded regression corpus of the test, unless a regression
+/// corpus of the test already contains it.
+fn record_regression(config: &Config, contract_id: &str, test_name: &str, regression: Regression) {
+    if !config.fuzz.regressions {
+        return
+    }
+    let Some(dir) = &config.fuzz.regression_persist_dir else { return };
+    if let Some(checked_in_dir) = &config.fuzz.regressions_dir {
+        let checked_in =
+            Regressions::load(&config.root.join(checked_in_dir), contract_id, test_name);
+        if checked_in.contains(&regression) {
+            return
+        }
+    }
+
+    let mut regressions = Regressions::load(&config.root.join(dir), contract_id, test_name);
+    if regressions.append(regression) {
+        if let Err(err) = regressions.save() {
+            error!(%err, "Failed to record regression");
+        }
+    }
+}
+
 fn fuzzer_with_cases(
     seed: Option<U256>,
     cases: u32,
@@ -764,10 +764,13 @@
     assert!(!artifact.exists());
 });
 
-// checks that `clean` removes fuzz and invariant cache dirs
+// checks that `clean` removes fuzz, invariant and regression cache dirs
 forgetest_init!(can_clean_test_cache, |prj, cmd| {
     prj.update_config(|config| {
-        config.fuzz = FuzzConfig::new("cache/fuzz".into());
+        config.fuzz = FuzzConfig {
+            regression_persist_dir: Some("cache/regressions".into()),
+            ..FuzzConfig::new("cache/fuzz".into())
+        };
         config.invariant = InvariantConfig::new("cache/invariant".into());
     });
     // default test contract is written in custom out directory
@@ -775,13 +778,17 @@
     let _ = fs::create_dir(fuzz_cache_dir.clone());
     let invariant_cache_dir = prj.root().join("cache/invariant");
     let _ = fs::create_dir(invariant_cache_dir.clone());
+    let regression_cache_dir = prj.root().join("cache/regressions");
+    let _ = fs::create_dir(regression_cache_dir.clone());
 
     assert!(fuzz_cache_dir.exists());
     assert!(invariant_cache_dir.exists());
+    assert!(regression_cache_dir.exists());
 
     cmd.forge_fuse().arg("clean").assert_empty_stdout();
     assert!(!fuzz_cache_dir.exists());
     assert!(!invariant_cache_dir.exists());
+    assert!(!regression_cache_dir.exists());
 });
 
 // checks that extra output works
@@ -1070,6 +1070,9 @@
 gas_report_samples = 256
 failure_persist_dir = "cache/fuzz"
 failure_persist_file = "failures"
+regressions = true
+regression_persist_dir = "cache/regressions"
+regressions_dir = "regressions"
 show_logs = false
 
 [invariant]
@@ -1174,8 +1177,12 @@
     "gas_report_samples": 256,
     "failure_persist_dir": "cache/fuzz",
     "failure_persist_file": "failures",
+    "regressions": true,
+    "regression_persist_dir": "cache/regressions",
+    "regressions_dir": "regressions",
     "show_logs": false,
-    "timeout": null
+    "timeout": null,
+    "corpus_dir": null
   },
   "invariant": {
     "runs": 256,
@@ -1193,7 +1200,9 @@
     "failure_persist_dir": "cache/invariant",
     "show_metrics": false,
     "timeout": null,
-    "show_solidity": false
+    "show_solidity": false,
+    "corpus_dir": null,
+    "threads": null
   },
   "ffi": false,
   "allow_internal_expect_revert": false,
@@ -20,7 +20,9 @@
 mod geiger;
 mod inline_config;
 mod multi_script;
+mod mutate;
 mod odyssey;
+mod regressions;
 mod script;
 mod soldeer;
 mod svm;
@@ -0,0 +1,86 @@
+//! Tests for the `forge mutate` command.
+
+forgetest_init!(reports_surviving_mutants, |prj, cmd| {
+    prj.wipe_contracts();
+
+    prj.add_raw_source(
+        "Checker.sol",
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+contract Checker {
+    function isAdult(uint256 age) public pure returns (bool) {
+        require(age < 200, "invalid age");
+        return age >= 18;
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    prj.add_test(
+        "Checker.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+import {Checker} from "../src/Checker.sol";
+
+contract CheckerTest is Test {
+    Checker checker;
+
+    function setUp() public {
+        checker = new Checker();
+    }
+
+    function test_IsAdult() public view {
+        assertTrue(checker.isAdult(18));
+        assertFalse(checker.isAdult(17));
+    }
+
+    function test_RevertIf_InvalidAge() public {
+        vm.expectRevert("invalid age");
+        checker.isAdult(300);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.arg("mutate").assert_success().stdout_eq(str![[r#"
+...
+Running tests...
+Testing 3 mutants of src/Checker.sol...
+
+src/Checker.sol: 2 killed, 1 survived, 0 invalid (66.67% mutation score)
+  line 6: replaced `<` with `<=` (boundary)
+
+Total: 2 killed, 1 survived, 0 invalid (66.67% mutation score)
+
+"#]]);
+
+    // Results are reused as long as the source and its tests do not change.
+    cmd.forge_fuse().args(["mutate", "--json"]).assert_success().stdout_eq(str![[r#"
+{"src/Checker.sol":[{"kind":"deleted_require","line":6,[..],"status":"killed"},{"kind":"boundary","line":6,[..],"status":"survived"},{"kind":"boundary","line":7,[..],"status":"killed"}]}
+
+"#]]);
+
+    cmd.forge_fuse().arg("mutate").assert_success().stdout_eq(str![[r#"
+...
+Running tests...
+Reusing results of 3 mutants of src/Checker.sol
+
+src/Checker.sol: 2 killed, 1 survived, 0 invalid (66.67% mutation score)
+  line 6: replaced `<` with `<=` (boundary)
+
+Total: 2 killed, 1 survived, 0 invalid (66.67% mutation score)
+
+"#]]);
+
+    // Results are not reused once the config the tests run with changes.
+    prj.update_config(|config| config.fuzz.runs = 10);
+    cmd.forge_fuse().arg("mutate").assert_success().stdout_eq(str![[r#"
+...
+Running tests...
+Testing 3 mutants of src/Checker.sol...
+...
+"#]]);
+});
@@ -0,0 +1,143 @@
+//! Tests for the regression corpus of fuzz tests and the `forge regressions` command.
+
+use foundry_test_utils::{util::OutputExt, TestProject};
+use std::path::Path;
+
+const RECORDED_CORPUS: &str =
+    "cache/regressions/test/Regression.t.sol/RegressionTest/testFuzz_Value.json";
+const CHECKED_IN_CORPUS: &str =
+    "regressions/test/Regression.t.sol/RegressionTest/testFuzz_Value.json";
+
+/// Disables the fuzz dictionary, so that the fuzzer does not find the counterexample of a test
+/// that only breaks for a single value.
+fn disable_dictionary(prj: &TestProject) {
+    prj.update_config(|config| config.fuzz.dictionary.dictionary_weight = 0);
+}
+
+/// Writes a fuzz test with the given requirement on its `x` input.
+fn add_regression_test(prj: &TestProject, requirement: &str) {
+    prj.add_test(
+        "Regression.t.sol",
+        &format!(
+            r#"
+import {{Test}} from "forge-std/Test.sol";
+
+contract RegressionTest is Test {{
+    function testFuzz_Value(uint256 x) public pure {{
+        require({requirement}, "broken");
+    }}
+}}
+"#
+        ),
+    )
+    .unwrap();
+}
+
+/// Returns the calldata of the counterexamples of the regression corpus at the given path.
+fn corpus_calldata(path: &Path) -> Vec<String> {
+    let entries: serde_json::Value = foundry_common::fs::read_json_file(path).unwrap();
+    entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["counterexample"]["Single"]["calldata"].as_str().unwrap().to_string())
+        .collect()
+}
+
+// checks that counterexamples are recorded and replayed before fuzzing, until they are pruned
+forgetest_init!(can_record_replay_and_prune_regressions, |prj, cmd| {
+    disable_dictionary(&prj);
+    add_regression_test(&prj, "x < 1000");
+
+    cmd.args(["test", "--mt", "testFuzz_Value"]).assert_failure();
+    let recorded = prj.root().join(RECORDED_CORPUS);
+    let calldata = corpus_calldata(&recorded);
+    assert_eq!(calldata.len(), 1);
+
+    // Only the recorded counterexample breaks the test now, which the fuzzer won't find. The
+    // persisted proptest failure is removed so that the failure can only come from the replay.
+    let value = &calldata[0][10..];
+    add_regression_test(&prj, &format!("x != 0x{value}"));
+    let _ = std::fs::remove_dir_all(prj.root().join("cache/fuzz"));
+
+    let output = cmd.forge_fuse().args(["test", "--mt", "testFuzz_Value"]).assert_failure();
+    let stdout = output.get_output().stdout_lossy();
+    assert!(stdout.contains("[FAIL: revert: broken; counterexample: calldata="), "{stdout}");
+    assert!(stdout.contains(&calldata[0]), "{stdout}");
+    assert_eq!(corpus_calldata(&recorded), calldata);
+
+    cmd.forge_fuse().args(["regressions", "list"]).assert_success().stdout_eq(str![[r#"
+test/Regression.t.sol:RegressionTest::testFuzz_Value (recorded)
+	[0] revert: broken
+		calldata=[..] args=[..]
+
+"#]]);
+
+    cmd.forge_fuse().args(["regressions", "prune", "--index", "0"]).assert_success().stdout_eq(
+        str![[r#"
+Pruned 1 counterexample(s) of test/Regression.t.sol:RegressionTest::testFuzz_Value
+
+"#]],
+    );
+    assert!(!recorded.exists());
+
+    cmd.forge_fuse().args(["regressions", "list"]).assert_empty_stdout();
+    let _ = std::fs::remove_dir_all(prj.root().join("cache/fuzz"));
+    cmd.forge_fuse().args(["test", "--mt", "testFuzz_Value"]).assert_success();
+});
+
+// checks that promoted counterexamples are replayed from the checked-in corpus
+forgetest_init!(can_promote_regressions, |prj, cmd| {
+    disable_dictionary(&prj);
+    add_regression_test(&prj, "x < 1000");
+
+    cmd.args(["test", "--mt", "testFuzz_Value"]).assert_failure();
+    let calldata = corpus_calldata(&prj.root().join(RECORDED_CORPUS));
+
+    cmd.forge_fuse().args(["regressions", "promote"]).assert_success().stdout_eq(str![[r#"
+Promoted 1 counterexample(s) of test/Regression.t.sol:RegressionTest::testFuzz_Value
+
+"#]]);
+    assert!(!prj.root().join(RECORDED_CORPUS).exists());
+    assert_eq!(corpus_calldata(&prj.root().join(CHECKED_IN_CORPUS)), calldata);
+
+    cmd.forge_fuse().args(["regressions", "list"]).assert_success().stdout_eq(str![[r#"
+test/Regression.t.sol:RegressionTest::testFuzz_Value (checked-in)
+	[0] revert: broken
+		calldata=[..] args=[..]
+
+"#]]);
+
+    // The checked-in counterexample is replayed first, and not recorded again.
+    let value = &calldata[0][10..];
+    add_regression_test(&prj, &format!("x != 0x{value}"));
+    let _ = std::fs::remove_dir_all(prj.root().join("cache/fuzz"));
+    let output = cmd.forge_fuse().args(["test", "--mt", "testFuzz_Value"]).assert_failure();
+    let stdout = output.get_output().stdout_lossy();
+    assert!(stdout.contains(&calldata[0]), "{stdout}");
+    assert!(!prj.root().join(RECORDED_CORPUS).exists());
+});
+
+// checks that no counterexamples are recorded or replayed if the regression corpus is disabled
+forgetest_init!(can_disable_regressions, |prj, cmd| {
+    disable_dictionary(&prj);
+    add_regression_test(&prj, "x < 1000");
+    prj.update_config(|config| config.fuzz.regressions = false);
+
+    cmd.args(["test", "--mt", "testFuzz_Value"]).assert_failure();
+    assert!(!prj.root().join(RECORDED_CORPUS).exists());
+
+    // a checked-in counterexample is not replayed either
+    prj.update_config(|config| config.fuzz.regressions = true);
+    let _ = std::fs::remove_dir_all(prj.root().join("cache/fuzz"));
+    cmd.forge_fuse().args(["test", "--mt", "testFuzz_Value"]).assert_failure();
+    cmd.forge_fuse().args(["regressions", "promote"]).assert_success();
+    assert!(prj.root().join(CHECKED_IN_CORPUS).exists());
+
+    prj.update_config(|config| config.fuzz.regressions = false);
+    let calldata = corpus_calldata(&prj.root().join(CHECKED_IN_CORPUS));
+    let value = &calldata[0][10..];
+    add_regression_test(&prj, &format!("x != 0x{value}"));
+    let _ = std::fs::remove_dir_all(prj.root().join("cache/fuzz"));
+    cmd.forge_fuse().args(["test", "--mt", "testFuzz_Value"]).assert_success();
+});
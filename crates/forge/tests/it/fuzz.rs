@@ -160,6 +160,31 @@ macro_rules! run_fail {
     assert_ne!(initial_calldata, new_calldata);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuzz_corpus() {
+    let filter = Filter::new(r"testSuccessChecker\(uint256\)", ".*", ".*fuzz/FuzzPositive.t.sol");
+    let corpus_dir = tempfile::tempdir().unwrap();
+    let test_corpus_dir = corpus_dir.path().join("FuzzPositive").join("testSuccessChecker");
+
+    let run = || {
+        let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+            config.fuzz.corpus_dir = Some(corpus_dir.path().to_path_buf());
+        });
+        let results = runner.test_collect(&filter);
+        let result = &results["default/fuzz/FuzzPositive.t.sol:FuzzPositive"].test_results
+            ["testSuccessChecker(uint256)"];
+        assert_eq!(result.status, TestStatus::Success, "{:?}", result.reason);
+        std::fs::read_dir(&test_corpus_dir).unwrap().count()
+    };
+
+    // inputs that reached new coverage are kept
+    let entries = run();
+    assert!(entries > 0);
+
+    // the corpus is reused, so the same coverage doesn't add entries
+    assert_eq!(run(), entries);
+}
+
 forgetest_init!(test_can_scrape_bytecode, |prj, cmd| {
     prj.update_config(|config| config.optimizer = Some(true));
     prj.add_source(
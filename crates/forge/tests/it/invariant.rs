@@ -320,6 +320,59 @@ async fn check_shrink_sequence(test_pattern: &str, expected_len: usize) {
     };
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_parallel_runs() {
+    let filter = Filter::new(
+        "invariant_with_require",
+        ".*",
+        ".*fuzz/invariant/common/InvariantShrinkWithAssert.t.sol",
+    );
+    // Runs distributed across workers report the same failure for the same seed.
+    let counterexamples = (0..2)
+        .map(|_| {
+            let failure_dir = tempfile::tempdir().unwrap();
+            let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+                config.fuzz.seed = Some(U256::from(100u32));
+                config.invariant.runs = 8;
+                config.invariant.depth = 15;
+                config.invariant.threads = Some(4);
+                config.invariant.failure_persist_dir = Some(failure_dir.path().into());
+            });
+            match get_counterexample!(runner, &filter) {
+                CounterExample::Single(_) => panic!("CounterExample should be a sequence."),
+                CounterExample::Sequence(_, sequence) => sequence
+                    .iter()
+                    .map(|call| (call.sender, call.calldata.clone()))
+                    .collect::<Vec<_>>(),
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(counterexamples[0], counterexamples[1]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(windows, ignore = "for some reason there's different rng")]
+async fn test_invariant_shrink_args() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/invariant/common/InvariantShrinkArgs.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+        config.fuzz.seed = Some(U256::from(100u32));
+        config.invariant.runs = 10;
+        config.invariant.depth = 15;
+    });
+
+    match get_counterexample!(runner, &filter) {
+        CounterExample::Single(_) => panic!("CounterExample should be a sequence."),
+        CounterExample::Sequence(_, sequence) => {
+            // Arguments are shrunk to the smallest values still breaking the invariant.
+            assert_eq!(sequence.len(), 1);
+            assert_eq!(
+                sequence[0].raw_args.as_deref(),
+                Some("1001, 0x000000, 0x0000000000000000000000000000000000000000")
+            );
+        }
+    };
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[cfg_attr(windows, ignore = "for some reason there's different rng")]
 async fn test_shrink_big_sequence() {
@@ -122,8 +122,12 @@ pub fn config(&self) -> Config {
             gas_report_samples: 256,
             failure_persist_dir: Some(tempfile::tempdir().unwrap().into_path()),
             failure_persist_file: Some("testfailure".to_string()),
+            regressions: false,
+            regression_persist_dir: None,
+            regressions_dir: None,
             show_logs: false,
             timeout: None,
+            corpus_dir: None,
         };
         config.invariant = InvariantConfig {
             runs: 256,
@@ -150,6 +154,8 @@ pub fn config(&self) -> Config {
             show_metrics: false,
             timeout: None,
             show_solidity: false,
+            corpus_dir: None,
+            threads: None,
         };
 
         config.sanitized()
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import "ds-test/test.sol";
+
+contract ShrinkArgs {
+    bool public broken;
+
+    function work(uint256 x, bytes calldata data, address who) public {
+        if (x > 1000 && data.length > 2 && who != address(this)) {
+            broken = true;
+        }
+    }
+}
+
+contract InvariantShrinkArgs is DSTest {
+    ShrinkArgs target;
+
+    function setUp() public {
+        target = new ShrinkArgs();
+    }
+
+    function invariant_shrink_args() public view {
+        require(!target.broken(), "broken");
+    }
+}